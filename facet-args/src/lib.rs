@@ -1,82 +1,123 @@
-use facet_core::{Facet, FieldAttribute};
-use facet_reflect::{PokeStruct, PokeUninit};
+use std::borrow::Cow;
 
-fn parse_field(field: PokeUninit, value: &str, field_index: usize, ps: &mut PokeStruct<'_>) {
-    let field_shape = field.shape();
-    log::trace!("Field shape: {}", field_shape);
+use facet_core::{Def, Facet, Field, FieldAttribute, Shape};
+use facet_reflect::{
+    DeserializeError, Deserializer, PathError, PokeUninit, Token, deserialize_opaque,
+};
 
-    let field = match field {
-        PokeUninit::Scalar(pv) => {
-            let pv = match pv.typed::<String>() {
-                Ok(pv) => {
-                    pv.put(value.to_string());
-                    unsafe { ps.mark_initialized(field_index) }
-                    return;
-                }
-                Err(pv) => pv,
-            };
-            let pv = match pv.typed::<bool>() {
-                Ok(pv) => {
-                    log::trace!("Boolean field detected, setting to true");
-                    pv.put(value.to_lowercase() == "true");
-                    unsafe { ps.mark_initialized(field_index) }
-                    return;
-                }
-                Err(pv) => pv,
-            };
-            PokeUninit::Scalar(pv)
+fn is_positional(field: &Field) -> bool {
+    field
+        .attributes
+        .iter()
+        .any(|a| matches!(a, FieldAttribute::Arbitrary(a) if a.contains("positional")))
+}
+
+/// Parses command-line arguments into a struct: `--name value` sets a named field, `--name`
+/// alone sets a boolean one, and other arguments fill the positional fields in order.
+///
+/// Fails if an argument is unknown, a value doesn't parse, or a field is missing; the error
+/// says which field it's about.
+pub fn from_slice<'facet, T: Facet<'facet>>(s: &[&str]) -> Result<T, PathError<DeserializeError>> {
+    log::trace!("Entering from_slice function");
+    let (poke, _guard) = PokeUninit::alloc::<T>();
+    let mut de = ArgsDeserializer::new(T::SHAPE, s);
+    // SAFETY: arguments are copied, never borrowed
+    let opaque = unsafe { deserialize_opaque(poke, &mut de) }?;
+    Ok(unsafe { opaque.read::<T>() })
+}
+
+/// Turns command-line arguments into the tokens of a map: `--name value` and positional
+/// arguments become entries of the struct's fields, boolean flags are set by their name alone
+struct ArgsDeserializer<'a> {
+    shape: &'static Shape,
+    fields: &'static [Field],
+    /// The arguments left to read
+    args: &'a [&'a str],
+    /// The positional fields that haven't been given a value yet, the next one last
+    positional: Vec<&'static Field>,
+    started: bool,
+    /// The value of the field whose name was just handed out
+    value: Option<Token<'static>>,
+}
+
+impl<'a> ArgsDeserializer<'a> {
+    fn new(shape: &'static Shape, args: &'a [&'a str]) -> Self {
+        // anything else than a struct is reported by the driver, which expects a map for it
+        let fields = match shape.def {
+            Def::Struct(def) => def.fields,
+            _ => &[],
+        };
+        ArgsDeserializer {
+            shape,
+            fields,
+            args,
+            positional: fields.iter().filter(|f| is_positional(f)).rev().collect(),
+            started: false,
+            value: None,
         }
-        field => field,
-    };
+    }
 
-    let parse = field_shape.vtable.parse.unwrap_or_else(|| {
-        log::trace!("No parse function found for shape {}", field.shape());
-        panic!("shape {} does not support parse", field.shape())
-    });
-    log::trace!("Parsing field value");
-    unsafe { (parse)(value, field.into_value().data()) }.unwrap_or_else(|e| {
-        log::trace!("Failed to parse field: {}", e);
-        panic!("Failed to parse field of shape {}: {}", field_shape, e)
-    });
-    unsafe { ps.mark_initialized(field_index) }
+    fn next_arg(&mut self) -> Option<&'a str> {
+        let (arg, rest) = self.args.split_first()?;
+        self.args = rest;
+        Some(arg)
+    }
 }
 
-pub fn from_slice<T: Facet>(s: &[&str]) -> T {
-    log::trace!("Entering from_slice function");
-    let mut s = s;
-    let (poke, guard) = PokeUninit::alloc::<T>();
-    log::trace!("Allocated Poke for type T");
-    let mut ps = poke.into_struct();
-    log::trace!("Converted Poke into struct");
+impl<'input> Deserializer<'input> for ArgsDeserializer<'_> {
+    type Error = DeserializeError;
 
-    while let Some(token) = s.first() {
-        log::trace!("Processing token: {}", token);
-        s = &s[1..];
+    fn next(&mut self) -> Result<Token<'input>, DeserializeError> {
+        if let Some(value) = self.value.take() {
+            return Ok(value);
+        }
+        if !self.started {
+            self.started = true;
+            return Ok(Token::StartMap(None));
+        }
+        let Some(arg) = self.next_arg() else {
+            return Ok(Token::EndMap);
+        };
+        log::trace!("Processing token: {}", arg);
 
-        if let Some(key) = token.strip_prefix("--") {
+        let field = if let Some(key) = arg.strip_prefix("--") {
             log::trace!("Found named argument: {}", key);
-            let (field_index, field) = ps.field_by_name(key).unwrap();
-            if field.shape().is_type::<bool>() {
-                parse_field(field, "true", field_index, &mut ps);
+            let field = self
+                .fields
+                .iter()
+                .find(|f| f.serialized_name() == key)
+                .ok_or_else(|| DeserializeError::UnknownField {
+                    field: key.to_string(),
+                    shape: self.shape,
+                    suggestion: facet_reflect::did_you_mean(
+                        key,
+                        self.fields.iter().map(|f| f.serialized_name()),
+                    ),
+                })?;
+            let value = if field.shape.is_type::<bool>() {
+                log::trace!("Boolean field detected, setting to true");
+                Token::Bool(true)
             } else {
-                let value = s.first().expect("expected value after argument");
-                log::trace!("Field value: {}", value);
-                s = &s[1..];
-                parse_field(field, value, field_index, &mut ps);
-            }
+                let value = self.next_arg().ok_or(DeserializeError::UnexpectedToken {
+                    expected: "a value after the argument",
+                    got: "the end of the arguments",
+                })?;
+                Token::Str(Cow::Owned(value.to_string()))
+            };
+            self.value = Some(value);
+            field
         } else {
-            log::trace!("Encountered positional argument: {}", token);
-            for f in ps.def().fields {
-                if f.attributes
-                    .iter()
-                    .any(|a| matches!(a, FieldAttribute::Arbitrary(a) if a.contains("positional")))
-                {
-                    let (field_index, field) = ps.field_by_name(f.name).unwrap();
-                    parse_field(field, token, field_index, &mut ps);
-                    break;
-                }
-            }
-        }
+            log::trace!("Encountered positional argument: {}", arg);
+            let field = self
+                .positional
+                .pop()
+                .ok_or(DeserializeError::UnexpectedToken {
+                    expected: "a named argument",
+                    got: "a positional argument",
+                })?;
+            self.value = Some(Token::Str(Cow::Owned(arg.to_string())));
+            field
+        };
+        Ok(Token::Str(Cow::Borrowed(field.serialized_name())))
     }
-    ps.build(Some(guard))
 }
@@ -17,6 +17,102 @@ fn test_arg_parse() {
         concurrency: usize,
     }
 
-    let args: Args = facet_args::from_slice(&["--verbose", "--concurrency", "14", "example.rs"]);
+    let args: Args =
+        facet_args::from_slice(&["--verbose", "--concurrency", "14", "example.rs"]).unwrap();
     eprintln!("args: {}", args.pretty());
 }
+
+#[test]
+fn test_arg_parse_error() {
+    facet_testhelpers::setup();
+
+    #[derive(Facet, Debug)]
+    struct Args {
+        #[facet(named, short = 'j')]
+        concurrency: usize,
+    }
+
+    let err = facet_args::from_slice::<Args>(&["--concurrency", "many"]).unwrap_err();
+    assert!(
+        err.to_string()
+            .starts_with("concurrency: invalid value for usize"),
+        "{err}"
+    );
+}
+
+#[test]
+fn test_arg_parse_positionals_in_order() {
+    facet_testhelpers::setup();
+
+    #[derive(Facet, Debug, PartialEq)]
+    struct Args {
+        #[facet(positional)]
+        from: String,
+
+        #[facet(named)]
+        force: bool,
+
+        #[facet(positional)]
+        to: String,
+    }
+
+    let args: Args = facet_args::from_slice(&["a.txt", "--force", "b.txt"]).unwrap();
+    assert_eq!(
+        args,
+        Args {
+            from: "a.txt".to_string(),
+            force: true,
+            to: "b.txt".to_string(),
+        }
+    );
+}
+
+#[test]
+fn test_arg_parse_unknown_argument() {
+    facet_testhelpers::setup();
+
+    #[derive(Facet, Debug)]
+    struct Args {
+        #[facet(named)]
+        force: bool,
+    }
+
+    let err = facet_args::from_slice::<Args>(&["--forse"]).unwrap_err();
+    assert!(
+        err.to_string()
+            .contains("unknown field `forse` for Args, did you mean `force`?"),
+        "{err}"
+    );
+}
+
+#[test]
+fn test_arg_parse_renamed() {
+    facet_testhelpers::setup();
+
+    #[derive(Facet, Debug, PartialEq)]
+    #[facet(rename_all = "kebab-case")]
+    struct Args {
+        #[facet(named)]
+        dry_run: bool,
+
+        #[facet(named)]
+        #[facet(rename = "jobs")]
+        concurrency: usize,
+    }
+
+    let args: Args = facet_args::from_slice(&["--dry-run", "--jobs", "4"]).unwrap();
+    assert_eq!(
+        args,
+        Args {
+            dry_run: true,
+            concurrency: 4,
+        }
+    );
+
+    let err = facet_args::from_slice::<Args>(&["--concurrency", "4"]).unwrap_err();
+    assert!(
+        err.to_string()
+            .contains("unknown field `concurrency` for Args"),
+        "{err}"
+    );
+}
@@ -2,9 +2,9 @@ use crate::*;
 use core::alloc::Layout;
 use core::{cmp::Ordering, iter::zip};
 
-unsafe impl<T, const L: usize> Facet for [T; L]
+unsafe impl<'a, T, const L: usize> Facet<'a> for [T; L]
 where
-    T: Facet,
+    T: Facet<'a>,
 {
     const SHAPE: &'static Shape = &const {
         Shape::builder()
@@ -169,7 +169,15 @@ where
                                     "Index out of bounds: the len is {L} but the index is {index}"
                                 );
                             }
-                            OpaqueConst::new(ptr.as_ptr::<[T; L]>())
+                            OpaqueConst::new(ptr.as_ref::<[T; L]>().as_ptr().add(index))
+                        })
+                        .get_item_ptr_mut(|ptr, index| unsafe {
+                            if index >= L {
+                                panic!(
+                                    "Index out of bounds: the len is {L} but the index is {index}"
+                                );
+                            }
+                            Opaque::new(ptr.as_mut::<[T; L]>().as_mut_ptr().add(index))
                         })
                         .build()
                         },
@@ -0,0 +1,223 @@
+use core::{alloc::Layout, hash::Hash, mem::MaybeUninit};
+
+use alloc::boxed::Box;
+use alloc::collections::BTreeMap;
+
+use crate::opaque::{Opaque, OpaqueConst, OpaqueUninit};
+
+use crate::{
+    ConstTypeId, Def, Facet, MapDef, MapIterVTable, MapVTable, MarkerTraits, Shape, ValueVTable,
+};
+
+type BTreeMapIterator<'mem, K, V> = alloc::collections::btree_map::Iter<'mem, K, V>;
+
+unsafe impl<'a, K, V> Facet<'a> for BTreeMap<K, V>
+where
+    K: Facet<'a> + core::cmp::Eq + core::cmp::Ord + 'static,
+    V: Facet<'a> + 'static,
+{
+    const SHAPE: &'static Shape = &const {
+        Shape::builder()
+            .id(ConstTypeId::of::<BTreeMap<K, V>>())
+            .layout(Layout::new::<BTreeMap<K, V>>())
+            .vtable(
+                &const {
+                    let mut builder = ValueVTable::builder()
+                        .marker_traits({
+                            let mut traits = MarkerTraits::empty();
+                            if K::SHAPE.vtable.marker_traits.contains(MarkerTraits::SEND)
+                                && V::SHAPE.vtable.marker_traits.contains(MarkerTraits::SEND)
+                            {
+                                traits = traits.union(MarkerTraits::SEND);
+                            }
+                            if K::SHAPE.vtable.marker_traits.contains(MarkerTraits::SYNC)
+                                && V::SHAPE.vtable.marker_traits.contains(MarkerTraits::SYNC)
+                            {
+                                traits = traits.union(MarkerTraits::SYNC);
+                            }
+                            if K::SHAPE.vtable.marker_traits.contains(MarkerTraits::EQ)
+                                && V::SHAPE.vtable.marker_traits.contains(MarkerTraits::EQ)
+                            {
+                                traits = traits.union(MarkerTraits::EQ);
+                            }
+                            traits
+                        })
+                        .type_name(|f, opts| {
+                            if let Some(opts) = opts.for_children() {
+                                write!(f, "BTreeMap<")?;
+                                (K::SHAPE.vtable.type_name)(f, opts)?;
+                                write!(f, ", ")?;
+                                (V::SHAPE.vtable.type_name)(f, opts)?;
+                                write!(f, ">")
+                            } else {
+                                write!(f, "BTreeMap<⋯>")
+                            }
+                        })
+                        .drop_in_place(|value| unsafe { value.drop_in_place::<BTreeMap<K, V>>() });
+
+                    if K::SHAPE.vtable.debug.is_some() && V::SHAPE.vtable.debug.is_some() {
+                        builder = builder.debug(|value, f| unsafe {
+                            let value = value.as_ref::<BTreeMap<K, V>>();
+                            let k_debug = K::SHAPE.vtable.debug.unwrap_unchecked();
+                            let v_debug = V::SHAPE.vtable.debug.unwrap_unchecked();
+                            write!(f, "{{")?;
+                            for (i, (key, val)) in value.iter().enumerate() {
+                                if i > 0 {
+                                    write!(f, ", ")?;
+                                }
+                                (k_debug)(OpaqueConst::new(key as *const _), f)?;
+                                write!(f, ": ")?;
+                                (v_debug)(OpaqueConst::new(val as *const _), f)?;
+                            }
+                            write!(f, "}}")
+                        });
+                    }
+
+                    builder =
+                        builder.default_in_place(|target| unsafe { target.put(Self::default()) });
+
+                    if K::SHAPE.vtable.clone_into.is_some() && V::SHAPE.vtable.clone_into.is_some()
+                    {
+                        builder = builder.clone_into(|src, dst| unsafe {
+                            let src = src.as_ref::<BTreeMap<K, V>>();
+                            let k_clone = K::SHAPE.vtable.clone_into.unwrap_unchecked();
+                            let v_clone = V::SHAPE.vtable.clone_into.unwrap_unchecked();
+                            let mut map = BTreeMap::new();
+                            for (k, v) in src {
+                                let mut new_k = MaybeUninit::<K>::uninit();
+                                let mut new_v = MaybeUninit::<V>::uninit();
+                                (k_clone)(
+                                    OpaqueConst::new(k as *const _),
+                                    OpaqueUninit::from_maybe_uninit(&mut new_k),
+                                );
+                                (v_clone)(
+                                    OpaqueConst::new(v as *const _),
+                                    OpaqueUninit::from_maybe_uninit(&mut new_v),
+                                );
+                                map.insert(new_k.assume_init(), new_v.assume_init());
+                            }
+                            dst.put(map)
+                        });
+                    }
+
+                    if K::SHAPE.vtable.eq.is_some() && V::SHAPE.vtable.eq.is_some() {
+                        builder = builder.eq(|a, b| unsafe {
+                            let a = a.as_ref::<BTreeMap<K, V>>();
+                            let b = b.as_ref::<BTreeMap<K, V>>();
+                            let k_eq = K::SHAPE.vtable.eq.unwrap_unchecked();
+                            let v_eq = V::SHAPE.vtable.eq.unwrap_unchecked();
+                            a.len() == b.len()
+                                && a.iter()
+                                    .zip(b.iter())
+                                    .all(|((key_a, val_a), (key_b, val_b))| {
+                                        (k_eq)(
+                                            OpaqueConst::new(key_a as *const _),
+                                            OpaqueConst::new(key_b as *const _),
+                                        ) && (v_eq)(
+                                            OpaqueConst::new(val_a as *const _),
+                                            OpaqueConst::new(val_b as *const _),
+                                        )
+                                    })
+                        });
+                    }
+
+                    if K::SHAPE.vtable.hash.is_some() && V::SHAPE.vtable.hash.is_some() {
+                        builder = builder.hash(|value, hasher_this, hasher_write_fn| unsafe {
+                            use crate::HasherProxy;
+                            let map = value.as_ref::<BTreeMap<K, V>>();
+                            let k_hash = K::SHAPE.vtable.hash.unwrap_unchecked();
+                            let v_hash = V::SHAPE.vtable.hash.unwrap_unchecked();
+                            let mut hasher = HasherProxy::new(hasher_this, hasher_write_fn);
+                            map.len().hash(&mut hasher);
+                            for (k, v) in map {
+                                (k_hash)(
+                                    OpaqueConst::new(k as *const _),
+                                    hasher_this,
+                                    hasher_write_fn,
+                                );
+                                (v_hash)(
+                                    OpaqueConst::new(v as *const _),
+                                    hasher_this,
+                                    hasher_write_fn,
+                                );
+                            }
+                        });
+                    }
+
+                    builder.build()
+                },
+            )
+            .def(Def::Map(
+                MapDef::builder()
+                    .k(K::SHAPE)
+                    .v(V::SHAPE)
+                    .vtable(
+                        &const {
+                            MapVTable::builder()
+                                .init_in_place_with_capacity(|uninit, _capacity| unsafe {
+                                    Ok(uninit.put(Self::new()))
+                                })
+                                .insert(|ptr, key, value| unsafe {
+                                    let map = ptr.as_mut::<BTreeMap<K, V>>();
+                                    let key = key.read::<K>();
+                                    let value = value.read::<V>();
+                                    map.insert(key, value);
+                                })
+                                .len(|ptr| unsafe {
+                                    let map = ptr.as_ref::<BTreeMap<K, V>>();
+                                    map.len()
+                                })
+                                .contains_key(|ptr, key| unsafe {
+                                    let map = ptr.as_ref::<BTreeMap<K, V>>();
+                                    map.contains_key(key.as_ref())
+                                })
+                                .get_value_ptr(|ptr, key| unsafe {
+                                    let map = ptr.as_ref::<BTreeMap<K, V>>();
+                                    map.get(key.as_ref())
+                                        .map(|v| OpaqueConst::new(v as *const _))
+                                })
+                                .get_value_ptr_mut(|ptr, key| unsafe {
+                                    let map = ptr.as_mut::<BTreeMap<K, V>>();
+                                    map.get_mut(key.as_ref()).map(|v| Opaque::new(v as *mut _))
+                                })
+                                .remove(|ptr, key| unsafe {
+                                    let map = ptr.as_mut::<BTreeMap<K, V>>();
+                                    map.remove(key.as_ref()).is_some()
+                                })
+                                .clear(|ptr| unsafe {
+                                    let map = ptr.as_mut::<BTreeMap<K, V>>();
+                                    map.clear();
+                                })
+                                .iter(|ptr| unsafe {
+                                    let map = ptr.as_ref::<BTreeMap<K, V>>();
+                                    let iter_state = Box::new(map.iter());
+                                    Opaque::new(Box::into_raw(iter_state) as *mut u8)
+                                })
+                                .iter_vtable(
+                                    MapIterVTable::builder()
+                                        .next(|iter_ptr| unsafe {
+                                            let state =
+                                                iter_ptr.as_mut::<BTreeMapIterator<'_, K, V>>();
+                                            state.next().map(|(key, value)| {
+                                                (
+                                                    OpaqueConst::new(key as *const K),
+                                                    OpaqueConst::new(value as *const V),
+                                                )
+                                            })
+                                        })
+                                        .dealloc(|iter_ptr| unsafe {
+                                            drop(Box::from_raw(
+                                                iter_ptr.as_ptr::<BTreeMapIterator<'_, K, V>>()
+                                                    as *mut BTreeMapIterator<'_, K, V>,
+                                            ));
+                                        })
+                                        .build(),
+                                )
+                                .build()
+                        },
+                    )
+                    .build(),
+            ))
+            .build()
+    };
+}
@@ -0,0 +1,164 @@
+use core::{alloc::Layout, hash::Hash, mem::MaybeUninit};
+
+use alloc::boxed::Box;
+use alloc::collections::BTreeSet;
+
+use crate::opaque::{Opaque, OpaqueConst, OpaqueUninit};
+
+use crate::{
+    ConstTypeId, Def, Facet, MarkerTraits, SetDef, SetIterVTable, SetVTable, Shape, ValueVTable,
+};
+
+type BTreeSetIterator<'mem, T> = alloc::collections::btree_set::Iter<'mem, T>;
+
+unsafe impl<'a, T> Facet<'a> for BTreeSet<T>
+where
+    T: Facet<'a> + core::cmp::Eq + core::cmp::Ord + 'static,
+{
+    const SHAPE: &'static Shape = &const {
+        Shape::builder()
+            .id(ConstTypeId::of::<BTreeSet<T>>())
+            .layout(Layout::new::<BTreeSet<T>>())
+            .vtable(
+                &const {
+                    let mut builder = ValueVTable::builder()
+                        .marker_traits({
+                            let mut traits = MarkerTraits::empty();
+                            if T::SHAPE.vtable.marker_traits.contains(MarkerTraits::SEND) {
+                                traits = traits.union(MarkerTraits::SEND);
+                            }
+                            if T::SHAPE.vtable.marker_traits.contains(MarkerTraits::SYNC) {
+                                traits = traits.union(MarkerTraits::SYNC);
+                            }
+                            if T::SHAPE.vtable.marker_traits.contains(MarkerTraits::EQ) {
+                                traits = traits.union(MarkerTraits::EQ);
+                            }
+                            traits
+                        })
+                        .type_name(|f, opts| {
+                            if let Some(opts) = opts.for_children() {
+                                write!(f, "BTreeSet<")?;
+                                (T::SHAPE.vtable.type_name)(f, opts)?;
+                                write!(f, ">")
+                            } else {
+                                write!(f, "BTreeSet<⋯>")
+                            }
+                        })
+                        .drop_in_place(|value| unsafe { value.drop_in_place::<BTreeSet<T>>() })
+                        .default_in_place(|target| unsafe { target.put(Self::default()) });
+
+                    if T::SHAPE.vtable.debug.is_some() {
+                        builder = builder.debug(|value, f| unsafe {
+                            let value = value.as_ref::<BTreeSet<T>>();
+                            let t_debug = T::SHAPE.vtable.debug.unwrap_unchecked();
+                            write!(f, "{{")?;
+                            for (i, item) in value.iter().enumerate() {
+                                if i > 0 {
+                                    write!(f, ", ")?;
+                                }
+                                (t_debug)(OpaqueConst::new(item as *const _), f)?;
+                            }
+                            write!(f, "}}")
+                        });
+                    }
+
+                    if T::SHAPE.vtable.clone_into.is_some() {
+                        builder = builder.clone_into(|src, dst| unsafe {
+                            let src = src.as_ref::<BTreeSet<T>>();
+                            let t_clone = T::SHAPE.vtable.clone_into.unwrap_unchecked();
+                            let mut set = BTreeSet::new();
+                            for item in src {
+                                let mut new_item = MaybeUninit::<T>::uninit();
+                                (t_clone)(
+                                    OpaqueConst::new(item as *const _),
+                                    OpaqueUninit::from_maybe_uninit(&mut new_item),
+                                );
+                                set.insert(new_item.assume_init());
+                            }
+                            dst.put(set)
+                        });
+                    }
+
+                    builder = builder.eq(|a, b| unsafe {
+                        a.as_ref::<BTreeSet<T>>() == b.as_ref::<BTreeSet<T>>()
+                    });
+
+                    builder = builder.ord(|a, b| unsafe {
+                        a.as_ref::<BTreeSet<T>>().cmp(b.as_ref::<BTreeSet<T>>())
+                    });
+
+                    builder = builder.partial_ord(|a, b| unsafe {
+                        Some(a.as_ref::<BTreeSet<T>>().cmp(b.as_ref::<BTreeSet<T>>()))
+                    });
+
+                    if T::SHAPE.vtable.hash.is_some() {
+                        builder = builder.hash(|value, hasher_this, hasher_write_fn| unsafe {
+                            use crate::HasherProxy;
+                            let set = value.as_ref::<BTreeSet<T>>();
+                            let t_hash = T::SHAPE.vtable.hash.unwrap_unchecked();
+                            let mut hasher = HasherProxy::new(hasher_this, hasher_write_fn);
+                            set.len().hash(&mut hasher);
+                            for item in set {
+                                (t_hash)(
+                                    OpaqueConst::new(item as *const _),
+                                    hasher_this,
+                                    hasher_write_fn,
+                                );
+                            }
+                        });
+                    }
+
+                    builder.build()
+                },
+            )
+            .def(Def::Set(
+                SetDef::builder()
+                    .t(T::SHAPE)
+                    .vtable(
+                        &const {
+                            SetVTable::builder()
+                                .init_in_place_with_capacity(|uninit, _capacity| unsafe {
+                                    Ok(uninit.put(Self::new()))
+                                })
+                                .insert(|ptr, value| unsafe {
+                                    let set = ptr.as_mut::<BTreeSet<T>>();
+                                    set.insert(value.read::<T>())
+                                })
+                                .len(|ptr| unsafe {
+                                    let set = ptr.as_ref::<BTreeSet<T>>();
+                                    set.len()
+                                })
+                                .contains(|ptr, value| unsafe {
+                                    let set = ptr.as_ref::<BTreeSet<T>>();
+                                    set.contains(value.as_ref::<T>())
+                                })
+                                .iter(|ptr| unsafe {
+                                    let set = ptr.as_ref::<BTreeSet<T>>();
+                                    let iter_state = Box::new(set.iter());
+                                    Opaque::new(Box::into_raw(iter_state) as *mut u8)
+                                })
+                                .iter_vtable(
+                                    SetIterVTable::builder()
+                                        .next(|iter_ptr| unsafe {
+                                            let state =
+                                                iter_ptr.as_mut::<BTreeSetIterator<'_, T>>();
+                                            state
+                                                .next()
+                                                .map(|item| OpaqueConst::new(item as *const T))
+                                        })
+                                        .dealloc(|iter_ptr| unsafe {
+                                            drop(Box::from_raw(
+                                                iter_ptr.as_ptr::<BTreeSetIterator<'_, T>>()
+                                                    as *mut BTreeSetIterator<'_, T>,
+                                            ));
+                                        })
+                                        .build(),
+                                )
+                                .build()
+                        },
+                    )
+                    .build(),
+            ))
+            .build()
+    };
+}
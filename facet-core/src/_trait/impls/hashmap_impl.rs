@@ -1,9 +1,9 @@
 use alloc::collections::VecDeque;
-use core::{alloc::Layout, hash::Hash};
+use core::{alloc::Layout, hash::Hash, mem::MaybeUninit};
 use std::collections::HashMap;
 use std::hash::RandomState;
 
-use crate::opaque::{Opaque, OpaqueConst};
+use crate::opaque::{Opaque, OpaqueConst, OpaqueUninit};
 
 use crate::{
     ConstTypeId, Def, Facet, MapDef, MapIterVTable, MapVTable, MarkerTraits, ScalarAffinity,
@@ -15,11 +15,11 @@ struct HashMapIterator<'mem, K> {
     keys: VecDeque<&'mem K>,
 }
 
-unsafe impl<K, V, S> Facet for HashMap<K, V, S>
+unsafe impl<'a, K, V, S> Facet<'a> for HashMap<K, V, S>
 where
-    K: Facet + core::cmp::Eq + core::hash::Hash + 'static,
-    V: Facet + 'static,
-    S: Facet + Default,
+    K: Facet<'a> + core::cmp::Eq + core::hash::Hash + 'static,
+    V: Facet<'a> + 'static,
+    S: Facet<'a> + Default,
 {
     const SHAPE: &'static Shape = &const {
         Shape::builder()
@@ -81,8 +81,29 @@ where
                     builder =
                         builder.default_in_place(|target| unsafe { target.put(Self::default()) });
 
-                    builder = builder
-                        .clone_into(|src, dst| unsafe { dst.put(src.as_ref::<HashMap<K, V>>()) });
+                    if K::SHAPE.vtable.clone_into.is_some() && V::SHAPE.vtable.clone_into.is_some()
+                    {
+                        builder = builder.clone_into(|src, dst| unsafe {
+                            let src = src.as_ref::<HashMap<K, V>>();
+                            let k_clone = K::SHAPE.vtable.clone_into.unwrap_unchecked();
+                            let v_clone = V::SHAPE.vtable.clone_into.unwrap_unchecked();
+                            let mut map = HashMap::<K, V>::with_capacity(src.len());
+                            for (k, v) in src {
+                                let mut new_k = MaybeUninit::<K>::uninit();
+                                let mut new_v = MaybeUninit::<V>::uninit();
+                                (k_clone)(
+                                    OpaqueConst::new(k as *const _),
+                                    OpaqueUninit::from_maybe_uninit(&mut new_k),
+                                );
+                                (v_clone)(
+                                    OpaqueConst::new(v as *const _),
+                                    OpaqueUninit::from_maybe_uninit(&mut new_v),
+                                );
+                                map.insert(new_k.assume_init(), new_v.assume_init());
+                            }
+                            dst.put(map)
+                        });
+                    }
 
                     if K::SHAPE.vtable.eq.is_some() && V::SHAPE.vtable.eq.is_some() {
                         builder = builder.eq(|a, b| unsafe {
@@ -163,6 +184,18 @@ where
                                     map.get(key.as_ref())
                                         .map(|v| OpaqueConst::new(v as *const _))
                                 })
+                                .get_value_ptr_mut(|ptr, key| unsafe {
+                                    let map = ptr.as_mut::<HashMap<K, V>>();
+                                    map.get_mut(key.as_ref()).map(|v| Opaque::new(v as *mut _))
+                                })
+                                .remove(|ptr, key| unsafe {
+                                    let map = ptr.as_mut::<HashMap<K, V>>();
+                                    map.remove(key.as_ref()).is_some()
+                                })
+                                .clear(|ptr| unsafe {
+                                    let map = ptr.as_mut::<HashMap<K, V>>();
+                                    map.clear();
+                                })
                                 .iter(|ptr| unsafe {
                                     let map = ptr.as_ref::<HashMap<K, V>>();
                                     let keys: VecDeque<&K> = map.keys().collect();
@@ -202,7 +235,7 @@ where
     };
 }
 
-unsafe impl Facet for RandomState {
+unsafe impl Facet<'_> for RandomState {
     const SHAPE: &'static Shape = &const {
         Shape::builder()
             .id(ConstTypeId::of::<RandomState>())
@@ -0,0 +1,145 @@
+use core::hash::BuildHasher;
+use core::{alloc::Layout, mem::MaybeUninit};
+use std::collections::HashSet;
+
+use crate::opaque::{Opaque, OpaqueConst, OpaqueUninit};
+
+use crate::{
+    ConstTypeId, Def, Facet, MarkerTraits, SetDef, SetIterVTable, SetVTable, Shape, ValueVTable,
+};
+
+type HashSetIterator<'mem, T> = std::collections::hash_set::Iter<'mem, T>;
+
+unsafe impl<'a, T, S> Facet<'a> for HashSet<T, S>
+where
+    T: Facet<'a> + core::cmp::Eq + core::hash::Hash + 'static,
+    S: Facet<'a> + Default + BuildHasher,
+{
+    const SHAPE: &'static Shape = &const {
+        Shape::builder()
+            .id(ConstTypeId::of::<HashSet<T, S>>())
+            .layout(Layout::new::<HashSet<T, S>>())
+            .vtable(
+                &const {
+                    let mut builder = ValueVTable::builder()
+                        .marker_traits({
+                            let mut traits = MarkerTraits::empty();
+                            if T::SHAPE.vtable.marker_traits.contains(MarkerTraits::SEND) {
+                                traits = traits.union(MarkerTraits::SEND);
+                            }
+                            if T::SHAPE.vtable.marker_traits.contains(MarkerTraits::SYNC) {
+                                traits = traits.union(MarkerTraits::SYNC);
+                            }
+                            if T::SHAPE.vtable.marker_traits.contains(MarkerTraits::EQ) {
+                                traits = traits.union(MarkerTraits::EQ);
+                            }
+                            traits
+                        })
+                        .type_name(|f, opts| {
+                            if let Some(opts) = opts.for_children() {
+                                write!(f, "HashSet<")?;
+                                (T::SHAPE.vtable.type_name)(f, opts)?;
+                                write!(f, ">")
+                            } else {
+                                write!(f, "HashSet<⋯>")
+                            }
+                        })
+                        .drop_in_place(|value| unsafe { value.drop_in_place::<HashSet<T, S>>() })
+                        .default_in_place(|target| unsafe { target.put(Self::default()) });
+
+                    if T::SHAPE.vtable.debug.is_some() {
+                        builder = builder.debug(|value, f| unsafe {
+                            let value = value.as_ref::<HashSet<T, S>>();
+                            let t_debug = T::SHAPE.vtable.debug.unwrap_unchecked();
+                            write!(f, "{{")?;
+                            for (i, item) in value.iter().enumerate() {
+                                if i > 0 {
+                                    write!(f, ", ")?;
+                                }
+                                (t_debug)(OpaqueConst::new(item as *const _), f)?;
+                            }
+                            write!(f, "}}")
+                        });
+                    }
+
+                    if T::SHAPE.vtable.clone_into.is_some() {
+                        builder = builder.clone_into(|src, dst| unsafe {
+                            let src = src.as_ref::<HashSet<T, S>>();
+                            let t_clone = T::SHAPE.vtable.clone_into.unwrap_unchecked();
+                            let mut set =
+                                HashSet::with_capacity_and_hasher(src.len(), S::default());
+                            for item in src {
+                                let mut new_item = MaybeUninit::<T>::uninit();
+                                (t_clone)(
+                                    OpaqueConst::new(item as *const _),
+                                    OpaqueUninit::from_maybe_uninit(&mut new_item),
+                                );
+                                set.insert(new_item.assume_init());
+                            }
+                            dst.put(set)
+                        });
+                    }
+
+                    // Set equality only relies on `T: Eq + Hash`, which is required anyway
+                    builder = builder.eq(|a, b| unsafe {
+                        let a = a.as_ref::<HashSet<T, S>>();
+                        let b = b.as_ref::<HashSet<T, S>>();
+                        a.len() == b.len() && a.iter().all(|item| b.contains(item))
+                    });
+
+                    builder.build()
+                },
+            )
+            .def(Def::Set(
+                SetDef::builder()
+                    .t(T::SHAPE)
+                    .vtable(
+                        &const {
+                            SetVTable::builder()
+                                .init_in_place_with_capacity(|uninit, capacity| unsafe {
+                                    Ok(uninit.put(Self::with_capacity_and_hasher(
+                                        capacity,
+                                        S::default(),
+                                    )))
+                                })
+                                .insert(|ptr, value| unsafe {
+                                    let set = ptr.as_mut::<HashSet<T, S>>();
+                                    set.insert(value.read::<T>())
+                                })
+                                .len(|ptr| unsafe {
+                                    let set = ptr.as_ref::<HashSet<T, S>>();
+                                    set.len()
+                                })
+                                .contains(|ptr, value| unsafe {
+                                    let set = ptr.as_ref::<HashSet<T, S>>();
+                                    set.contains(value.as_ref::<T>())
+                                })
+                                .iter(|ptr| unsafe {
+                                    let set = ptr.as_ref::<HashSet<T, S>>();
+                                    let iter_state = Box::new(set.iter());
+                                    Opaque::new(Box::into_raw(iter_state) as *mut u8)
+                                })
+                                .iter_vtable(
+                                    SetIterVTable::builder()
+                                        .next(|iter_ptr| unsafe {
+                                            let state = iter_ptr.as_mut::<HashSetIterator<'_, T>>();
+                                            state
+                                                .next()
+                                                .map(|item| OpaqueConst::new(item as *const T))
+                                        })
+                                        .dealloc(|iter_ptr| unsafe {
+                                            drop(Box::from_raw(
+                                                iter_ptr.as_ptr::<HashSetIterator<'_, T>>()
+                                                    as *mut HashSetIterator<'_, T>,
+                                            ));
+                                        })
+                                        .build(),
+                                )
+                                .build()
+                        },
+                    )
+                    .build(),
+            ))
+            .build()
+    };
+}
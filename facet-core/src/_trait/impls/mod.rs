@@ -1,8 +1,18 @@
 mod array_impl;
+#[cfg(feature = "alloc")]
+mod btreemap_impl;
+#[cfg(feature = "alloc")]
+mod btreeset_impl;
 #[cfg(feature = "std")]
 mod hashmap_impl;
+#[cfg(feature = "std")]
+mod hashset_impl;
 mod scalar_impls;
 mod slice_impl;
+#[cfg(feature = "alloc")]
+mod smartptr_impls;
 mod tuples_impls;
 #[cfg(feature = "alloc")]
 mod vec_impl;
+#[cfg(feature = "alloc")]
+mod vecdeque_impl;
@@ -5,7 +5,7 @@ use core::num::NonZero;
 use core::option::Option;
 use typeid::ConstTypeId;
 
-unsafe impl Facet for ConstTypeId {
+unsafe impl Facet<'_> for ConstTypeId {
     const SHAPE: &'static Shape = &const {
         Shape::builder()
             .id(ConstTypeId::of::<ConstTypeId>())
@@ -20,7 +20,7 @@ unsafe impl Facet for ConstTypeId {
     };
 }
 
-unsafe impl Facet for () {
+unsafe impl Facet<'_> for () {
     const SHAPE: &'static Shape = &const {
         Shape::builder()
             .id(ConstTypeId::of::<()>())
@@ -35,7 +35,7 @@ unsafe impl Facet for () {
     };
 }
 
-unsafe impl<T: ?Sized> Facet for core::marker::PhantomData<T> {
+unsafe impl<T: ?Sized> Facet<'_> for core::marker::PhantomData<T> {
     const SHAPE: &'static Shape = &const {
         Shape::builder()
             .id(ConstTypeId::of::<Self>())
@@ -51,7 +51,7 @@ unsafe impl<T: ?Sized> Facet for core::marker::PhantomData<T> {
 }
 
 #[cfg(feature = "alloc")]
-unsafe impl Facet for alloc::string::String {
+unsafe impl Facet<'_> for alloc::string::String {
     const SHAPE: &'static Shape = &const {
         Shape::builder()
             .id(ConstTypeId::of::<alloc::string::String>())
@@ -70,7 +70,7 @@ unsafe impl Facet for alloc::string::String {
     };
 }
 
-unsafe impl Facet for char {
+unsafe impl Facet<'_> for char {
     const SHAPE: &'static Shape = &const {
         Shape::builder()
             .id(ConstTypeId::of::<char>())
@@ -85,7 +85,7 @@ unsafe impl Facet for char {
     };
 }
 
-unsafe impl Facet for &str {
+unsafe impl<'a: 'b, 'b> Facet<'a> for &'b str {
     const SHAPE: &'static Shape = &const {
         Shape::builder()
             .id(ConstTypeId::of::<&str>())
@@ -101,7 +101,7 @@ unsafe impl Facet for &str {
 }
 
 #[cfg(feature = "alloc")]
-unsafe impl Facet for alloc::borrow::Cow<'_, str> {
+unsafe impl<'a: 'b, 'b> Facet<'a> for alloc::borrow::Cow<'b, str> {
     const SHAPE: &'static Shape = &const {
         Shape::builder()
             .id(ConstTypeId::of::<alloc::borrow::Cow<'_, str>>())
@@ -119,7 +119,7 @@ unsafe impl Facet for alloc::borrow::Cow<'_, str> {
     };
 }
 
-unsafe impl Facet for bool {
+unsafe impl Facet<'_> for bool {
     const SHAPE: &'static Shape = &const {
         Shape::builder()
             .id(ConstTypeId::of::<bool>())
@@ -136,7 +136,7 @@ unsafe impl Facet for bool {
 
 macro_rules! impl_facet_for_integer {
     ($type:ty, $affinity:expr, $nz_affinity:expr) => {
-        unsafe impl Facet for $type {
+        unsafe impl Facet<'_> for $type {
             const SHAPE: &'static Shape = &const {
                 Shape::builder()
                     .id(ConstTypeId::of::<Self>())
@@ -152,7 +152,7 @@ macro_rules! impl_facet_for_integer {
             };
         }
 
-        unsafe impl Facet for NonZero<$type> {
+        unsafe impl Facet<'_> for NonZero<$type> {
             const SHAPE: &'static Shape = &const {
                 Shape::builder()
                     .id(ConstTypeId::of::<Self>())
@@ -417,7 +417,7 @@ static NAN_F64: f64 = f64::NAN;
 static POSITIVE_ZERO_F64: f64 = 0.0f64;
 static NEGATIVE_ZERO_F64: f64 = -0.0f64;
 
-unsafe impl Facet for f32 {
+unsafe impl Facet<'_> for f32 {
     const SHAPE: &'static Shape = &const {
         Shape::builder()
             .id(ConstTypeId::of::<f32>())
@@ -443,7 +443,7 @@ unsafe impl Facet for f32 {
     };
 }
 
-unsafe impl Facet for f64 {
+unsafe impl Facet<'_> for f64 {
     const SHAPE: &'static Shape = &const {
         Shape::builder()
             .id(ConstTypeId::of::<f64>())
@@ -469,7 +469,7 @@ unsafe impl Facet for f64 {
     };
 }
 
-unsafe impl Facet for core::net::SocketAddr {
+unsafe impl Facet<'_> for core::net::SocketAddr {
     const SHAPE: &'static Shape = &const {
         Shape::builder()
             .id(ConstTypeId::of::<Self>())
@@ -487,7 +487,7 @@ unsafe impl Facet for core::net::SocketAddr {
     };
 }
 
-unsafe impl Facet for core::net::IpAddr {
+unsafe impl Facet<'_> for core::net::IpAddr {
     const SHAPE: &'static Shape = &const {
         Shape::builder()
             .id(ConstTypeId::of::<Self>())
@@ -505,7 +505,7 @@ unsafe impl Facet for core::net::IpAddr {
     };
 }
 
-unsafe impl Facet for core::net::Ipv4Addr {
+unsafe impl Facet<'_> for core::net::Ipv4Addr {
     const SHAPE: &'static Shape = &const {
         Shape::builder()
             .id(ConstTypeId::of::<Self>())
@@ -523,7 +523,7 @@ unsafe impl Facet for core::net::Ipv4Addr {
     };
 }
 
-unsafe impl Facet for core::net::Ipv6Addr {
+unsafe impl Facet<'_> for core::net::Ipv6Addr {
     const SHAPE: &'static Shape = &const {
         Shape::builder()
             .id(ConstTypeId::of::<Self>())
@@ -541,7 +541,26 @@ unsafe impl Facet for core::net::Ipv6Addr {
     };
 }
 
-unsafe impl<T: Facet> Facet for Option<T> {
+#[cfg(feature = "std")]
+unsafe impl Facet<'_> for std::time::SystemTime {
+    const SHAPE: &'static Shape = &const {
+        Shape::builder()
+            .id(ConstTypeId::of::<Self>())
+            .layout(Layout::new::<Self>())
+            .def(Def::Scalar(
+                ScalarDef::builder()
+                    .affinity(ScalarAffinity::other().build())
+                    .build(),
+            ))
+            .vtable(value_vtable!(std::time::SystemTime, |f, _opts| write!(
+                f,
+                "SystemTime"
+            )))
+            .build()
+    };
+}
+
+unsafe impl<'a, T: Facet<'a>> Facet<'a> for Option<T> {
     const SHAPE: &'static Shape = &const {
         Shape::builder()
             .id(ConstTypeId::of::<Self>())
@@ -1,9 +1,9 @@
 use crate::*;
 use core::alloc::Layout;
 
-unsafe impl<T> Facet for &[T]
+unsafe impl<'a: 'b, 'b, T> Facet<'a> for &'b [T]
 where
-    T: Facet,
+    T: Facet<'a>,
 {
     const SHAPE: &'static Shape = &const {
         Shape::builder()
@@ -0,0 +1,290 @@
+use core::alloc::Layout;
+
+use alloc::boxed::Box;
+use alloc::rc::{self, Rc};
+use alloc::sync::{self, Arc};
+
+use crate::opaque::{OpaqueConst, OpaqueUninit};
+use crate::{
+    ConstTypeId, Def, Facet, KnownSmartPointer, MarkerTraits, Shape, SmartPointerDef,
+    SmartPointerFlags, SmartPointerVTable, ValueVTable,
+};
+
+/// Forwards the pointee's display, debug, comparison and hashing functions
+/// through a strong smart pointer `$ptr<T>`.
+macro_rules! forward_to_pointee {
+    ($builder:ident, $ptr:ident) => {{
+        if T::SHAPE.vtable.display.is_some() {
+            $builder = $builder.display(|value, f| unsafe {
+                let value = value.as_ref::<$ptr<T>>();
+                (T::SHAPE.vtable.display.unwrap_unchecked())(OpaqueConst::new(&**value), f)
+            });
+        }
+
+        if T::SHAPE.vtable.debug.is_some() {
+            $builder = $builder.debug(|value, f| unsafe {
+                let value = value.as_ref::<$ptr<T>>();
+                (T::SHAPE.vtable.debug.unwrap_unchecked())(OpaqueConst::new(&**value), f)
+            });
+        }
+
+        if T::SHAPE.vtable.eq.is_some() {
+            $builder = $builder.eq(|a, b| unsafe {
+                let a = a.as_ref::<$ptr<T>>();
+                let b = b.as_ref::<$ptr<T>>();
+                (T::SHAPE.vtable.eq.unwrap_unchecked())(
+                    OpaqueConst::new(&**a),
+                    OpaqueConst::new(&**b),
+                )
+            });
+        }
+
+        if T::SHAPE.vtable.partial_ord.is_some() {
+            $builder = $builder.partial_ord(|a, b| unsafe {
+                let a = a.as_ref::<$ptr<T>>();
+                let b = b.as_ref::<$ptr<T>>();
+                (T::SHAPE.vtable.partial_ord.unwrap_unchecked())(
+                    OpaqueConst::new(&**a),
+                    OpaqueConst::new(&**b),
+                )
+            });
+        }
+
+        if T::SHAPE.vtable.ord.is_some() {
+            $builder = $builder.ord(|a, b| unsafe {
+                let a = a.as_ref::<$ptr<T>>();
+                let b = b.as_ref::<$ptr<T>>();
+                (T::SHAPE.vtable.ord.unwrap_unchecked())(
+                    OpaqueConst::new(&**a),
+                    OpaqueConst::new(&**b),
+                )
+            });
+        }
+
+        if T::SHAPE.vtable.hash.is_some() {
+            $builder = $builder.hash(|value, hasher_this, hasher_write_fn| unsafe {
+                let value = value.as_ref::<$ptr<T>>();
+                (T::SHAPE.vtable.hash.unwrap_unchecked())(
+                    OpaqueConst::new(&**value),
+                    hasher_this,
+                    hasher_write_fn,
+                )
+            });
+        }
+    }};
+}
+
+unsafe impl<'a, T: Facet<'a>> Facet<'a> for Box<T> {
+    const SHAPE: &'static Shape = &const {
+        Shape::builder()
+            .id(ConstTypeId::of::<Box<T>>())
+            .layout(Layout::new::<Box<T>>())
+            .vtable(
+                &const {
+                    let mut builder = ValueVTable::builder()
+                        .type_name(|f, opts| {
+                            if let Some(opts) = opts.for_children() {
+                                write!(f, "Box<")?;
+                                (T::SHAPE.vtable.type_name)(f, opts)?;
+                                write!(f, ">")
+                            } else {
+                                write!(f, "Box<⋯>")
+                            }
+                        })
+                        .drop_in_place(|value| unsafe { value.drop_in_place::<Box<T>>() });
+
+                    if T::SHAPE.vtable.default_in_place.is_some() {
+                        builder = builder.default_in_place(|target| unsafe {
+                            let mut boxed = Box::<T>::new_uninit();
+                            (T::SHAPE.vtable.default_in_place.unwrap_unchecked())(
+                                OpaqueUninit::new(boxed.as_mut_ptr()),
+                            );
+                            target.put(boxed.assume_init())
+                        });
+                    }
+
+                    if T::SHAPE.vtable.clone_into.is_some() {
+                        builder = builder.clone_into(|src, dst| unsafe {
+                            let src = src.as_ref::<Box<T>>();
+                            let mut boxed = Box::<T>::new_uninit();
+                            (T::SHAPE.vtable.clone_into.unwrap_unchecked())(
+                                OpaqueConst::new(&**src),
+                                OpaqueUninit::new(boxed.as_mut_ptr()),
+                            );
+                            dst.put(boxed.assume_init())
+                        });
+                    }
+
+                    forward_to_pointee!(builder, Box);
+
+                    let mut traits = MarkerTraits::empty();
+                    if T::SHAPE.vtable.marker_traits.contains(MarkerTraits::SEND) {
+                        traits = traits.union(MarkerTraits::SEND);
+                    }
+                    if T::SHAPE.vtable.marker_traits.contains(MarkerTraits::SYNC) {
+                        traits = traits.union(MarkerTraits::SYNC);
+                    }
+                    if T::SHAPE.vtable.marker_traits.contains(MarkerTraits::EQ) {
+                        traits = traits.union(MarkerTraits::EQ);
+                    }
+                    builder = builder.marker_traits(traits);
+
+                    builder.build()
+                },
+            )
+            .def(Def::SmartPointer(
+                SmartPointerDef::builder()
+                    .vtable(
+                        &const {
+                            SmartPointerVTable::builder()
+                                .borrow(|this| unsafe {
+                                    OpaqueConst::new(&**this.as_ref::<Box<T>>())
+                                })
+                                .new_into(|this, value| unsafe {
+                                    this.put(Box::new(value.read::<T>()))
+                                })
+                                .build()
+                        },
+                    )
+                    .t(T::SHAPE)
+                    .known(KnownSmartPointer::Box)
+                    .build(),
+            ))
+            .build()
+    };
+}
+
+/// Implements `Facet` for a reference-counted pointer (`Rc` or `Arc`) and its
+/// `Weak` counterpart.
+macro_rules! impl_facet_for_refcounted {
+    ($ptr:ident, $weak_mod:ident, $known:ident, $known_weak:ident, $flags:expr) => {
+        unsafe impl<'a, T: Facet<'a>> Facet<'a> for $ptr<T> {
+            const SHAPE: &'static Shape = &const {
+                Shape::builder()
+                    .id(ConstTypeId::of::<$ptr<T>>())
+                    .layout(Layout::new::<$ptr<T>>())
+                    .vtable(
+                        &const {
+                            let mut builder = ValueVTable::builder()
+                                .type_name(|f, opts| {
+                                    if let Some(opts) = opts.for_children() {
+                                        write!(f, concat!(stringify!($ptr), "<"))?;
+                                        (T::SHAPE.vtable.type_name)(f, opts)?;
+                                        write!(f, ">")
+                                    } else {
+                                        write!(f, concat!(stringify!($ptr), "<⋯>"))
+                                    }
+                                })
+                                .drop_in_place(|value| unsafe { value.drop_in_place::<$ptr<T>>() })
+                                .clone_into(|src, dst| unsafe {
+                                    dst.put($ptr::clone(src.as_ref::<$ptr<T>>()))
+                                });
+
+                            if T::SHAPE.vtable.default_in_place.is_some() {
+                                builder = builder.default_in_place(|target| unsafe {
+                                    let mut ptr = $ptr::<T>::new_uninit();
+                                    (T::SHAPE.vtable.default_in_place.unwrap_unchecked())(
+                                        OpaqueUninit::new(
+                                            $ptr::get_mut(&mut ptr).unwrap().as_mut_ptr(),
+                                        ),
+                                    );
+                                    target.put(ptr.assume_init())
+                                });
+                            }
+
+                            forward_to_pointee!(builder, $ptr);
+
+                            let mut traits = refcounted_marker_traits(T::SHAPE, $flags);
+                            if T::SHAPE.vtable.marker_traits.contains(MarkerTraits::EQ) {
+                                traits = traits.union(MarkerTraits::EQ);
+                            }
+                            builder = builder.marker_traits(traits);
+
+                            builder.build()
+                        },
+                    )
+                    .def(Def::SmartPointer(
+                        SmartPointerDef::builder()
+                            .vtable(
+                                &const {
+                                    SmartPointerVTable::builder()
+                                        .borrow(|this| unsafe {
+                                            OpaqueConst::new(&**this.as_ref::<$ptr<T>>())
+                                        })
+                                        .new_into(|this, value| unsafe {
+                                            this.put($ptr::new(value.read::<T>()))
+                                        })
+                                        .build()
+                                },
+                            )
+                            .t(T::SHAPE)
+                            .flags($flags)
+                            .known(KnownSmartPointer::$known)
+                            .build(),
+                    ))
+                    .build()
+            };
+        }
+
+        unsafe impl<'a, T: Facet<'a>> Facet<'a> for $weak_mod::Weak<T> {
+            const SHAPE: &'static Shape = &const {
+                Shape::builder()
+                    .id(ConstTypeId::of::<$weak_mod::Weak<T>>())
+                    .layout(Layout::new::<$weak_mod::Weak<T>>())
+                    .vtable(
+                        &const {
+                            ValueVTable::builder()
+                                .type_name(|f, opts| {
+                                    if let Some(opts) = opts.for_children() {
+                                        write!(f, "Weak<")?;
+                                        (T::SHAPE.vtable.type_name)(f, opts)?;
+                                        write!(f, ">")
+                                    } else {
+                                        write!(f, "Weak<⋯>")
+                                    }
+                                })
+                                .drop_in_place(|value| unsafe {
+                                    value.drop_in_place::<$weak_mod::Weak<T>>()
+                                })
+                                .default_in_place(|target| unsafe {
+                                    target.put($weak_mod::Weak::<T>::new())
+                                })
+                                .clone_into(|src, dst| unsafe {
+                                    dst.put($weak_mod::Weak::clone(
+                                        src.as_ref::<$weak_mod::Weak<T>>(),
+                                    ))
+                                })
+                                .debug(|_value, f| write!(f, "(Weak)"))
+                                .marker_traits(refcounted_marker_traits(T::SHAPE, $flags))
+                                .build()
+                        },
+                    )
+                    .def(Def::SmartPointer(
+                        SmartPointerDef::builder()
+                            .vtable(&const { SmartPointerVTable::builder().build() })
+                            .t(T::SHAPE)
+                            .flags($flags.union(SmartPointerFlags::WEAK))
+                            .known(KnownSmartPointer::$known_weak)
+                            .build(),
+                    ))
+                    .build()
+            };
+        }
+    };
+}
+
+/// `Rc` and `rc::Weak` are never `Send` nor `Sync`; `Arc` and `sync::Weak` are
+/// both as long as the pointee is `Send + Sync`.
+const fn refcounted_marker_traits(t: &'static Shape, flags: SmartPointerFlags) -> MarkerTraits {
+    if flags.contains(SmartPointerFlags::ATOMIC)
+        && t.vtable.marker_traits.contains(MarkerTraits::SEND)
+        && t.vtable.marker_traits.contains(MarkerTraits::SYNC)
+    {
+        MarkerTraits::SEND.union(MarkerTraits::SYNC)
+    } else {
+        MarkerTraits::empty()
+    }
+}
+
+impl_facet_for_refcounted!(Rc, rc, Rc, RcWeak, SmartPointerFlags::EMPTY);
+impl_facet_for_refcounted!(Arc, sync, Arc, ArcWeak, SmartPointerFlags::ATOMIC);
@@ -45,14 +45,14 @@ macro_rules! field {
     };
 }
 
-unsafe impl<T0> Facet for (T0,)
+unsafe impl<'a, T0> Facet<'a> for (T0,)
 where
-    T0: Facet,
+    T0: Facet<'a>,
 {
     const SHAPE: &'static Shape = &const {
-        fn type_name<T0>(f: &mut fmt::Formatter, opts: TypeNameOpts) -> fmt::Result
+        fn type_name<'a, T0>(f: &mut fmt::Formatter, opts: TypeNameOpts) -> fmt::Result
         where
-            T0: Facet,
+            T0: Facet<'a>,
         {
             write_type_name_list(f, opts, "(", ", ", ")", &[T0::SHAPE])
         }
@@ -106,16 +106,16 @@ where
             .build()
     };
 }
-unsafe impl<T0, T1> Facet for (T0, T1)
+unsafe impl<'a, T0, T1> Facet<'a> for (T0, T1)
 where
-    T0: Facet,
-    T1: Facet,
+    T0: Facet<'a>,
+    T1: Facet<'a>,
 {
     const SHAPE: &'static Shape = &const {
-        fn type_name<T0, T1>(f: &mut fmt::Formatter, opts: TypeNameOpts) -> fmt::Result
+        fn type_name<'a, T0, T1>(f: &mut fmt::Formatter, opts: TypeNameOpts) -> fmt::Result
         where
-            T0: Facet,
-            T1: Facet,
+            T0: Facet<'a>,
+            T1: Facet<'a>,
         {
             write_type_name_list(f, opts, "(", ", ", ")", &[T0::SHAPE, T1::SHAPE])
         }
@@ -189,18 +189,18 @@ where
             .build()
     };
 }
-unsafe impl<T0, T1, T2> Facet for (T0, T1, T2)
+unsafe impl<'a, T0, T1, T2> Facet<'a> for (T0, T1, T2)
 where
-    T0: Facet,
-    T1: Facet,
-    T2: Facet,
+    T0: Facet<'a>,
+    T1: Facet<'a>,
+    T2: Facet<'a>,
 {
     const SHAPE: &'static Shape = &const {
-        fn type_name<T0, T1, T2>(f: &mut fmt::Formatter, opts: TypeNameOpts) -> fmt::Result
+        fn type_name<'a, T0, T1, T2>(f: &mut fmt::Formatter, opts: TypeNameOpts) -> fmt::Result
         where
-            T0: Facet,
-            T1: Facet,
-            T2: Facet,
+            T0: Facet<'a>,
+            T1: Facet<'a>,
+            T2: Facet<'a>,
         {
             write_type_name_list(f, opts, "(", ", ", ")", &[T0::SHAPE, T1::SHAPE, T2::SHAPE])
         }
@@ -302,20 +302,20 @@ where
             .build()
     };
 }
-unsafe impl<T0, T1, T2, T3> Facet for (T0, T1, T2, T3)
+unsafe impl<'a, T0, T1, T2, T3> Facet<'a> for (T0, T1, T2, T3)
 where
-    T0: Facet,
-    T1: Facet,
-    T2: Facet,
-    T3: Facet,
+    T0: Facet<'a>,
+    T1: Facet<'a>,
+    T2: Facet<'a>,
+    T3: Facet<'a>,
 {
     const SHAPE: &'static Shape = &const {
-        fn type_name<T0, T1, T2, T3>(f: &mut fmt::Formatter, opts: TypeNameOpts) -> fmt::Result
+        fn type_name<'a, T0, T1, T2, T3>(f: &mut fmt::Formatter, opts: TypeNameOpts) -> fmt::Result
         where
-            T0: Facet,
-            T1: Facet,
-            T2: Facet,
-            T3: Facet,
+            T0: Facet<'a>,
+            T1: Facet<'a>,
+            T2: Facet<'a>,
+            T3: Facet<'a>,
         {
             write_type_name_list(
                 f,
@@ -445,22 +445,25 @@ where
             .build()
     };
 }
-unsafe impl<T0, T1, T2, T3, T4> Facet for (T0, T1, T2, T3, T4)
+unsafe impl<'a, T0, T1, T2, T3, T4> Facet<'a> for (T0, T1, T2, T3, T4)
 where
-    T0: Facet,
-    T1: Facet,
-    T2: Facet,
-    T3: Facet,
-    T4: Facet,
+    T0: Facet<'a>,
+    T1: Facet<'a>,
+    T2: Facet<'a>,
+    T3: Facet<'a>,
+    T4: Facet<'a>,
 {
     const SHAPE: &'static Shape = &const {
-        fn type_name<T0, T1, T2, T3, T4>(f: &mut fmt::Formatter, opts: TypeNameOpts) -> fmt::Result
+        fn type_name<'a, T0, T1, T2, T3, T4>(
+            f: &mut fmt::Formatter,
+            opts: TypeNameOpts,
+        ) -> fmt::Result
         where
-            T0: Facet,
-            T1: Facet,
-            T2: Facet,
-            T3: Facet,
-            T4: Facet,
+            T0: Facet<'a>,
+            T1: Facet<'a>,
+            T2: Facet<'a>,
+            T3: Facet<'a>,
+            T4: Facet<'a>,
         {
             write_type_name_list(
                 f,
@@ -617,27 +620,27 @@ where
             .build()
     };
 }
-unsafe impl<T0, T1, T2, T3, T4, T5> Facet for (T0, T1, T2, T3, T4, T5)
+unsafe impl<'a, T0, T1, T2, T3, T4, T5> Facet<'a> for (T0, T1, T2, T3, T4, T5)
 where
-    T0: Facet,
-    T1: Facet,
-    T2: Facet,
-    T3: Facet,
-    T4: Facet,
-    T5: Facet,
+    T0: Facet<'a>,
+    T1: Facet<'a>,
+    T2: Facet<'a>,
+    T3: Facet<'a>,
+    T4: Facet<'a>,
+    T5: Facet<'a>,
 {
     const SHAPE: &'static Shape = &const {
-        fn type_name<T0, T1, T2, T3, T4, T5>(
+        fn type_name<'a, T0, T1, T2, T3, T4, T5>(
             f: &mut fmt::Formatter,
             opts: TypeNameOpts,
         ) -> fmt::Result
         where
-            T0: Facet,
-            T1: Facet,
-            T2: Facet,
-            T3: Facet,
-            T4: Facet,
-            T5: Facet,
+            T0: Facet<'a>,
+            T1: Facet<'a>,
+            T2: Facet<'a>,
+            T3: Facet<'a>,
+            T4: Facet<'a>,
+            T5: Facet<'a>,
         {
             write_type_name_list(
                 f,
@@ -823,29 +826,29 @@ where
             .build()
     };
 }
-unsafe impl<T0, T1, T2, T3, T4, T5, T6> Facet for (T0, T1, T2, T3, T4, T5, T6)
+unsafe impl<'a, T0, T1, T2, T3, T4, T5, T6> Facet<'a> for (T0, T1, T2, T3, T4, T5, T6)
 where
-    T0: Facet,
-    T1: Facet,
-    T2: Facet,
-    T3: Facet,
-    T4: Facet,
-    T5: Facet,
-    T6: Facet,
+    T0: Facet<'a>,
+    T1: Facet<'a>,
+    T2: Facet<'a>,
+    T3: Facet<'a>,
+    T4: Facet<'a>,
+    T5: Facet<'a>,
+    T6: Facet<'a>,
 {
     const SHAPE: &'static Shape = &const {
-        fn type_name<T0, T1, T2, T3, T4, T5, T6>(
+        fn type_name<'a, T0, T1, T2, T3, T4, T5, T6>(
             f: &mut fmt::Formatter,
             opts: TypeNameOpts,
         ) -> fmt::Result
         where
-            T0: Facet,
-            T1: Facet,
-            T2: Facet,
-            T3: Facet,
-            T4: Facet,
-            T5: Facet,
-            T6: Facet,
+            T0: Facet<'a>,
+            T1: Facet<'a>,
+            T2: Facet<'a>,
+            T3: Facet<'a>,
+            T4: Facet<'a>,
+            T5: Facet<'a>,
+            T6: Facet<'a>,
         {
             write_type_name_list(
                 f,
@@ -1054,31 +1057,31 @@ where
             .build()
     };
 }
-unsafe impl<T0, T1, T2, T3, T4, T5, T6, T7> Facet for (T0, T1, T2, T3, T4, T5, T6, T7)
+unsafe impl<'a, T0, T1, T2, T3, T4, T5, T6, T7> Facet<'a> for (T0, T1, T2, T3, T4, T5, T6, T7)
 where
-    T0: Facet,
-    T1: Facet,
-    T2: Facet,
-    T3: Facet,
-    T4: Facet,
-    T5: Facet,
-    T6: Facet,
-    T7: Facet,
+    T0: Facet<'a>,
+    T1: Facet<'a>,
+    T2: Facet<'a>,
+    T3: Facet<'a>,
+    T4: Facet<'a>,
+    T5: Facet<'a>,
+    T6: Facet<'a>,
+    T7: Facet<'a>,
 {
     const SHAPE: &'static Shape = &const {
-        fn type_name<T0, T1, T2, T3, T4, T5, T6, T7>(
+        fn type_name<'a, T0, T1, T2, T3, T4, T5, T6, T7>(
             f: &mut fmt::Formatter,
             opts: TypeNameOpts,
         ) -> fmt::Result
         where
-            T0: Facet,
-            T1: Facet,
-            T2: Facet,
-            T3: Facet,
-            T4: Facet,
-            T5: Facet,
-            T6: Facet,
-            T7: Facet,
+            T0: Facet<'a>,
+            T1: Facet<'a>,
+            T2: Facet<'a>,
+            T3: Facet<'a>,
+            T4: Facet<'a>,
+            T5: Facet<'a>,
+            T6: Facet<'a>,
+            T7: Facet<'a>,
         {
             write_type_name_list(
                 f,
@@ -1311,33 +1314,34 @@ where
             .build()
     };
 }
-unsafe impl<T0, T1, T2, T3, T4, T5, T6, T7, T8> Facet for (T0, T1, T2, T3, T4, T5, T6, T7, T8)
+unsafe impl<'a, T0, T1, T2, T3, T4, T5, T6, T7, T8> Facet<'a>
+    for (T0, T1, T2, T3, T4, T5, T6, T7, T8)
 where
-    T0: Facet,
-    T1: Facet,
-    T2: Facet,
-    T3: Facet,
-    T4: Facet,
-    T5: Facet,
-    T6: Facet,
-    T7: Facet,
-    T8: Facet,
+    T0: Facet<'a>,
+    T1: Facet<'a>,
+    T2: Facet<'a>,
+    T3: Facet<'a>,
+    T4: Facet<'a>,
+    T5: Facet<'a>,
+    T6: Facet<'a>,
+    T7: Facet<'a>,
+    T8: Facet<'a>,
 {
     const SHAPE: &'static Shape = &const {
-        fn type_name<T0, T1, T2, T3, T4, T5, T6, T7, T8>(
+        fn type_name<'a, T0, T1, T2, T3, T4, T5, T6, T7, T8>(
             f: &mut fmt::Formatter,
             opts: TypeNameOpts,
         ) -> fmt::Result
         where
-            T0: Facet,
-            T1: Facet,
-            T2: Facet,
-            T3: Facet,
-            T4: Facet,
-            T5: Facet,
-            T6: Facet,
-            T7: Facet,
-            T8: Facet,
+            T0: Facet<'a>,
+            T1: Facet<'a>,
+            T2: Facet<'a>,
+            T3: Facet<'a>,
+            T4: Facet<'a>,
+            T5: Facet<'a>,
+            T6: Facet<'a>,
+            T7: Facet<'a>,
+            T8: Facet<'a>,
         {
             write_type_name_list(
                 f,
@@ -1593,36 +1597,36 @@ where
             .build()
     };
 }
-unsafe impl<T0, T1, T2, T3, T4, T5, T6, T7, T8, T9> Facet
+unsafe impl<'a, T0, T1, T2, T3, T4, T5, T6, T7, T8, T9> Facet<'a>
     for (T0, T1, T2, T3, T4, T5, T6, T7, T8, T9)
 where
-    T0: Facet,
-    T1: Facet,
-    T2: Facet,
-    T3: Facet,
-    T4: Facet,
-    T5: Facet,
-    T6: Facet,
-    T7: Facet,
-    T8: Facet,
-    T9: Facet,
+    T0: Facet<'a>,
+    T1: Facet<'a>,
+    T2: Facet<'a>,
+    T3: Facet<'a>,
+    T4: Facet<'a>,
+    T5: Facet<'a>,
+    T6: Facet<'a>,
+    T7: Facet<'a>,
+    T8: Facet<'a>,
+    T9: Facet<'a>,
 {
     const SHAPE: &'static Shape = &const {
-        fn type_name<T0, T1, T2, T3, T4, T5, T6, T7, T8, T9>(
+        fn type_name<'a, T0, T1, T2, T3, T4, T5, T6, T7, T8, T9>(
             f: &mut fmt::Formatter,
             opts: TypeNameOpts,
         ) -> fmt::Result
         where
-            T0: Facet,
-            T1: Facet,
-            T2: Facet,
-            T3: Facet,
-            T4: Facet,
-            T5: Facet,
-            T6: Facet,
-            T7: Facet,
-            T8: Facet,
-            T9: Facet,
+            T0: Facet<'a>,
+            T1: Facet<'a>,
+            T2: Facet<'a>,
+            T3: Facet<'a>,
+            T4: Facet<'a>,
+            T5: Facet<'a>,
+            T6: Facet<'a>,
+            T7: Facet<'a>,
+            T8: Facet<'a>,
+            T9: Facet<'a>,
         {
             write_type_name_list(
                 f,
@@ -1904,38 +1908,38 @@ where
             .build()
     };
 }
-unsafe impl<T0, T1, T2, T3, T4, T5, T6, T7, T8, T9, T10> Facet
+unsafe impl<'a, T0, T1, T2, T3, T4, T5, T6, T7, T8, T9, T10> Facet<'a>
     for (T0, T1, T2, T3, T4, T5, T6, T7, T8, T9, T10)
 where
-    T0: Facet,
-    T1: Facet,
-    T2: Facet,
-    T3: Facet,
-    T4: Facet,
-    T5: Facet,
-    T6: Facet,
-    T7: Facet,
-    T8: Facet,
-    T9: Facet,
-    T10: Facet,
+    T0: Facet<'a>,
+    T1: Facet<'a>,
+    T2: Facet<'a>,
+    T3: Facet<'a>,
+    T4: Facet<'a>,
+    T5: Facet<'a>,
+    T6: Facet<'a>,
+    T7: Facet<'a>,
+    T8: Facet<'a>,
+    T9: Facet<'a>,
+    T10: Facet<'a>,
 {
     const SHAPE: &'static Shape = &const {
-        fn type_name<T0, T1, T2, T3, T4, T5, T6, T7, T8, T9, T10>(
+        fn type_name<'a, T0, T1, T2, T3, T4, T5, T6, T7, T8, T9, T10>(
             f: &mut fmt::Formatter,
             opts: TypeNameOpts,
         ) -> fmt::Result
         where
-            T0: Facet,
-            T1: Facet,
-            T2: Facet,
-            T3: Facet,
-            T4: Facet,
-            T5: Facet,
-            T6: Facet,
-            T7: Facet,
-            T8: Facet,
-            T9: Facet,
-            T10: Facet,
+            T0: Facet<'a>,
+            T1: Facet<'a>,
+            T2: Facet<'a>,
+            T3: Facet<'a>,
+            T4: Facet<'a>,
+            T5: Facet<'a>,
+            T6: Facet<'a>,
+            T7: Facet<'a>,
+            T8: Facet<'a>,
+            T9: Facet<'a>,
+            T10: Facet<'a>,
         {
             write_type_name_list(
                 f,
@@ -2254,40 +2258,40 @@ where
             .build()
     };
 }
-unsafe impl<T0, T1, T2, T3, T4, T5, T6, T7, T8, T9, T10, T11> Facet
+unsafe impl<'a, T0, T1, T2, T3, T4, T5, T6, T7, T8, T9, T10, T11> Facet<'a>
     for (T0, T1, T2, T3, T4, T5, T6, T7, T8, T9, T10, T11)
 where
-    T0: Facet,
-    T1: Facet,
-    T2: Facet,
-    T3: Facet,
-    T4: Facet,
-    T5: Facet,
-    T6: Facet,
-    T7: Facet,
-    T8: Facet,
-    T9: Facet,
-    T10: Facet,
-    T11: Facet,
+    T0: Facet<'a>,
+    T1: Facet<'a>,
+    T2: Facet<'a>,
+    T3: Facet<'a>,
+    T4: Facet<'a>,
+    T5: Facet<'a>,
+    T6: Facet<'a>,
+    T7: Facet<'a>,
+    T8: Facet<'a>,
+    T9: Facet<'a>,
+    T10: Facet<'a>,
+    T11: Facet<'a>,
 {
     const SHAPE: &'static Shape = &const {
-        fn type_name<T0, T1, T2, T3, T4, T5, T6, T7, T8, T9, T10, T11>(
+        fn type_name<'a, T0, T1, T2, T3, T4, T5, T6, T7, T8, T9, T10, T11>(
             f: &mut fmt::Formatter,
             opts: TypeNameOpts,
         ) -> fmt::Result
         where
-            T0: Facet,
-            T1: Facet,
-            T2: Facet,
-            T3: Facet,
-            T4: Facet,
-            T5: Facet,
-            T6: Facet,
-            T7: Facet,
-            T8: Facet,
-            T9: Facet,
-            T10: Facet,
-            T11: Facet,
+            T0: Facet<'a>,
+            T1: Facet<'a>,
+            T2: Facet<'a>,
+            T3: Facet<'a>,
+            T4: Facet<'a>,
+            T5: Facet<'a>,
+            T6: Facet<'a>,
+            T7: Facet<'a>,
+            T8: Facet<'a>,
+            T9: Facet<'a>,
+            T10: Facet<'a>,
+            T11: Facet<'a>,
         {
             write_type_name_list(
                 f,
@@ -1,11 +1,11 @@
 use crate::*;
-use core::{alloc::Layout, hash::Hash as _};
+use core::{alloc::Layout, hash::Hash as _, mem::MaybeUninit};
 
 use alloc::vec::Vec;
 
-unsafe impl<T> Facet for Vec<T>
+unsafe impl<'a, T> Facet<'a> for Vec<T>
 where
-    T: Facet,
+    T: Facet<'a>,
 {
     const SHAPE: &'static Shape = &const {
         Shape::builder()
@@ -24,8 +24,24 @@ where
                             }
                         })
                         .drop_in_place(|value| unsafe { value.drop_in_place::<Vec<T>>() })
-                        .default_in_place(|target| unsafe { target.put(Self::default()) })
-                        .clone_into(|src, dst| unsafe { dst.put(src.as_ref::<Vec<T>>()) });
+                        .default_in_place(|target| unsafe { target.put(Self::default()) });
+
+                    if T::SHAPE.vtable.clone_into.is_some() {
+                        builder = builder.clone_into(|src, dst| unsafe {
+                            let src = src.as_ref::<Vec<T>>();
+                            let t_clone = T::SHAPE.vtable.clone_into.unwrap_unchecked();
+                            let mut list = Vec::with_capacity(src.len());
+                            for item in src {
+                                let mut new_item = MaybeUninit::<T>::uninit();
+                                (t_clone)(
+                                    OpaqueConst::new(item as *const _),
+                                    OpaqueUninit::from_maybe_uninit(&mut new_item),
+                                );
+                                list.push(new_item.assume_init());
+                            }
+                            dst.put(list)
+                        });
+                    }
 
                     if T::SHAPE.vtable.debug.is_some() {
                         builder = builder.debug(|value, f| {
@@ -120,6 +136,29 @@ where
                             }
                             OpaqueConst::new(vec.as_ptr().add(index))
                         })
+                        .get_item_ptr_mut(|ptr, index| unsafe {
+                            let vec = ptr.as_mut::<Vec<T>>();
+                            let len = vec.len();
+                            if index >= len {
+                                panic!(
+                                    "Index out of bounds: the len is {len} but the index is {index}"
+                                );
+                            }
+                            Opaque::new(vec.as_mut_ptr().add(index))
+                        })
+                        .insert(|ptr, index, item| unsafe {
+                            let vec = ptr.as_mut::<Vec<T>>();
+                            let item = item.read::<T>();
+                            vec.insert(index, item);
+                        })
+                        .remove(|ptr, index| unsafe {
+                            let vec = ptr.as_mut::<Vec<T>>();
+                            vec.remove(index);
+                        })
+                        .truncate(|ptr, len| unsafe {
+                            let vec = ptr.as_mut::<Vec<T>>();
+                            vec.truncate(len);
+                        })
                         .build()
                         },
                     )
@@ -0,0 +1,175 @@
+use crate::*;
+use core::{alloc::Layout, hash::Hash as _, mem::MaybeUninit};
+
+use alloc::collections::VecDeque;
+
+unsafe impl<'a, T> Facet<'a> for VecDeque<T>
+where
+    T: Facet<'a>,
+{
+    const SHAPE: &'static Shape = &const {
+        Shape::builder()
+            .id(ConstTypeId::of::<VecDeque<T>>())
+            .layout(Layout::new::<VecDeque<T>>())
+            .vtable(
+                &const {
+                    let mut builder = ValueVTable::builder()
+                        .type_name(|f, opts| {
+                            if let Some(opts) = opts.for_children() {
+                                write!(f, "VecDeque<")?;
+                                (T::SHAPE.vtable.type_name)(f, opts)?;
+                                write!(f, ">")
+                            } else {
+                                write!(f, "VecDeque<⋯>")
+                            }
+                        })
+                        .drop_in_place(|value| unsafe { value.drop_in_place::<VecDeque<T>>() })
+                        .default_in_place(|target| unsafe { target.put(Self::default()) });
+
+                    if T::SHAPE.vtable.clone_into.is_some() {
+                        builder = builder.clone_into(|src, dst| unsafe {
+                            let src = src.as_ref::<VecDeque<T>>();
+                            let t_clone = T::SHAPE.vtable.clone_into.unwrap_unchecked();
+                            let mut list = VecDeque::with_capacity(src.len());
+                            for item in src {
+                                let mut new_item = MaybeUninit::<T>::uninit();
+                                (t_clone)(
+                                    OpaqueConst::new(item as *const _),
+                                    OpaqueUninit::from_maybe_uninit(&mut new_item),
+                                );
+                                list.push_back(new_item.assume_init());
+                            }
+                            dst.put(list)
+                        });
+                    }
+
+                    if T::SHAPE.vtable.debug.is_some() {
+                        builder = builder.debug(|value, f| {
+                            let value = unsafe { value.as_ref::<VecDeque<T>>() };
+                            write!(f, "[")?;
+                            for (i, item) in value.iter().enumerate() {
+                                if i > 0 {
+                                    write!(f, ", ")?;
+                                }
+                                unsafe {
+                                    (T::SHAPE.vtable.debug.unwrap_unchecked())(
+                                        OpaqueConst::new(item),
+                                        f,
+                                    )?;
+                                }
+                            }
+                            write!(f, "]")
+                        });
+                    }
+
+                    if T::SHAPE.vtable.eq.is_some() {
+                        builder = builder.eq(|a, b| unsafe {
+                            let a = a.as_ref::<VecDeque<T>>();
+                            let b = b.as_ref::<VecDeque<T>>();
+                            if a.len() != b.len() {
+                                return false;
+                            }
+                            for (item_a, item_b) in a.iter().zip(b.iter()) {
+                                if !(T::SHAPE.vtable.eq.unwrap_unchecked())(
+                                    OpaqueConst::new(item_a),
+                                    OpaqueConst::new(item_b),
+                                ) {
+                                    return false;
+                                }
+                            }
+                            true
+                        });
+                    }
+
+                    if T::SHAPE.vtable.hash.is_some() {
+                        builder = builder.hash(|value, hasher_this, hasher_write_fn| unsafe {
+                            use crate::HasherProxy;
+                            let deque = value.as_ref::<VecDeque<T>>();
+                            let t_hash = T::SHAPE.vtable.hash.unwrap_unchecked();
+                            let mut hasher = HasherProxy::new(hasher_this, hasher_write_fn);
+                            deque.len().hash(&mut hasher);
+                            for item in deque {
+                                (t_hash)(OpaqueConst::new(item), hasher_this, hasher_write_fn);
+                            }
+                        });
+                    }
+
+                    let mut traits = MarkerTraits::empty();
+                    if T::SHAPE.vtable.marker_traits.contains(MarkerTraits::SEND) {
+                        traits = traits.union(MarkerTraits::SEND);
+                    }
+                    if T::SHAPE.vtable.marker_traits.contains(MarkerTraits::SYNC) {
+                        traits = traits.union(MarkerTraits::SYNC);
+                    }
+                    if T::SHAPE.vtable.marker_traits.contains(MarkerTraits::EQ) {
+                        traits = traits.union(MarkerTraits::EQ);
+                    }
+                    builder = builder.marker_traits(traits);
+
+                    builder.build()
+                },
+            )
+            .def(Def::List(
+                ListDef::builder()
+                    .vtable(
+                        &const {
+                            ListVTable::builder()
+                        .init_in_place_with_capacity(|data, capacity| unsafe {
+                            Ok(data.put(Self::with_capacity(capacity)))
+                        })
+                        .push(|ptr, item| unsafe {
+                            let deque = ptr.as_mut::<VecDeque<T>>();
+                            let item = item.read::<T>();
+                            (*deque).push_back(item);
+                        })
+                        .len(|ptr| unsafe {
+                            let deque = ptr.as_ref::<VecDeque<T>>();
+                            deque.len()
+                        })
+                        .get_item_ptr(|ptr, index| unsafe {
+                            let deque = ptr.as_ref::<VecDeque<T>>();
+                            let len = deque.len();
+                            if index >= len {
+                                panic!(
+                                    "Index out of bounds: the len is {len} but the index is {index}"
+                                );
+                            }
+                            OpaqueConst::new(&deque[index])
+                        })
+                        .get_item_ptr_mut(|ptr, index| unsafe {
+                            let deque = ptr.as_mut::<VecDeque<T>>();
+                            let len = deque.len();
+                            if index >= len {
+                                panic!(
+                                    "Index out of bounds: the len is {len} but the index is {index}"
+                                );
+                            }
+                            Opaque::new(&mut deque[index])
+                        })
+                        .insert(|ptr, index, item| unsafe {
+                            let deque = ptr.as_mut::<VecDeque<T>>();
+                            let item = item.read::<T>();
+                            deque.insert(index, item);
+                        })
+                        .remove(|ptr, index| unsafe {
+                            let deque = ptr.as_mut::<VecDeque<T>>();
+                            let len = deque.len();
+                            if deque.remove(index).is_none() {
+                                panic!(
+                                    "Index out of bounds: the len is {len} but the index is {index}"
+                                );
+                            }
+                        })
+                        .truncate(|ptr, len| unsafe {
+                            let deque = ptr.as_mut::<VecDeque<T>>();
+                            deque.truncate(len);
+                        })
+                        .build()
+                        },
+                    )
+                    .t(T::SHAPE)
+                    .build(),
+            ))
+            .build()
+    };
+}
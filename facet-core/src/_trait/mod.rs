@@ -17,12 +17,19 @@ pub use macros::*;
 /// all the serializers, deserializers, the entire ecosystem is unsafe.
 ///
 /// You're responsible for describing the type layout properly, and annotating all the invariants.
-pub unsafe trait Facet: Sized {
+///
+/// # Lifetime
+///
+/// `Facet<'a>` means "this type may be built out of data borrowed for `'a`". Owned types
+/// implement it for every lifetime, while `&'b str` only implements it for lifetimes that
+/// outlive `'b`. Deserializers that borrow from their input take `T: Facet<'input>`, which
+/// keeps them from handing out borrows that outlive the input.
+pub unsafe trait Facet<'a>: Sized {
     /// The shape of this type
     const SHAPE: &'static Shape;
 
     /// Returns true if the type of `self` is equal to the type of `other`
-    fn type_eq<Other: Facet>() -> bool {
+    fn type_eq<Other: Facet<'a>>() -> bool {
         Self::SHAPE == Other::SHAPE
     }
 }
@@ -33,6 +33,39 @@ pub type ListLenFn = unsafe fn(list: OpaqueConst) -> usize;
 /// The `list` parameter must point to aligned, initialized memory of the correct type.
 pub type ListGetItemPtrFn = unsafe fn(list: OpaqueConst, index: usize) -> OpaqueConst;
 
+/// Get mutable pointer to the item at the given index. Panics if out of bounds.
+///
+/// # Safety
+///
+/// The `list` parameter must point to aligned, initialized memory of the correct type.
+pub type ListGetItemPtrMutFn = unsafe fn(list: Opaque, index: usize) -> Opaque;
+
+/// Insert an item at the given index, shifting the items after it. Panics if `index` is
+/// greater than the length.
+///
+/// # Safety
+///
+/// The `list` parameter must point to aligned, initialized memory of the correct type.
+/// `item` is moved out of (with [`core::ptr::read`]) — it should be deallocated
+/// afterwards but NOT dropped.
+pub type ListInsertFn = unsafe fn(list: Opaque, index: usize, item: Opaque);
+
+/// Remove and drop the item at the given index, shifting the items after it. Panics if
+/// out of bounds.
+///
+/// # Safety
+///
+/// The `list` parameter must point to aligned, initialized memory of the correct type.
+pub type ListRemoveFn = unsafe fn(list: Opaque, index: usize);
+
+/// Shorten the list to `len` items, dropping the rest. Does nothing if the list is
+/// already shorter.
+///
+/// # Safety
+///
+/// The `list` parameter must point to aligned, initialized memory of the correct type.
+pub type ListTruncateFn = unsafe fn(list: Opaque, len: usize);
+
 /// Virtual table for a list-like type (like `Vec<T>`,
 /// but also `HashSet<T>`, etc.)
 #[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
@@ -50,6 +83,18 @@ pub struct ListVTable {
 
     /// cf. [`ListGetItemPtrFn`]
     pub get_item_ptr: ListGetItemPtrFn,
+
+    /// cf. [`ListGetItemPtrMutFn`], `None` for read-only lists like slices
+    pub get_item_ptr_mut: Option<ListGetItemPtrMutFn>,
+
+    /// cf. [`ListInsertFn`], `None` for fixed-size lists like arrays
+    pub insert: Option<ListInsertFn>,
+
+    /// cf. [`ListRemoveFn`], `None` for fixed-size lists like arrays
+    pub remove: Option<ListRemoveFn>,
+
+    /// cf. [`ListTruncateFn`], `None` for fixed-size lists like arrays
+    pub truncate: Option<ListTruncateFn>,
 }
 
 impl ListVTable {
@@ -65,6 +110,10 @@ pub struct ListVTableBuilder {
     push: Option<ListPushFn>,
     len: Option<ListLenFn>,
     get_item_ptr: Option<ListGetItemPtrFn>,
+    get_item_ptr_mut: Option<ListGetItemPtrMutFn>,
+    insert: Option<ListInsertFn>,
+    remove: Option<ListRemoveFn>,
+    truncate: Option<ListTruncateFn>,
 }
 
 impl ListVTableBuilder {
@@ -76,6 +125,10 @@ impl ListVTableBuilder {
             push: None,
             len: None,
             get_item_ptr: None,
+            get_item_ptr_mut: None,
+            insert: None,
+            remove: None,
+            truncate: None,
         }
     }
 
@@ -103,17 +156,46 @@ impl ListVTableBuilder {
         self
     }
 
+    /// Sets the get_item_ptr_mut field
+    pub const fn get_item_ptr_mut(mut self, f: ListGetItemPtrMutFn) -> Self {
+        self.get_item_ptr_mut = Some(f);
+        self
+    }
+
+    /// Sets the insert field
+    pub const fn insert(mut self, f: ListInsertFn) -> Self {
+        self.insert = Some(f);
+        self
+    }
+
+    /// Sets the remove field
+    pub const fn remove(mut self, f: ListRemoveFn) -> Self {
+        self.remove = Some(f);
+        self
+    }
+
+    /// Sets the truncate field
+    pub const fn truncate(mut self, f: ListTruncateFn) -> Self {
+        self.truncate = Some(f);
+        self
+    }
+
     /// Builds the [`ListVTable`] from the current state of the builder.
     ///
     /// # Panics
     ///
-    /// This method will panic if any of the required fields are `None`.
+    /// This method will panic if any of the required fields are `None`. `get_item_ptr_mut`,
+    /// `insert`, `remove` and `truncate` are optional.
     pub const fn build(self) -> ListVTable {
         ListVTable {
             init_in_place_with_capacity: self.init_in_place_with_capacity.unwrap(),
             push: self.push.unwrap(),
             len: self.len.unwrap(),
             get_item_ptr: self.get_item_ptr.unwrap(),
+            get_item_ptr_mut: self.get_item_ptr_mut,
+            insert: self.insert,
+            remove: self.remove,
+            truncate: self.truncate,
         }
     }
 }
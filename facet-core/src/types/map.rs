@@ -44,6 +44,29 @@ pub type MapGetValuePtrFn = for<'map, 'key> unsafe fn(
     key: OpaqueConst<'key>,
 ) -> Option<OpaqueConst<'map>>;
 
+/// Get mutable pointer to a value for a given key, returns None if not found
+///
+/// # Safety
+///
+/// The `map` parameter must point to aligned, initialized memory of the correct type.
+pub type MapGetValuePtrMutFn =
+    for<'map, 'key> unsafe fn(map: Opaque<'map>, key: OpaqueConst<'key>) -> Option<Opaque<'map>>;
+
+/// Remove the entry for a given key, dropping its key and value. Returns whether the key
+/// was in the map.
+///
+/// # Safety
+///
+/// The `map` parameter must point to aligned, initialized memory of the correct type.
+pub type MapRemoveFn = for<'map, 'key> unsafe fn(map: Opaque<'map>, key: OpaqueConst<'key>) -> bool;
+
+/// Remove and drop all entries of the map
+///
+/// # Safety
+///
+/// The `map` parameter must point to aligned, initialized memory of the correct type.
+pub type MapClearFn = for<'map> unsafe fn(map: Opaque<'map>);
+
 /// Get an iterator over the map
 ///
 /// # Safety
@@ -145,6 +168,15 @@ pub struct MapVTable {
     /// cf. [`MapGetValuePtrFn`]
     pub get_value_ptr_fn: MapGetValuePtrFn,
 
+    /// cf. [`MapGetValuePtrMutFn`]
+    pub get_value_ptr_mut_fn: MapGetValuePtrMutFn,
+
+    /// cf. [`MapRemoveFn`]
+    pub remove_fn: MapRemoveFn,
+
+    /// cf. [`MapClearFn`]
+    pub clear_fn: MapClearFn,
+
     /// cf. [`MapIterFn`]
     pub iter_fn: MapIterFn,
 
@@ -166,6 +198,9 @@ pub struct MapVTableBuilder {
     len_fn: Option<MapLenFn>,
     contains_key_fn: Option<MapContainsKeyFn>,
     get_value_ptr_fn: Option<MapGetValuePtrFn>,
+    get_value_ptr_mut_fn: Option<MapGetValuePtrMutFn>,
+    remove_fn: Option<MapRemoveFn>,
+    clear_fn: Option<MapClearFn>,
     iter_fn: Option<MapIterFn>,
     iter_vtable: Option<MapIterVTable>,
 }
@@ -180,6 +215,9 @@ impl MapVTableBuilder {
             len_fn: None,
             contains_key_fn: None,
             get_value_ptr_fn: None,
+            get_value_ptr_mut_fn: None,
+            remove_fn: None,
+            clear_fn: None,
             iter_fn: None,
             iter_vtable: None,
         }
@@ -215,6 +253,24 @@ impl MapVTableBuilder {
         self
     }
 
+    /// Sets the get_value_ptr_mut_fn field
+    pub const fn get_value_ptr_mut(mut self, f: MapGetValuePtrMutFn) -> Self {
+        self.get_value_ptr_mut_fn = Some(f);
+        self
+    }
+
+    /// Sets the remove_fn field
+    pub const fn remove(mut self, f: MapRemoveFn) -> Self {
+        self.remove_fn = Some(f);
+        self
+    }
+
+    /// Sets the clear_fn field
+    pub const fn clear(mut self, f: MapClearFn) -> Self {
+        self.clear_fn = Some(f);
+        self
+    }
+
     /// Sets the iter_fn field
     pub const fn iter(mut self, f: MapIterFn) -> Self {
         self.iter_fn = Some(f);
@@ -239,6 +295,9 @@ impl MapVTableBuilder {
             len_fn: self.len_fn.unwrap(),
             contains_key_fn: self.contains_key_fn.unwrap(),
             get_value_ptr_fn: self.get_value_ptr_fn.unwrap(),
+            get_value_ptr_mut_fn: self.get_value_ptr_mut_fn.unwrap(),
+            remove_fn: self.remove_fn.unwrap(),
+            clear_fn: self.clear_fn.unwrap(),
             iter_fn: self.iter_fn.unwrap(),
             iter_vtable: self.iter_vtable.unwrap(),
         }
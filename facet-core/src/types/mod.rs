@@ -9,16 +9,22 @@ pub use list::*;
 mod map;
 pub use map::*;
 
+mod set;
+pub use set::*;
+
 mod value;
 pub use value::*;
 
 mod option;
 pub use option::*;
 
+mod smartptr;
+pub use smartptr::*;
+
 mod scalar_affinities;
 pub use scalar_affinities::*;
 
-use crate::{ConstTypeId, Facet};
+use crate::{ConstTypeId, Facet, OpaqueConst};
 
 /// Schema for reflection of a type
 #[derive(Clone, Copy, Debug)]
@@ -130,14 +136,14 @@ impl Shape {
     }
 
     /// Check if this shape is of the given type
-    pub fn is_type<Other: Facet>(&'static self) -> bool {
+    pub fn is_type<'a, Other: Facet<'a>>(&'static self) -> bool {
         let l = self;
         let r = Other::SHAPE;
         l == r
     }
 
     /// Assert that this shape is of the given type, panicking if it's not
-    pub fn assert_type<Other: Facet>(&'static self) {
+    pub fn assert_type<'a, Other: Facet<'a>>(&'static self) {
         assert!(
             self.is_type::<Other>(),
             "Type mismatch: expected {}, found {self}",
@@ -319,6 +325,10 @@ pub struct StructDef {
 
     /// all fields, in declaration order (not necessarily in memory order)
     pub fields: &'static [Field],
+
+    /// whether deserializers must reject keys that don't match any field, instead of
+    /// skipping them (cf. `#[facet(deny_unknown_fields)]`)
+    pub deny_unknown_fields: bool,
 }
 
 impl StructDef {
@@ -332,6 +342,7 @@ impl StructDef {
 pub struct StructDefBuilder {
     kind: Option<StructKind>,
     fields: Option<&'static [Field]>,
+    deny_unknown_fields: bool,
 }
 
 impl StructDefBuilder {
@@ -341,6 +352,7 @@ impl StructDefBuilder {
         Self {
             kind: None,
             fields: None,
+            deny_unknown_fields: false,
         }
     }
 
@@ -356,11 +368,18 @@ impl StructDefBuilder {
         self
     }
 
+    /// Makes deserializers reject unknown keys instead of skipping them
+    pub const fn deny_unknown_fields(mut self, deny_unknown_fields: bool) -> Self {
+        self.deny_unknown_fields = deny_unknown_fields;
+        self
+    }
+
     /// Builds the StructDef
     pub const fn build(self) -> StructDef {
         StructDef {
             kind: self.kind.unwrap(),
             fields: self.fields.unwrap(),
+            deny_unknown_fields: self.deny_unknown_fields,
         }
     }
 }
@@ -391,6 +410,10 @@ pub struct Field {
     /// key for the struct field (for tuples and tuple-structs, this is the 0-based index)
     pub name: &'static str,
 
+    /// name of the field in serialized formats, if it differs from `name`
+    /// (set via `#[facet(rename = "...")]` or the container's `rename_all`)
+    pub rename: Option<&'static str>,
+
     /// schema of the inner type
     pub shape: &'static Shape,
 
@@ -403,6 +426,14 @@ pub struct Field {
     /// arbitrary attributes set via the derive macro
     pub attributes: &'static [FieldAttribute],
 
+    /// custom default for the field (set via `#[facet(default = "path")]`); when this is `None`
+    /// but the field has [`FieldFlags::DEFAULT`], the field shape's `default_in_place` is used
+    pub default: Option<DefaultInPlaceFn>,
+
+    /// predicate deciding whether the field is left out when serializing
+    /// (set via `#[facet(skip_serializing_if = "path")]`)
+    pub skip_serializing_if: Option<SkipSerializingIfFn>,
+
     /// doc comments
     pub doc: &'static [&'static str],
 }
@@ -412,15 +443,50 @@ impl Field {
     pub const fn builder() -> FieldBuilder {
         FieldBuilder::new()
     }
+
+    /// Returns the name this field should be serialized and deserialized as
+    pub const fn serialized_name(&self) -> &'static str {
+        match self.rename {
+            Some(rename) => rename,
+            None => self.name,
+        }
+    }
+
+    /// Returns true if this field should be left out when serializing `value`, either because
+    /// it has [`FieldFlags::SKIP_SERIALIZING`] or because its `skip_serializing_if` predicate
+    /// holds.
+    ///
+    /// # Safety
+    ///
+    /// `value` must point to this field's value, i.e. to an initialized value of `self.shape`.
+    pub unsafe fn should_skip_serializing(&self, value: OpaqueConst<'_>) -> bool {
+        if self.flags.contains(FieldFlags::SKIP_SERIALIZING) {
+            return true;
+        }
+        match self.skip_serializing_if {
+            Some(predicate) => unsafe { predicate(value) },
+            None => false,
+        }
+    }
 }
 
+/// Function deciding whether a field should be skipped when serializing
+///
+/// # Safety
+///
+/// The `value` parameter must point to an initialized value of the field's shape.
+pub type SkipSerializingIfFn = for<'mem> unsafe fn(value: OpaqueConst<'mem>) -> bool;
+
 /// Builder for Field
 pub struct FieldBuilder {
     name: Option<&'static str>,
+    rename: Option<&'static str>,
     shape: Option<&'static Shape>,
     offset: Option<usize>,
     flags: Option<FieldFlags>,
     attributes: &'static [FieldAttribute],
+    default: Option<DefaultInPlaceFn>,
+    skip_serializing_if: Option<SkipSerializingIfFn>,
     doc: &'static [&'static str],
 }
 
@@ -441,10 +507,13 @@ impl FieldBuilder {
     pub const fn new() -> Self {
         Self {
             name: None,
+            rename: None,
             shape: None,
             offset: None,
             flags: None,
             attributes: &[],
+            default: None,
+            skip_serializing_if: None,
             doc: &[],
         }
     }
@@ -455,6 +524,12 @@ impl FieldBuilder {
         self
     }
 
+    /// Sets the serialized name for the Field
+    pub const fn rename(mut self, rename: &'static str) -> Self {
+        self.rename = Some(rename);
+        self
+    }
+
     /// Sets the shape for the Field
     pub const fn shape(mut self, shape: &'static Shape) -> Self {
         self.shape = Some(shape);
@@ -479,6 +554,18 @@ impl FieldBuilder {
         self
     }
 
+    /// Sets the custom default for the Field
+    pub const fn default(mut self, default: DefaultInPlaceFn) -> Self {
+        self.default = Some(default);
+        self
+    }
+
+    /// Sets the predicate deciding whether the Field is skipped when serializing
+    pub const fn skip_serializing_if(mut self, predicate: SkipSerializingIfFn) -> Self {
+        self.skip_serializing_if = Some(predicate);
+        self
+    }
+
     /// Sets the doc comments for the Field
     pub const fn doc(mut self, doc: &'static [&'static str]) -> Self {
         self.doc = doc;
@@ -489,6 +576,7 @@ impl FieldBuilder {
     pub const fn build(self) -> Field {
         Field {
             name: self.name.unwrap(),
+            rename: self.rename,
             shape: self.shape.unwrap(),
             offset: self.offset.unwrap(),
             flags: match self.flags {
@@ -496,6 +584,8 @@ impl FieldBuilder {
                 None => FieldFlags::EMPTY,
             },
             attributes: self.attributes,
+            default: self.default,
+            skip_serializing_if: self.skip_serializing_if,
             doc: self.doc,
         }
     }
@@ -510,6 +600,21 @@ bitflags::bitflags! {
 
         /// Flag indicating this field contains sensitive data that should not be displayed
         const SENSITIVE = 1 << 0;
+
+        /// Flag indicating this field can be left out when building the struct: it is then
+        /// set to its default (cf. [`Field::default`])
+        const DEFAULT = 1 << 1;
+
+        /// Flag indicating this field is left out when serializing
+        const SKIP_SERIALIZING = 1 << 2;
+
+        /// Flag indicating this field is never read when deserializing: it must have a default
+        /// (cf. [`FieldFlags::DEFAULT`]) to be filled in with
+        const SKIP_DESERIALIZING = 1 << 3;
+
+        /// Flag indicating the fields of this struct-typed field are merged into the parent's
+        /// on the wire, instead of being nested under the field's own name
+        const FLATTEN = 1 << 4;
     }
 }
 
@@ -529,6 +634,10 @@ impl core::fmt::Display for FieldFlags {
         // Define a vector of flag entries: (flag, name)
         let flags = [
             (FieldFlags::SENSITIVE, "sensitive"),
+            (FieldFlags::DEFAULT, "default"),
+            (FieldFlags::SKIP_SERIALIZING, "skip_serializing"),
+            (FieldFlags::SKIP_DESERIALIZING, "skip_deserializing"),
+            (FieldFlags::FLATTEN, "flatten"),
             // Future flags can be easily added here:
             // (FieldFlags::SOME_FLAG, "some_flag"),
             // (FieldFlags::ANOTHER_FLAG, "another_flag"),
@@ -616,6 +725,61 @@ impl MapDefBuilder {
     }
 }
 
+/// Fields for set types
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+#[repr(C)]
+#[non_exhaustive]
+pub struct SetDef {
+    /// vtable for interacting with the set
+    pub vtable: &'static SetVTable,
+    /// shape of the values in the set
+    pub t: &'static Shape,
+}
+
+impl SetDef {
+    /// Returns a builder for SetDef
+    pub const fn builder() -> SetDefBuilder {
+        SetDefBuilder::new()
+    }
+}
+
+/// Builder for SetDef
+pub struct SetDefBuilder {
+    vtable: Option<&'static SetVTable>,
+    t: Option<&'static Shape>,
+}
+
+impl SetDefBuilder {
+    /// Creates a new SetDefBuilder
+    #[allow(clippy::new_without_default)]
+    pub const fn new() -> Self {
+        Self {
+            vtable: None,
+            t: None,
+        }
+    }
+
+    /// Sets the vtable for the SetDef
+    pub const fn vtable(mut self, vtable: &'static SetVTable) -> Self {
+        self.vtable = Some(vtable);
+        self
+    }
+
+    /// Sets the value shape for the SetDef
+    pub const fn t(mut self, t: &'static Shape) -> Self {
+        self.t = Some(t);
+        self
+    }
+
+    /// Builds the SetDef
+    pub const fn build(self) -> SetDef {
+        SetDef {
+            vtable: self.vtable.unwrap(),
+            t: self.t.unwrap(),
+        }
+    }
+}
+
 /// Fields for list types
 #[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
 #[repr(C)]
@@ -735,6 +899,8 @@ pub struct EnumDef {
     pub repr: EnumRepr,
     /// all variants for this enum
     pub variants: &'static [Variant],
+    /// how the active variant is represented in serialized formats
+    pub tagging: EnumTagging,
 }
 
 impl EnumDef {
@@ -748,6 +914,7 @@ impl EnumDef {
 pub struct EnumDefBuilder {
     repr: Option<EnumRepr>,
     variants: Option<&'static [Variant]>,
+    tagging: EnumTagging,
 }
 
 impl EnumDefBuilder {
@@ -757,6 +924,7 @@ impl EnumDefBuilder {
         Self {
             repr: None,
             variants: None,
+            tagging: EnumTagging::External,
         }
     }
 
@@ -772,15 +940,54 @@ impl EnumDefBuilder {
         self
     }
 
+    /// Sets the tagging strategy for the EnumDef (externally tagged by default)
+    pub const fn tagging(mut self, tagging: EnumTagging) -> Self {
+        self.tagging = tagging;
+        self
+    }
+
     /// Builds the EnumDef
     pub const fn build(self) -> EnumDef {
         EnumDef {
             repr: self.repr.unwrap(),
             variants: self.variants.unwrap(),
+            tagging: self.tagging,
         }
     }
 }
 
+/// How the active variant of an enum is represented in serialized formats,
+/// shown here for JSON with `enum E { A, B { x: u32 } }`
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Default)]
+#[repr(C)]
+#[non_exhaustive]
+pub enum EnumTagging {
+    /// `"A"` and `{"B": {"x": 1}}` (the default)
+    #[default]
+    External,
+
+    /// `{"type": "A"}` and `{"type": "B", "x": 1}`, set via `#[facet(tag = "type")]`
+    ///
+    /// Only unit and struct variants can be internally tagged.
+    Internal {
+        /// key holding the variant name
+        tag: &'static str,
+    },
+
+    /// `{"t": "A"}` and `{"t": "B", "c": {"x": 1}}`, set via
+    /// `#[facet(tag = "t", content = "c")]`
+    Adjacent {
+        /// key holding the variant name
+        tag: &'static str,
+        /// key holding the variant's fields
+        content: &'static str,
+    },
+
+    /// `null` and `{"x": 1}`, set via `#[facet(untagged)]`: when deserializing, the first
+    /// variant that matches the input wins
+    Untagged,
+}
+
 /// Describes a variant of an enum
 #[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
 #[repr(C)]
@@ -789,6 +996,10 @@ pub struct Variant {
     /// Name of the variant
     pub name: &'static str,
 
+    /// Name of the variant in serialized formats, if it differs from `name`
+    /// (set via `#[facet(rename = "...")]` or the enum's `rename_all`)
+    pub rename: Option<&'static str>,
+
     /// Discriminant value (if available)
     pub discriminant: Option<i64>,
 
@@ -807,11 +1018,20 @@ impl Variant {
     pub const fn builder() -> VariantBuilder {
         VariantBuilder::new()
     }
+
+    /// Returns the name this variant should be serialized and deserialized as
+    pub const fn serialized_name(&self) -> &'static str {
+        match self.rename {
+            Some(rename) => rename,
+            None => self.name,
+        }
+    }
 }
 
 /// Builder for Variant
 pub struct VariantBuilder {
     name: Option<&'static str>,
+    rename: Option<&'static str>,
     discriminant: Option<Option<i64>>,
     kind: Option<VariantKind>,
     offset: Option<usize>,
@@ -824,6 +1044,7 @@ impl VariantBuilder {
     pub const fn new() -> Self {
         Self {
             name: None,
+            rename: None,
             discriminant: None,
             kind: None,
             offset: None,
@@ -837,6 +1058,12 @@ impl VariantBuilder {
         self
     }
 
+    /// Sets the serialized name for the Variant
+    pub const fn rename(mut self, rename: &'static str) -> Self {
+        self.rename = Some(rename);
+        self
+    }
+
     /// Sets the discriminant for the Variant
     pub const fn discriminant(mut self, discriminant: Option<i64>) -> Self {
         self.discriminant = Some(discriminant);
@@ -865,6 +1092,7 @@ impl VariantBuilder {
     pub const fn build(self) -> Variant {
         Variant {
             name: self.name.unwrap(),
+            rename: self.rename,
             discriminant: self.discriminant.unwrap(),
             kind: self.kind.unwrap(),
             offset: self.offset.unwrap(),
@@ -1000,11 +1228,19 @@ pub enum Def {
     /// e.g. `struct Struct { field: u32 }`, `struct TupleStruct(u32, u32);`, `(u32, u32)`
     Struct(StructDef),
 
-    /// Map — keys are dynamic (and strings, sorry), values are homogeneous
+    /// Map — keys and values are both homogeneous
     ///
-    /// e.g. `Map<String, T>`
+    /// Keys can be any shape: formats that only have string keys (JSON, TOML, YAML)
+    /// go through the key's [`ValueVTable::display`] and [`ValueVTable::parse`].
+    ///
+    /// e.g. `HashMap<String, T>`, `BTreeMap<u32, T>`
     Map(MapDef),
 
+    /// Collection of unique, homogeneous values
+    ///
+    /// e.g. `HashSet<T>`, `BTreeSet<T>`
+    Set(SetDef),
+
     /// Ordered list of heterogenous values, variable size
     ///
     /// e.g. `Vec<T>`
@@ -1019,6 +1255,11 @@ pub enum Def {
     ///
     /// e.g. `Option<T>`
     Option(OptionDef),
+
+    /// Smart pointer, owning or referring to a single value of another shape
+    ///
+    /// e.g. `Box<T>`, `Rc<T>`, `Arc<T>`, `Weak<T>`
+    SmartPointer(SmartPointerDef),
 }
 
 /// A characteristic a shape can have
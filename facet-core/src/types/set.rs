@@ -0,0 +1,224 @@
+use crate::opaque::{Opaque, OpaqueConst, OpaqueUninit};
+
+/// Initialize a set in place with a given capacity
+///
+/// # Safety
+///
+/// The `set` parameter must point to uninitialized memory of sufficient size.
+/// The function must properly initialize the memory.
+pub type SetInitInPlaceWithCapacityFn =
+    unsafe fn(set: OpaqueUninit, capacity: usize) -> Result<Opaque, ()>;
+
+/// Insert a value into the set. Returns `false` if the set already contained
+/// an equal value, in which case `value` has been dropped.
+///
+/// # Safety
+///
+/// The `set` parameter must point to aligned, initialized memory of the correct type.
+/// `value` is moved out of (with [`core::ptr::read`]) — it should be deallocated
+/// afterwards but NOT dropped.
+pub type SetInsertFn =
+    for<'set, 'value> unsafe fn(set: Opaque<'set>, value: Opaque<'value>) -> bool;
+
+/// Get the number of values in the set
+///
+/// # Safety
+///
+/// The `set` parameter must point to aligned, initialized memory of the correct type.
+pub type SetLenFn = for<'set> unsafe fn(set: OpaqueConst<'set>) -> usize;
+
+/// Check if the set contains a value
+///
+/// # Safety
+///
+/// The `set` parameter must point to aligned, initialized memory of the correct type.
+pub type SetContainsFn =
+    for<'set, 'value> unsafe fn(set: OpaqueConst<'set>, value: OpaqueConst<'value>) -> bool;
+
+/// Get an iterator over the set
+///
+/// # Safety
+///
+/// The `set` parameter must point to aligned, initialized memory of the correct type.
+pub type SetIterFn = for<'set> unsafe fn(set: OpaqueConst<'set>) -> Opaque<'set>;
+
+/// Get the next value from the iterator
+///
+/// # Safety
+///
+/// The `iter` parameter must point to aligned, initialized memory of the correct type.
+pub type SetIterNextFn = for<'iter> unsafe fn(iter: Opaque<'iter>) -> Option<OpaqueConst<'iter>>;
+
+/// Deallocate the iterator
+///
+/// # Safety
+///
+/// The `iter` parameter must point to aligned, initialized memory of the correct type.
+pub type SetIterDeallocFn = for<'iter> unsafe fn(iter: Opaque<'iter>);
+
+/// VTable for an iterator over a set
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+#[repr(C)]
+#[non_exhaustive]
+pub struct SetIterVTable {
+    /// cf. [`SetIterNextFn`]
+    pub next: SetIterNextFn,
+
+    /// cf. [`SetIterDeallocFn`]
+    pub dealloc: SetIterDeallocFn,
+}
+
+impl SetIterVTable {
+    /// Returns a builder for SetIterVTable
+    pub const fn builder() -> SetIterVTableBuilder {
+        SetIterVTableBuilder::new()
+    }
+}
+
+/// Builds a [`SetIterVTable`]
+pub struct SetIterVTableBuilder {
+    next: Option<SetIterNextFn>,
+    dealloc: Option<SetIterDeallocFn>,
+}
+
+impl SetIterVTableBuilder {
+    /// Creates a new [`SetIterVTableBuilder`] with all fields set to `None`.
+    #[allow(clippy::new_without_default)]
+    pub const fn new() -> Self {
+        Self {
+            next: None,
+            dealloc: None,
+        }
+    }
+
+    /// Sets the next field
+    pub const fn next(mut self, f: SetIterNextFn) -> Self {
+        self.next = Some(f);
+        self
+    }
+
+    /// Sets the dealloc field
+    pub const fn dealloc(mut self, f: SetIterDeallocFn) -> Self {
+        self.dealloc = Some(f);
+        self
+    }
+
+    /// Builds the [`SetIterVTable`] from the current state of the builder.
+    ///
+    /// # Panics
+    ///
+    /// This method will panic if any of the required fields are `None`.
+    pub const fn build(self) -> SetIterVTable {
+        SetIterVTable {
+            next: self.next.unwrap(),
+            dealloc: self.dealloc.unwrap(),
+        }
+    }
+}
+
+/// Virtual table for a Set<T>
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+#[repr(C)]
+pub struct SetVTable {
+    /// cf. [`SetInitInPlaceWithCapacityFn`]
+    pub init_in_place_with_capacity_fn: SetInitInPlaceWithCapacityFn,
+
+    /// cf. [`SetInsertFn`]
+    pub insert_fn: SetInsertFn,
+
+    /// cf. [`SetLenFn`]
+    pub len_fn: SetLenFn,
+
+    /// cf. [`SetContainsFn`]
+    pub contains_fn: SetContainsFn,
+
+    /// cf. [`SetIterFn`]
+    pub iter_fn: SetIterFn,
+
+    /// Virtual table for set iterator operations
+    pub iter_vtable: SetIterVTable,
+}
+
+impl SetVTable {
+    /// Returns a builder for SetVTable
+    pub const fn builder() -> SetVTableBuilder {
+        SetVTableBuilder::new()
+    }
+}
+
+/// Builds a [`SetVTable`]
+pub struct SetVTableBuilder {
+    init_in_place_with_capacity_fn: Option<SetInitInPlaceWithCapacityFn>,
+    insert_fn: Option<SetInsertFn>,
+    len_fn: Option<SetLenFn>,
+    contains_fn: Option<SetContainsFn>,
+    iter_fn: Option<SetIterFn>,
+    iter_vtable: Option<SetIterVTable>,
+}
+
+impl SetVTableBuilder {
+    /// Creates a new [`SetVTableBuilder`] with all fields set to `None`.
+    #[allow(clippy::new_without_default)]
+    pub const fn new() -> Self {
+        Self {
+            init_in_place_with_capacity_fn: None,
+            insert_fn: None,
+            len_fn: None,
+            contains_fn: None,
+            iter_fn: None,
+            iter_vtable: None,
+        }
+    }
+
+    /// Sets the init_in_place_with_capacity_fn field
+    pub const fn init_in_place_with_capacity(mut self, f: SetInitInPlaceWithCapacityFn) -> Self {
+        self.init_in_place_with_capacity_fn = Some(f);
+        self
+    }
+
+    /// Sets the insert_fn field
+    pub const fn insert(mut self, f: SetInsertFn) -> Self {
+        self.insert_fn = Some(f);
+        self
+    }
+
+    /// Sets the len_fn field
+    pub const fn len(mut self, f: SetLenFn) -> Self {
+        self.len_fn = Some(f);
+        self
+    }
+
+    /// Sets the contains_fn field
+    pub const fn contains(mut self, f: SetContainsFn) -> Self {
+        self.contains_fn = Some(f);
+        self
+    }
+
+    /// Sets the iter_fn field
+    pub const fn iter(mut self, f: SetIterFn) -> Self {
+        self.iter_fn = Some(f);
+        self
+    }
+
+    /// Sets the iter_vtable field
+    pub const fn iter_vtable(mut self, vtable: SetIterVTable) -> Self {
+        self.iter_vtable = Some(vtable);
+        self
+    }
+
+    /// Builds the [`SetVTable`] from the current state of the builder.
+    ///
+    /// # Panics
+    ///
+    /// This method will panic if any of the required fields are `None`.
+    pub const fn build(self) -> SetVTable {
+        SetVTable {
+            init_in_place_with_capacity_fn: self.init_in_place_with_capacity_fn.unwrap(),
+            insert_fn: self.insert_fn.unwrap(),
+            len_fn: self.len_fn.unwrap(),
+            contains_fn: self.contains_fn.unwrap(),
+            iter_fn: self.iter_fn.unwrap(),
+            iter_vtable: self.iter_vtable.unwrap(),
+        }
+    }
+}
@@ -0,0 +1,207 @@
+use crate::opaque::{Opaque, OpaqueConst, OpaqueUninit};
+
+use super::Shape;
+
+/// Describes a smart pointer — a type that owns (or refers to) a single
+/// heap-allocated value of another shape.
+///
+/// e.g. `Box<T>`, `Rc<T>`, `Arc<T>`, `Weak<T>`
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+#[repr(C)]
+#[non_exhaustive]
+pub struct SmartPointerDef {
+    /// vtable for interacting with the smart pointer
+    pub vtable: &'static SmartPointerVTable,
+    /// shape of the pointee
+    pub t: &'static Shape,
+    /// strong/weak, atomic or not, etc.
+    pub flags: SmartPointerFlags,
+    /// which well-known smart pointer this is, if any
+    pub known: Option<KnownSmartPointer>,
+}
+
+impl SmartPointerDef {
+    /// Returns a builder for SmartPointerDef
+    pub const fn builder() -> SmartPointerDefBuilder {
+        SmartPointerDefBuilder::new()
+    }
+
+    /// Returns true if this pointer keeps its pointee alive
+    pub const fn is_strong(&self) -> bool {
+        !self.flags.contains(SmartPointerFlags::WEAK)
+    }
+
+    /// Returns true if this pointer does not keep its pointee alive
+    pub const fn is_weak(&self) -> bool {
+        self.flags.contains(SmartPointerFlags::WEAK)
+    }
+
+    /// Returns true if the reference count (if any) is updated atomically
+    pub const fn is_atomic(&self) -> bool {
+        self.flags.contains(SmartPointerFlags::ATOMIC)
+    }
+}
+
+/// Builder for SmartPointerDef
+pub struct SmartPointerDefBuilder {
+    vtable: Option<&'static SmartPointerVTable>,
+    t: Option<&'static Shape>,
+    flags: SmartPointerFlags,
+    known: Option<KnownSmartPointer>,
+}
+
+impl SmartPointerDefBuilder {
+    /// Creates a new SmartPointerDefBuilder
+    #[allow(clippy::new_without_default)]
+    pub const fn new() -> Self {
+        Self {
+            vtable: None,
+            t: None,
+            flags: SmartPointerFlags::EMPTY,
+            known: None,
+        }
+    }
+
+    /// Sets the vtable for the SmartPointerDef
+    pub const fn vtable(mut self, vtable: &'static SmartPointerVTable) -> Self {
+        self.vtable = Some(vtable);
+        self
+    }
+
+    /// Sets the pointee shape for the SmartPointerDef
+    pub const fn t(mut self, t: &'static Shape) -> Self {
+        self.t = Some(t);
+        self
+    }
+
+    /// Sets the flags for the SmartPointerDef
+    pub const fn flags(mut self, flags: SmartPointerFlags) -> Self {
+        self.flags = flags;
+        self
+    }
+
+    /// Sets the well-known smart pointer kind for the SmartPointerDef
+    pub const fn known(mut self, known: KnownSmartPointer) -> Self {
+        self.known = Some(known);
+        self
+    }
+
+    /// Builds the SmartPointerDef
+    pub const fn build(self) -> SmartPointerDef {
+        SmartPointerDef {
+            vtable: self.vtable.unwrap(),
+            t: self.t.unwrap(),
+            flags: self.flags,
+            known: self.known,
+        }
+    }
+}
+
+bitflags::bitflags! {
+    /// Flags describing the ownership semantics of a smart pointer
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    pub struct SmartPointerFlags: u8 {
+        /// An empty set of flags: a strong, non-atomic pointer (e.g. `Box<T>`, `Rc<T>`)
+        const EMPTY = 0;
+
+        /// The pointer does not keep its pointee alive, and may dangle (e.g. `Weak<T>`)
+        const WEAK = 1 << 0;
+
+        /// The reference count is updated atomically (e.g. `Arc<T>`)
+        const ATOMIC = 1 << 1;
+    }
+}
+
+/// Smart pointers from the standard library that facet knows about
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+#[repr(C)]
+#[non_exhaustive]
+pub enum KnownSmartPointer {
+    /// `Box<T>`
+    Box,
+    /// `Rc<T>`
+    Rc,
+    /// `rc::Weak<T>`
+    RcWeak,
+    /// `Arc<T>`
+    Arc,
+    /// `sync::Weak<T>`
+    ArcWeak,
+}
+
+/// Borrow the pointee of a smart pointer
+///
+/// # Safety
+///
+/// The `this` parameter must point to aligned, initialized memory of the correct type.
+/// The returned pointer is valid for as long as `this` is.
+pub type SmartPointerBorrowFn = for<'ptr> unsafe fn(this: OpaqueConst<'ptr>) -> OpaqueConst<'ptr>;
+
+/// Allocate a new smart pointer around a value
+///
+/// # Safety
+///
+/// The `this` parameter must point to uninitialized memory of sufficient size.
+/// The function must properly initialize the memory.
+/// `value` is moved out of (with [`core::ptr::read`]) — it should be deallocated
+/// afterwards but NOT dropped.
+pub type SmartPointerNewIntoFn =
+    for<'ptr> unsafe fn(this: OpaqueUninit<'ptr>, value: OpaqueConst<'_>) -> Opaque<'ptr>;
+
+/// Virtual table for smart pointers
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+#[repr(C)]
+#[non_exhaustive]
+pub struct SmartPointerVTable {
+    /// cf. [`SmartPointerBorrowFn`] — `None` for weak pointers, which can't be
+    /// borrowed without upgrading them first.
+    pub borrow_fn: Option<SmartPointerBorrowFn>,
+
+    /// cf. [`SmartPointerNewIntoFn`] — `None` for weak pointers, which can't own
+    /// their pointee.
+    pub new_into_fn: Option<SmartPointerNewIntoFn>,
+}
+
+impl SmartPointerVTable {
+    /// Returns a builder for SmartPointerVTable
+    pub const fn builder() -> SmartPointerVTableBuilder {
+        SmartPointerVTableBuilder::new()
+    }
+}
+
+/// Builds a [`SmartPointerVTable`]
+pub struct SmartPointerVTableBuilder {
+    borrow_fn: Option<SmartPointerBorrowFn>,
+    new_into_fn: Option<SmartPointerNewIntoFn>,
+}
+
+impl SmartPointerVTableBuilder {
+    /// Creates a new [`SmartPointerVTableBuilder`] with all fields set to `None`.
+    #[allow(clippy::new_without_default)]
+    pub const fn new() -> Self {
+        Self {
+            borrow_fn: None,
+            new_into_fn: None,
+        }
+    }
+
+    /// Sets the borrow_fn field
+    pub const fn borrow(mut self, f: SmartPointerBorrowFn) -> Self {
+        self.borrow_fn = Some(f);
+        self
+    }
+
+    /// Sets the new_into_fn field
+    pub const fn new_into(mut self, f: SmartPointerNewIntoFn) -> Self {
+        self.new_into_fn = Some(f);
+        self
+    }
+
+    /// Builds the [`SmartPointerVTable`] from the current state of the builder.
+    pub const fn build(self) -> SmartPointerVTable {
+        SmartPointerVTable {
+            borrow_fn: self.borrow_fn,
+            new_into_fn: self.new_into_fn,
+        }
+    }
+}
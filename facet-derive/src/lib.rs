@@ -3,6 +3,9 @@
 
 mod process_enum;
 mod process_struct;
+mod rename_rule;
+
+use rename_rule::RenameRule;
 
 use unsynn::*;
 
@@ -19,6 +22,18 @@ keyword! {
     KMut = "mut";
     KFacet = "facet";
     KSensitive = "sensitive";
+    KRename = "rename";
+    KRenameAll = "rename_all";
+    KDefault = "default";
+    KSkip = "skip";
+    KSkipSerializing = "skip_serializing";
+    KSkipDeserializing = "skip_deserializing";
+    KSkipSerializingIf = "skip_serializing_if";
+    KTag = "tag";
+    KContent = "content";
+    KUntagged = "untagged";
+    KFlatten = "flatten";
+    KDenyUnknownFields = "deny_unknown_fields";
 }
 
 operator! {
@@ -68,9 +83,56 @@ unsynn! {
 
     enum FacetInner {
         Sensitive(KSensitive),
+        Rename(RenameInner),
+        RenameAll(RenameAllInner),
+        Default(DefaultInner),
+        Skip(KSkip),
+        SkipSerializing(KSkipSerializing),
+        SkipDeserializing(KSkipDeserializing),
+        SkipSerializingIf(SkipSerializingIfInner),
+        Tag(TagInner),
+        Content(ContentInner),
+        Untagged(KUntagged),
+        Flatten(KFlatten),
+        DenyUnknownFields(KDenyUnknownFields),
         Other(Vec<TokenTree>)
     }
 
+    struct TagInner {
+        _kw_tag: KTag,
+        _eq: Eq,
+        value: LiteralString,
+    }
+
+    struct ContentInner {
+        _kw_content: KContent,
+        _eq: Eq,
+        value: LiteralString,
+    }
+
+    struct SkipSerializingIfInner {
+        _kw_skip_serializing_if: KSkipSerializingIf,
+        _eq: Eq,
+        value: LiteralString,
+    }
+
+    struct DefaultInner {
+        _kw_default: KDefault,
+        value: Option<Cons<Eq, Vec<TokenTree>>>,
+    }
+
+    struct RenameInner {
+        _kw_rename: KRename,
+        _eq: Eq,
+        value: LiteralString,
+    }
+
+    struct RenameAllInner {
+        _kw_rename_all: KRenameAll,
+        _eq: Eq,
+        value: LiteralString,
+    }
+
     struct DocInner {
         _kw_doc: KDoc,
         _eq: Eq,
@@ -234,9 +296,7 @@ impl core::fmt::Display for AngleTokenTree {
         match &self.0 {
             Either::First(it) => {
                 write!(f, "<")?;
-                for it in it.second.iter() {
-                    write!(f, "{}", it.second)?;
-                }
+                write_token_seq(f, it.second.iter().map(|it| &it.second))?;
                 write!(f, ">")?;
             }
             Either::Second(it) => write!(f, "{}", it)?,
@@ -247,13 +307,29 @@ impl core::fmt::Display for AngleTokenTree {
     }
 }
 
+/// Writes a sequence of tokens back out. Words need a space between them, so that e.g.
+/// `&'static str` doesn't become `&'staticstr`, but punctuation (`::`, `'a`) has to stay glued.
+fn write_token_seq<'t>(
+    f: &mut core::fmt::Formatter<'_>,
+    tokens: impl Iterator<Item = &'t AngleTokenTree>,
+) -> core::fmt::Result {
+    let is_word_char = |c: char| c.is_alphanumeric() || c == '_';
+    let mut prev_ends_word = false;
+    for tt in tokens {
+        let tt = tt.to_string();
+        if prev_ends_word && tt.starts_with(is_word_char) {
+            write!(f, " ")?;
+        }
+        write!(f, "{}", tt)?;
+        prev_ends_word = tt.ends_with(is_word_char);
+    }
+    Ok(())
+}
+
 struct VerbatimDisplay<'a, C>(&'a VerbatimUntil<C>);
 impl<C> core::fmt::Display for VerbatimDisplay<'_, C> {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
-        for tt in self.0.0.iter() {
-            write!(f, "{}", tt.value.second)?;
-        }
-        Ok(())
+        write_token_seq(f, self.0.0.iter().map(|tt| &tt.value.second))
     }
 }
 
@@ -344,23 +420,178 @@ pub(crate) fn build_maybe_doc(attrs: &[Attribute]) -> String {
     }
 }
 
+/// Returns the rule set by `#[facet(rename_all = "...")]` on a struct or enum, if any.
+/// Unknown rules are reported as a `compile_error!` invocation.
+pub(crate) fn container_rename_all(
+    attrs: &[Attribute],
+) -> core::result::Result<Option<RenameRule>, String> {
+    let mut rule = None;
+    for attr in attrs {
+        if let AttributeInner::Facet(facet_attr) = &attr.body.content {
+            if let FacetInner::RenameAll(rename_all) = &facet_attr.inner.content {
+                let value = rename_all.value.as_str();
+                rule = Some(RenameRule::from_attr(value).ok_or_else(|| {
+                    let message = format!(
+                        "Unknown rename_all rule {value:?}, expected one of \"lowercase\", \"UPPERCASE\", \"PascalCase\", \"camelCase\", \"snake_case\", \"SCREAMING_SNAKE_CASE\", \"kebab-case\" or \"SCREAMING-KEBAB-CASE\""
+                    );
+                    format!("compile_error!({message:?});")
+                })?);
+            }
+        }
+    }
+    Ok(rule)
+}
+
+/// Whether the container has a `#[facet(deny_unknown_fields)]` attribute
+pub(crate) fn container_denies_unknown_fields(attrs: &[Attribute]) -> bool {
+    attrs.iter().any(|attr| {
+        matches!(
+            &attr.body.content,
+            AttributeInner::Facet(facet_attr)
+                if matches!(facet_attr.inner.content, FacetInner::DenyUnknownFields(_))
+        )
+    })
+}
+
+/// Builds the `.tagging(...)` call for an enum from its `#[facet(tag = "...")]`,
+/// `#[facet(content = "...")]` and `#[facet(untagged)]` attributes.
+/// Invalid combinations are reported as a `compile_error!` invocation.
+pub(crate) fn container_tagging(attrs: &[Attribute]) -> core::result::Result<String, String> {
+    let mut tag = None;
+    let mut content = None;
+    let mut untagged = false;
+    for attr in attrs {
+        if let AttributeInner::Facet(facet_attr) = &attr.body.content {
+            match &facet_attr.inner.content {
+                FacetInner::Tag(inner) => tag = Some(inner.value.as_str().to_string()),
+                FacetInner::Content(inner) => content = Some(inner.value.as_str().to_string()),
+                FacetInner::Untagged(_) => untagged = true,
+                _ => {}
+            }
+        }
+    }
+
+    let error = |message: &str| format!("compile_error!({message:?});");
+    match (tag, content, untagged) {
+        (None, None, false) => Ok(String::new()),
+        (None, None, true) => Ok(".tagging(::facet::EnumTagging::Untagged)".to_string()),
+        (Some(tag), None, false) => Ok(format!(
+            ".tagging(::facet::EnumTagging::Internal {{ tag: {tag:?} }})"
+        )),
+        (Some(tag), Some(content), false) => Ok(format!(
+            ".tagging(::facet::EnumTagging::Adjacent {{ tag: {tag:?}, content: {content:?} }})"
+        )),
+        (None, Some(_), _) => Err(error(
+            "#[facet(content = \"...\")] requires #[facet(tag = \"...\")]",
+        )),
+        (Some(_), _, true) => Err(error(
+            "#[facet(untagged)] cannot be combined with #[facet(tag = \"...\")]",
+        )),
+    }
+}
+
+/// Builds the `.rename(...)` call for a field or variant: an explicit `#[facet(rename = "...")]`
+/// wins over the container's `rename_all` rule.
+pub(crate) fn build_maybe_rename(
+    name: &str,
+    attrs: &[Attribute],
+    rename_all: Option<RenameRule>,
+) -> String {
+    let explicit = attrs.iter().find_map(|attr| match &attr.body.content {
+        AttributeInner::Facet(facet_attr) => match &facet_attr.inner.content {
+            FacetInner::Rename(rename) => Some(rename.value.as_str().to_string()),
+            _ => None,
+        },
+        _ => None,
+    });
+
+    // the explicit rename is written back as the literal it was given as, escapes included
+    match (explicit, rename_all) {
+        (Some(rename), _) if rename != name => format!(".rename(\"{rename}\")"),
+        (None, Some(rule)) => match rule.apply(name) {
+            rename if rename != name => format!(".rename({rename:?})"),
+            _ => String::new(),
+        },
+        _ => String::new(),
+    }
+}
+
 pub(crate) fn gen_struct_field(
     field_name: &str,
+    field_type: &str,
     struct_name: &str,
     generics: &str,
     attrs: &[Attribute],
+    rename_all: Option<RenameRule>,
 ) -> String {
     // Determine field flags
-    let mut flags = "::facet::FieldFlags::EMPTY";
+    let mut flags = vec![];
+    let mut maybe_default = String::new();
+    let mut maybe_skip_serializing_if = String::new();
     let mut attribute_list: Vec<String> = vec![];
     let mut doc_lines: Vec<&str> = vec![];
     for attr in attrs {
         match &attr.body.content {
             AttributeInner::Facet(facet_attr) => match &facet_attr.inner.content {
                 FacetInner::Sensitive(_ksensitive) => {
-                    flags = "::facet::FieldFlags::SENSITIVE";
+                    flags.push("::facet::FieldFlags::SENSITIVE");
                     attribute_list.push("::facet::FieldAttribute::Sensitive".to_string());
                 }
+                FacetInner::Default(default) => {
+                    flags.push("::facet::FieldFlags::DEFAULT");
+                    if let Some(value) = &default.value {
+                        // the function is named by a path, quoted or not, like `skip_serializing_if`
+                        let path = match &value.second[..] {
+                            [TokenTree::Literal(lit)] => {
+                                let lit = lit.to_string();
+                                match lit.strip_prefix('"').and_then(|s| s.strip_suffix('"')) {
+                                    Some(path) => path.to_string(),
+                                    None => {
+                                        let message = format!(
+                                            "#[facet(default = ...)] takes the path of a function returning the default, e.g. `default = \"my_default\"`, not {lit}"
+                                        );
+                                        format!("(|| compile_error!({message:?}))")
+                                    }
+                                }
+                            }
+                            _ => value.second.tokens_to_string(),
+                        };
+                        maybe_default = format!(
+                            ".default(|target| unsafe {{ target.put::<{field_type}>({path}()) }})"
+                        );
+                    }
+                }
+                FacetInner::Skip(_) => {
+                    flags.push("::facet::FieldFlags::SKIP_SERIALIZING");
+                    flags.push("::facet::FieldFlags::SKIP_DESERIALIZING");
+                    flags.push("::facet::FieldFlags::DEFAULT");
+                }
+                FacetInner::SkipSerializing(_) => {
+                    flags.push("::facet::FieldFlags::SKIP_SERIALIZING");
+                }
+                FacetInner::SkipDeserializing(_) => {
+                    // the field is filled in with its default instead
+                    flags.push("::facet::FieldFlags::SKIP_DESERIALIZING");
+                    flags.push("::facet::FieldFlags::DEFAULT");
+                }
+                FacetInner::Flatten(_) => {
+                    flags.push("::facet::FieldFlags::FLATTEN");
+                }
+                FacetInner::SkipSerializingIf(skip_if) => {
+                    maybe_skip_serializing_if = format!(
+                        ".skip_serializing_if(|value| unsafe {{ {}(value.as_ref::<{field_type}>()) }})",
+                        skip_if.value.as_str()
+                    );
+                }
+                FacetInner::Rename(_) | FacetInner::RenameAll(_) => {
+                    // handled by `build_maybe_rename`
+                }
+                FacetInner::Tag(_) | FacetInner::Content(_) | FacetInner::Untagged(_) => {
+                    // container attributes, handled by `container_tagging`
+                }
+                FacetInner::DenyUnknownFields(_) => {
+                    // container attribute, handled by `process_struct`
+                }
                 FacetInner::Other(tt) => {
                     attribute_list.push(format!(
                         r#"::facet::FieldAttribute::Arbitrary({:?})"#,
@@ -377,7 +608,14 @@ pub(crate) fn gen_struct_field(
             }
         }
     }
+    let flags = match flags.split_first() {
+        Some((first, rest)) => rest.iter().fold(first.to_string(), |acc, flag| {
+            format!("{acc}.union({flag})")
+        }),
+        None => "::facet::FieldFlags::EMPTY".to_string(),
+    };
     let attributes = attribute_list.join(",");
+    let maybe_rename = build_maybe_rename(field_name, attrs, rename_all);
 
     let maybe_field_doc = if doc_lines.is_empty() {
         String::new()
@@ -389,10 +627,13 @@ pub(crate) fn gen_struct_field(
     format!(
         "::facet::Field::builder()
     .name(\"{field_name}\")
+    {maybe_rename}
     .shape(::facet::shape_of(&|s: {struct_name}<{generics}>| s.{field_name}))
     .offset(::core::mem::offset_of!({struct_name}<{generics}>, {field_name}))
     .flags({flags})
     .attributes(&[{attributes}])
+    {maybe_default}
+    {maybe_skip_serializing_if}
     {maybe_field_doc}
     .build()"
     )
@@ -446,3 +687,63 @@ fn generics_split_for_impl(generics: Option<&GenericParams>) -> (String, String)
     let generics_target = generics_target.join(", ");
     (generics_impl, generics_target)
 }
+
+/// Builds the `where` clause of the generated `Facet<'__facet>` impl.
+///
+/// On top of the user's own clauses, `'__facet` must outlive every lifetime parameter, every
+/// type parameter the user didn't bound must be `Facet<'__facet>`, and so must every field
+/// type that mentions a lifetime (e.g. `&'static str`), so that a deserializer can't store borrows from a
+/// shorter-lived input in those fields.
+fn build_impl_where_clauses(
+    clauses: Option<&WhereClauses>,
+    generics: Option<&GenericParams>,
+    field_types: impl IntoIterator<Item = String>,
+) -> String {
+    let mut predicates = Vec::new();
+    if let Some(generics) = generics {
+        for param in generics.params.0.iter() {
+            match &param.value {
+                GenericParam::Lifetime { name, .. } => {
+                    predicates.push(format!("'__facet: {name}"));
+                }
+                // users that already bound the parameter by `Facet` pick its lifetime themselves,
+                // adding ours on top would make the two bounds ambiguous
+                GenericParam::Type { name, bounds, .. } => {
+                    let name = name.to_string();
+                    let bounded_in_params = bounds
+                        .as_ref()
+                        .is_some_and(|b| VerbatimDisplay(&b.second).to_string().contains("Facet"));
+                    let bounded_in_clauses = clauses.is_some_and(|c| {
+                        c.clauses.0.iter().any(|clause| {
+                            VerbatimDisplay(&clause.value._pred).to_string() == name
+                                && VerbatimDisplay(&clause.value.bounds)
+                                    .to_string()
+                                    .contains("Facet")
+                        })
+                    });
+                    if !bounded_in_params && !bounded_in_clauses {
+                        predicates.push(format!("{name}: ::facet::Facet<'__facet>"));
+                    }
+                }
+                GenericParam::Const { .. } => {}
+            }
+        }
+    }
+    for field_type in field_types {
+        if field_type.contains('\'') {
+            let predicate = format!("{field_type}: ::facet::Facet<'__facet>");
+            if !predicates.contains(&predicate) {
+                predicates.push(predicate);
+            }
+        }
+    }
+
+    let user_clauses = clauses.map_or(String::new(), ToString::to_string);
+    let user_clauses = user_clauses.trim_end().trim_end_matches(',');
+    match (user_clauses.is_empty(), predicates.is_empty()) {
+        (true, true) => String::new(),
+        (true, false) => format!("where {}", predicates.join(", ")),
+        (false, true) => user_clauses.to_string(),
+        (false, false) => format!("{user_clauses}, {}", predicates.join(", ")),
+    }
+}
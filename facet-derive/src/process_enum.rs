@@ -70,6 +70,20 @@ type EnumVariant = Delimited<EnumVariantLike, Comma>;
 /// ```
 pub(crate) fn process_enum(parsed: Enum) -> proc_macro::TokenStream {
     let enum_name = parsed.name.to_string();
+    let rename_all = match container_rename_all(&parsed.attributes) {
+        Ok(rule) => rule,
+        Err(err) => return err.into_token_stream().into(),
+    };
+    let maybe_tagging = match container_tagging(&parsed.attributes) {
+        Ok(tagging) => tagging,
+        Err(err) => return err.into_token_stream().into(),
+    };
+    if container_denies_unknown_fields(&parsed.attributes) {
+        let message = "#[facet(deny_unknown_fields)] is only supported on structs";
+        return format!("compile_error!({message:?});")
+            .into_token_stream()
+            .into();
+    }
     let (generics_def, generics_use) = generics_split_for_impl(parsed.generics.as_ref());
     let where_clauses = parsed
         .clauses
@@ -136,6 +150,7 @@ pub(crate) fn process_enum(parsed: Enum) -> proc_macro::TokenStream {
                 &generics_def,
                 &generics_use,
                 &where_clauses,
+                rename_all,
             )
         }
         (false, Some(discriminant_type)) => process_primitive_enum(
@@ -145,6 +160,7 @@ pub(crate) fn process_enum(parsed: Enum) -> proc_macro::TokenStream {
             &generics_def,
             &generics_use,
             &where_clauses,
+            rename_all,
         ),
         _ => {
             return r#"compile_error!("Enums must have an explicit representation (e.g. #[repr(u8)] or #[repr(C)]) to be used with Facet")"#
@@ -169,6 +185,33 @@ pub(crate) fn process_enum(parsed: Enum) -> proc_macro::TokenStream {
         String::new()
     };
     let maybe_container_doc = build_maybe_doc(&parsed.attributes);
+    let field_types = parsed
+        .body
+        .content
+        .0
+        .iter()
+        .flat_map(|variant| match &variant.value {
+            EnumVariantLike::Unit(_) => vec![],
+            EnumVariantLike::Tuple(tuple) => tuple
+                .fields
+                .content
+                .0
+                .iter()
+                .map(|field| VerbatimDisplay(&field.value.typ).to_string())
+                .collect(),
+            EnumVariantLike::Struct(struct_var) => struct_var
+                .fields
+                .content
+                .0
+                .iter()
+                .map(|field| VerbatimDisplay(&field.value.typ).to_string())
+                .collect(),
+        });
+    let impl_where_clauses = build_impl_where_clauses(
+        parsed.clauses.as_ref(),
+        parsed.generics.as_ref(),
+        field_types,
+    );
 
     // Generate the impl
     let output = format!(
@@ -176,7 +219,7 @@ pub(crate) fn process_enum(parsed: Enum) -> proc_macro::TokenStream {
 {static_decl}
 
 #[automatically_derived]
-unsafe impl<{generics_def}> ::facet::Facet for {enum_name}<{generics_use}> {where_clauses} {{
+unsafe impl<'__facet, {generics_def}> ::facet::Facet<'__facet> for {enum_name}<{generics_use}> {impl_where_clauses} {{
     const SHAPE: &'static ::facet::Shape = &const {{
         // Define all shadow structs at the beginning of the const block
         // to ensure they're in scope for offset_of! macros
@@ -194,6 +237,7 @@ unsafe impl<{generics_def}> ::facet::Facet for {enum_name}<{generics_use}> {wher
                 // which are now defined above
                 .variants(&const {{[ {variants} ]}})
                 .repr(::facet::EnumRepr::{repr_type})
+                {maybe_tagging}
                 .build()))
             {maybe_container_doc}
             .build()
@@ -223,6 +267,7 @@ fn process_c_style_enum(
     generics_def: &str,
     generics_use: &str,
     where_clauses: &str,
+    rename_all: Option<RenameRule>,
 ) -> ProcessedEnumBody {
     // Collect shadow struct definitions separately from variant expressions
     let mut shadow_struct_defs = Vec::new();
@@ -282,6 +327,7 @@ fn process_c_style_enum(
             EnumVariantLike::Unit(unit) => {
                 let variant_name = unit.name.to_string();
                 let maybe_doc = build_maybe_doc(&unit.attributes);
+                let maybe_rename = build_maybe_rename(&variant_name, &unit.attributes, rename_all);
 
                 // Generate shadow struct for this tuple variant to calculate offsets
                 let shadow_struct_name = format!("__ShadowField{enum_name}_{variant_name}");
@@ -293,6 +339,7 @@ fn process_c_style_enum(
                 variant_expressions.push(format!(
                     "::facet::Variant::builder()
                     .name({variant_name:?})
+                    {maybe_rename}
                     .discriminant(Some({discriminant_value}))
                     .offset(::core::mem::offset_of!({shadow_repr_name}, _fields))
                     .kind(::facet::VariantKind::Unit)
@@ -303,6 +350,7 @@ fn process_c_style_enum(
             EnumVariantLike::Tuple(tuple) => {
                 let variant_name = tuple.name.to_string();
                 let maybe_doc = build_maybe_doc(&tuple.attributes);
+                let maybe_rename = build_maybe_rename(&variant_name, &tuple.attributes, rename_all);
 
                 // Generate shadow struct for this tuple variant to calculate offsets
                 let shadow_struct_name = format!("__ShadowField{enum_name}_{variant_name}");
@@ -335,11 +383,14 @@ fn process_c_style_enum(
                     .enumerate()
                     .map(|(idx, field)| {
                         let field_name = format!("_{idx}");
+                        let field_type = VerbatimDisplay(&field.value.typ).to_string();
                         gen_struct_field(
                             &field_name,
+                            &field_type,
                             &shadow_struct_name,
                             generics_use,
                             &field.value.attributes,
+                            None,
                         )
                     })
                     .collect::<Vec<String>>()
@@ -354,6 +405,7 @@ fn process_c_style_enum(
 
                         ::facet::Variant::builder()
                             .name({variant_name:?})
+                            {maybe_rename}
                             .discriminant(Some({discriminant_value}))
                             .offset(::core::mem::offset_of!({shadow_repr_name}, _fields))
                             .kind(::facet::VariantKind::Tuple {{ fields }})
@@ -365,6 +417,8 @@ fn process_c_style_enum(
             EnumVariantLike::Struct(struct_var) => {
                 let variant_name = struct_var.name.to_string();
                 let maybe_doc = build_maybe_doc(&struct_var.attributes);
+                let maybe_rename =
+                    build_maybe_rename(&variant_name, &struct_var.attributes, rename_all);
 
                 // Generate shadow struct for this struct variant to calculate offsets
                 let shadow_struct_name = format!("__ShadowField{}_{}", enum_name, variant_name);
@@ -396,11 +450,14 @@ fn process_c_style_enum(
                     .iter()
                     .map(|field| {
                         let field_name = field.value.name.to_string();
+                        let field_type = VerbatimDisplay(&field.value.typ).to_string();
                         gen_struct_field(
                             &field_name,
+                            &field_type,
                             &shadow_struct_name,
                             generics_use,
                             &field.value.attributes,
+                            None,
                         )
                     })
                     .collect::<Vec<String>>()
@@ -415,6 +472,7 @@ fn process_c_style_enum(
 
                         ::facet::Variant::builder()
                             .name({variant_name:?})
+                            {maybe_rename}
                             .discriminant(Some({discriminant_value}))
                             .offset(::core::mem::offset_of!({shadow_repr_name}, _fields))
                             .kind(::facet::VariantKind::Struct {{ fields }})
@@ -450,6 +508,7 @@ fn process_primitive_enum(
     generics_def: &str,
     generics_use: &str,
     where_clauses: &str,
+    rename_all: Option<RenameRule>,
 ) -> ProcessedEnumBody {
     // Collect shadow struct definitions separately from variant expressions
     let mut shadow_struct_defs = Vec::new();
@@ -461,10 +520,12 @@ fn process_primitive_enum(
             EnumVariantLike::Unit(unit) => {
                 let variant_name = unit.name.to_string();
                 let maybe_doc = build_maybe_doc(&unit.attributes);
+                let maybe_rename = build_maybe_rename(&variant_name, &unit.attributes, rename_all);
 
                 variant_expressions.push(format!(
                     "::facet::Variant::builder()
                     .name({variant_name:?})
+                    {maybe_rename}
                     .discriminant(Some({discriminant_value}))
                     .offset(0)
                     .kind(::facet::VariantKind::Unit)
@@ -475,6 +536,7 @@ fn process_primitive_enum(
             EnumVariantLike::Tuple(tuple) => {
                 let variant_name = tuple.name.to_string();
                 let maybe_doc = build_maybe_doc(&tuple.attributes);
+                let maybe_rename = build_maybe_rename(&variant_name, &tuple.attributes, rename_all);
 
                 // Generate shadow struct for this tuple variant to calculate offsets
                 let shadow_struct_name = format!("__Shadow{}_{}", enum_name, variant_name);
@@ -508,11 +570,14 @@ fn process_primitive_enum(
                     .enumerate()
                     .map(|(idx, field)| {
                         let field_name = format!("_{idx}");
+                        let field_type = VerbatimDisplay(&field.value.typ).to_string();
                         gen_struct_field(
                             &field_name,
+                            &field_type,
                             &shadow_struct_name,
                             generics_use,
                             &field.value.attributes,
+                            None,
                         )
                     })
                     .collect::<Vec<String>>()
@@ -527,6 +592,7 @@ fn process_primitive_enum(
 
                         ::facet::Variant::builder()
                             .name({variant_name:?})
+                            {maybe_rename}
                             .discriminant(Some({discriminant_value}))
                             .offset(0)
                             .kind(::facet::VariantKind::Tuple {{ fields }})
@@ -538,6 +604,8 @@ fn process_primitive_enum(
             EnumVariantLike::Struct(struct_var) => {
                 let variant_name = struct_var.name.to_string();
                 let maybe_doc = build_maybe_doc(&struct_var.attributes);
+                let maybe_rename =
+                    build_maybe_rename(&variant_name, &struct_var.attributes, rename_all);
 
                 // Generate shadow struct for this struct variant to calculate offsets
                 let shadow_struct_name = format!("__Shadow{}_{}", enum_name, variant_name);
@@ -570,11 +638,14 @@ fn process_primitive_enum(
                     .iter()
                     .map(|field| {
                         let field_name = field.value.name.to_string();
+                        let field_type = VerbatimDisplay(&field.value.typ).to_string();
                         gen_struct_field(
                             &field_name,
+                            &field_type,
                             &shadow_struct_name,
                             generics_use,
                             &field.value.attributes,
+                            None,
                         )
                     })
                     .collect::<Vec<String>>()
@@ -591,6 +662,7 @@ fn process_primitive_enum(
 
                         ::facet::Variant::builder()
                             .name({variant_name:?})
+                            {maybe_rename}
                             .discriminant(Some({discriminant_value}))
                             .offset(0)
                             .kind(::facet::VariantKind::Struct {{ fields }})
@@ -11,6 +11,10 @@ use super::*;
 /// ```
 pub(crate) fn process_struct(parsed: Struct) -> proc_macro::TokenStream {
     let struct_name = parsed.name.to_string();
+    let rename_all = match container_rename_all(&parsed.attributes) {
+        Ok(rule) => rule,
+        Err(err) => return err.into_token_stream().into(),
+    };
 
     // Generate field definitions
     let (generics_def, generics_use) = generics_split_for_impl(parsed.generics.as_ref());
@@ -26,11 +30,14 @@ pub(crate) fn process_struct(parsed: Struct) -> proc_macro::TokenStream {
                 .iter()
                 .map(|field| {
                     let field_name = field.value.name.to_string();
+                    let field_type = VerbatimDisplay(&field.value.typ).to_string();
                     gen_struct_field(
                         &field_name,
+                        &field_type,
                         &struct_name,
                         &generics_use,
                         &field.value.attributes,
+                        rename_all,
                     )
                 })
                 .collect::<Vec<String>>()
@@ -49,11 +56,14 @@ pub(crate) fn process_struct(parsed: Struct) -> proc_macro::TokenStream {
                 .enumerate()
                 .map(|(index, field)| {
                     let field_name = format!("{index}");
+                    let field_type = VerbatimDisplay(&field.value.typ).to_string();
                     gen_struct_field(
                         &field_name,
+                        &field_type,
                         &struct_name,
                         &generics_use,
                         &field.value.attributes,
+                        None,
                     )
                 })
                 .collect::<Vec<String>>()
@@ -72,7 +82,28 @@ pub(crate) fn process_struct(parsed: Struct) -> proc_macro::TokenStream {
         String::new()
     };
     let maybe_container_doc = build_maybe_doc(&parsed.attributes);
-    let where_clauses = where_clauses.map_or(String::new(), ToString::to_string);
+    let maybe_deny_unknown_fields = if container_denies_unknown_fields(&parsed.attributes) {
+        ".deny_unknown_fields(true)"
+    } else {
+        ""
+    };
+    let field_types: Vec<String> = match &parsed.kind {
+        StructKind::Struct { fields, .. } => fields
+            .content
+            .0
+            .iter()
+            .map(|field| VerbatimDisplay(&field.value.typ).to_string())
+            .collect(),
+        StructKind::TupleStruct { fields, .. } => fields
+            .content
+            .0
+            .iter()
+            .map(|field| VerbatimDisplay(&field.value.typ).to_string())
+            .collect(),
+        StructKind::UnitStruct { .. } => vec![],
+    };
+    let where_clauses =
+        build_impl_where_clauses(where_clauses, parsed.generics.as_ref(), field_types);
 
     // Generate the impl
     let output = format!(
@@ -80,7 +111,7 @@ pub(crate) fn process_struct(parsed: Struct) -> proc_macro::TokenStream {
 {static_decl}
 
 #[automatically_derived]
-unsafe impl<{generics_def}> ::facet::Facet for {struct_name}<{generics_use}> {where_clauses} {{
+unsafe impl<'__facet, {generics_def}> ::facet::Facet<'__facet> for {struct_name}<{generics_use}> {where_clauses} {{
     const SHAPE: &'static ::facet::Shape = &const {{
         let fields: &'static [::facet::Field] = &const {{[{fields}]}};
 
@@ -94,6 +125,7 @@ unsafe impl<{generics_def}> ::facet::Facet for {struct_name}<{generics_use}> {wh
             .def(::facet::Def::Struct(::facet::StructDef::builder()
                 .kind({kind})
                 .fields(fields)
+                {maybe_deny_unknown_fields}
                 .build()))
             {maybe_container_doc}
             .build()
@@ -1,9 +1,7 @@
-use std::num::NonZero;
+use crate::parser::{JsonParseError, JsonParseErrorKind, JsonParseErrorWithContext, JsonParser};
 
-use crate::parser::{JsonParseErrorKind, JsonParseErrorWithContext, JsonParser};
-
-use facet_core::{Facet, Opaque, OpaqueUninit};
-use facet_reflect::{PokeList, PokeMap, PokeStruct, PokeUninit, PokeValueUninit};
+use facet_core::{Facet, Opaque};
+use facet_reflect::{DeserializeError, Deserializer, PokeUninit, Token, deserialize_opaque};
 use log::trace;
 
 /// Deserializes a JSON string into a value of type `T` that implements `Facet`.
@@ -27,346 +25,294 @@ use log::trace;
 /// let json = r#"{"name":"Alice","age":30}"#;
 /// let person: Person = facet_json::from_str(json).unwrap();
 /// ```
-pub fn from_str<T: Facet>(json: &str) -> Result<T, JsonParseErrorWithContext<'_>> {
+///
+/// `&str` and `Cow<str>` fields borrow straight from `json` when the string has no escape
+/// sequences. A `Cow<str>` falls back to an owned string otherwise, while a `&str` fails with
+/// [`JsonParseErrorKind::EscapedBorrowedString`].
+/// ```
+/// # use facet::Facet;
+/// # use std::borrow::Cow;
+/// #[derive(Facet)]
+/// struct Line<'a> {
+///     level: &'a str,
+///     message: Cow<'a, str>,
+/// }
+///
+/// let json = r#"{"level":"info","message":"a \"quoted\" word"}"#;
+/// let line: Line = facet_json::from_str(json).unwrap();
+/// assert_eq!(line.level, "info");
+/// assert!(matches!(line.message, Cow::Owned(_)));
+/// ```
+///
+/// Borrowed fields can't outlive the input:
+/// ```compile_fail
+/// # use facet::Facet;
+/// #[derive(Facet)]
+/// struct Static {
+///     name: &'static str,
+/// }
+///
+/// let json = String::from(r#"{"name":"Alice"}"#);
+/// let s: Static = facet_json::from_str(&json).unwrap();
+/// ```
+pub fn from_str<'input, T: Facet<'input>>(
+    json: &'input str,
+) -> Result<T, JsonParseErrorWithContext<'input>> {
+    from_str_with_options(json, DeserializeOptions::default())
+}
+
+/// Deserializes a JSON string into a value of type `T`, like [`from_str`], with the given
+/// options.
+///
+/// # Example
+/// ```
+/// # use facet::Facet;
+/// # use facet_json::{DeserializeOptions, JsonParseErrorKind};
+/// # #[derive(Facet, Debug)]
+/// # struct Person { name: String, age: u64 }
+/// let json = r#"{"name":"Alice","agee":30}"#;
+/// let options = DeserializeOptions::default().with_deny_unknown_fields(true);
+/// let err = facet_json::from_str_with_options::<Person>(json, options).unwrap_err();
+/// assert!(matches!(
+///     err.error.kind,
+///     JsonParseErrorKind::UnknownField { suggestion: Some("age"), .. }
+/// ));
+/// ```
+pub fn from_str_with_options<'input, T: Facet<'input>>(
+    json: &'input str,
+    options: DeserializeOptions,
+) -> Result<T, JsonParseErrorWithContext<'input>> {
     let (poke, _guard) = PokeUninit::alloc::<T>();
-    let opaque = from_str_opaque(poke, json)?;
+    // SAFETY: `T: Facet<'input>`, so it may hold borrows from `json`
+    let opaque = unsafe { from_str_opaque_with_options(poke, json, options)? };
     Ok(unsafe { opaque.read::<T>() })
 }
 
 /// Deserialize a `Poke` object from a JSON string.
-pub fn from_str_opaque<'input, 'mem>(
+///
+/// # Safety
+///
+/// `&str` and `Cow<str>` values may borrow from `json`: the deserialized value must not be
+/// used after `json` is dropped, i.e. its type must be `Facet<'input>`.
+pub unsafe fn from_str_opaque<'input, 'mem>(
+    poke: PokeUninit<'mem>,
+    json: &'input str,
+) -> Result<Opaque<'mem>, JsonParseErrorWithContext<'input>> {
+    unsafe { from_str_opaque_with_options(poke, json, DeserializeOptions::default()) }
+}
+
+/// Deserialize a `Poke` object from a JSON string, with the given options.
+///
+/// # Safety
+///
+/// See [`from_str_opaque`].
+pub unsafe fn from_str_opaque_with_options<'input, 'mem>(
     poke: PokeUninit<'mem>,
     json: &'input str,
+    options: DeserializeOptions,
 ) -> Result<Opaque<'mem>, JsonParseErrorWithContext<'input>> {
     trace!("Starting JSON deserialization");
     let mut parser = JsonParser::new(json);
-    deserialize_value(&mut parser, poke)
+    unsafe { deserialize_value(&mut parser, options, poke) }
+        .map_err(|error| JsonParseErrorWithContext { error, input: json })
 }
 
-macro_rules! int {
-    ($parser:expr, $parsed:expr, $pv:expr, $type:ty) => {
-        if $pv.shape().is_type::<$type>() {
-            let n = <$type>::try_from($parsed?).map_err(|e| {
-                $parser.make_error(JsonParseErrorKind::Custom(format!(
-                    "Invalid value for {}: {e}",
-                    stringify!($type)
-                )))
-            })?;
-            return Ok($pv.put(n));
-        }
-
-        if $pv.shape().is_type::<NonZero<$type>>() {
-            let n = <$type>::try_from($parsed?).map_err(|e| {
-                $parser.make_error(JsonParseErrorKind::Custom(format!(
-                    "Invalid value for core::num::NonZero<{}>: {e}",
-                    stringify!($type)
-                )))
-            })?;
-            let n = NonZero::new(n).ok_or_else(|| {
-                $parser.make_error(JsonParseErrorKind::Custom(format!(
-                    "Can't deserialize zero to core::num::NonZero<{}>",
-                    stringify!($type)
-                )))
-            })?;
-
-            return Ok($pv.put(n));
-        }
-    };
+/// Options for [`from_str_with_options`]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DeserializeOptions {
+    deny_unknown_fields: bool,
 }
 
-macro_rules! unsigneds {
-    ($parser:expr, $pv:expr, $type:ty, $($types:ty),*) => {
-        int!($parser, $parser.parse_u64(), $pv, $type);
-        unsigneds!($parser, $pv, $($types),*);
-    };
-    ($parser:expr, $pv:expr, $type:ty) => {
-        int!($parser, $parser.parse_u64(), $pv, $type);
-    };
+impl DeserializeOptions {
+    /// Rejects object keys that don't match any field, for every struct, as if they all had
+    /// a `#[facet(deny_unknown_fields)]` attribute. By default, unknown keys are skipped.
+    pub fn with_deny_unknown_fields(mut self, deny_unknown_fields: bool) -> Self {
+        self.deny_unknown_fields = deny_unknown_fields;
+        self
+    }
 }
 
-macro_rules! signeds {
-    ($parser:expr, $pv:expr, $type:ty, $($types:ty),*) => {
-        int!($parser, $parser.parse_i64(), $pv, $type);
-        signeds!($parser, $pv, $($types),*);
-    };
-    ($parser:expr, $pv:expr, $type:ty) => {
-        int!($parser, $parser.parse_i64(), $pv, $type);
+/// Deserializes the next value of `parser` into the value `poke` points to. Errors carry the
+/// path and shape of the value that was being deserialized when they happened.
+///
+/// # Safety
+///
+/// See [`from_str_opaque`].
+pub(crate) unsafe fn deserialize_value<'mem>(
+    parser: &mut JsonParser<'_>,
+    options: DeserializeOptions,
+    poke: PokeUninit<'mem>,
+) -> Result<Opaque<'mem>, JsonParseError> {
+    let mut de = JsonDeserializer {
+        parser,
+        options,
+        open: Vec::new(),
+        token_start: 0,
     };
+    unsafe { deserialize_opaque(poke, &mut de) }.map_err(|e| JsonParseError {
+        path: e.path,
+        shape: Some(e.shape),
+        ..e.error
+    })
 }
 
-/// Deserializes a value from JSON using an iterative approach.
-///
-/// This function takes a JSON parser and a Poke object and deserializes the JSON
-/// into the Poke object. It uses an iterative approach with a stack to avoid
-/// recursion.
-pub(crate) fn deserialize_value<'input, 'mem>(
-    parser: &mut JsonParser<'input>,
-    root_poke: PokeUninit<'mem>,
-) -> Result<Opaque<'mem>, JsonParseErrorWithContext<'input>> {
-    use std::collections::VecDeque;
-
-    enum StackItem<'mem> {
-        Value {
-            poke: PokeUninit<'mem>,
-        },
-        FinishStruct {
-            ps: PokeStruct<'mem>,
-        },
-        StructField {
-            key: String,
-        },
-        AfterStructField {
-            index: usize,
-        },
-        FinishList {
-            pl: PokeList<'mem>,
-        },
-        AfterListItem {
-            item: OpaqueUninit<'mem>,
-        },
-        FinishMap {
-            pm: PokeMap<'mem>,
-        },
-        AfterMapValue {
-            key: String,
-            value: OpaqueUninit<'mem>,
-        },
-    }
-
-    let mut result = None;
-    let mut stack = VecDeque::new();
-    stack.push_back(StackItem::Value { poke: root_poke });
-
-    while let Some(item) = stack.pop_front() {
-        match item {
-            StackItem::Value { poke } => {
-                let shape = poke.shape();
-                trace!("Deserializing {shape}");
-
-                match poke {
-                    PokeUninit::Scalar(pv) => {
-                        trace!("Deserializing \x1b[1;36mscalar\x1b[0m");
-                        fn aux<'input, 'mem>(
-                            parser: &mut JsonParser<'input>,
-                            pv: PokeValueUninit<'mem>,
-                        ) -> Result<Opaque<'mem>, JsonParseErrorWithContext<'input>>
-                        {
-                            if pv.shape().is_type::<String>() {
-                                let s = parser.parse_string()?;
-                                let data = pv.put(s);
-                                return Ok(data);
-                            }
-                            if pv.shape().is_type::<bool>() {
-                                let b = parser.parse_bool()?;
-                                return Ok(pv.put(b));
-                            }
-                            if pv.shape().is_type::<f32>() {
-                                let n = parser.parse_f64()? as f32;
-                                return Ok(pv.put(n));
-                            }
-                            if pv.shape().is_type::<f64>() {
-                                let n = parser.parse_f64()?;
-                                return Ok(pv.put(n));
-                            }
-
-                            unsigneds!(parser, pv, u8, u16, u32, u64, u128, usize);
-                            signeds!(parser, pv, i8, i16, i32, i64, i128, isize);
-
-                            panic!("Unknown scalar shape: {}", pv.shape());
-                        }
-
-                        result = Some(aux(parser, pv)?);
-                    }
-                    PokeUninit::Struct(ps) => {
-                        trace!("Deserializing \x1b[1;36mstruct\x1b[0m");
-                        stack.push_front(StackItem::FinishStruct { ps });
-
-                        let first_key = parser.expect_object_start()?;
-                        if let Some(key) = first_key {
-                            stack.push_front(StackItem::StructField { key });
-                        }
-                    }
-                    PokeUninit::List(list_uninit) => {
-                        trace!("Deserializing \x1b[1;36marray\x1b[0m");
-                        parser.expect_array_start()?;
-
-                        let pl = list_uninit.init(None).unwrap_or_else(|_| {
-                            panic!("Failed to initialize list");
-                        });
-
-                        let has_element = parser.parse_array_element()?;
-
-                        if let Some(true) = has_element {
-                            let item_shape = pl.def().t;
-                            let item_data =
-                                OpaqueUninit::new(unsafe { std::alloc::alloc(item_shape.layout) });
-                            let item_poke =
-                                unsafe { PokeUninit::unchecked_new(item_data, item_shape) };
-
-                            stack.push_front(StackItem::FinishList { pl });
-                            stack.push_front(StackItem::AfterListItem { item: item_data });
-                            stack.push_front(StackItem::Value { poke: item_poke });
-                        } else {
-                            stack.push_front(StackItem::FinishList { pl });
-                        }
-                    }
-                    PokeUninit::Map(map_uninit) => {
-                        trace!("Deserializing \x1b[1;36mhashmap\x1b[0m");
-                        let first_key = parser.expect_object_start()?;
-
-                        let pm = map_uninit.init(None).unwrap_or_else(|_| {
-                            panic!("Failed to initialize map"); // TODO: map errors
-                        });
-
-                        if let Some(key) = first_key {
-                            let value_shape = pm.def().v;
-                            let value_data =
-                                OpaqueUninit::new(unsafe { std::alloc::alloc(value_shape.layout) });
-                            let value_poke =
-                                unsafe { PokeUninit::unchecked_new(value_data, value_shape) };
-
-                            stack.push_front(StackItem::FinishMap { pm });
-                            stack.push_front(StackItem::AfterMapValue {
-                                key,
-                                value: value_data,
-                            });
-                            stack.push_front(StackItem::Value { poke: value_poke });
-                        } else {
-                            stack.push_front(StackItem::FinishMap { pm });
-                        }
-                    }
-                    PokeUninit::Enum(pe) => {
-                        trace!("Deserializing \x1b[1;36menum\x1b[0m");
-                        let variant_str = parser.parse_string()?;
+/// What comes next in an array or object being read
+#[derive(Clone, Copy)]
+enum Next {
+    /// An item of an array, or its end
+    Item,
+    /// The first key of an object, or its end
+    FirstKey,
+    /// A comma and the next key of an object, or its end
+    Key,
+    /// The value of an object entry
+    Value,
+}
 
-                        let pe = pe.set_variant_by_name(&variant_str).map_err(|_| {
-                            parser.make_error(JsonParseErrorKind::Custom(format!(
-                                "Invalid enum variant: {}",
-                                variant_str
-                            )))
-                        })?;
+/// Reads JSON values one token at a time, for [`deserialize_opaque`] to build
+struct JsonDeserializer<'p, 'input> {
+    parser: &'p mut JsonParser<'input>,
+    options: DeserializeOptions,
+    /// The arrays and objects being read, innermost last
+    open: Vec<Next>,
+    /// Where the last token started, which errors about it point at
+    token_start: usize,
+}
 
-                        trace!("Finished deserializing \x1b[1;36menum\x1b[0m");
-                        let opaque = pe.build_in_place();
-                        result = Some(opaque);
-                    }
-                    _ => todo!("unsupported poke type"),
+impl<'input> JsonDeserializer<'_, 'input> {
+    /// Reads up to the next value, unless the innermost container ends or the next token is
+    /// a key: then that token is returned instead.
+    fn before_value(&mut self) -> Result<Option<Token<'input>>, JsonParseError> {
+        let Some(next) = self.open.last_mut() else {
+            return Ok(None);
+        };
+        let key = match *next {
+            Next::Item => {
+                if self.parser.parse_array_element()? == Some(false) {
+                    self.open.pop();
+                    return Ok(Some(Token::EndList));
                 }
+                return Ok(None);
             }
-            StackItem::StructField { key } => {
-                trace!("Processing struct key: \x1b[1;33m{}\x1b[0m", key);
-
-                let ps = match stack.front_mut().unwrap() {
-                    StackItem::FinishStruct { ps } => ps,
-                    _ => unreachable!(),
-                };
-
-                match ps.field_by_name(&key) {
-                    Ok((index, field_poke)) => {
-                        trace!("Found field, it's at index: \x1b[1;33m{index}\x1b[0m");
-
-                        stack.push_front(StackItem::AfterStructField { index });
-
-                        stack.push_front(StackItem::Value { poke: field_poke });
-                    }
-                    Err(_) => {
-                        trace!("No field named \x1b[1;36m{}\x1b[0m", key);
-                        return Err(parser.make_error(JsonParseErrorKind::UnknownField(key)));
-                    }
-                }
+            Next::Value => {
+                *next = Next::Key;
+                return Ok(None);
             }
-            StackItem::AfterStructField { index } => {
-                trace!("After processing struct field at index: \x1b[1;33m{index}\x1b[0m");
-
-                let ps = match stack.front_mut().unwrap() {
-                    StackItem::FinishStruct { ps } => ps,
-                    _ => unreachable!(),
-                };
-
-                unsafe {
-                    ps.mark_initialized(index);
-                }
-
-                let next_key = parser.parse_object_key()?;
-                if let Some(next_key) = next_key {
-                    stack.push_front(StackItem::StructField { key: next_key });
-                }
+            Next::FirstKey => self.parser.parse_first_object_key()?,
+            Next::Key => self.parser.parse_object_key()?,
+        };
+        match key {
+            Some(key) => {
+                *next = Next::Value;
+                self.token_start = self.parser.key_position;
+                Ok(Some(Token::Str(key)))
             }
-            StackItem::FinishStruct { ps } => {
-                trace!("Finished deserializing \x1b[1;36mstruct\x1b[0m");
-
-                let opaque = ps.build_in_place();
-                result = Some(opaque);
+            None => {
+                self.open.pop();
+                Ok(Some(Token::EndMap))
             }
-            StackItem::AfterListItem { item } => {
-                trace!("Processing array item at index");
+        }
+    }
+}
 
-                let pl = match stack.front_mut().unwrap() {
-                    StackItem::FinishList { pl } => pl,
-                    _ => unreachable!(),
-                };
-                let item = unsafe { item.assume_init() };
-                unsafe {
-                    pl.push(item);
-                }
-                unsafe { std::alloc::dealloc(item.as_mut_byte_ptr(), pl.def().t.layout) };
+impl<'input> Deserializer<'input> for JsonDeserializer<'_, 'input> {
+    type Error = JsonParseError;
 
-                let has_next = parser.parse_array_element()?;
-                if let Some(true) = has_next {
-                    let item_shape = pl.def().t;
-                    let item_data =
-                        OpaqueUninit::new(unsafe { std::alloc::alloc(item_shape.layout) });
-                    let item_poke = unsafe { PokeUninit::unchecked_new(item_data, item_shape) };
+    fn next(&mut self) -> Result<Token<'input>, JsonParseError> {
+        self.parser.skip_whitespace();
+        self.token_start = self.parser.position;
+        if let Some(token) = self.before_value()? {
+            return Ok(token);
+        }
 
-                    stack.push_front(StackItem::AfterListItem { item: item_data });
-                    stack.push_front(StackItem::Value { poke: item_poke });
-                }
+        let byte = self.parser.peek_byte();
+        self.token_start = self.parser.position;
+        let token = match byte {
+            Some(b'"') => Token::Str(self.parser.parse_str()?),
+            Some(b'[') => {
+                self.parser.expect_array_start()?;
+                self.open.push(Next::Item);
+                Token::StartList(None)
             }
-            StackItem::FinishList { pl } => {
-                trace!("Finished deserializing \x1b[1;36marray\x1b[0m");
-                let opaque = pl.build_in_place();
-                result = Some(opaque);
+            Some(b'{') => {
+                self.parser.expect_opening_brace()?;
+                self.open.push(Next::FirstKey);
+                Token::StartMap(None)
             }
-            StackItem::AfterMapValue { mut key, value } => {
-                trace!("Processing hashmap key: \x1b[1;33m{}\x1b[0m", key);
-
-                let pm = match stack.front_mut().unwrap() {
-                    StackItem::FinishMap { pm } => pm,
-                    _ => unreachable!(),
-                };
-                let key_data = Opaque::new(&mut key);
-                let value = unsafe { value.assume_init() };
-                unsafe {
-                    pm.insert(key_data, value);
-                }
-                core::mem::forget(key); // key has been moved out of
-                unsafe { std::alloc::dealloc(value.as_mut_byte_ptr(), pm.def().v.layout) };
-
-                let next_key = parser.parse_object_key()?;
-                if let Some(next_key) = next_key {
-                    let value_shape = pm.def().v;
-                    let value_data =
-                        OpaqueUninit::new(unsafe { std::alloc::alloc(value_shape.layout) });
-                    let value_poke = unsafe { PokeUninit::unchecked_new(value_data, value_shape) };
-
-                    stack.push_front(StackItem::AfterMapValue {
-                        key: next_key,
-                        value: value_data,
-                    });
-                    stack.push_front(StackItem::Value { poke: value_poke });
-                }
+            Some(b't' | b'f') => Token::Bool(self.parser.parse_bool()?),
+            Some(b'n') => {
+                self.parser.parse_null()?;
+                Token::Null
             }
-            StackItem::FinishMap { pm } => {
-                trace!("Finished deserializing \x1b[1;36mhashmap\x1b[0m");
-                let opaque = pm.build_in_place();
-                result = Some(opaque);
+            Some(_) => self.parser.parse_number()?,
+            None => {
+                return Err(self
+                    .parser
+                    .make_error(JsonParseErrorKind::UnexpectedEndOfInput));
             }
+        };
+        trace!("Read token {token:?}");
+        Ok(token)
+    }
+
+    fn skip(&mut self) -> Result<(), JsonParseError> {
+        match self.before_value()? {
+            Some(Token::EndList) => Err(self.error(DeserializeError::UnexpectedToken {
+                expected: "a value",
+                got: "the end of a list",
+            })),
+            Some(Token::EndMap) => Err(self.error(DeserializeError::UnexpectedToken {
+                expected: "a value",
+                got: "the end of a map",
+            })),
+            // a key is a string
+            Some(_) => Ok(()),
+            None => self.parser.skip_value(),
         }
     }
 
-    result.ok_or_else(|| {
-        parser.make_error(JsonParseErrorKind::Custom(
-            "Unexpected end of input".to_string(),
-        ))
-    })
+    fn error(&self, error: DeserializeError) -> JsonParseError {
+        let position = match error {
+            DeserializeError::UnknownField { .. } => self.parser.key_position,
+            DeserializeError::Reflect(_) => self.parser.position,
+            _ => self.token_start,
+        };
+        // tokens that aren't what's expected are reported like the parser reports them
+        let kind = match error {
+            DeserializeError::UnexpectedToken {
+                expected: "a string",
+                ..
+            } => JsonParseErrorKind::ExpectedOpeningQuote,
+            DeserializeError::UnexpectedToken {
+                expected: "a number",
+                ..
+            } => JsonParseErrorKind::ExpectedNumber,
+            DeserializeError::UnexpectedToken {
+                expected: "a map", ..
+            } => JsonParseErrorKind::ExpectedOpeningBrace,
+            DeserializeError::UnexpectedToken {
+                expected: "a list", ..
+            } => JsonParseErrorKind::ExpectedOpeningBracket,
+            DeserializeError::UnknownField {
+                field,
+                shape,
+                suggestion,
+            } => JsonParseErrorKind::UnknownField {
+                field,
+                shape,
+                suggestion,
+            },
+            DeserializeError::BorrowedString { .. } if self.parser.can_borrow() => {
+                JsonParseErrorKind::EscapedBorrowedString
+            }
+            DeserializeError::BorrowedString { .. } => JsonParseErrorKind::BorrowedStringFromReader,
+            DeserializeError::Reflect(err) => JsonParseErrorKind::Reflect(err),
+            err => JsonParseErrorKind::Deserialize(err),
+        };
+        self.parser.make_error_at(kind, position)
+    }
+
+    fn deny_unknown_fields(&self) -> bool {
+        self.options.deny_unknown_fields
+    }
 }
@@ -3,7 +3,12 @@
 
 mod deserialize;
 mod parser;
+mod reader;
+mod report;
 mod serialize;
 
 pub use deserialize::*;
+pub use parser::{JsonParseError, JsonParseErrorKind, JsonParseErrorWithContext};
+pub use reader::*;
+pub use report::*;
 pub use serialize::*;
@@ -3,46 +3,120 @@
 //! For now it is extremely naive, it's just a proof of concept, it doesn't use SIMD or anything,
 //! it's not fast, it's nothing, it's just proving that we can use facet types to deserialize something.
 
+use std::borrow::Cow;
+use std::cell::Cell;
+use std::io::{self, Read};
+
+use facet_core::{Shape, TypeNameOpts};
+use facet_reflect::{DeserializeError, ReflectPath, Token};
+
+/// An error that happened while deserializing JSON
 #[derive(Debug)]
 pub struct JsonParseError {
+    /// What went wrong
     pub kind: JsonParseErrorKind,
+    /// Byte offset in the input where it went wrong
     pub position: usize,
+    /// Where in the value being deserialized it went wrong, e.g. `servers[2].port`
+    pub path: ReflectPath,
+    /// The shape of the value that was being deserialized there, if any
+    pub shape: Option<&'static Shape>,
 }
 
+/// The different kinds of [`JsonParseError`]
 #[derive(Debug)]
 #[non_exhaustive]
 pub enum JsonParseErrorKind {
+    /// A string was expected, but it didn't start with `"`
     ExpectedOpeningQuote,
+    /// The input ended in the middle of a string
     UnterminatedString,
+    /// A string contained an unknown escape sequence
     InvalidEscapeSequence(char),
+    /// A `\u` escape sequence had less than 4 hex digits
     IncompleteUnicodeEscape,
+    /// A `\u` escape sequence wasn't valid hex, or not a valid code point
     InvalidUnicodeEscape,
+    /// A number was expected
     ExpectedNumber,
+    /// A number couldn't be parsed
     InvalidNumberFormat,
+    /// An object was expected, but it didn't start with `{`
     ExpectedOpeningBrace,
+    /// An array was expected, but it didn't start with `[`
     ExpectedOpeningBracket,
+    /// An object key wasn't followed by `:`
     ExpectedColon,
+    /// The input ended before the value was complete
     UnexpectedEndOfInput,
+    /// Something else than a valid JSON value was found
     InvalidValue,
+    /// An object wasn't closed with `}`
     ExpectedClosingBrace,
+    /// An array wasn't closed with `]`
     ExpectedClosingBracket,
-    UnknownField(String),
+    /// An object key didn't match any field of the struct being deserialized, which doesn't
+    /// allow unknown fields. The error's position is the one of the key.
+    UnknownField {
+        /// The unknown key
+        field: String,
+        /// The struct (or enum) being deserialized
+        shape: &'static Shape,
+        /// The closest field name, if any is close enough to be a likely typo
+        suggestion: Option<&'static str>,
+    },
+    /// A string with escape sequences was found where a `&str` was expected: `&str` can only
+    /// borrow from the input, so it can't hold the unescaped string. `Cow<str>` can.
+    EscapedBorrowedString,
+    /// A `&str` was expected, but the input is read from a reader, so nothing can borrow
+    /// from it. `String` or `Cow<str>` can be used instead.
+    BorrowedStringFromReader,
+    /// The input read from a reader isn't valid UTF-8
+    InvalidUtf8,
+    /// A newline-delimited JSON record was followed by something else than a newline
+    ExpectedLineEnd,
+    /// The reader the input is read from failed
+    Io(std::io::Error),
+    /// The value doesn't fit the type it's deserialized into, e.g. a number that's out of range
+    /// or an unknown enum variant
+    Deserialize(DeserializeError),
+    /// The value could not be built, e.g. because fields were missing
+    Reflect(facet_reflect::ReflectError),
+    /// Any other error
     Custom(String),
 }
 
 impl JsonParseError {
+    /// Creates an error of the given kind at the given byte offset
     pub fn new(kind: JsonParseErrorKind, position: usize) -> Self {
-        JsonParseError { kind, position }
+        JsonParseError {
+            kind,
+            position,
+            path: ReflectPath::new(),
+            shape: None,
+        }
     }
 }
 
+/// Errors of the deserialize driver, which doesn't know where in the input they happened: the
+/// JSON deserializer reports them with their position instead.
+impl From<DeserializeError> for JsonParseError {
+    fn from(err: DeserializeError) -> Self {
+        JsonParseError::new(JsonParseErrorKind::Deserialize(err), 0)
+    }
+}
+
+/// A [`JsonParseError`] along with the input it happened in, to show where it happened
 #[derive(Debug)]
 pub struct JsonParseErrorWithContext<'input> {
+    /// The error itself
     pub error: JsonParseError,
+    /// The whole input
     pub input: &'input str,
 }
 
 impl JsonParseErrorWithContext<'_> {
+    /// Drops the input, keeping only the error
     pub fn strip_context(self) -> JsonParseError {
         self.error
     }
@@ -50,7 +124,22 @@ impl JsonParseErrorWithContext<'_> {
 
 impl core::fmt::Display for JsonParseError {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
-        let error_message = match &self.kind {
+        if !self.path.is_empty() {
+            write!(f, "{}: ", self.path)?;
+        }
+        write!(f, "{} at position {}", self.kind, self.position)?;
+        if let Some(shape) = self.shape {
+            write!(f, " (expected ")?;
+            shape.write_type_name(f, TypeNameOpts::infinite())?;
+            write!(f, ")")?;
+        }
+        Ok(())
+    }
+}
+
+impl core::fmt::Display for JsonParseErrorKind {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let error_message = match self {
             JsonParseErrorKind::ExpectedOpeningQuote => "Expected opening quote for string",
             JsonParseErrorKind::UnterminatedString => "Unterminated string",
             JsonParseErrorKind::InvalidEscapeSequence(ch) => {
@@ -67,219 +156,432 @@ impl core::fmt::Display for JsonParseError {
             JsonParseErrorKind::InvalidValue => "Invalid value",
             JsonParseErrorKind::ExpectedClosingBrace => "Expected closing brace for object",
             JsonParseErrorKind::ExpectedClosingBracket => "Expected closing bracket for array",
-            JsonParseErrorKind::UnknownField(field) => {
-                return write!(f, "Unknown field: {}", field);
+            JsonParseErrorKind::EscapedBorrowedString => {
+                "String contains escape sequences, it can't be borrowed as &str"
             }
+            JsonParseErrorKind::BorrowedStringFromReader => {
+                "Can't borrow a &str from input read from a reader"
+            }
+            JsonParseErrorKind::InvalidUtf8 => "Invalid UTF-8",
+            JsonParseErrorKind::ExpectedLineEnd => "Expected the end of the line",
+            JsonParseErrorKind::Io(err) => return write!(f, "I/O error: {}", err),
+            JsonParseErrorKind::UnknownField {
+                field,
+                shape,
+                suggestion,
+            } => {
+                write!(f, "Unknown field {field:?} for {shape}")?;
+                if let Some(suggestion) = suggestion {
+                    write!(f, " (did you mean {suggestion:?}?)")?;
+                }
+                return Ok(());
+            }
+            JsonParseErrorKind::Deserialize(err) => return write!(f, "{}", err),
+            JsonParseErrorKind::Reflect(err) => return write!(f, "{}", err),
             JsonParseErrorKind::Custom(msg) => msg,
         };
 
-        write!(f, "{} at position {}", error_message, self.position)
+        write!(f, "{}", error_message)
     }
 }
 
+/// Shows the line of the input the error is on, without colors: see
+/// [`JsonParseErrorWithContext::report`] for more control.
 impl core::fmt::Display for JsonParseErrorWithContext<'_> {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
-        let context_start = self.error.position.saturating_sub(20);
-        let context_end = (self.error.position + 20).min(self.input.len());
-        let context = &self.input[context_start..context_end];
-        let arrow_position = self.error.position - context_start;
-
-        writeln!(f, "{}", self.error)?;
-        writeln!(f, "\x1b[36m{}\x1b[0m", context)?;
-        write!(f, "{}\x1b[31m^\x1b[0m", " ".repeat(arrow_position))
+        write!(f, "{}", self.report().with_colors(false))
     }
 }
 
 impl core::error::Error for JsonParseError {}
 
+/// How many bytes are read from a reader at once
+const READ_CHUNK_SIZE: usize = 8 * 1024;
+
+/// Where a [`JsonParser`] reads its input from
+enum Source<'input> {
+    /// The whole input is in memory, strings can borrow from it
+    Str(&'input str),
+    /// The input is read as the parser goes, only a window of it is kept in memory
+    Reader {
+        reader: Box<dyn Read + 'input>,
+        /// The window: input bytes from `offset` onwards
+        buf: Vec<u8>,
+        /// Position of `buf[0]` in the input
+        offset: usize,
+        eof: bool,
+    },
+}
+
 pub struct JsonParser<'input> {
-    pub input: &'input str,
+    source: Source<'input>,
     pub position: usize,
+    /// Position of the opening quote of the last object key parsed
+    pub key_position: usize,
+    /// Start of the token being parsed: with a reader, the input before it can be dropped
+    token_start: usize,
+    /// The error the reader failed with, reported instead of the next parse error
+    io_error: Cell<Option<io::Error>>,
 }
 
 impl<'a> JsonParser<'a> {
     pub fn new(input: &'a str) -> Self {
-        JsonParser { input, position: 0 }
+        Self::with_source(Source::Str(input))
     }
 
-    pub fn make_error(&self, kind: JsonParseErrorKind) -> JsonParseErrorWithContext<'a> {
-        JsonParseErrorWithContext {
-            error: JsonParseError::new(kind, self.position),
-            input: self.input,
-        }
+    /// Creates a parser that reads its input from `reader`, as it goes
+    pub fn from_reader(reader: impl Read + 'a) -> Self {
+        Self::with_source(Source::Reader {
+            reader: Box::new(reader),
+            buf: Vec::new(),
+            offset: 0,
+            eof: false,
+        })
     }
 
-    pub fn parse_string(&mut self) -> Result<String, JsonParseErrorWithContext<'a>> {
-        self.skip_whitespace();
-        if self.position >= self.input.len() || self.input.as_bytes()[self.position] != b'"' {
-            return Err(self.make_error(JsonParseErrorKind::ExpectedOpeningQuote));
+    fn with_source(source: Source<'a>) -> Self {
+        JsonParser {
+            source,
+            position: 0,
+            key_position: 0,
+            token_start: 0,
+            io_error: Cell::new(None),
         }
-        self.position += 1;
+    }
 
-        let mut result = String::new();
-        let mut escaped = false;
+    /// Whether strings can be borrowed from the input, i.e. it isn't read from a reader
+    pub fn can_borrow(&self) -> bool {
+        matches!(self.source, Source::Str(_))
+    }
 
-        while self.position < self.input.len() {
-            let ch = self.input.as_bytes()[self.position];
-            self.position += 1;
+    pub fn make_error(&self, kind: JsonParseErrorKind) -> JsonParseError {
+        self.make_error_at(kind, self.position)
+    }
+
+    pub fn make_error_at(&self, kind: JsonParseErrorKind, position: usize) -> JsonParseError {
+        // an I/O error usually shows up as an unexpected end of input, report the real cause
+        let kind = match self.io_error.take() {
+            Some(err) => JsonParseErrorKind::Io(err),
+            None => kind,
+        };
+        JsonParseError::new(kind, position)
+    }
+
+    /// Returns the byte at `position`, reading more input if needed
+    fn byte_at(&mut self, position: usize) -> Option<u8> {
+        match &mut self.source {
+            Source::Str(input) => input.as_bytes().get(position).copied(),
+            Source::Reader {
+                reader,
+                buf,
+                offset,
+                eof,
+            } => {
+                while position >= *offset + buf.len() && !*eof {
+                    // drop the input that can't be looked at anymore, to keep the window small
+                    if self.token_start > *offset {
+                        buf.drain(..self.token_start - *offset);
+                        *offset = self.token_start;
+                    }
 
-            if escaped {
-                match ch {
-                    b'"' | b'\\' | b'/' => result.push(ch as char),
-                    b'b' => result.push('\x08'),
-                    b'f' => result.push('\x0C'),
-                    b'n' => result.push('\n'),
-                    b'r' => result.push('\r'),
-                    b't' => result.push('\t'),
-                    b'u' => {
-                        // Parse 4-digit hex code
-                        if self.position + 4 > self.input.len() {
-                            return Err(
-                                self.make_error(JsonParseErrorKind::IncompleteUnicodeEscape)
-                            );
+                    let len = buf.len();
+                    buf.resize(len + READ_CHUNK_SIZE, 0);
+                    match reader.read(&mut buf[len..]) {
+                        Ok(0) => {
+                            buf.truncate(len);
+                            *eof = true;
                         }
-                        let hex = &self.input[self.position..self.position + 4];
-                        self.position += 4;
-                        if let Ok(code) = u16::from_str_radix(hex, 16) {
-                            result.push(char::from_u32(code as u32).unwrap_or('\u{FFFD}'));
-                        } else {
-                            return Err(self.make_error(JsonParseErrorKind::InvalidUnicodeEscape));
+                        Ok(n) => buf.truncate(len + n),
+                        Err(e) if e.kind() == io::ErrorKind::Interrupted => buf.truncate(len),
+                        Err(e) => {
+                            buf.truncate(len);
+                            *eof = true;
+                            self.io_error.set(Some(e));
                         }
                     }
-                    _ => {
-                        return Err(
-                            self.make_error(JsonParseErrorKind::InvalidEscapeSequence(ch as char))
-                        );
-                    }
                 }
-                escaped = false;
-            } else if ch == b'\\' {
-                escaped = true;
-            } else if ch == b'"' {
-                return Ok(result);
-            } else {
-                result.push(ch as char);
+                position
+                    .checked_sub(*offset)
+                    .and_then(|i| buf.get(i))
+                    .copied()
             }
         }
+    }
 
-        Err(self.make_error(JsonParseErrorKind::UnterminatedString))
+    /// Returns the byte at the current position, reading more input if needed
+    fn current_byte(&mut self) -> Option<u8> {
+        self.byte_at(self.position)
     }
 
-    pub fn parse_u64(&mut self) -> Result<u64, JsonParseErrorWithContext<'a>> {
-        self.skip_whitespace();
-        let start = self.position;
-        while self.position < self.input.len()
-            && self.input.as_bytes()[self.position].is_ascii_digit()
-        {
-            self.position += 1;
+    /// Returns the input between `start` and `end`, which must have been read already and
+    /// not be dropped, i.e. be in the current token.
+    fn str_between(&self, start: usize, end: usize) -> Result<&str, JsonParseError> {
+        match &self.source {
+            Source::Str(input) => Ok(&input[start..end]),
+            Source::Reader { buf, offset, .. } => {
+                core::str::from_utf8(&buf[start - offset..end - offset])
+                    .map_err(|_| self.make_error_at(JsonParseErrorKind::InvalidUtf8, start))
+            }
         }
-        if start == self.position {
-            return Err(self.make_error(JsonParseErrorKind::ExpectedNumber));
+    }
+
+    /// Consumes `literal` if the input continues with it
+    fn eat_literal(&mut self, literal: &[u8]) -> bool {
+        let matches = literal
+            .iter()
+            .enumerate()
+            .all(|(i, &b)| self.byte_at(self.position + i) == Some(b));
+        if matches {
+            self.position += literal.len();
         }
-        let num_str = &self.input[start..self.position];
-        num_str
-            .parse::<u64>()
-            .map_err(|_| self.make_error(JsonParseErrorKind::InvalidNumberFormat))
+        matches
     }
 
-    pub fn parse_i64(&mut self) -> Result<i64, JsonParseErrorWithContext<'a>> {
+    /// Parses a string, borrowing it from the input unless it contains escape sequences or
+    /// the input comes from a reader
+    pub fn parse_str(&mut self) -> Result<Cow<'a, str>, JsonParseError> {
         self.skip_whitespace();
-        let start = self.position;
-
-        // Allow leading minus sign
-        if self.position < self.input.len() && self.input.as_bytes()[self.position] == b'-' {
-            self.position += 1;
+        if self.current_byte() != Some(b'"') {
+            return Err(self.make_error(JsonParseErrorKind::ExpectedOpeningQuote));
         }
+        self.position += 1;
 
-        // Parse digits
-        while self.position < self.input.len()
-            && self.input.as_bytes()[self.position].is_ascii_digit()
-        {
-            self.position += 1;
+        let start = self.position;
+        loop {
+            match self.current_byte() {
+                Some(b'"') => {
+                    let s = match self.source {
+                        Source::Str(input) => Cow::Borrowed(&input[start..self.position]),
+                        Source::Reader { .. } => {
+                            Cow::Owned(self.str_between(start, self.position)?.to_owned())
+                        }
+                    };
+                    self.position += 1;
+                    return Ok(s);
+                }
+                Some(b'\\') => break,
+                Some(_) => self.position += 1,
+                None => return Err(self.make_error(JsonParseErrorKind::UnterminatedString)),
+            }
         }
 
-        if start == self.position
-            || (self.position == start + 1 && self.input.as_bytes()[start] == b'-')
-        {
-            // Handle case where only '-' was found or nothing was parsed
-            return Err(self.make_error(JsonParseErrorKind::ExpectedNumber));
+        // There's an escape sequence: unescape into an owned string, copying the unescaped
+        // runs in between as they are. Quotes and backslashes are ASCII, so the run boundaries
+        // are always char boundaries.
+        let mut result = String::from(self.str_between(start, self.position)?);
+        let mut run_start = self.position;
+        loop {
+            match self.current_byte() {
+                Some(b'"') => {
+                    result.push_str(self.str_between(run_start, self.position)?);
+                    self.position += 1;
+                    return Ok(Cow::Owned(result));
+                }
+                Some(b'\\') => {
+                    result.push_str(self.str_between(run_start, self.position)?);
+                    self.position += 1;
+                    let Some(ch) = self.current_byte() else {
+                        break;
+                    };
+                    self.position += 1;
+                    match ch {
+                        b'"' | b'\\' | b'/' => result.push(ch as char),
+                        b'b' => result.push('\x08'),
+                        b'f' => result.push('\x0C'),
+                        b'n' => result.push('\n'),
+                        b'r' => result.push('\r'),
+                        b't' => result.push('\t'),
+                        b'u' => {
+                            // Parse 4-digit hex code
+                            if self.byte_at(self.position + 3).is_none() {
+                                return Err(
+                                    self.make_error(JsonParseErrorKind::IncompleteUnicodeEscape)
+                                );
+                            }
+                            let hex = self.str_between(self.position, self.position + 4);
+                            let code = hex.ok().and_then(|hex| u16::from_str_radix(hex, 16).ok());
+                            let Some(code) = code else {
+                                return Err(
+                                    self.make_error(JsonParseErrorKind::InvalidUnicodeEscape)
+                                );
+                            };
+                            self.position += 4;
+                            result.push(char::from_u32(code as u32).unwrap_or('\u{FFFD}'));
+                        }
+                        _ => {
+                            return Err(self.make_error(
+                                JsonParseErrorKind::InvalidEscapeSequence(ch as char),
+                            ));
+                        }
+                    }
+                    run_start = self.position;
+                }
+                Some(_) => self.position += 1,
+                None => break,
+            }
         }
 
-        let num_str = &self.input[start..self.position];
-        num_str
-            .parse::<i64>()
-            .map_err(|_| self.make_error(JsonParseErrorKind::InvalidNumberFormat))
+        Err(self.make_error(JsonParseErrorKind::UnterminatedString))
     }
 
-    // Generic number parsing helper
-    fn parse_number<T>(&mut self) -> Result<T, JsonParseErrorWithContext<'a>>
-    where
-        T: core::str::FromStr,
-        <T as core::str::FromStr>::Err: core::fmt::Debug, // Ensure the error type can be debug printed
-    {
+    /// Advances past the digits at the current position
+    fn skip_digits(&mut self) {
+        while self.current_byte().is_some_and(|b| b.is_ascii_digit()) {
+            self.position += 1;
+        }
+    }
+
+    /// Parses a number, into an integer token unless it has a fraction or an exponent.
+    /// Integers that don't fit in 64 bits are handed out in their string form, for 128-bit
+    /// integers to be parsed from.
+    pub fn parse_number(&mut self) -> Result<Token<'a>, JsonParseError> {
         self.skip_whitespace();
         let start = self.position;
         // Allow leading minus sign
-        if self.position < self.input.len() && self.input.as_bytes()[self.position] == b'-' {
+        if self.current_byte() == Some(b'-') {
             self.position += 1;
         }
-        // Allow digits and decimal point
-        while self.position < self.input.len() {
-            let byte = self.input.as_bytes()[self.position];
-            if byte.is_ascii_digit() || byte == b'.' {
-                self.position += 1;
-            } else {
-                break;
-            }
-        }
-
-        if start == self.position
-            || (self.position == start + 1 && self.input.as_bytes()[start] == b'-')
-        {
+        let digits = self.position;
+        self.skip_digits();
+        if digits == self.position {
             // Handle case where only '-' was found or nothing was parsed
+            self.position = start;
             return Err(self.make_error(JsonParseErrorKind::ExpectedNumber));
         }
 
-        let num_str = &self.input[start..self.position];
-        num_str
-            .parse::<T>()
-            .map_err(|_| self.make_error(JsonParseErrorKind::InvalidNumberFormat))
-    }
+        let mut integer = true;
+        if self.current_byte() == Some(b'.') {
+            integer = false;
+            self.position += 1;
+            self.skip_digits();
+        }
+        if let Some(b'e' | b'E') = self.current_byte() {
+            integer = false;
+            self.position += 1;
+            if let Some(b'+' | b'-') = self.current_byte() {
+                self.position += 1;
+            }
+            self.skip_digits();
+        }
 
-    pub fn parse_f64(&mut self) -> Result<f64, JsonParseErrorWithContext<'a>> {
-        self.parse_number()
+        let num_str = self.str_between(start, self.position)?;
+        let big = || Token::Str(Cow::Owned(num_str.to_owned()));
+        let token = if !integer {
+            num_str.parse().map(Token::F64).ok()
+        } else if num_str.starts_with('-') {
+            Some(num_str.parse().map_or_else(|_| big(), Token::I64))
+        } else {
+            Some(num_str.parse().map_or_else(|_| big(), Token::U64))
+        };
+        token.ok_or_else(|| self.make_error_at(JsonParseErrorKind::InvalidNumberFormat, start))
     }
 
-    pub fn parse_bool(&mut self) -> Result<bool, JsonParseErrorWithContext<'a>> {
+    pub fn parse_bool(&mut self) -> Result<bool, JsonParseError> {
         self.skip_whitespace();
-        if self.position + 4 <= self.input.len()
-            && &self.input[self.position..self.position + 4] == "true"
-        {
-            self.position += 4;
+        if self.eat_literal(b"true") {
             return Ok(true);
         }
-        if self.position + 5 <= self.input.len()
-            && &self.input[self.position..self.position + 5] == "false"
-        {
-            self.position += 5;
+        if self.eat_literal(b"false") {
             return Ok(false);
         }
         Err(self.make_error(JsonParseErrorKind::InvalidValue))
     }
 
+    pub fn parse_null(&mut self) -> Result<(), JsonParseError> {
+        self.skip_whitespace();
+        if self.eat_literal(b"null") {
+            return Ok(());
+        }
+        Err(self.make_error(JsonParseErrorKind::InvalidValue))
+    }
+
+    /// Returns the next non-whitespace byte, without consuming it.
+    pub fn peek_byte(&mut self) -> Option<u8> {
+        self.skip_whitespace();
+        self.current_byte()
+    }
+
+    /// Skips over a complete value (scalar, array or object), without interpreting it.
+    pub fn skip_value(&mut self) -> Result<(), JsonParseError> {
+        match self.peek_byte() {
+            Some(b'"') => self.parse_str().map(|_| ()),
+            Some(b'[') => {
+                self.expect_array_start()?;
+                if self.peek_byte() == Some(b']') {
+                    self.position += 1;
+                    return Ok(());
+                }
+                loop {
+                    self.skip_value()?;
+                    if self.parse_array_element()? != Some(true) {
+                        return Ok(());
+                    }
+                }
+            }
+            Some(b'{') => {
+                let mut key = self.expect_object_start()?;
+                while key.is_some() {
+                    self.skip_value()?;
+                    key = self.parse_object_key()?;
+                }
+                Ok(())
+            }
+            Some(b't' | b'f') => self.parse_bool().map(|_| ()),
+            Some(b'n') => self.parse_null(),
+            Some(_) => self.parse_number().map(|_| ()),
+            None => Err(self.make_error(JsonParseErrorKind::UnexpectedEndOfInput)),
+        }
+    }
+
+    /// Skips whitespace, up to the start of the next token
     pub fn skip_whitespace(&mut self) {
-        while self.position < self.input.len() {
-            match self.input.as_bytes()[self.position] {
-                b' ' | b'\t' | b'\n' | b'\r' => self.position += 1,
-                _ => break,
+        while let Some(b' ' | b'\t' | b'\n' | b'\r') = self.current_byte() {
+            self.position += 1;
+        }
+        self.token_start = self.position;
+    }
+
+    /// Whether there's nothing but whitespace left in the input
+    pub fn at_end(&mut self) -> bool {
+        self.peek_byte().is_none()
+    }
+
+    /// Expects the rest of the current line to be blank, and consumes it along with the
+    /// newline, if any.
+    pub fn expect_line_end(&mut self) -> Result<(), JsonParseError> {
+        while let Some(b' ' | b'\t' | b'\r') = self.current_byte() {
+            self.position += 1;
+        }
+        match self.current_byte() {
+            Some(b'\n') => {
+                self.position += 1;
+                Ok(())
+            }
+            None => Ok(()),
+            Some(_) => Err(self.make_error(JsonParseErrorKind::ExpectedLineEnd)),
+        }
+    }
+
+    /// Recovers from a failed parse by skipping the rest of the current line along with the
+    /// newline.
+    pub fn recover_at_next_line(&mut self) {
+        loop {
+            self.token_start = self.position;
+            match self.current_byte() {
+                Some(b'\n') => {
+                    self.position += 1;
+                    return;
+                }
+                Some(_) => self.position += 1,
+                None => return,
             }
         }
     }
 
     /// Expects the start of an array.
-    pub fn expect_array_start(&mut self) -> Result<(), JsonParseErrorWithContext<'a>> {
+    pub fn expect_array_start(&mut self) -> Result<(), JsonParseError> {
         self.skip_whitespace();
-        if self.position >= self.input.len() || self.input.as_bytes()[self.position] != b'[' {
+        if self.current_byte() != Some(b'[') {
             return Err(self.make_error(JsonParseErrorKind::ExpectedOpeningBracket));
         }
         self.position += 1;
@@ -289,53 +591,69 @@ impl<'a> JsonParser<'a> {
     /// Expects the end of an array or a comma followed by the next element.
     /// Returns Some(true) if there's another element, Some(false) if the array has ended,
     /// or an error if the JSON is malformed.
-    pub fn parse_array_element(&mut self) -> Result<Option<bool>, JsonParseErrorWithContext<'a>> {
+    pub fn parse_array_element(&mut self) -> Result<Option<bool>, JsonParseError> {
         self.skip_whitespace();
-        if self.position >= self.input.len() {
-            return Err(self.make_error(JsonParseErrorKind::UnexpectedEndOfInput));
-        }
-
-        match self.input.as_bytes()[self.position] {
-            b',' => {
+        match self.current_byte() {
+            None => Err(self.make_error(JsonParseErrorKind::UnexpectedEndOfInput)),
+            Some(b',') => {
                 self.position += 1;
                 self.skip_whitespace();
                 Ok(Some(true)) // There's another element
             }
-            b']' => {
+            Some(b']') => {
                 self.position += 1;
                 Ok(Some(false)) // End of array
             }
-            _ => {
+            Some(_) => {
                 // First element doesn't need a comma
                 Ok(Some(true))
             }
         }
     }
 
+    /// Parses an object key and the `:` after it
+    fn parse_key(&mut self) -> Result<Cow<'a, str>, JsonParseError> {
+        self.key_position = self.position;
+        let key = self.parse_str()?;
+        self.skip_whitespace();
+        if self.current_byte() == Some(b':') {
+            self.position += 1;
+            Ok(key)
+        } else {
+            Err(self.make_error(JsonParseErrorKind::ExpectedColon))
+        }
+    }
+
     /// Expects the start of an object and returns the first key if present.
     /// Returns None if the object is empty.
-    pub fn expect_object_start(&mut self) -> Result<Option<String>, JsonParseErrorWithContext<'a>> {
+    pub fn expect_object_start(&mut self) -> Result<Option<Cow<'a, str>>, JsonParseError> {
+        self.expect_opening_brace()?;
+        self.parse_first_object_key()
+    }
+
+    /// Expects the `{` that starts an object, see [`Self::parse_first_object_key`] for what
+    /// comes after it.
+    pub fn expect_opening_brace(&mut self) -> Result<(), JsonParseError> {
         self.skip_whitespace();
-        if self.position >= self.input.len() || self.input.as_bytes()[self.position] != b'{' {
+        if self.current_byte() != Some(b'{') {
             return Err(self.make_error(JsonParseErrorKind::ExpectedOpeningBrace));
         }
         self.position += 1;
+        Ok(())
+    }
+
+    /// Returns the first key of an object whose `{` has been consumed, or None if the object
+    /// is empty, consuming its `}`.
+    pub fn parse_first_object_key(&mut self) -> Result<Option<Cow<'a, str>>, JsonParseError> {
         self.skip_whitespace();
 
-        if self.position < self.input.len() && self.input.as_bytes()[self.position] == b'"' {
-            let key = self.parse_string()?;
-            self.skip_whitespace();
-            if self.position < self.input.len() && self.input.as_bytes()[self.position] == b':' {
+        match self.current_byte() {
+            Some(b'"') => self.parse_key().map(Some),
+            Some(b'}') => {
                 self.position += 1;
-                Ok(Some(key))
-            } else {
-                Err(self.make_error(JsonParseErrorKind::ExpectedColon))
+                Ok(None)
             }
-        } else if self.position < self.input.len() && self.input.as_bytes()[self.position] == b'}' {
-            self.position += 1;
-            Ok(None)
-        } else {
-            Err(self.make_error(JsonParseErrorKind::InvalidValue))
+            _ => Err(self.make_error(JsonParseErrorKind::InvalidValue)),
         }
     }
 
@@ -350,36 +668,24 @@ impl<'a> JsonParser<'a> {
     ///
     /// The function also takes care of skipping whitespace before and after tokens.
     /// If it reaches the end of input unexpectedly, it returns an appropriate error.
-    pub fn parse_object_key(&mut self) -> Result<Option<String>, JsonParseErrorWithContext<'a>> {
+    pub fn parse_object_key(&mut self) -> Result<Option<Cow<'a, str>>, JsonParseError> {
         self.skip_whitespace();
-        if self.position >= self.input.len() {
-            return Err(self.make_error(JsonParseErrorKind::UnexpectedEndOfInput));
-        }
-        match self.input.as_bytes()[self.position] {
-            b',' => {
+        match self.current_byte() {
+            None => Err(self.make_error(JsonParseErrorKind::UnexpectedEndOfInput)),
+            Some(b',') => {
                 self.position += 1;
                 self.skip_whitespace();
-                if self.position < self.input.len() && self.input.as_bytes()[self.position] == b'"'
-                {
-                    let key = self.parse_string()?;
-                    self.skip_whitespace();
-                    if self.position < self.input.len()
-                        && self.input.as_bytes()[self.position] == b':'
-                    {
-                        self.position += 1;
-                        Ok(Some(key))
-                    } else {
-                        Err(self.make_error(JsonParseErrorKind::ExpectedColon))
-                    }
+                if self.current_byte() == Some(b'"') {
+                    self.parse_key().map(Some)
                 } else {
                     Err(self.make_error(JsonParseErrorKind::InvalidValue))
                 }
             }
-            b'}' => {
+            Some(b'}') => {
                 self.position += 1;
                 Ok(None)
             }
-            _ => Err(self.make_error(JsonParseErrorKind::InvalidValue)),
+            Some(_) => Err(self.make_error(JsonParseErrorKind::InvalidValue)),
         }
     }
 }
@@ -0,0 +1,100 @@
+use std::io::Read;
+use std::marker::PhantomData;
+
+use facet_core::Facet;
+use facet_reflect::PokeUninit;
+
+use crate::deserialize::{DeserializeOptions, deserialize_value};
+use crate::parser::{JsonParseError, JsonParseErrorKind, JsonParser};
+
+/// Deserializes JSON read from `reader` into a value of type `T`.
+///
+/// The input is parsed as it's read, in chunks: only the part of it the parser may still need
+/// is kept in memory, so large files and pipes can be read without loading them entirely.
+/// Since nothing can borrow from the input, `Cow<str>` fields are always owned and `&str`
+/// fields fail with [`JsonParseErrorKind::BorrowedStringFromReader`].
+///
+/// Errors don't carry the input around, unlike the ones of [`from_str`], and I/O errors are
+/// reported as [`JsonParseErrorKind::Io`].
+///
+/// # Example
+/// ```
+/// # use facet::Facet;
+/// # #[derive(Facet)]
+/// # struct Person { name: String, age: u64 }
+/// let file: &[u8] = br#"{"name":"Alice","age":30}"#;
+/// let person: Person = facet_json::from_reader(file).unwrap();
+/// assert_eq!(person.name, "Alice");
+/// ```
+///
+/// [`from_str`]: crate::from_str
+pub fn from_reader<'facet, R: Read, T: Facet<'facet>>(reader: R) -> Result<T, JsonParseError> {
+    let mut parser = JsonParser::from_reader(reader);
+    read_value(&mut parser)
+}
+
+/// Deserializes one value of type `T` with `parser`, which reads from a reader
+fn read_value<'facet, T: Facet<'facet>>(parser: &mut JsonParser<'_>) -> Result<T, JsonParseError> {
+    let (poke, _guard) = PokeUninit::alloc::<T>();
+    // SAFETY: the parser reads from a reader, so the value doesn't borrow anything
+    let opaque = unsafe { deserialize_value(parser, DeserializeOptions::default(), poke)? };
+    Ok(unsafe { opaque.read::<T>() })
+}
+
+/// Reads newline-delimited JSON (one value per line) from `reader`, deserializing each line
+/// into a `T` as the returned iterator is advanced.
+///
+/// Blank lines are skipped. A line that fails to deserialize yields an error and iteration
+/// carries on with the next line, so callers can choose between stopping at the first error
+/// and skipping bad records.
+///
+/// # Example
+/// ```
+/// # use facet::Facet;
+/// #[derive(Facet)]
+/// struct Event {
+///     id: u64,
+/// }
+///
+/// let input: &[u8] = b"{\"id\":1}\n{\"id\":2}\n";
+/// let ids = facet_json::from_ndjson_reader::<_, Event>(input)
+///     .map(|event| event.map(|event| event.id))
+///     .collect::<Result<Vec<_>, _>>()
+///     .unwrap();
+/// assert_eq!(ids, [1, 2]);
+/// ```
+pub fn from_ndjson_reader<'r, R: Read + 'r, T>(reader: R) -> NdjsonReader<'r, T> {
+    NdjsonReader {
+        parser: JsonParser::from_reader(reader),
+        done: false,
+        _phantom: PhantomData,
+    }
+}
+
+/// An iterator over the values of newline-delimited JSON, see [`from_ndjson_reader`]
+pub struct NdjsonReader<'r, T> {
+    parser: JsonParser<'r>,
+    done: bool,
+    _phantom: PhantomData<fn() -> T>,
+}
+
+impl<'facet, T: Facet<'facet>> Iterator for NdjsonReader<'_, T> {
+    type Item = Result<T, JsonParseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done || self.parser.at_end() {
+            return None;
+        }
+
+        let result = read_value(&mut self.parser)
+            .and_then(|value| self.parser.expect_line_end().map(|()| value));
+        if let Err(err) = &result {
+            // the reader itself failed, there's nothing left to read
+            if matches!(err.kind, JsonParseErrorKind::Io(_)) {
+                self.done = true;
+            }
+            self.parser.recover_at_next_line();
+        }
+        Some(result)
+    }
+}
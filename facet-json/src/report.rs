@@ -0,0 +1,175 @@
+//! Diagnostics for JSON parse errors, showing where in the input they happened
+
+use core::fmt::{self, Display, Formatter};
+
+use facet_ansi::{Style, Stylize as _};
+use facet_core::{Shape, TypeNameOpts};
+
+use crate::JsonParseErrorWithContext;
+
+/// How many characters of the offending line are shown on each side of the error, so that
+/// minified inputs don't end up on screen whole
+const CONTEXT_CHARS: usize = 40;
+
+impl<'input> JsonParseErrorWithContext<'input> {
+    /// Renders the error as a diagnostic: the message, the line of the input it's on with a
+    /// caret under the column, the type that was expected there, and the path of the value
+    /// that was being deserialized.
+    ///
+    /// Colors are on by default, turn them off for log files with
+    /// [`JsonErrorReport::with_colors`].
+    ///
+    /// # Example
+    /// ```
+    /// # use facet::Facet;
+    /// #[derive(Facet, Debug)]
+    /// struct Server {
+    ///     port: u16,
+    /// }
+    ///
+    /// let json = "{\n  \"port\": \"http\"\n}";
+    /// let err = facet_json::from_str::<Server>(json).unwrap_err();
+    /// assert_eq!(
+    ///     err.report().with_colors(false).to_string(),
+    ///     "\
+    /// error: invalid value for u16: \"http\"
+    ///  --> 2:11
+    ///   |
+    /// 2 |   \"port\": \"http\"
+    ///   |           ^ expected u16
+    ///   |
+    ///   = path: port"
+    /// );
+    /// ```
+    pub fn report(&self) -> JsonErrorReport<'_, 'input> {
+        JsonErrorReport {
+            error: self,
+            use_colors: true,
+        }
+    }
+}
+
+/// A [`JsonParseErrorWithContext`] rendered as a diagnostic, see
+/// [`JsonParseErrorWithContext::report`]
+pub struct JsonErrorReport<'a, 'input> {
+    error: &'a JsonParseErrorWithContext<'input>,
+    use_colors: bool,
+}
+
+impl JsonErrorReport<'_, '_> {
+    /// Enable or disable colors
+    pub fn with_colors(mut self, use_colors: bool) -> Self {
+        self.use_colors = use_colors;
+        self
+    }
+
+    fn style(&self, style: Style) -> Style {
+        if self.use_colors { style } else { Style::new() }
+    }
+}
+
+impl Display for JsonErrorReport<'_, '_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let error = &self.error.error;
+        let red = self.style(Style::new().red().bold());
+        let blue = self.style(Style::new().blue().bold());
+        let bold = self.style(Style::new().bold());
+
+        write!(
+            f,
+            "{}{} {}",
+            "error".style(red),
+            ":".style(bold),
+            error.kind.to_string().style(bold)
+        )?;
+
+        // input read from a reader isn't kept around, there's no line to show
+        let pad = if self.error.input.is_empty() {
+            write!(f, "\n {} position {}", "-->".style(blue), error.position)?;
+            if let Some(shape) = error.shape {
+                write!(f, "\n  {} expected: {}", "=".style(blue), TypeName(shape))?;
+            }
+            " ".to_string()
+        } else {
+            let (number, line, column) = locate(self.error.input, error.position);
+            let number_str = number.to_string();
+            let pad = " ".repeat(number_str.len());
+            let (text, caret) = window(line, column);
+
+            write!(f, "\n{pad}{} {number}:{}", "-->".style(blue), column + 1)?;
+            write!(f, "\n{pad} {}", "|".style(blue))?;
+            write!(f, "\n{} {} {text}", number_str.style(blue), "|".style(blue))?;
+            write!(
+                f,
+                "\n{pad} {} {}{}",
+                "|".style(blue),
+                " ".repeat(caret),
+                "^".style(red)
+            )?;
+            if let Some(shape) = error.shape {
+                write!(f, " {}", format!("expected {}", TypeName(shape)).style(red))?;
+            }
+            if !error.path.is_empty() {
+                write!(f, "\n{pad} {}", "|".style(blue))?;
+            }
+            pad
+        };
+
+        if !error.path.is_empty() {
+            write!(f, "\n{pad} {} path: {}", "=".style(blue), error.path)?;
+        }
+        Ok(())
+    }
+}
+
+/// Finds the line `position` is on: its number (from 1), its text, and the column of
+/// `position` in it (in characters, from 0)
+fn locate(input: &str, position: usize) -> (usize, &str, usize) {
+    let mut position = position.min(input.len());
+    while !input.is_char_boundary(position) {
+        position -= 1;
+    }
+    let line_start = input[..position].rfind('\n').map_or(0, |i| i + 1);
+    let line_end = input[position..]
+        .find('\n')
+        .map_or(input.len(), |i| position + i);
+    let line = input[line_start..line_end].trim_end_matches('\r');
+    let number = input[..line_start].matches('\n').count() + 1;
+    let column = input[line_start..position].chars().count();
+    (number, line, column)
+}
+
+/// Cuts the part of `line` around `column` that gets shown, returning it along with where
+/// the caret goes under it
+fn window(line: &str, column: usize) -> (String, usize) {
+    let len = line.chars().count();
+    let start = column.saturating_sub(CONTEXT_CHARS);
+    let end = (column + CONTEXT_CHARS).min(len);
+
+    let mut text = String::new();
+    let mut caret = column - start;
+    if start > 0 {
+        text.push_str("...");
+        caret += 3;
+    }
+    // tabs would throw the caret off
+    text.extend(
+        line.chars()
+            .skip(start)
+            .take(end - start)
+            .map(|c| if c == '\t' { ' ' } else { c }),
+    );
+    if end < len {
+        text.push_str("...");
+    }
+    (text, caret)
+}
+
+/// Writes the full name of a shape
+struct TypeName(&'static Shape);
+
+impl Display for TypeName {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        self.0.write_type_name(f, TypeNameOpts::infinite())
+    }
+}
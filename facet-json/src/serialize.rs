@@ -1,6 +1,5 @@
-use facet_reflect::{Peek, PeekValue};
+use facet_reflect::{Peek, PeekValue, Serializer, serialize};
 use log::trace;
-use std::collections::VecDeque;
 use std::io::{self, Write};
 use std::num::NonZero;
 
@@ -44,7 +43,7 @@ macro_rules! string {
     ($pv:expr, $writer:expr, $type:ty) => {
         if $pv.shape().is_type::<$type>() {
             let value = unsafe { $pv.data().as_ref::<$type>() };
-            return write!($writer, "\"{}\"", value.escape_debug());
+            return write_json_string($writer, value);
         }
     };
 }
@@ -73,266 +72,174 @@ fn peek_value_to_json<W: Write>(pv: PeekValue, writer: &mut W) -> io::Result<()>
     Ok(())
 }
 
+/// JSON object keys are always strings: string keys are written as-is, any other
+/// key goes through its `Display` implementation.
+fn peek_map_key_to_json<W: Write>(pv: PeekValue, writer: &mut W) -> io::Result<()> {
+    strings!(pv, writer, String, std::borrow::Cow<'_, str>, &str);
+
+    if pv.vtable().display.is_none() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("map key of type {} cannot be displayed", pv.shape()),
+        ));
+    }
+    let key = Peek::Value(pv).to_string();
+    write_json_string(writer, &key)
+}
+
+/// Writes `s` as a JSON string, quoted, escaping quotes, backslashes and control characters
+fn write_json_string<W: Write>(writer: &mut W, s: &str) -> io::Result<()> {
+    let bytes = s.as_bytes();
+    writer.write_all(b"\"")?;
+    let mut start = 0;
+    for (i, &byte) in bytes.iter().enumerate() {
+        if !matches!(byte, b'"' | b'\\' | 0x00..=0x1f) {
+            continue;
+        }
+        writer.write_all(&bytes[start..i])?;
+        match byte {
+            b'"' => writer.write_all(br#"\""#)?,
+            b'\\' => writer.write_all(br"\\")?,
+            b'\n' => writer.write_all(br"\n")?,
+            b'\r' => writer.write_all(br"\r")?,
+            b'\t' => writer.write_all(br"\t")?,
+            0x08 => writer.write_all(br"\b")?,
+            0x0c => writer.write_all(br"\f")?,
+            _ => write!(writer, "\\u{byte:04x}")?,
+        }
+        start = i + 1;
+    }
+    writer.write_all(&bytes[start..])?;
+    writer.write_all(b"\"")
+}
+
 /// Serializes any Facet type to JSON
 pub fn to_json<W: Write>(peek: Peek<'_>, writer: &mut W, indent: bool) -> io::Result<()> {
-    #[derive(Debug)]
-    enum StackItem<'mem> {
-        Value {
-            peek: Peek<'mem>,
-            level: usize,
-        },
-        StructField {
-            field_name: String,
-            peek: Peek<'mem>,
-            level: usize,
-            is_first: bool,
-        },
-        StructEnd {
-            level: usize,
-            had_fields: bool,
-        },
-        ListItem {
-            peek: Peek<'mem>,
-            level: usize,
-            is_first: bool,
-        },
-        ListEnd {
-            level: usize,
-            had_items: bool,
-        },
-        MapEntry {
-            key: Peek<'mem>,
-            value: Peek<'mem>,
-            level: usize,
-            is_first: bool,
-        },
-        MapEnd {
-            level: usize,
-            had_entries: bool,
-        },
+    let mut serializer = JsonSerializer {
+        writer,
+        indent,
+        has_items: Vec::new(),
+    };
+    serialize(peek, &mut serializer)
+}
+
+/// Writes JSON as a [`Serializer`] is told what's in the value
+struct JsonSerializer<'w, W: Write> {
+    writer: &'w mut W,
+    indent: bool,
+    /// For each object or array being written, whether it has items yet
+    has_items: Vec<bool>,
+}
+
+impl<W: Write> JsonSerializer<'_, W> {
+    fn start(&mut self, open: &str) -> io::Result<()> {
+        self.has_items.push(false);
+        write!(self.writer, "{open}")
     }
 
-    let mut stack: VecDeque<StackItem> = VecDeque::new();
-    stack.push_back(StackItem::Value { peek, level: 0 });
-
-    while let Some(item) = stack.pop_front() {
-        match item {
-            StackItem::Value { peek, level } => {
-                match peek {
-                    Peek::Value(pv) => {
-                        peek_value_to_json(pv, writer)?;
-                    }
-                    Peek::Struct(ps) => {
-                        write!(writer, "{{")?;
-                        if indent {
-                            writeln!(writer)?
-                        }
-
-                        let fields: Vec<_> = ps.fields().collect();
-                        stack.push_front(StackItem::StructEnd {
-                            level,
-                            had_fields: !fields.is_empty(),
-                        });
-
-                        // Push fields in reverse order so they'll be processed in the correct order
-                        for (i, field) in fields.into_iter().enumerate().rev() {
-                            stack.push_front(StackItem::StructField {
-                                field_name: field.0.to_string(),
-                                peek: field.1,
-                                level,
-                                is_first: i == 0,
-                            });
-                        }
-                    }
-                    Peek::List(pl) => {
-                        write!(writer, "[")?;
-                        if indent {
-                            writeln!(writer)?
-                        }
-
-                        let mut items = Vec::new();
-                        let mut index = 0;
-                        while let Some(item) = pl.item_at(index) {
-                            items.push(item);
-                            index += 1;
-                        }
-
-                        stack.push_front(StackItem::ListEnd {
-                            level,
-                            had_items: !items.is_empty(),
-                        });
-
-                        // Push items in reverse order
-                        for (i, item) in items.into_iter().enumerate().rev() {
-                            stack.push_front(StackItem::ListItem {
-                                peek: item,
-                                level,
-                                is_first: i == 0,
-                            });
-                        }
-                    }
-                    Peek::Map(pm) => {
-                        write!(writer, "{{")?;
-                        if indent {
-                            writeln!(writer)?
-                        }
-
-                        // Collect entries using the iterator and convert them to the format expected by MapEntry
-                        let entries: Vec<(PeekValue<'_>, Peek<'_>)> = pm
-                            .iter()
-                            .map(|(key, value)| (key.as_value(), value))
-                            .collect();
-
-                        stack.push_front(StackItem::MapEnd {
-                            level,
-                            had_entries: !entries.is_empty(),
-                        });
-
-                        // Push entries in reverse order
-                        for (i, (key, value)) in entries.into_iter().enumerate().rev() {
-                            stack.push_front(StackItem::MapEntry {
-                                key: Peek::Value(key),
-                                value,
-                                level,
-                                is_first: i == 0,
-                            });
-                        }
-                    }
-                    Peek::Option(popt) => {
-                        if let Some(p) = popt.value() {
-                            peek_value_to_json(p.as_value(), writer)?
-                        } else {
-                            write!(writer, "null")?
-                        }
-                    }
-                    _ => todo!("unsupported peek type: {:?}", peek),
-                }
-            }
-            StackItem::StructField {
-                field_name,
-                peek,
-                level,
-                is_first,
-            } => {
-                if !is_first {
-                    write!(writer, ",")?;
-                    if indent {
-                        writeln!(writer)?
-                    }
-                }
-
-                if indent {
-                    write!(writer, "{:indent$}", "", indent = (level + 1) * 2)?
-                }
-                write!(writer, "\"{}\":", field_name)?;
-                if indent {
-                    write!(writer, " ")?
-                }
-
-                stack.push_front(StackItem::Value {
-                    peek,
-                    level: level + 1,
-                });
-            }
-            StackItem::StructEnd { level, had_fields } => {
-                if had_fields && indent {
-                    writeln!(writer)?;
-                    write!(writer, "{:indent$}", "", indent = level * 2)?
-                }
-                write!(writer, "}}")?
-            }
-            StackItem::ListItem {
-                peek,
-                level,
-                is_first,
-            } => {
-                if !is_first {
-                    write!(writer, ",")?;
-                    if indent {
-                        writeln!(writer)?
-                    }
-                }
-
-                if indent {
-                    write!(writer, "{:indent$}", "", indent = (level + 1) * 2)?
-                }
-
-                stack.push_front(StackItem::Value {
-                    peek,
-                    level: level + 1,
-                });
-            }
-            StackItem::ListEnd { level, had_items } => {
-                if had_items && indent {
-                    writeln!(writer)?;
-                    write!(writer, "{:indent$}", "", indent = level * 2)?
-                }
-                write!(writer, "]")?
-            }
-            StackItem::MapEntry {
-                key,
-                value,
-                level,
-                is_first,
-            } => {
-                if !is_first {
-                    write!(writer, ",")?;
-                    if indent {
-                        writeln!(writer)?
-                    }
-                }
-
-                if indent {
-                    write!(writer, "{:indent$}", "", indent = (level + 1) * 2)?
-                }
-
-                // Process key first (inline with no indentation)
-                let mut temp_writer = Vec::new();
-                let mut temp_stack = VecDeque::new();
-                temp_stack.push_back(StackItem::Value {
-                    peek: key,
-                    level: 0,
-                });
-
-                while let Some(temp_item) = temp_stack.pop_front() {
-                    match temp_item {
-                        StackItem::Value { peek, level: _ } => match peek {
-                            Peek::Value(pv) => {
-                                trace!("{:?}", pv.shape());
-                                peek_value_to_json(pv, &mut temp_writer)?
-                            }
-                            _ => write!(&mut temp_writer, "\"<complex_key>\"")?,
-                        },
-                        _ => {
-                            // This should not happen for simple key serialization
-                            write!(&mut temp_writer, "\"<invalid_key>\"")?
-                        }
-                    }
-                }
-
-                let key_string = String::from_utf8(temp_writer).unwrap();
-                write!(writer, "{}:", key_string)?;
-
-                if indent {
-                    write!(writer, " ")?
-                }
-
-                stack.push_front(StackItem::Value {
-                    peek: value,
-                    level: level + 1,
-                });
-            }
-            StackItem::MapEnd { level, had_entries } => {
-                if had_entries && indent {
-                    writeln!(writer)?;
-                    write!(writer, "{:indent$}", "", indent = level * 2)?
-                }
-                write!(writer, "}}")?
+    /// Writes the separator before an item, and its indentation
+    fn item(&mut self) -> io::Result<()> {
+        let level = self.has_items.len();
+        if let Some(has_items) = self.has_items.last_mut() {
+            if *has_items {
+                write!(self.writer, ",")?;
             }
+            *has_items = true;
+        }
+        if self.indent {
+            writeln!(self.writer)?;
+            write!(self.writer, "{:indent$}", "", indent = level * 2)?;
         }
+        Ok(())
     }
 
-    Ok(())
+    /// Writes the `:` between an object key and its value
+    fn colon(&mut self) -> io::Result<()> {
+        write!(self.writer, ":")?;
+        if self.indent {
+            write!(self.writer, " ")?
+        }
+        Ok(())
+    }
+
+    fn end(&mut self, close: &str) -> io::Result<()> {
+        let had_items = self.has_items.pop().unwrap_or(false);
+        if had_items && self.indent {
+            writeln!(self.writer)?;
+            write!(
+                self.writer,
+                "{:indent$}",
+                "",
+                indent = self.has_items.len() * 2
+            )?
+        }
+        write!(self.writer, "{close}")
+    }
+}
+
+impl<W: Write> Serializer for JsonSerializer<'_, W> {
+    type Error = io::Error;
+
+    fn scalar(&mut self, value: PeekValue<'_>) -> io::Result<()> {
+        peek_value_to_json(value, self.writer)
+    }
+
+    fn str(&mut self, s: &str) -> io::Result<()> {
+        write_json_string(self.writer, s)
+    }
+
+    fn none(&mut self) -> io::Result<()> {
+        write!(self.writer, "null")
+    }
+
+    fn start_struct(&mut self, _len: usize) -> io::Result<()> {
+        self.start("{")
+    }
+
+    fn field(&mut self, name: &str) -> io::Result<()> {
+        self.item()?;
+        write_json_string(self.writer, name)?;
+        self.colon()
+    }
+
+    fn end_struct(&mut self) -> io::Result<()> {
+        self.end("}")
+    }
+
+    fn start_list(&mut self, _len: usize) -> io::Result<()> {
+        self.start("[")
+    }
+
+    fn list_item(&mut self) -> io::Result<()> {
+        self.item()
+    }
+
+    fn end_list(&mut self) -> io::Result<()> {
+        self.end("]")
+    }
+
+    fn start_map(&mut self, _len: usize) -> io::Result<()> {
+        self.start("{")
+    }
+
+    fn map_key(&mut self, key: Peek<'_>) -> io::Result<()> {
+        self.item()?;
+        trace!("Serializing map key of type {}", key.shape());
+        peek_map_key_to_json(key.as_value(), self.writer)
+    }
+
+    fn map_value(&mut self) -> io::Result<()> {
+        self.colon()
+    }
+
+    fn end_map(&mut self) -> io::Result<()> {
+        self.end("}")
+    }
+
+    fn tuples_as_lists(&self) -> bool {
+        // tuple structs are objects with fields named "0", "1"...
+        false
+    }
 }
 
 /// Serializes any Facet type to JSON and returns it as a String
@@ -1,7 +1,8 @@
+use std::borrow::Cow;
 use std::num::NonZero;
 
 use facet::Facet;
-use facet_json::from_str;
+use facet_json::{DeserializeOptions, JsonParseErrorKind, from_str, from_str_with_options};
 
 #[test]
 fn json_read_simple_struct() {
@@ -141,6 +142,48 @@ fn json_read_more_types() {
     assert!((test_struct.f64_val - std::f64::consts::PI).abs() < f64::EPSILON);
 }
 
+#[test]
+fn json_read_options_unit_and_wide_numbers() {
+    facet_testhelpers::setup();
+
+    #[derive(Facet, Debug, PartialEq)]
+    struct Wide {
+        none: Option<String>,
+        some: Option<Vec<u8>>,
+        unit: (),
+        u128_val: u128,
+        i128_val: i128,
+        exp: f64,
+    }
+
+    let json = r#"{
+        "none": null,
+        "some": [1, 2],
+        "unit": null,
+        "u128_val": 340282366920938463463374607431768211455,
+        "i128_val": -170141183460469231731687303715884105728,
+        "exp": -1.5e3
+    }"#;
+    let wide: Wide = from_str(json).unwrap();
+    assert_eq!(
+        wide,
+        Wide {
+            none: None,
+            some: Some(vec![1, 2]),
+            unit: (),
+            u128_val: u128::MAX,
+            i128_val: i128::MIN,
+            exp: -1500.0,
+        }
+    );
+
+    let err = from_str::<Option<u8>>("256").unwrap_err();
+    assert!(
+        err.to_string().contains("256 is out of range for u8"),
+        "{err}"
+    );
+}
+
 #[test]
 fn test_from_json_with_nested_structs() {
     facet_testhelpers::setup();
@@ -171,3 +214,383 @@ fn test_from_json_with_nested_structs() {
     assert_eq!(test_struct.name, "Outer");
     assert_eq!(test_struct.inner.value, 42);
 }
+
+#[test]
+fn test_from_json_with_smart_pointers() {
+    facet_testhelpers::setup();
+
+    #[derive(Facet, Debug, PartialEq)]
+    struct Config {
+        name: String,
+    }
+
+    #[derive(Facet, Debug, PartialEq)]
+    struct Wrapper {
+        boxed: Box<Config>,
+        shared: std::sync::Arc<Config>,
+        counted: std::rc::Rc<Vec<u32>>,
+    }
+
+    let json = r#"{
+        "boxed": { "name": "in a box" },
+        "shared": { "name": "in an arc" },
+        "counted": [1, 2, 3]
+    }"#;
+
+    let wrapper: Wrapper = match from_str(json) {
+        Ok(s) => s,
+        Err(e) => panic!("Error deserializing JSON: {}", e),
+    };
+
+    assert_eq!(wrapper.boxed.name, "in a box");
+    assert_eq!(wrapper.shared.name, "in an arc");
+    assert_eq!(*wrapper.counted, vec![1, 2, 3]);
+}
+
+#[test]
+fn test_from_json_with_zero_sized_pointees() {
+    facet_testhelpers::setup();
+
+    #[derive(Facet, Debug, PartialEq)]
+    struct Marker {}
+
+    #[derive(Facet, Debug, PartialEq)]
+    struct Wrapper {
+        boxed: Box<Marker>,
+        shared: std::sync::Arc<Marker>,
+        units: Vec<Marker>,
+    }
+
+    let wrapper: Wrapper = from_str(r#"{"boxed": {}, "shared": {}, "units": [{}, {}]}"#)
+        .unwrap_or_else(|e| panic!("Error deserializing JSON: {}", e));
+    assert_eq!(wrapper.units.len(), 2);
+}
+
+#[test]
+fn test_from_json_with_default_fields() {
+    facet_testhelpers::setup();
+
+    fn default_retries() -> u32 {
+        3
+    }
+
+    #[derive(Facet, Debug, PartialEq)]
+    struct Job {
+        name: String,
+        #[facet(default)]
+        tags: Vec<String>,
+        #[facet(default = "default_retries")]
+        retries: u32,
+    }
+
+    let job: Job = from_str(r#"{"name":"build"}"#).unwrap();
+    assert_eq!(
+        job,
+        Job {
+            name: "build".to_string(),
+            tags: vec![],
+            retries: 3,
+        }
+    );
+
+    let job: Job = from_str(r#"{"name":"test","retries":0,"tags":["ci"]}"#).unwrap();
+    assert_eq!(
+        job,
+        Job {
+            name: "test".to_string(),
+            tags: vec!["ci".to_string()],
+            retries: 0,
+        }
+    );
+}
+
+#[test]
+fn test_from_json_missing_fields_is_an_error() {
+    facet_testhelpers::setup();
+
+    #[derive(Facet, Debug)]
+    struct Point {
+        x: i32,
+        y: i32,
+    }
+
+    #[derive(Facet, Debug)]
+    struct Line {
+        start: Point,
+        end: Point,
+    }
+
+    let err = from_str::<Point>(r#"{"x":1}"#).unwrap_err();
+    assert!(
+        err.to_string().contains("missing field in Point: `y`"),
+        "{err}"
+    );
+
+    let err = from_str::<Line>(r#"{"start":{"x":1,"y":2},"end":{}}"#).unwrap_err();
+    assert!(
+        err.to_string()
+            .contains("missing fields in Point: `x`, `y`"),
+        "{err}"
+    );
+}
+
+#[test]
+fn test_from_json_error_paths() {
+    facet_testhelpers::setup();
+
+    #[derive(Facet, Debug)]
+    #[allow(dead_code)]
+    struct Tls {
+        cert_path: String,
+    }
+
+    #[derive(Facet, Debug)]
+    #[allow(dead_code)]
+    struct Server {
+        port: u16,
+        tls: Tls,
+        tags: std::collections::HashMap<String, Vec<u8>>,
+    }
+
+    #[derive(Facet, Debug)]
+    #[allow(dead_code)]
+    struct Config {
+        servers: Vec<Server>,
+    }
+
+    let server = r#"{"port":1,"tls":{"cert_path":"a"},"tags":{}}"#;
+    let config = |last: &str| format!(r#"{{"servers":[{server},{server},{last}]}}"#);
+
+    let json = config(r#"{"port":1,"tls":{"cert_path":7}}"#);
+    let err = from_str::<Config>(&json).unwrap_err();
+    assert_eq!(err.error.path.to_string(), "servers[2].tls.cert_path");
+    assert!(
+        err.error
+            .to_string()
+            .starts_with("servers[2].tls.cert_path: Expected opening quote for string"),
+        "{err}"
+    );
+    assert!(
+        err.error.to_string().ends_with(" (expected String)"),
+        "{err}"
+    );
+
+    let json = config(r#"{"port":1,"tls":{"cert_path":"a"},"tags":{"a":[1,300]}}"#);
+    let err = from_str::<Config>(&json).unwrap_err();
+    assert_eq!(err.error.path.to_string(), r#"servers[2].tags["a"][1]"#);
+
+    // errors reading a list item point at it too
+    let json = config(r#"{"port":1,"tls":{"cert_path":"a"},"tags":{"a":[1,]}}"#);
+    let err = from_str::<Config>(&json).unwrap_err();
+    assert_eq!(err.error.path.to_string(), r#"servers[2].tags["a"][1]"#);
+
+    let json = config(r#"{"port":1}"#);
+    let err = from_str::<Config>(&json).unwrap_err();
+    assert_eq!(err.error.path.to_string(), "servers[2]");
+    assert!(
+        err.error.to_string().ends_with(" (expected Server)"),
+        "{err}"
+    );
+}
+
+#[test]
+fn test_from_json_error_report() {
+    facet_testhelpers::setup();
+
+    #[derive(Facet, Debug)]
+    #[allow(dead_code)]
+    struct Tls {
+        cert_path: String,
+    }
+
+    #[derive(Facet, Debug)]
+    #[allow(dead_code)]
+    struct Server {
+        tls: Tls,
+    }
+
+    #[derive(Facet, Debug)]
+    #[allow(dead_code)]
+    struct Config {
+        servers: Vec<Server>,
+    }
+
+    let json = r#"{
+  "servers": [
+    { "tls": { "cert_path": "a.pem" } },
+    { "tls": { "cert_path": 7 } }
+  ]
+}"#;
+    let err = from_str::<Config>(json).unwrap_err();
+    let plain = err.report().with_colors(false).to_string();
+    assert_eq!(
+        plain,
+        r#"error: Expected opening quote for string
+ --> 4:29
+  |
+4 |     { "tls": { "cert_path": 7 } }
+  |                             ^ expected String
+  |
+  = path: servers[1].tls.cert_path"#
+    );
+    // Display is meant for logs, it has no colors
+    assert_eq!(err.to_string(), plain);
+    assert!(err.report().to_string().contains("\x1b["));
+
+    // long lines are cut around the error
+    let json = format!(
+        r#"{{"servers":[{}{{"tls":{{"cert_path":false}}}}]}}"#,
+        r#"{"tls":{"cert_path":"a.pem"}},"#.repeat(10)
+    );
+    let err = from_str::<Config>(&json).unwrap_err();
+    let report = err.report().with_colors(false).to_string();
+    let lines = report.lines().collect::<Vec<_>>();
+    let position = json.find("false").unwrap();
+    assert_eq!(lines[1], format!(" --> 1:{}", position + 1));
+    assert_eq!(lines[3], format!("1 | ...{}", &json[position - 40..]));
+    assert_eq!(lines[4], format!("  | {}^ expected String", " ".repeat(43)));
+}
+
+#[test]
+fn test_from_json_skipped_fields() {
+    facet_testhelpers::setup();
+
+    #[derive(Facet, Debug, PartialEq)]
+    struct Session {
+        id: u64,
+        #[facet(skip)]
+        cache: Vec<u8>,
+        #[facet(skip_deserializing)]
+        hits: u32,
+    }
+
+    let session: Session = from_str(r#"{"id":7}"#).unwrap();
+    assert_eq!(
+        session,
+        Session {
+            id: 7,
+            cache: vec![],
+            hits: 0,
+        }
+    );
+
+    // skipped fields can't be set from the input: they're unknown keys
+    let session: Session = from_str(r#"{"id":7,"hits":3}"#).unwrap();
+    assert_eq!(session.hits, 0);
+    let options = DeserializeOptions::default().with_deny_unknown_fields(true);
+    assert!(from_str_with_options::<Session>(r#"{"id":7,"hits":3}"#, options).is_err());
+}
+
+#[test]
+fn test_from_json_unknown_fields() {
+    facet_testhelpers::setup();
+
+    #[derive(Facet, Debug, PartialEq)]
+    struct Person {
+        name: String,
+        age: u64,
+    }
+
+    #[derive(Facet, Debug, PartialEq)]
+    #[facet(deny_unknown_fields)]
+    struct StrictPerson {
+        name: String,
+        age: u64,
+    }
+
+    // Unknown keys are skipped by default, whatever their value
+    let json = r#"{"name":"Alice","extra":{"a":[1,{"b":null}]},"age":30}"#;
+    let person: Person = from_str(json).unwrap();
+    assert_eq!(
+        person,
+        Person {
+            name: "Alice".to_string(),
+            age: 30
+        }
+    );
+
+    // ...unless the struct denies them
+    let json = r#"{"name":"Alice", "nmae":"Bob","age":30}"#;
+    let err = from_str::<StrictPerson>(json).unwrap_err();
+    let JsonParseErrorKind::UnknownField {
+        field,
+        shape,
+        suggestion,
+    } = &err.error.kind
+    else {
+        panic!("expected an unknown field error, got {err}");
+    };
+    assert_eq!(field, "nmae");
+    assert_eq!(*shape, StrictPerson::SHAPE);
+    assert_eq!(*suggestion, Some("name"));
+    assert_eq!(err.error.position, 17);
+    assert_eq!(
+        err.error.to_string(),
+        r#"Unknown field "nmae" for StrictPerson (did you mean "name"?) at position 17 (expected StrictPerson)"#
+    );
+
+    // ...or the options do
+    let options = DeserializeOptions::default().with_deny_unknown_fields(true);
+    let err = from_str_with_options::<Person>(r#"{"zzz":1}"#, options).unwrap_err();
+    assert!(matches!(
+        err.error.kind,
+        JsonParseErrorKind::UnknownField {
+            suggestion: None,
+            ..
+        }
+    ));
+}
+
+#[test]
+fn json_read_borrowed_strings() {
+    facet_testhelpers::setup();
+
+    #[derive(Facet, Debug)]
+    struct LogLine<'a> {
+        level: &'a str,
+        target: Cow<'a, str>,
+        message: Cow<'a, str>,
+        tags: Vec<&'a str>,
+    }
+
+    let json = r#"{"level": "info", "target": "héllo", "message": "a \"quoted\"\nword", "tags": ["a", "b"]}"#;
+    let line: LogLine = from_str(json).unwrap();
+
+    let input = json.as_bytes().as_ptr_range();
+    let borrows = |s: &str| input.contains(&s.as_ptr());
+
+    assert_eq!(line.level, "info");
+    assert!(borrows(line.level));
+    assert_eq!(line.target, "héllo");
+    assert!(matches!(line.target, Cow::Borrowed(s) if borrows(s)));
+    assert_eq!(line.message, "a \"quoted\"\nword");
+    assert!(matches!(line.message, Cow::Owned(_)));
+    assert_eq!(line.tags, ["a", "b"]);
+    assert!(line.tags.iter().all(|tag| borrows(tag)));
+}
+
+#[test]
+fn json_read_borrowed_str_with_escapes() {
+    facet_testhelpers::setup();
+
+    #[derive(Facet, Debug)]
+    struct Borrowed<'a> {
+        name: &'a str,
+    }
+
+    let json = r#"{"name": "tab\there"}"#;
+    let err = from_str::<Borrowed>(json).unwrap_err();
+    assert!(matches!(
+        err.error.kind,
+        JsonParseErrorKind::EscapedBorrowedString
+    ));
+    assert_eq!(err.error.position, json.find("\"tab").unwrap());
+}
+
+#[test]
+fn json_read_unicode_strings() {
+    facet_testhelpers::setup();
+
+    let s: String = from_str(r#""日本 é ü""#).unwrap();
+    assert_eq!(s, "日本 é ü");
+}
@@ -0,0 +1,226 @@
+use std::borrow::Cow;
+use std::io::{self, Read};
+
+use facet::Facet;
+use facet_json::{JsonParseErrorKind, from_ndjson_reader, from_reader};
+
+/// A reader that hands out at most `chunk` bytes per read, so that tokens end up split across
+/// reads
+struct Chunked<'a> {
+    input: &'a [u8],
+    chunk: usize,
+}
+
+impl Read for Chunked<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = self.chunk.min(buf.len()).min(self.input.len());
+        buf[..n].copy_from_slice(&self.input[..n]);
+        self.input = &self.input[n..];
+        Ok(n)
+    }
+}
+
+fn chunked(input: &str, chunk: usize) -> Chunked<'_> {
+    Chunked {
+        input: input.as_bytes(),
+        chunk,
+    }
+}
+
+/// A reader that fails once `input` is exhausted
+struct Failing<'a> {
+    input: &'a [u8],
+}
+
+impl Read for Failing<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.input.is_empty() {
+            return Err(io::Error::new(io::ErrorKind::BrokenPipe, "pipe closed"));
+        }
+        let n = buf.len().min(self.input.len());
+        buf[..n].copy_from_slice(&self.input[..n]);
+        self.input = &self.input[n..];
+        Ok(n)
+    }
+}
+
+#[derive(Facet, Debug, PartialEq)]
+struct Person {
+    name: String,
+    age: u64,
+    tags: Vec<String>,
+}
+
+#[test]
+fn json_reader_small_chunks() {
+    facet_testhelpers::setup();
+
+    let json = r#" {"name": "Zoë \"Z\" Ångström 🦀", "age": 30, "tags": ["a", "é", ""]} "#;
+    for chunk in [1, 2, 3, 7] {
+        let person: Person = from_reader(chunked(json, chunk)).unwrap();
+        assert_eq!(
+            person,
+            Person {
+                name: "Zoë \"Z\" Ångström 🦀".to_string(),
+                age: 30,
+                tags: vec!["a".to_string(), "é".to_string(), String::new()],
+            }
+        );
+    }
+}
+
+#[test]
+fn json_reader_larger_than_buffer() {
+    facet_testhelpers::setup();
+
+    let values: Vec<String> = (0..5000).map(|i| format!("value number {i} — ✓")).collect();
+    let json = format!(
+        "[{}]",
+        values
+            .iter()
+            .map(|v| format!("{v:?}"))
+            .collect::<Vec<_>>()
+            .join(",")
+    );
+    let read: Vec<String> = from_reader(chunked(&json, 1000)).unwrap();
+    assert_eq!(read, values);
+}
+
+#[test]
+fn json_reader_lookahead_across_refills() {
+    facet_testhelpers::setup();
+
+    #[derive(Facet, Debug, PartialEq)]
+    #[repr(u8)]
+    #[facet(tag = "type")]
+    #[allow(dead_code)]
+    enum Doc {
+        Small { body: String },
+        Big { body: String },
+    }
+
+    // the tag comes after a body much larger than a read, which must be kept around to be
+    // parsed once the tag is known
+    let body = "x".repeat(50_000);
+    let json = format!(r#"{{"body":"{body}","type":"Big"}}"#);
+    let doc: Doc = from_reader(chunked(&json, 4096)).unwrap();
+    assert_eq!(doc, Doc::Big { body });
+
+    #[derive(Facet, Debug, PartialEq)]
+    #[repr(u8)]
+    #[facet(untagged)]
+    #[allow(dead_code)]
+    enum Value {
+        Numbers(Vec<u32>),
+        Words(Vec<String>),
+    }
+
+    let words: Vec<String> = (0..3000).map(|i| format!("w{i}")).collect();
+    let json = format!(
+        "[{}]",
+        words
+            .iter()
+            .map(|w| format!("{w:?}"))
+            .collect::<Vec<_>>()
+            .join(",")
+    );
+    let value: Value = from_reader(chunked(&json, 512)).unwrap();
+    assert_eq!(value, Value::Words(words));
+}
+
+#[test]
+fn json_reader_strings_are_owned() {
+    facet_testhelpers::setup();
+
+    #[derive(Facet)]
+    struct WithCow<'a> {
+        name: Cow<'a, str>,
+    }
+
+    let s: WithCow = from_reader(chunked(r#"{"name":"Alice"}"#, 3)).unwrap();
+    assert!(matches!(s.name, Cow::Owned(ref n) if n == "Alice"));
+
+    #[derive(Facet, Debug)]
+    struct WithStr<'a> {
+        #[allow(dead_code)]
+        name: &'a str,
+    }
+
+    let err = from_reader::<_, WithStr>(chunked(r#"{"name": "Alice"}"#, 3)).unwrap_err();
+    assert!(
+        matches!(err.kind, JsonParseErrorKind::BorrowedStringFromReader),
+        "{err}"
+    );
+    assert_eq!(err.position, 9);
+}
+
+#[test]
+fn json_reader_errors() {
+    facet_testhelpers::setup();
+
+    let err = from_reader::<_, Person>(chunked(r#"{"name": "Alice", "age": x}"#, 4)).unwrap_err();
+    assert_eq!(err.position, 25);
+
+    let err = from_reader::<_, String>(&b"\"\xff\""[..]).unwrap_err();
+    assert!(matches!(err.kind, JsonParseErrorKind::InvalidUtf8), "{err}");
+
+    let err = from_reader::<_, Person>(Failing {
+        input: br#"{"name": "Alice", "#,
+    })
+    .unwrap_err();
+    match err.kind {
+        JsonParseErrorKind::Io(e) => assert_eq!(e.kind(), io::ErrorKind::BrokenPipe),
+        kind => panic!("expected an I/O error, got {kind:?}"),
+    }
+}
+
+#[test]
+fn json_reader_ndjson() {
+    facet_testhelpers::setup();
+
+    #[derive(Facet, Debug, PartialEq)]
+    struct Event {
+        id: u64,
+        kind: String,
+    }
+
+    let input = concat!(
+        "{\"id\": 1, \"kind\": \"start\"}\n",
+        "\n",
+        "  {\"id\": 2, \"kind\": \"tick\"}  \r\n",
+        "{\"id\": \"three\", \"kind\": \"tick\"}\n",
+        "{\"id\": 4, \"kind\": \"tick\"} {\"id\": 5}\n",
+        "{\"id\": 6, \"kind\": \"stop\"}",
+    );
+    for chunk in [1, 5, 1024] {
+        let events: Vec<_> = from_ndjson_reader::<_, Event>(chunked(input, chunk)).collect();
+        assert_eq!(events.len(), 5);
+        assert_eq!(
+            events[0].as_ref().unwrap(),
+            &Event {
+                id: 1,
+                kind: "start".to_string()
+            }
+        );
+        assert_eq!(events[1].as_ref().unwrap().id, 2);
+        assert!(events[2].is_err());
+        let err = events[3].as_ref().unwrap_err();
+        assert!(
+            matches!(err.kind, JsonParseErrorKind::ExpectedLineEnd),
+            "{err}"
+        );
+        assert_eq!(events[4].as_ref().unwrap().id, 6);
+    }
+
+    // an I/O error ends the iteration
+    let events: Vec<_> = from_ndjson_reader::<_, Event>(Failing {
+        input: b"{\"id\": 1, \"kind\": \"start\"}\n{\"id\"",
+    })
+    .collect();
+    assert_eq!(events.len(), 2);
+    assert!(events[0].is_ok());
+    assert!(matches!(
+        events[1].as_ref().unwrap_err().kind,
+        JsonParseErrorKind::Io(_)
+    ));
+}
@@ -0,0 +1,535 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque};
+
+use facet::Facet;
+use facet_json::{from_str, to_json_string};
+use facet_reflect::Peek;
+
+#[test]
+fn json_roundtrip_btreemap() {
+    facet_testhelpers::setup();
+
+    #[derive(Facet, Debug, PartialEq)]
+    struct Scores {
+        scores: BTreeMap<String, u32>,
+    }
+
+    let value = Scores {
+        scores: BTreeMap::from([
+            ("carol".to_string(), 3),
+            ("alice".to_string(), 1),
+            ("bob".to_string(), 2),
+        ]),
+    };
+
+    // BTreeMap iterates in key order, so the output is deterministic
+    let json = to_json_string(Peek::new(&value), false);
+    assert_eq!(json, r#"{"scores":{"alice":1,"bob":2,"carol":3}}"#);
+
+    let parsed: Scores = from_str(&json).unwrap();
+    assert_eq!(parsed, value);
+}
+
+#[test]
+fn json_roundtrip_btreeset() {
+    facet_testhelpers::setup();
+
+    #[derive(Facet, Debug, PartialEq)]
+    struct Tags {
+        tags: BTreeSet<String>,
+    }
+
+    let value = Tags {
+        tags: BTreeSet::from(["zeta".to_string(), "alpha".to_string()]),
+    };
+
+    let json = to_json_string(Peek::new(&value), false);
+    assert_eq!(json, r#"{"tags":["alpha","zeta"]}"#);
+
+    let parsed: Tags = from_str(&json).unwrap();
+    assert_eq!(parsed, value);
+}
+
+#[test]
+fn json_roundtrip_hashset() {
+    facet_testhelpers::setup();
+
+    #[derive(Facet, Debug, PartialEq)]
+    struct Ids {
+        ids: HashSet<u64>,
+    }
+
+    let value = Ids {
+        ids: HashSet::from([1, 2, 3, 42]),
+    };
+
+    let json = to_json_string(Peek::new(&value), false);
+    let parsed: Ids = from_str(&json).unwrap();
+    assert_eq!(parsed, value);
+
+    // Duplicates collapse on the way in
+    let parsed: Ids = from_str(r#"{"ids":[7,7,8]}"#).unwrap();
+    assert_eq!(parsed.ids, HashSet::from([7, 8]));
+}
+
+#[test]
+fn json_roundtrip_vecdeque() {
+    facet_testhelpers::setup();
+
+    #[derive(Facet, Debug, PartialEq)]
+    struct Queue {
+        jobs: VecDeque<String>,
+        empty: VecDeque<u8>,
+    }
+
+    let mut jobs = VecDeque::new();
+    jobs.push_back("second".to_string());
+    jobs.push_front("first".to_string());
+    jobs.push_back("third".to_string());
+
+    let value = Queue {
+        jobs,
+        empty: VecDeque::new(),
+    };
+
+    let json = to_json_string(Peek::new(&value), false);
+    assert_eq!(json, r#"{"jobs":["first","second","third"],"empty":[]}"#);
+
+    let parsed: Queue = from_str(&json).unwrap();
+    assert_eq!(parsed, value);
+}
+
+#[test]
+fn json_roundtrip_integer_map_keys() {
+    facet_testhelpers::setup();
+
+    #[derive(Facet, Debug, PartialEq)]
+    struct Lookup {
+        by_id: BTreeMap<u32, String>,
+        by_offset: HashMap<i64, bool>,
+    }
+
+    let value = Lookup {
+        by_id: BTreeMap::from([(2, "two".to_string()), (10, "ten".to_string())]),
+        by_offset: HashMap::from([(-1, true)]),
+    };
+
+    // Keys are stringified, since JSON object keys are always strings
+    let json = to_json_string(Peek::new(&value), false);
+    assert_eq!(
+        json,
+        r#"{"by_id":{"2":"two","10":"ten"},"by_offset":{"-1":true}}"#
+    );
+
+    let parsed: Lookup = from_str(&json).unwrap();
+    assert_eq!(parsed, value);
+}
+
+#[test]
+fn json_roundtrip_newtype_map_keys() {
+    facet_testhelpers::setup();
+
+    #[derive(Facet, Debug, PartialEq, Eq, PartialOrd, Ord)]
+    struct UserId(u32);
+
+    impl core::fmt::Display for UserId {
+        fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+            write!(f, "user-{}", self.0)
+        }
+    }
+
+    impl core::str::FromStr for UserId {
+        type Err = &'static str;
+
+        fn from_str(s: &str) -> Result<Self, Self::Err> {
+            let id = s.strip_prefix("user-").ok_or("missing user- prefix")?;
+            id.parse().map(UserId).map_err(|_| "invalid user id")
+        }
+    }
+
+    #[derive(Facet, Debug, PartialEq)]
+    struct Names {
+        names: BTreeMap<UserId, String>,
+    }
+
+    let value = Names {
+        names: BTreeMap::from([
+            (UserId(1), "Alice".to_string()),
+            (UserId(2), "Bob".to_string()),
+        ]),
+    };
+
+    let json = to_json_string(Peek::new(&value), false);
+    assert_eq!(json, r#"{"names":{"user-1":"Alice","user-2":"Bob"}}"#);
+
+    let parsed: Names = from_str(&json).unwrap();
+    assert_eq!(parsed, value);
+}
+
+#[test]
+fn json_invalid_map_key() {
+    facet_testhelpers::setup();
+
+    #[derive(Facet, Debug)]
+    struct Lookup {
+        by_id: HashMap<u8, String>,
+    }
+
+    let err = from_str::<Lookup>(r#"{"by_id":{"300":"too big"}}"#).unwrap_err();
+    assert!(
+        err.to_string().contains("invalid value for u8: \"300\""),
+        "unexpected error: {err}"
+    );
+}
+
+#[test]
+fn json_roundtrip_renamed_fields_and_variants() {
+    facet_testhelpers::setup();
+
+    #[derive(Facet, Debug, PartialEq)]
+    #[repr(u8)]
+    #[facet(rename_all = "kebab-case")]
+    enum Role {
+        SuperUser,
+        #[facet(rename = "guest")]
+        Visitor,
+    }
+
+    #[derive(Facet, Debug, PartialEq)]
+    #[facet(rename_all = "camelCase")]
+    struct Account {
+        user_name: String,
+        #[facet(rename = "type")]
+        role: Role,
+        other_role: Role,
+    }
+
+    let value = Account {
+        user_name: "alice".to_string(),
+        role: Role::SuperUser,
+        other_role: Role::Visitor,
+    };
+
+    let json = to_json_string(Peek::new(&value), false);
+    assert_eq!(
+        json,
+        r#"{"userName":"alice","type":"super-user","otherRole":"guest"}"#
+    );
+
+    let parsed: Account = from_str(&json).unwrap();
+    assert_eq!(parsed, value);
+
+    // The Rust names are not accepted once a field is renamed
+    assert!(from_str::<Account>(r#"{"user_name":"alice"}"#).is_err());
+
+    // and missing fields are reported by the names the input uses
+    let err = from_str::<Account>(r#"{"userName":"alice"}"#).unwrap_err();
+    assert!(
+        err.to_string()
+            .contains("missing fields in Account: `type`, `otherRole`"),
+        "{err}"
+    );
+}
+
+#[derive(Facet, Debug, PartialEq)]
+#[repr(u8)]
+#[allow(dead_code)]
+enum External {
+    Unit,
+    Newtype(u32),
+    Pair(u32, String),
+    Point { x: u32, y: u32 },
+}
+
+#[test]
+fn json_roundtrip_externally_tagged_enum() {
+    facet_testhelpers::setup();
+
+    let cases = [
+        (External::Unit, r#""Unit""#),
+        (External::Newtype(7), r#"{"Newtype":7}"#),
+        (External::Pair(1, "a".to_string()), r#"{"Pair":[1,"a"]}"#),
+        (External::Point { x: 1, y: 2 }, r#"{"Point":{"x":1,"y":2}}"#),
+    ];
+    for (value, expected) in cases {
+        let json = to_json_string(Peek::new(&value), false);
+        assert_eq!(json, expected);
+        assert_eq!(from_str::<External>(&json).unwrap(), value);
+    }
+
+    assert!(from_str::<External>(r#""Nope""#).is_err());
+    assert!(from_str::<External>(r#"{"Newtype":1,"Unit":null}"#).is_err());
+    assert!(from_str::<External>(r#"{"Pair":[1,"a",2]}"#).is_err());
+}
+
+#[test]
+fn json_roundtrip_enum_payloads_in_containers() {
+    facet_testhelpers::setup();
+
+    #[derive(Facet, Debug, PartialEq)]
+    struct Job {
+        id: u32,
+        tags: Vec<String>,
+    }
+
+    #[derive(Facet, Debug, PartialEq)]
+    #[repr(u8)]
+    #[allow(dead_code)]
+    enum Event {
+        Idle,
+        Nothing(),
+        Cleared {},
+        Started(Job),
+        Batch(Vec<Job>),
+        Retried(Job, Box<u32>),
+        Failed { job: Job, codes: Vec<u8> },
+    }
+
+    #[derive(Facet, Debug, PartialEq)]
+    struct Log {
+        events: Vec<Event>,
+        last: Event,
+    }
+
+    let job = |id| Job {
+        id,
+        tags: vec![format!("t{id}")],
+    };
+    let value = Log {
+        events: vec![
+            Event::Idle,
+            Event::Nothing(),
+            Event::Cleared {},
+            Event::Started(job(1)),
+            Event::Batch(vec![job(2), job(3)]),
+            Event::Retried(job(4), Box::new(2)),
+        ],
+        last: Event::Failed {
+            job: job(5),
+            codes: vec![1, 2],
+        },
+    };
+
+    let json = to_json_string(Peek::new(&value), false);
+    assert_eq!(
+        json,
+        concat!(
+            r#"{"events":["Idle",{"Nothing":[]},{"Cleared":{}},"#,
+            r#"{"Started":{"id":1,"tags":["t1"]}},"#,
+            r#"{"Batch":[{"id":2,"tags":["t2"]},{"id":3,"tags":["t3"]}]},"#,
+            r#"{"Retried":[{"id":4,"tags":["t4"]},2]}],"#,
+            r#""last":{"Failed":{"job":{"id":5,"tags":["t5"]},"codes":[1,2]}}}"#
+        )
+    );
+    assert_eq!(from_str::<Log>(&json).unwrap(), value);
+
+    let pretty = to_json_string(Peek::new(&value), true);
+    assert_eq!(from_str::<Log>(&pretty).unwrap(), value);
+
+    // unit variants may also be written with an explicit null payload
+    assert_eq!(
+        from_str::<Log>(r#"{"events":[{"Idle":null}],"last":{"Idle":null}}"#).unwrap(),
+        Log {
+            events: vec![Event::Idle],
+            last: Event::Idle,
+        }
+    );
+
+    let err = from_str::<Log>(r#"{"events":[{"Batch":[{"id":1,"tags":[2]}]}],"last":"Idle"}"#)
+        .unwrap_err();
+    assert_eq!(err.error.path.to_string(), "events[0].Batch[0].tags[0]");
+
+    let err = from_str::<Log>(r#"{"events":[{"Started":{"id":"x"}}],"last":"Idle"}"#).unwrap_err();
+    assert_eq!(err.error.path.to_string(), "events[0].Started.id");
+
+    let err = from_str::<Log>(r#"{"events":[],"last":{"Failed":{"codes":[]}}}"#).unwrap_err();
+    assert!(err.to_string().contains("Failed.job"), "{err}");
+}
+
+#[test]
+fn json_roundtrip_internally_tagged_enum() {
+    facet_testhelpers::setup();
+
+    #[derive(Facet, Debug, PartialEq)]
+    #[repr(u8)]
+    #[facet(tag = "type")]
+    enum Shape {
+        Empty,
+        Circle { radius: u32 },
+        Rect { w: u32, h: u32 },
+    }
+
+    let cases = [
+        (Shape::Empty, r#"{"type":"Empty"}"#),
+        (
+            Shape::Circle { radius: 3 },
+            r#"{"type":"Circle","radius":3}"#,
+        ),
+        (Shape::Rect { w: 1, h: 2 }, r#"{"type":"Rect","w":1,"h":2}"#),
+    ];
+    for (value, expected) in cases {
+        let json = to_json_string(Peek::new(&value), false);
+        assert_eq!(json, expected);
+        assert_eq!(from_str::<Shape>(&json).unwrap(), value);
+    }
+
+    // The tag doesn't have to come first
+    assert_eq!(
+        from_str::<Shape>(r#"{"w":1,"h":2,"type":"Rect"}"#).unwrap(),
+        Shape::Rect { w: 1, h: 2 }
+    );
+
+    let err = from_str::<Shape>(r#"{"radius":3}"#).unwrap_err();
+    assert!(
+        err.to_string().contains("missing tag `type` for Shape"),
+        "{err}"
+    );
+}
+
+#[test]
+fn json_roundtrip_adjacently_tagged_enum() {
+    facet_testhelpers::setup();
+
+    #[derive(Facet, Debug, PartialEq)]
+    #[repr(u8)]
+    #[facet(tag = "t")]
+    #[facet(content = "c")]
+    #[allow(dead_code)]
+    enum Message {
+        Ping,
+        Text(String),
+        Move(u32, u32),
+        Resize { w: u32, h: u32 },
+    }
+
+    let cases = [
+        (Message::Ping, r#"{"t":"Ping"}"#),
+        (Message::Text("hi".to_string()), r#"{"t":"Text","c":"hi"}"#),
+        (Message::Move(1, 2), r#"{"t":"Move","c":[1,2]}"#),
+        (
+            Message::Resize { w: 3, h: 4 },
+            r#"{"t":"Resize","c":{"w":3,"h":4}}"#,
+        ),
+    ];
+    for (value, expected) in cases {
+        let json = to_json_string(Peek::new(&value), false);
+        assert_eq!(json, expected);
+        assert_eq!(from_str::<Message>(&json).unwrap(), value);
+    }
+
+    assert_eq!(
+        from_str::<Message>(r#"{"c":[1,2],"t":"Move"}"#).unwrap(),
+        Message::Move(1, 2)
+    );
+
+    let err = from_str::<Message>(r#"{"t":"Text","c":7}"#).unwrap_err();
+    assert_eq!(err.error.path.to_string(), "c.Text");
+    let err = from_str::<Message>(r#"{"t":"Move","c":[1,-2]}"#).unwrap_err();
+    assert_eq!(err.error.path.to_string(), "c.Move[1]");
+}
+
+#[test]
+fn json_roundtrip_untagged_enum() {
+    facet_testhelpers::setup();
+
+    #[derive(Facet, Debug, PartialEq)]
+    #[repr(u8)]
+    #[facet(untagged)]
+    enum Value {
+        Nothing,
+        Pair(u32, u32),
+        Number(u32),
+        Text(String),
+        Named { name: String },
+    }
+
+    let cases = [
+        (Value::Nothing, "null"),
+        (Value::Pair(1, 2), "[1,2]"),
+        (Value::Number(3), "3"),
+        (Value::Text("hi".to_string()), r#""hi""#),
+        (
+            Value::Named {
+                name: "n".to_string(),
+            },
+            r#"{"name":"n"}"#,
+        ),
+    ];
+    for (value, expected) in cases {
+        let json = to_json_string(Peek::new(&value), false);
+        assert_eq!(json, expected);
+        assert_eq!(from_str::<Value>(&json).unwrap(), value);
+    }
+
+    let err = from_str::<Value>("[true]").unwrap_err();
+    assert!(
+        err.to_string()
+            .contains("data did not match any variant of untagged enum Value"),
+        "{err}"
+    );
+}
+
+#[test]
+fn json_roundtrip_flattened_structs() {
+    facet_testhelpers::setup();
+
+    #[derive(Facet, Debug, PartialEq)]
+    struct Audit {
+        created_by: String,
+    }
+
+    #[derive(Facet, Debug, PartialEq)]
+    struct Metadata {
+        id: u64,
+        #[facet(flatten)]
+        audit: Audit,
+    }
+
+    #[derive(Facet, Debug, PartialEq)]
+    struct Pagination {
+        page: u32,
+    }
+
+    #[derive(Facet, Debug, PartialEq)]
+    struct Response {
+        #[facet(flatten)]
+        meta: Metadata,
+        items: Vec<String>,
+        #[facet(flatten)]
+        pagination: Pagination,
+    }
+
+    let value = Response {
+        meta: Metadata {
+            id: 7,
+            audit: Audit {
+                created_by: "alice".to_string(),
+            },
+        },
+        items: vec!["a".to_string()],
+        pagination: Pagination { page: 2 },
+    };
+
+    let json = to_json_string(Peek::new(&value), false);
+    assert_eq!(
+        json,
+        r#"{"id":7,"created_by":"alice","items":["a"],"page":2}"#
+    );
+
+    let parsed: Response = from_str(&json).unwrap();
+    assert_eq!(parsed, value);
+
+    // Keys of flattened structs can come in any order
+    let parsed: Response =
+        from_str(r#"{"page":2,"created_by":"alice","items":["a"],"id":7}"#).unwrap();
+    assert_eq!(parsed, value);
+
+    // The name of a flattened field is not a key
+    assert!(from_str::<Response>(r#"{"meta":{"id":7},"items":[],"page":2}"#).is_err());
+
+    let err = from_str::<Response>(r#"{"id":7,"items":[],"page":2}"#).unwrap_err();
+    assert!(
+        err.to_string()
+            .contains("missing field in Audit: `created_by`"),
+        "{err}"
+    );
+}
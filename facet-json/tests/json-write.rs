@@ -125,3 +125,97 @@ fn test_static_strings() {
 
     assert_eq!(json, r#"{"foo":"foo"}"#);
 }
+
+#[test]
+fn test_serialize_smart_pointers() {
+    facet_testhelpers::setup();
+
+    #[derive(Debug, Facet)]
+    struct Node {
+        value: u32,
+        next: Option<Box<Leaf>>,
+        shared: std::sync::Arc<Leaf>,
+        weak: std::rc::Weak<Leaf>,
+    }
+
+    #[derive(Debug, Facet)]
+    struct Leaf {
+        name: String,
+    }
+
+    let node = Node {
+        value: 1,
+        next: None,
+        shared: std::sync::Arc::new(Leaf {
+            name: "shared".to_string(),
+        }),
+        weak: std::rc::Weak::new(),
+    };
+
+    let json = to_json_string(Peek::new(&node), false);
+    assert_eq!(
+        json,
+        r#"{"value":1,"next":null,"shared":{"name":"shared"},"weak":null}"#
+    );
+
+    let boxed = Box::new(Leaf {
+        name: "boxed".to_string(),
+    });
+    let json = to_json_string(Peek::new(&boxed), false);
+    assert_eq!(json, r#"{"name":"boxed"}"#);
+}
+
+#[test]
+fn test_skipped_fields() {
+    facet_testhelpers::setup();
+
+    #[derive(Debug, Facet)]
+    struct Session {
+        id: u64,
+        #[facet(skip)]
+        cache: Vec<u8>,
+        #[facet(skip_serializing)]
+        token: String,
+        #[facet(skip_serializing_if = "Option::is_none")]
+        user: Option<String>,
+    }
+
+    let session = Session {
+        id: 7,
+        cache: vec![1, 2, 3],
+        token: "secret".to_string(),
+        user: None,
+    };
+    let json = to_json_string(Peek::new(&session), false);
+    assert_eq!(json, r#"{"id":7}"#);
+
+    let session = Session {
+        user: Some("alice".to_string()),
+        ..session
+    };
+    let json = to_json_string(Peek::new(&session), false);
+    assert_eq!(json, r#"{"id":7,"user":"alice"}"#);
+}
+
+#[test]
+fn json_write_escapes_strings_keys_and_field_names() {
+    facet_testhelpers::setup();
+
+    #[derive(Debug, PartialEq, Facet)]
+    struct Note {
+        #[facet(rename = "say \"hi\"")]
+        text: String,
+        tags: std::collections::BTreeMap<String, u8>,
+    }
+
+    let note = Note {
+        text: "tab\there\nquote\" backslash\\ bell\u{7} é".to_string(),
+        tags: [("a\"b\u{1}".to_string(), 1)].into(),
+    };
+    let json = to_json_string(Peek::new(&note), false);
+    assert_eq!(
+        json,
+        r#"{"say \"hi\"":"tab\there\nquote\" backslash\\ bell\u0007 é","tags":{"a\"b\u0001":1}}"#
+    );
+    assert_eq!(facet_json::from_str::<Note>(&json).unwrap(), note);
+}
@@ -1,5 +1,8 @@
 use core::fmt;
 
+use facet_core::Shape;
+use facet_reflect::{DeserializeError, ReflectError};
+
 #[derive(Debug)]
 #[non_exhaustive]
 /// Errors that can occur during MessagePack encoding/decoding operations
@@ -12,6 +15,16 @@ pub enum Error {
     InvalidData,
     /// Encountered a field name that isn't recognized
     UnknownField(String),
+    /// Encountered an enum variant name that isn't recognized
+    UnknownVariant(String),
+    /// The tag of an internally or adjacently tagged enum is missing
+    MissingTag(&'static str),
+    /// The data did not match any variant of an untagged enum
+    NoMatchingVariant,
+    /// The shape can't be decoded from MessagePack, e.g. a borrowed `&str`
+    UnsupportedShape(&'static Shape),
+    /// The decoded value could not be built, e.g. because fields were missing
+    Reflect(ReflectError),
 }
 
 impl fmt::Display for Error {
@@ -21,8 +34,31 @@ impl fmt::Display for Error {
             Error::InsufficientData => write!(f, "Insufficient data to decode"),
             Error::InvalidData => write!(f, "Invalid MessagePack data"),
             Error::UnknownField(field) => write!(f, "Unknown field: {}", field),
+            Error::UnknownVariant(variant) => write!(f, "Unknown variant: {}", variant),
+            Error::MissingTag(tag) => write!(f, "Missing enum tag: {}", tag),
+            Error::NoMatchingVariant => write!(f, "Data did not match any enum variant"),
+            Error::UnsupportedShape(shape) => write!(f, "Unsupported shape: {}", shape),
+            Error::Reflect(err) => write!(f, "{}", err),
         }
     }
 }
 
 impl std::error::Error for Error {}
+
+impl From<DeserializeError> for Error {
+    fn from(err: DeserializeError) -> Self {
+        match err {
+            DeserializeError::UnknownField { field, .. } => Error::UnknownField(field),
+            DeserializeError::UnknownVariant { variant, .. } => Error::UnknownVariant(variant),
+            DeserializeError::MissingTag { tag, .. } => Error::MissingTag(tag),
+            DeserializeError::NoMatchingVariant { .. } => Error::NoMatchingVariant,
+            DeserializeError::Unsupported { shape }
+            | DeserializeError::BorrowedString { shape } => Error::UnsupportedShape(shape),
+            DeserializeError::OutOfRange { .. } | DeserializeError::InvalidValue { .. } => {
+                Error::InvalidData
+            }
+            DeserializeError::Reflect(err) => Error::Reflect(err),
+            _ => Error::UnexpectedType,
+        }
+    }
+}
@@ -2,8 +2,8 @@ use crate::constants::*;
 use crate::errors::Error as DecodeError;
 
 use facet_core::{Facet, Opaque};
-use facet_reflect::PokeUninit;
-use log::trace;
+use facet_reflect::{Deserializer, PathError, PokeUninit, Token, deserialize_opaque};
+use std::borrow::Cow;
 
 /// Deserializes MessagePack-encoded data into a type that implements `Facet`.
 ///
@@ -28,7 +28,7 @@ use log::trace;
 /// let user: User = from_str(&msgpack_data).unwrap();
 /// assert_eq!(user, User { id: 42, username: "user123".to_string() });
 /// ```
-pub fn from_str<T: Facet>(msgpack: &[u8]) -> Result<T, DecodeError> {
+pub fn from_str<'facet, T: Facet<'facet>>(msgpack: &[u8]) -> Result<T, PathError<DecodeError>> {
     // Allocate a Poke for type T
     let (poke, _guard) = PokeUninit::alloc::<T>();
 
@@ -75,7 +75,8 @@ pub fn from_str<T: Facet>(msgpack: &[u8]) -> Result<T, DecodeError> {
 ///
 /// # Returns
 /// * `Ok(())` if deserialization was successful
-/// * `Err(DecodeError)` if an error occurred during deserialization
+/// * `Err(PathError<DecodeError>)` if an error occurred during deserialization, along with
+///   where in the value it happened
 ///
 /// # MessagePack Format
 /// This implementation follows the MessagePack specification:
@@ -84,64 +85,115 @@ pub fn from_str<T: Facet>(msgpack: &[u8]) -> Result<T, DecodeError> {
 pub fn from_slice_opaque<'mem>(
     poke: PokeUninit<'mem>,
     msgpack: &[u8],
-) -> Result<Opaque<'mem>, DecodeError> {
+) -> Result<Opaque<'mem>, PathError<DecodeError>> {
     let mut decoder = Decoder::new(msgpack);
+    // SAFETY: strings and bytes are copied out of the input, never borrowed
+    unsafe { deserialize_opaque(poke, &mut decoder) }
+}
 
-    fn deserialize_value<'mem>(
-        decoder: &mut Decoder,
-        poke: PokeUninit<'mem>,
-    ) -> Result<Opaque<'mem>, DecodeError> {
-        let shape = poke.shape();
-        trace!("Deserializing {:?}", shape);
-
-        let opaque = match poke {
-            PokeUninit::Scalar(pv) => {
-                trace!("Deserializing scalar");
-                if pv.shape().is_type::<String>() {
-                    let s = decoder.decode_string()?;
-                    let data = pv.put(s);
-                    data
-                } else if pv.shape().is_type::<u64>() {
-                    let n = decoder.decode_u64()?;
-                    pv.put(n)
-                } else {
-                    todo!("Unsupported scalar type: {}", pv.shape())
-                }
-            }
-            PokeUninit::Struct(mut ps) => {
-                trace!("Deserializing struct");
-                let map_len = decoder.decode_map_len()?;
+struct Decoder<'input> {
+    input: &'input [u8],
+    offset: usize,
+    /// The arrays and maps being read, innermost last: whether it's a map, and how many
+    /// values are left in it (two per map entry)
+    open: Vec<(bool, usize)>,
+}
 
-                for _ in 0..map_len {
-                    let key = decoder.decode_string()?;
-                    let (index, field_poke) = ps
-                        .field_by_name(&key)
-                        .map_err(|_| DecodeError::UnknownField(key))?;
+impl<'de> Deserializer<'de> for Decoder<'_> {
+    type Error = DecodeError;
 
-                    deserialize_value(decoder, field_poke)?;
-                    unsafe { ps.mark_initialized(index) };
-                }
-                ps.build_in_place()
-            }
-            _ => {
-                todo!("Unsupported shape: {:?}", shape)
+    fn next(&mut self) -> Result<Token<'de>, DecodeError> {
+        match self.open.last_mut() {
+            Some((is_map, 0)) => {
+                let is_map = *is_map;
+                self.open.pop();
+                return Ok(if is_map {
+                    Token::EndMap
+                } else {
+                    Token::EndList
+                });
             }
-        };
-
-        Ok(opaque)
+            Some((_, remaining)) => *remaining -= 1,
+            None => {}
+        }
+        self.decode_token()
     }
 
-    deserialize_value(&mut decoder, poke)
-}
-
-struct Decoder<'input> {
-    input: &'input [u8],
-    offset: usize,
+    fn skip(&mut self) -> Result<(), DecodeError> {
+        if let Some((_, remaining)) = self.open.last_mut() {
+            *remaining = remaining
+                .checked_sub(1)
+                .ok_or(DecodeError::UnexpectedType)?;
+        }
+        self.skip_value()
+    }
 }
 
 impl<'input> Decoder<'input> {
     fn new(input: &'input [u8]) -> Self {
-        Decoder { input, offset: 0 }
+        Decoder {
+            input,
+            offset: 0,
+            open: Vec::new(),
+        }
+    }
+
+    /// Decodes the next value, or the start of an array or map, as a token.
+    /// Strings and binary data are copied, so that tokens outlive the input.
+    fn decode_token(&mut self) -> Result<Token<'static>, DecodeError> {
+        let prefix = self.input.get(self.offset).copied();
+        let token = match prefix.ok_or(DecodeError::InsufficientData)? {
+            MSGPACK_NIL => {
+                self.offset += 1;
+                Token::Null
+            }
+            MSGPACK_FALSE | MSGPACK_TRUE => {
+                self.offset += 1;
+                Token::Bool(prefix == Some(MSGPACK_TRUE))
+            }
+            MSGPACK_FLOAT32 => {
+                self.offset += 1;
+                Token::F64(f32::from_bits(self.decode_u32()?) as f64)
+            }
+            MSGPACK_FLOAT64 => {
+                self.offset += 1;
+                Token::F64(f64::from_bits(self.decode_u64_raw()?))
+            }
+            MSGPACK_POSFIXINT_MIN..=MSGPACK_POSFIXINT_MAX
+            | MSGPACK_UINT8
+            | MSGPACK_UINT16
+            | MSGPACK_UINT32
+            | MSGPACK_UINT64 => Token::U64(self.decode_u64()?),
+            MSGPACK_INT8 | MSGPACK_INT16 | MSGPACK_INT32 | MSGPACK_INT64 => {
+                Token::I64(self.decode_i64()?)
+            }
+            prefix if (prefix as i8) >= MSGPACK_NEGFIXINT_MIN && (prefix as i8) < 0 => {
+                Token::I64(self.decode_i64()?)
+            }
+            MSGPACK_FIXSTR_MIN..=MSGPACK_FIXSTR_MAX
+            | MSGPACK_STR8
+            | MSGPACK_STR16
+            | MSGPACK_STR32 => Token::Str(Cow::Owned(self.decode_string()?)),
+            MSGPACK_BIN8 | MSGPACK_BIN16 | MSGPACK_BIN32 => {
+                Token::Bytes(Cow::Owned(self.decode_bin()?.to_vec()))
+            }
+            MSGPACK_FIXEXT4 | MSGPACK_FIXEXT8 | MSGPACK_EXT8 => {
+                let (secs, nanos) = self.decode_timestamp()?;
+                Token::Timestamp { secs, nanos }
+            }
+            MSGPACK_FIXARRAY_MIN..=MSGPACK_FIXARRAY_MAX | MSGPACK_ARRAY16 | MSGPACK_ARRAY32 => {
+                let len = self.decode_array_len()?;
+                self.open.push((false, len));
+                Token::StartList(Some(len))
+            }
+            MSGPACK_FIXMAP_MIN..=MSGPACK_FIXMAP_MAX | MSGPACK_MAP16 | MSGPACK_MAP32 => {
+                let len = self.decode_map_len()?;
+                self.open.push((true, len * 2));
+                Token::StartMap(Some(len))
+            }
+            _ => return Err(DecodeError::UnexpectedType),
+        };
+        Ok(token)
     }
 
     /// Decodes a single byte from the input.
@@ -179,6 +231,18 @@ impl<'input> Decoder<'input> {
         Ok(value)
     }
 
+    /// Decodes a 64-bit unsigned integer in big-endian byte order.
+    /// This is a low-level method used by other decoders.
+    fn decode_u64_raw(&mut self) -> Result<u64, DecodeError> {
+        if self.offset + 8 > self.input.len() {
+            return Err(DecodeError::InsufficientData);
+        }
+        let value =
+            u64::from_be_bytes(self.input[self.offset..self.offset + 8].try_into().unwrap());
+        self.offset += 8;
+        Ok(value)
+    }
+
     /// Decodes a MessagePack-encoded unsigned 64-bit integer.
     /// Handles the following MessagePack types:
     /// - positive fixint (0x00 - 0x7f): single-byte positive integer
@@ -193,21 +257,101 @@ impl<'input> Decoder<'input> {
             MSGPACK_UINT8 => Ok(self.decode_u8()? as u64),
             MSGPACK_UINT16 => Ok(self.decode_u16()? as u64),
             MSGPACK_UINT32 => Ok(self.decode_u32()? as u64),
-            MSGPACK_UINT64 => {
-                if self.offset + 8 > self.input.len() {
-                    return Err(DecodeError::InsufficientData);
-                }
-                let value = u64::from_be_bytes(
-                    self.input[self.offset..self.offset + 8].try_into().unwrap(),
-                );
-                self.offset += 8;
-                Ok(value)
-            }
+            MSGPACK_UINT64 => self.decode_u64_raw(),
             prefix @ MSGPACK_POSFIXINT_MIN..=MSGPACK_POSFIXINT_MAX => Ok(prefix as u64),
             _ => Err(DecodeError::UnexpectedType),
         }
     }
 
+    /// Decodes a MessagePack-encoded signed 64-bit integer.
+    /// Handles the following MessagePack types:
+    /// - negative fixint (0xe0 - 0xff): single-byte negative integer
+    /// - int8 (0xd0) / int16 (0xd1) / int32 (0xd2) / int64 (0xd3): signed integers (big-endian)
+    /// - any unsigned integer format that fits in an `i64`
+    ///
+    /// Ref: <https://github.com/msgpack/msgpack/blob/master/spec.md#int-format-family>
+    fn decode_i64(&mut self) -> Result<i64, DecodeError> {
+        let prefix = self.input.get(self.offset).copied();
+        match prefix.ok_or(DecodeError::InsufficientData)? {
+            MSGPACK_INT8 => {
+                self.offset += 1;
+                Ok(self.decode_u8()? as i8 as i64)
+            }
+            MSGPACK_INT16 => {
+                self.offset += 1;
+                Ok(self.decode_u16()? as i16 as i64)
+            }
+            MSGPACK_INT32 => {
+                self.offset += 1;
+                Ok(self.decode_u32()? as i32 as i64)
+            }
+            MSGPACK_INT64 => {
+                self.offset += 1;
+                Ok(self.decode_u64_raw()? as i64)
+            }
+            prefix if (prefix as i8) >= MSGPACK_NEGFIXINT_MIN && (prefix as i8) < 0 => {
+                self.offset += 1;
+                Ok(prefix as i8 as i64)
+            }
+            _ => i64::try_from(self.decode_u64()?).map_err(|_| DecodeError::InvalidData),
+        }
+    }
+
+    /// Decodes MessagePack-encoded binary data.
+    /// Handles the following MessagePack types:
+    /// - bin8 (0xc4): up to 255 bytes
+    /// - bin16 (0xc5): up to 65535 bytes
+    /// - bin32 (0xc6): up to 4294967295 bytes
+    ///
+    /// Ref: <https://github.com/msgpack/msgpack/blob/master/spec.md#formats-bin>
+    fn decode_bin(&mut self) -> Result<&'input [u8], DecodeError> {
+        let len = match self.decode_u8()? {
+            MSGPACK_BIN8 => self.decode_u8()? as usize,
+            MSGPACK_BIN16 => self.decode_u16()? as usize,
+            MSGPACK_BIN32 => self.decode_u32()? as usize,
+            _ => return Err(DecodeError::UnexpectedType),
+        };
+        if self.offset + len > self.input.len() {
+            return Err(DecodeError::InsufficientData);
+        }
+        let value = &self.input[self.offset..self.offset + len];
+        self.offset += len;
+        Ok(value)
+    }
+
+    /// Decodes a MessagePack timestamp extension (type -1).
+    /// Handles the following formats:
+    /// - timestamp 32 (fixext4): unsigned seconds
+    /// - timestamp 64 (fixext8): 30-bit nanoseconds and 34-bit unsigned seconds
+    /// - timestamp 96 (ext8 with a length of 12): 32-bit nanoseconds and signed 64-bit seconds
+    ///
+    /// Ref: <https://github.com/msgpack/msgpack/blob/master/spec.md#timestamp-extension-type>
+    fn decode_timestamp(&mut self) -> Result<(i64, u32), DecodeError> {
+        let prefix = self.decode_u8()?;
+        let len = match prefix {
+            MSGPACK_FIXEXT4 => 4,
+            MSGPACK_FIXEXT8 => 8,
+            MSGPACK_EXT8 => self.decode_u8()?,
+            _ => return Err(DecodeError::UnexpectedType),
+        };
+        if self.decode_u8()? as i8 != MSGPACK_EXT_TIMESTAMP {
+            return Err(DecodeError::UnexpectedType);
+        }
+        let (secs, nanos) = match len {
+            4 => (self.decode_u32()? as i64, 0),
+            8 => {
+                let data = self.decode_u64_raw()?;
+                ((data & 0x3_ffff_ffff) as i64, (data >> 34) as u32)
+            }
+            12 => {
+                let nanos = self.decode_u32()?;
+                (self.decode_u64_raw()? as i64, nanos)
+            }
+            _ => return Err(DecodeError::InvalidData),
+        };
+        Ok((secs, nanos))
+    }
+
     /// Decodes a MessagePack-encoded string.
     /// Handles the following MessagePack types:
     /// - fixstr (0xa0 - 0xbf): string up to 31 bytes
@@ -254,4 +398,79 @@ impl<'input> Decoder<'input> {
             _ => Err(DecodeError::UnexpectedType),
         }
     }
+
+    /// Decodes a MessagePack-encoded array length.
+    /// Handles the following MessagePack types:
+    /// - fixarray (0x90 - 0x9f): array with up to 15 elements
+    /// - array16 (0xdc): array with up to 65535 elements
+    /// - array32 (0xdd): array with up to 4294967295 elements
+    ///
+    /// Ref: <https://github.com/msgpack/msgpack/blob/master/spec.md#formats-array>
+    fn decode_array_len(&mut self) -> Result<usize, DecodeError> {
+        let prefix = self.decode_u8()?;
+
+        match prefix {
+            prefix @ MSGPACK_FIXARRAY_MIN..=MSGPACK_FIXARRAY_MAX => Ok((prefix & 0x0f) as usize),
+            MSGPACK_ARRAY16 => Ok(self.decode_u16()? as usize),
+            MSGPACK_ARRAY32 => Ok(self.decode_u32()? as usize),
+            _ => Err(DecodeError::UnexpectedType),
+        }
+    }
+
+    /// Skips over the next value, whatever its type, including nested arrays and maps.
+    ///
+    /// Ref: <https://github.com/msgpack/msgpack/blob/master/spec.md#overview>
+    fn skip_value(&mut self) -> Result<(), DecodeError> {
+        let prefix = self.decode_u8()?;
+        let len = match prefix {
+            MSGPACK_POSFIXINT_MIN..=MSGPACK_POSFIXINT_MAX
+            | MSGPACK_NIL
+            | MSGPACK_FALSE
+            | MSGPACK_TRUE => 0,
+            prefix if (prefix as i8) >= MSGPACK_NEGFIXINT_MIN && (prefix as i8) < 0 => 0,
+            MSGPACK_FIXSTR_MIN..=MSGPACK_FIXSTR_MAX => (prefix & 0x1f) as usize,
+            MSGPACK_UINT8 | MSGPACK_INT8 => 1,
+            MSGPACK_UINT16 | MSGPACK_INT16 => 2,
+            MSGPACK_UINT32 | MSGPACK_INT32 | MSGPACK_FLOAT32 => 4,
+            MSGPACK_UINT64 | MSGPACK_INT64 | MSGPACK_FLOAT64 => 8,
+            MSGPACK_STR8 | MSGPACK_BIN8 => self.decode_u8()? as usize,
+            MSGPACK_STR16 | MSGPACK_BIN16 => self.decode_u16()? as usize,
+            MSGPACK_STR32 | MSGPACK_BIN32 => self.decode_u32()? as usize,
+            // ext payloads are preceded by a one-byte type
+            MSGPACK_FIXEXT1 => 2,
+            MSGPACK_FIXEXT2 => 3,
+            MSGPACK_FIXEXT4 => 5,
+            MSGPACK_FIXEXT8 => 9,
+            MSGPACK_FIXEXT16 => 17,
+            MSGPACK_EXT8 => self.decode_u8()? as usize + 1,
+            MSGPACK_EXT16 => self.decode_u16()? as usize + 1,
+            MSGPACK_EXT32 => self.decode_u32()? as usize + 1,
+            MSGPACK_FIXARRAY_MIN..=MSGPACK_FIXARRAY_MAX
+            | MSGPACK_ARRAY16
+            | MSGPACK_ARRAY32
+            | MSGPACK_FIXMAP_MIN..=MSGPACK_FIXMAP_MAX
+            | MSGPACK_MAP16
+            | MSGPACK_MAP32 => {
+                self.offset -= 1;
+                let count = if prefix == MSGPACK_MAP16
+                    || prefix == MSGPACK_MAP32
+                    || (MSGPACK_FIXMAP_MIN..=MSGPACK_FIXMAP_MAX).contains(&prefix)
+                {
+                    self.decode_map_len()? * 2
+                } else {
+                    self.decode_array_len()?
+                };
+                for _ in 0..count {
+                    self.skip_value()?;
+                }
+                return Ok(());
+            }
+            _ => return Err(DecodeError::InvalidData),
+        };
+        if self.offset + len > self.input.len() {
+            return Err(DecodeError::InsufficientData);
+        }
+        self.offset += len;
+        Ok(())
+    }
 }
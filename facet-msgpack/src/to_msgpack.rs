@@ -1,69 +1,182 @@
-use facet_core::Facet;
-use facet_reflect::Peek;
+use crate::constants::*;
+use facet_core::{Def, Facet, ScalarAffinity, Shape};
+use facet_reflect::{Peek, PeekValue, Serializer};
 use log::trace;
+use std::borrow::Cow;
 use std::io::{self, Write};
+use std::num::NonZero;
+use std::time::{SystemTime, UNIX_EPOCH};
 
 /// Serializes any Facet type to MessagePack bytes
-pub fn to_vec<T: Facet>(value: &T) -> Vec<u8> {
+///
+/// Fails with [`io::ErrorKind::InvalidData`] if the value can't be represented in
+/// MessagePack, see [`to_writer`].
+pub fn to_vec<'facet, T: Facet<'facet>>(value: &T) -> io::Result<Vec<u8>> {
     let mut buffer = Vec::new();
-    let peek = Peek::new(value);
-    serialize(peek, &mut buffer).unwrap();
-    buffer
+    to_writer(&mut buffer, value)?;
+    Ok(buffer)
 }
 
 /// Serializes any Facet type to a writer in MessagePack format
-fn serialize<W: Write>(peek: Peek<'_>, writer: &mut W) -> io::Result<()> {
-    match peek {
-        Peek::Value(pv) => {
-            trace!("Serializing scalar");
-            if pv.shape().is_type::<String>() {
-                let value = unsafe { pv.data().as_ref::<String>() };
-                write_str(writer, value)
-            } else if pv.shape().is_type::<u64>() {
-                let value = unsafe { pv.data().as_ref::<u64>() };
-                write_u64(writer, *value)
-            } else if pv.shape().is_type::<u32>() {
-                let value = unsafe { pv.data().as_ref::<u32>() };
-                write_u32(writer, *value)
-            } else if pv.shape().is_type::<u16>() {
-                let value = unsafe { pv.data().as_ref::<u16>() };
-                write_u16(writer, *value)
-            } else if pv.shape().is_type::<u8>() {
-                let value = unsafe { pv.data().as_ref::<u8>() };
-                write_u8(writer, *value)
-            } else if pv.shape().is_type::<i64>() {
-                let value = unsafe { pv.data().as_ref::<i64>() };
-                write_i64(writer, *value)
-            } else if pv.shape().is_type::<i32>() {
-                let value = unsafe { pv.data().as_ref::<i32>() };
-                write_i32(writer, *value)
-            } else if pv.shape().is_type::<i16>() {
-                let value = unsafe { pv.data().as_ref::<i16>() };
-                write_i16(writer, *value)
-            } else if pv.shape().is_type::<i8>() {
-                let value = unsafe { pv.data().as_ref::<i8>() };
-                write_i8(writer, *value)
-            } else {
-                todo!("Unsupported scalar type: {}", pv.shape())
-            }
-        }
-        Peek::Struct(ps) => {
-            trace!("Serializing struct");
+///
+/// Fails if the writer does, or with [`io::ErrorKind::InvalidData`] if the value can't be
+/// represented in MessagePack (e.g. a `u128` that doesn't fit in 64 bits).
+pub fn to_writer<'facet, W: Write, T: Facet<'facet>>(writer: &mut W, value: &T) -> io::Result<()> {
+    facet_reflect::serialize(Peek::new(value), &mut MsgPackSerializer { writer })
+}
 
-            // Write map header
-            let fields: Vec<_> = ps.fields().collect();
-            write_map_len(writer, fields.len())?;
+fn invalid_data(message: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
 
-            // Write fields
-            for (name, field_peek) in fields {
-                write_str(writer, name)?;
-                serialize(field_peek, writer)?;
+macro_rules! ints {
+    ($pv:expr, $writer:expr, $write:ident, $($type:ty),*) => {
+        $(
+            if $pv.shape().is_type::<$type>() {
+                let value = unsafe { $pv.data().as_ref::<$type>() };
+                return $write($writer, *value);
             }
-            Ok(())
-        }
-        _ => {
-            todo!("Unsupported type: {:?}", peek)
-        }
+            if $pv.shape().is_type::<NonZero<$type>>() {
+                let value = unsafe { $pv.data().as_ref::<NonZero<$type>>() };
+                return $write($writer, value.get());
+            }
+        )*
+    };
+}
+
+macro_rules! wide_ints {
+    ($pv:expr, $writer:expr, $write:ident, $wide:ty, $($type:ty),*) => {
+        $(
+            if $pv.shape().is_type::<$type>() {
+                let value = unsafe { $pv.data().as_ref::<$type>() };
+                let value = <$wide>::try_from(*value)
+                    .map_err(|_| invalid_data(format!("{} is out of range for MessagePack", value)))?;
+                return $write($writer, value);
+            }
+            if $pv.shape().is_type::<NonZero<$type>>() {
+                let value = unsafe { $pv.data().as_ref::<NonZero<$type>>() };
+                let value = <$wide>::try_from(value.get())
+                    .map_err(|_| invalid_data(format!("{} is out of range for MessagePack", value)))?;
+                return $write($writer, value);
+            }
+        )*
+    };
+}
+
+/// `()` and `PhantomData<T>` carry no data and are written as nil
+pub(crate) fn is_empty_scalar(shape: &Shape) -> bool {
+    match shape.def {
+        Def::Scalar(sd) => matches!(sd.affinity, ScalarAffinity::Empty(_)),
+        _ => false,
+    }
+}
+
+fn serialize_scalar<W: Write>(pv: PeekValue<'_>, writer: &mut W) -> io::Result<()> {
+    let shape = pv.shape();
+    if is_empty_scalar(shape) {
+        return writer.write_all(&[MSGPACK_NIL]);
+    }
+    if shape.is_type::<bool>() {
+        let value = unsafe { pv.data().as_ref::<bool>() };
+        return writer.write_all(&[if *value { MSGPACK_TRUE } else { MSGPACK_FALSE }]);
+    }
+    if shape.is_type::<f32>() {
+        let value = unsafe { pv.data().as_ref::<f32>() };
+        writer.write_all(&[MSGPACK_FLOAT32])?;
+        return writer.write_all(&value.to_be_bytes());
+    }
+    if shape.is_type::<f64>() {
+        let value = unsafe { pv.data().as_ref::<f64>() };
+        writer.write_all(&[MSGPACK_FLOAT64])?;
+        return writer.write_all(&value.to_be_bytes());
+    }
+    if shape.is_type::<String>() {
+        let value = unsafe { pv.data().as_ref::<String>() };
+        return write_str(writer, value);
+    }
+    if shape.is_type::<&str>() {
+        let value = unsafe { pv.data().as_ref::<&str>() };
+        return write_str(writer, value);
+    }
+    if shape.is_type::<Cow<'_, str>>() {
+        let value = unsafe { pv.data().as_ref::<Cow<'_, str>>() };
+        return write_str(writer, value);
+    }
+    if shape.is_type::<SystemTime>() {
+        let value = unsafe { pv.data().as_ref::<SystemTime>() };
+        return write_timestamp(writer, *value);
+    }
+    ints!(pv, writer, write_u8, u8);
+    ints!(pv, writer, write_u16, u16);
+    ints!(pv, writer, write_u32, u32);
+    ints!(pv, writer, write_u64, u64);
+    ints!(pv, writer, write_i8, i8);
+    ints!(pv, writer, write_i16, i16);
+    ints!(pv, writer, write_i32, i32);
+    ints!(pv, writer, write_i64, i64);
+    wide_ints!(pv, writer, write_u64, u64, u128, usize);
+    wide_ints!(pv, writer, write_i64, i64, i128, isize);
+
+    // anything else (e.g. `char` or `IpAddr`) is written in its string form
+    if pv.vtable().display.is_some() {
+        return write_str(writer, &Peek::Value(pv).to_string());
+    }
+    Err(invalid_data(format!("unsupported scalar type: {}", shape)))
+}
+
+/// Writes MessagePack as a [`Serializer`] is told what's in the value
+struct MsgPackSerializer<'w, W: Write> {
+    writer: &'w mut W,
+}
+
+impl<W: Write> Serializer for MsgPackSerializer<'_, W> {
+    type Error = io::Error;
+
+    fn scalar(&mut self, value: PeekValue<'_>) -> io::Result<()> {
+        trace!("Serializing scalar");
+        serialize_scalar(value, self.writer)
+    }
+
+    fn str(&mut self, s: &str) -> io::Result<()> {
+        write_str(self.writer, s)
+    }
+
+    fn none(&mut self) -> io::Result<()> {
+        self.writer.write_all(&[MSGPACK_NIL])
+    }
+
+    fn bytes(&mut self, bytes: &[u8]) -> io::Result<()> {
+        // byte lists are written as `bin`, everything else as an array
+        write_bin(self.writer, bytes)
+    }
+
+    fn start_struct(&mut self, len: usize) -> io::Result<()> {
+        write_map_len(self.writer, len)
+    }
+
+    fn field(&mut self, name: &str) -> io::Result<()> {
+        write_str(self.writer, name)
+    }
+
+    fn end_struct(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+
+    fn start_list(&mut self, len: usize) -> io::Result<()> {
+        write_array_len(self.writer, len)
+    }
+
+    fn end_list(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+
+    // Unlike text formats, MessagePack keys can be any value, so they're written as-is
+    fn start_map(&mut self, len: usize) -> io::Result<()> {
+        write_map_len(self.writer, len)
+    }
+
+    fn end_map(&mut self) -> io::Result<()> {
+        Ok(())
     }
 }
 
@@ -94,6 +207,58 @@ fn write_str<W: Write>(writer: &mut W, s: &str) -> io::Result<()> {
     writer.write_all(bytes)
 }
 
+fn write_bin<W: Write>(writer: &mut W, bytes: &[u8]) -> io::Result<()> {
+    let len = bytes.len();
+    match len {
+        0..=255 => {
+            // bin8
+            writer.write_all(&[MSGPACK_BIN8, len as u8])?;
+        }
+        256..=65535 => {
+            // bin16
+            writer.write_all(&[MSGPACK_BIN16])?;
+            writer.write_all(&(len as u16).to_be_bytes())?;
+        }
+        _ => {
+            // bin32
+            writer.write_all(&[MSGPACK_BIN32])?;
+            writer.write_all(&(len as u32).to_be_bytes())?;
+        }
+    }
+    writer.write_all(bytes)
+}
+
+/// Writes a timestamp extension, picking the smallest of the 32, 64 and 96-bit formats
+fn write_timestamp<W: Write>(writer: &mut W, time: SystemTime) -> io::Result<()> {
+    let (secs, nanos) = match time.duration_since(UNIX_EPOCH) {
+        Ok(d) => (d.as_secs() as i64, d.subsec_nanos()),
+        Err(e) => {
+            // before the epoch: seconds are negative, nanoseconds still count forward
+            let d = e.duration();
+            match d.subsec_nanos() {
+                0 => (-(d.as_secs() as i64), 0),
+                n => (-(d.as_secs() as i64) - 1, 1_000_000_000 - n),
+            }
+        }
+    };
+    let ext_type = MSGPACK_EXT_TIMESTAMP as u8;
+    if secs >= 0 && secs >> 34 == 0 {
+        if nanos == 0 && secs <= u32::MAX as i64 {
+            // timestamp 32
+            writer.write_all(&[MSGPACK_FIXEXT4, ext_type])?;
+            return writer.write_all(&(secs as u32).to_be_bytes());
+        }
+        // timestamp 64
+        let data = ((nanos as u64) << 34) | secs as u64;
+        writer.write_all(&[MSGPACK_FIXEXT8, ext_type])?;
+        return writer.write_all(&data.to_be_bytes());
+    }
+    // timestamp 96
+    writer.write_all(&[MSGPACK_EXT8, 12, ext_type])?;
+    writer.write_all(&nanos.to_be_bytes())?;
+    writer.write_all(&secs.to_be_bytes())
+}
+
 fn write_u8<W: Write>(writer: &mut W, n: u8) -> io::Result<()> {
     match n {
         0..=127 => {
@@ -334,3 +499,22 @@ fn write_map_len<W: Write>(writer: &mut W, len: usize) -> io::Result<()> {
         }
     }
 }
+
+fn write_array_len<W: Write>(writer: &mut W, len: usize) -> io::Result<()> {
+    match len {
+        0..=15 => {
+            // fixarray
+            writer.write_all(&[(0x90 | len as u8)])
+        }
+        16..=65535 => {
+            // array16
+            writer.write_all(&[0xdc])?;
+            writer.write_all(&(len as u16).to_be_bytes())
+        }
+        _ => {
+            // array32
+            writer.write_all(&[0xdd])?;
+            writer.write_all(&(len as u32).to_be_bytes())
+        }
+    }
+}
@@ -31,3 +31,146 @@ fn it_works() {
         }
     );
 }
+
+#[test]
+fn missing_field_is_an_error() {
+    facet_testhelpers::setup();
+
+    #[derive(Debug, PartialEq, Facet)]
+    struct TestStruct {
+        name: String,
+        age: u64,
+    }
+
+    let data = [
+        0x81, // Fixmap with 1 element
+        0xa4, // Fixstr with length 4
+        0x6e, 0x61, 0x6d, 0x65, // "name"
+        0xa5, // Fixstr with length 5
+        0x41, 0x6c, 0x69, 0x63, 0x65, // "Alice"
+    ];
+
+    let err = facet_msgpack::from_str::<TestStruct>(&data).unwrap_err();
+    assert!(
+        matches!(&err.error, facet_msgpack::DecodeError::Reflect(_)),
+        "{err:?}"
+    );
+    assert_eq!(
+        err.to_string(),
+        "missing field in TestStruct: `age` (expected TestStruct)"
+    );
+}
+
+#[test]
+fn roundtrip_scalars() {
+    facet_testhelpers::setup();
+
+    #[derive(Debug, PartialEq, Facet)]
+    struct Scalars {
+        flag: bool,
+        single: f32,
+        double: f64,
+        letter: char,
+        cow: std::borrow::Cow<'static, str>,
+        wide: u128,
+        signed_wide: i128,
+        size: usize,
+        nonzero: std::num::NonZero<i32>,
+        ip: std::net::IpAddr,
+        socket: std::net::SocketAddr,
+        unit: (),
+        marker: std::marker::PhantomData<String>,
+    }
+
+    let value = Scalars {
+        flag: true,
+        single: 0.25,
+        double: -1e100,
+        letter: 'é',
+        cow: "borrowed".into(),
+        wide: u64::MAX as u128,
+        signed_wide: i64::MIN as i128,
+        size: 70_000,
+        nonzero: std::num::NonZero::new(-3).unwrap(),
+        ip: "::1".parse().unwrap(),
+        socket: "127.0.0.1:8080".parse().unwrap(),
+        unit: (),
+        marker: std::marker::PhantomData,
+    };
+
+    let decoded: Scalars =
+        facet_msgpack::from_str(&facet_msgpack::to_vec(&value).unwrap()).unwrap();
+    assert_eq!(decoded, value);
+
+    // integers are accepted where floats are expected
+    let decoded: f64 = facet_msgpack::from_str(&[0x05]).unwrap();
+    assert_eq!(decoded, 5.0);
+}
+
+#[test]
+fn roundtrip_collections() {
+    facet_testhelpers::setup();
+
+    #[derive(Debug, PartialEq, Facet)]
+    struct Point(i16, i16);
+
+    #[derive(Debug, PartialEq, Facet)]
+    struct Unit;
+
+    #[derive(Debug, PartialEq, Facet)]
+    struct Collections {
+        bytes: Vec<u8>,
+        points: Vec<Point>,
+        pair: (u8, String),
+        tags: std::collections::HashSet<String>,
+        lookup: std::collections::BTreeMap<u32, Vec<String>>,
+        maybe: Option<Box<Point>>,
+        nothing: Option<String>,
+        unit: Unit,
+    }
+
+    let value = Collections {
+        bytes: vec![0, 1, 2, 255],
+        points: vec![Point(1, -1), Point(300, -300)],
+        pair: (7, "seven".to_string()),
+        tags: ["a".to_string(), "b".to_string()].into(),
+        lookup: [(1, vec!["one".to_string()]), (2, vec![])].into(),
+        maybe: Some(Box::new(Point(0, 0))),
+        nothing: None,
+        unit: Unit,
+    };
+
+    let decoded: Collections =
+        facet_msgpack::from_str(&facet_msgpack::to_vec(&value).unwrap()).unwrap();
+    assert_eq!(decoded, value);
+
+    // byte lists can also be sent as plain arrays
+    let decoded: Vec<u8> = facet_msgpack::from_str(&[0x92, 0x01, 0xcc, 0xff]).unwrap();
+    assert_eq!(decoded, [1, 255]);
+}
+
+#[test]
+fn roundtrip_timestamps() {
+    facet_testhelpers::setup();
+
+    use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+    for time in [
+        UNIX_EPOCH,
+        UNIX_EPOCH + Duration::from_secs(1_700_000_000),
+        UNIX_EPOCH + Duration::new(1_700_000_000, 123_456_789),
+        UNIX_EPOCH + Duration::from_secs(1 << 40),
+        UNIX_EPOCH - Duration::new(86_400, 250_000_000),
+    ] {
+        let decoded: SystemTime =
+            facet_msgpack::from_str(&facet_msgpack::to_vec(&time).unwrap()).unwrap();
+        assert_eq!(decoded, time);
+    }
+
+    // a timestamp 64 with out of range nanoseconds is invalid
+    let err = facet_msgpack::from_str::<SystemTime>(&[
+        0xd7, 0xff, 0xff, 0xff, 0xff, 0xfc, 0x00, 0x00, 0x00, 0x00,
+    ])
+    .unwrap_err();
+    assert!(matches!(err.error, facet_msgpack::DecodeError::InvalidData));
+}
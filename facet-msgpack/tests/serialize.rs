@@ -32,7 +32,7 @@ fn test_integers() {
         int64: -9223372036854775808,
     };
 
-    let msgpack = to_vec(&test);
+    let msgpack = to_vec(&test).unwrap();
 
     // Expected format:
     // 8a                -- map with 10 elements
@@ -105,7 +105,7 @@ fn test_struct() {
         age: 30,
     };
 
-    let msgpack = to_vec(&test_struct);
+    let msgpack = to_vec(&test_struct).unwrap();
 
     // Expected MessagePack format:
     // 82                -- map with 2 elements
@@ -120,3 +120,314 @@ fn test_struct() {
 
     assert_eq!(msgpack, expected);
 }
+
+#[test]
+fn test_smart_pointers() {
+    facet_testhelpers::setup();
+
+    #[derive(Debug, PartialEq, Facet)]
+    struct Inner {
+        age: u64,
+    }
+
+    #[derive(Debug, PartialEq, Facet)]
+    struct Outer {
+        boxed: Box<Inner>,
+        shared: std::sync::Arc<String>,
+    }
+
+    let value = Outer {
+        boxed: Box::new(Inner { age: 30 }),
+        shared: std::sync::Arc::new("Alice".to_string()),
+    };
+
+    let msgpack = to_vec(&value).unwrap();
+
+    // Smart pointers are transparent: same bytes as the pointees themselves
+    let expected = [
+        0x82, // map with 2 elements
+        0xa5, 0x62, 0x6f, 0x78, 0x65, 0x64, // "boxed"
+        0x81, // map with 1 element
+        0xa3, 0x61, 0x67, 0x65, // "age"
+        0x1e, // 30
+        0xa6, 0x73, 0x68, 0x61, 0x72, 0x65, 0x64, // "shared"
+        0xa5, 0x41, 0x6c, 0x69, 0x63, 0x65, // "Alice"
+    ];
+    assert_eq!(msgpack, expected);
+
+    let decoded: Outer = facet_msgpack::from_str(&msgpack).unwrap();
+    assert_eq!(decoded, value);
+}
+
+#[test]
+fn test_map_with_native_keys() {
+    facet_testhelpers::setup();
+
+    #[derive(Debug, PartialEq, Facet)]
+    struct Lookup {
+        ids: std::collections::BTreeMap<u32, String>,
+        offsets: std::collections::BTreeMap<i16, u8>,
+    }
+
+    let value = Lookup {
+        ids: [(1, "a".to_string()), (300, "b".to_string())].into(),
+        offsets: [(-5, 1)].into(),
+    };
+
+    let msgpack = to_vec(&value).unwrap();
+
+    // Keys keep their native MessagePack types instead of being stringified
+    let expected = [
+        0x82, // map with 2 elements
+        0xa3, 0x69, 0x64, 0x73, // "ids"
+        0x82, // map with 2 elements
+        0x01, // 1
+        0xa1, 0x61, // "a"
+        0xcd, 0x01, 0x2c, // 300
+        0xa1, 0x62, // "b"
+        0xa7, 0x6f, 0x66, 0x66, 0x73, 0x65, 0x74, 0x73, // "offsets"
+        0x81, // map with 1 element
+        0xfb, // -5
+        0x01, // 1
+    ];
+    assert_eq!(msgpack, expected);
+
+    let decoded: Lookup = facet_msgpack::from_str(&msgpack).unwrap();
+    assert_eq!(decoded, value);
+}
+
+#[test]
+fn test_renamed_fields() {
+    facet_testhelpers::setup();
+
+    #[derive(Debug, PartialEq, Facet)]
+    #[facet(rename_all = "camelCase")]
+    struct Point {
+        pos_x: u8,
+        #[facet(rename = "y")]
+        pos_y: u8,
+    }
+
+    let value = Point { pos_x: 1, pos_y: 2 };
+    let msgpack = to_vec(&value).unwrap();
+
+    let expected = [
+        0x82, // map with 2 elements
+        0xa4, 0x70, 0x6f, 0x73, 0x58, // "posX"
+        0x01, // 1
+        0xa1, 0x79, // "y"
+        0x02, // 2
+    ];
+    assert_eq!(msgpack, expected);
+
+    let decoded: Point = facet_msgpack::from_str(&msgpack).unwrap();
+    assert_eq!(decoded, value);
+}
+
+#[test]
+fn test_skipped_fields() {
+    facet_testhelpers::setup();
+
+    #[derive(Debug, PartialEq, Facet)]
+    struct Point {
+        x: u8,
+        #[facet(skip)]
+        cached_norm: u8,
+        #[facet(skip_serializing_if = "Option::is_none")]
+        label: Option<u8>,
+    }
+
+    let value = Point {
+        x: 1,
+        cached_norm: 42,
+        label: None,
+    };
+    let msgpack = to_vec(&value).unwrap();
+
+    let expected = [
+        0x81, // map with 1 element
+        0xa1, 0x78, // "x"
+        0x01, // 1
+    ];
+    assert_eq!(msgpack, expected);
+}
+
+#[test]
+fn test_tagged_enums() {
+    facet_testhelpers::setup();
+
+    #[derive(Debug, PartialEq, Facet)]
+    #[repr(u8)]
+    #[allow(dead_code)]
+    enum External {
+        Unit,
+        Newtype(u8),
+        Pair(u8, u8),
+    }
+
+    let cases = [
+        (External::Unit, vec![0xa4, b'U', b'n', b'i', b't']),
+        (
+            External::Newtype(7),
+            vec![0x81, 0xa7, b'N', b'e', b'w', b't', b'y', b'p', b'e', 0x07],
+        ),
+        (
+            External::Pair(1, 2),
+            vec![0x81, 0xa4, b'P', b'a', b'i', b'r', 0x92, 0x01, 0x02],
+        ),
+    ];
+    for (value, expected) in cases {
+        let msgpack = to_vec(&value).unwrap();
+        assert_eq!(msgpack, expected);
+        let decoded: External = facet_msgpack::from_str(&msgpack).unwrap();
+        assert_eq!(decoded, value);
+    }
+
+    #[derive(Debug, PartialEq, Facet)]
+    #[repr(u8)]
+    #[facet(tag = "t")]
+    enum Internal {
+        Circle { r: u8 },
+    }
+
+    let value = Internal::Circle { r: 3 };
+    let msgpack = to_vec(&value).unwrap();
+    let expected = [
+        0x82, // map with 2 elements
+        0xa1, b't', // "t"
+        0xa6, b'C', b'i', b'r', b'c', b'l', b'e', // "Circle"
+        0xa1, b'r', // "r"
+        0x03, // 3
+    ];
+    assert_eq!(msgpack, expected);
+    let decoded: Internal = facet_msgpack::from_str(&msgpack).unwrap();
+    assert_eq!(decoded, value);
+
+    // The tag doesn't have to come first
+    let reordered = [
+        0x82, 0xa1, b'r', 0x03, 0xa1, b't', 0xa6, b'C', b'i', b'r', b'c', b'l', b'e',
+    ];
+    let decoded: Internal = facet_msgpack::from_str(&reordered).unwrap();
+    assert_eq!(decoded, value);
+
+    #[derive(Debug, PartialEq, Facet)]
+    #[repr(u8)]
+    #[facet(tag = "t")]
+    #[facet(content = "c")]
+    enum Adjacent {
+        Ping,
+        Text(String),
+    }
+
+    for value in [Adjacent::Ping, Adjacent::Text("hi".to_string())] {
+        let decoded: Adjacent = facet_msgpack::from_str(&to_vec(&value).unwrap()).unwrap();
+        assert_eq!(decoded, value);
+    }
+
+    #[derive(Debug, PartialEq, Facet)]
+    #[repr(u8)]
+    #[facet(untagged)]
+    enum Untagged {
+        Number(u8),
+        Text(String),
+    }
+
+    assert_eq!(to_vec(&Untagged::Number(5)).unwrap(), [0x05]);
+    for value in [Untagged::Number(5), Untagged::Text("five".to_string())] {
+        let decoded: Untagged = facet_msgpack::from_str(&to_vec(&value).unwrap()).unwrap();
+        assert_eq!(decoded, value);
+    }
+}
+
+#[test]
+fn test_scalars() {
+    facet_testhelpers::setup();
+
+    assert_eq!(to_vec(&true).unwrap(), [0xc3]);
+    assert_eq!(to_vec(&false).unwrap(), [0xc2]);
+    assert_eq!(to_vec(&()).unwrap(), [0xc0]);
+    assert_eq!(to_vec(&1.5f32).unwrap(), [0xca, 0x3f, 0xc0, 0x00, 0x00]);
+    assert_eq!(
+        to_vec(&-2.0f64).unwrap(),
+        [0xcb, 0xc0, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00]
+    );
+    assert_eq!(to_vec(&'x').unwrap(), [0xa1, b'x']);
+    assert_eq!(to_vec(&"hi").unwrap(), [0xa2, b'h', b'i']);
+    assert_eq!(
+        to_vec(&std::num::NonZero::new(200u16).unwrap()).unwrap(),
+        [0xcc, 0xc8]
+    );
+    assert_eq!(to_vec(&300usize).unwrap(), [0xcd, 0x01, 0x2c]);
+    assert_eq!(to_vec(&-5i128).unwrap(), [0xfb]);
+    assert_eq!(
+        to_vec(&std::net::Ipv4Addr::LOCALHOST).unwrap(),
+        [0xa9, b'1', b'2', b'7', b'.', b'0', b'.', b'0', b'.', b'1']
+    );
+
+    let mut buffer = Vec::new();
+    let err = facet_msgpack::to_writer(&mut buffer, &u128::MAX).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+    let err = to_vec(&i128::MIN).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+}
+
+#[test]
+fn test_collections() {
+    facet_testhelpers::setup();
+
+    #[derive(Debug, Facet)]
+    struct Point(u8, i8);
+
+    assert_eq!(to_vec(&vec![1u16, 2, 3]).unwrap(), [0x93, 0x01, 0x02, 0x03]);
+    assert_eq!(to_vec(&Point(1, -1)).unwrap(), [0x92, 0x01, 0xff]);
+    assert_eq!(to_vec(&(true, "a")).unwrap(), [0x92, 0xc3, 0xa1, b'a']);
+    assert_eq!(
+        to_vec(&std::collections::BTreeSet::from([2u8, 1])).unwrap(),
+        [0x92, 0x01, 0x02]
+    );
+    assert_eq!(to_vec(&Some(1u8)).unwrap(), [0x01]);
+    assert_eq!(to_vec(&None::<u8>).unwrap(), [0xc0]);
+}
+
+#[test]
+fn test_bin() {
+    facet_testhelpers::setup();
+
+    assert_eq!(
+        to_vec(&vec![1u8, 2, 3]).unwrap(),
+        [0xc4, 0x03, 0x01, 0x02, 0x03]
+    );
+    let bytes: &[u8] = &[0xff; 300];
+    let msgpack = to_vec(&bytes).unwrap();
+    assert_eq!(msgpack[..3], [0xc5, 0x01, 0x2c]);
+    assert_eq!(msgpack.len(), 303);
+}
+
+#[test]
+fn test_timestamps() {
+    facet_testhelpers::setup();
+
+    use std::time::{Duration, UNIX_EPOCH};
+
+    // timestamp 32
+    let time = UNIX_EPOCH + Duration::from_secs(1);
+    assert_eq!(to_vec(&time).unwrap(), [0xd6, 0xff, 0x00, 0x00, 0x00, 0x01]);
+
+    // timestamp 64
+    let time = UNIX_EPOCH + Duration::new(1, 1);
+    assert_eq!(
+        to_vec(&time).unwrap(),
+        [0xd7, 0xff, 0x00, 0x00, 0x00, 0x04, 0x00, 0x00, 0x00, 0x01]
+    );
+
+    // timestamp 96
+    let time = UNIX_EPOCH - Duration::from_millis(500);
+    assert_eq!(
+        to_vec(&time).unwrap(),
+        [
+            0xc7, 0x0c, 0xff, // ext8, 12 bytes, type -1
+            0x1d, 0xcd, 0x65, 0x00, // 500_000_000 nanoseconds
+            0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, // -1 second
+        ]
+    );
+}
@@ -0,0 +1,177 @@
+//! Rendering the differences between two values, and asserting that there are none
+
+use core::fmt::{self, Write};
+
+use facet_ansi::{Style, Stylize as _};
+use facet_core::Facet;
+use facet_reflect::{Change, Diff, Peek, diff};
+
+use crate::printer::PrettyPrinter;
+
+/// Renders a [`Diff`] as a unified view: the path of each part that differs, followed by its
+/// old contents on lines starting with `-` and its new contents on lines starting with `+`
+///
+/// # Example
+/// ```
+/// # use facet::Facet;
+/// # use facet_pretty::DiffPrinter;
+/// #[derive(Facet)]
+/// struct Server {
+///     host: String,
+///     port: u16,
+/// }
+///
+/// let a = Server { host: "localhost".into(), port: 8080 };
+/// let b = Server { host: "localhost".into(), port: 9090 };
+///
+/// assert_eq!(
+///     DiffPrinter::new().with_colors(false).format(&a, &b),
+///     "port\n- 8080\n+ 9090\n"
+/// );
+/// ```
+pub struct DiffPrinter {
+    use_colors: bool,
+    printer: PrettyPrinter,
+}
+
+impl Default for DiffPrinter {
+    fn default() -> Self {
+        Self {
+            use_colors: true,
+            printer: PrettyPrinter::new().with_colors(false),
+        }
+    }
+}
+
+impl DiffPrinter {
+    /// Create a new DiffPrinter with default settings
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Enable or disable colors
+    pub fn with_colors(mut self, use_colors: bool) -> Self {
+        self.use_colors = use_colors;
+        self
+    }
+
+    /// Set the printer used for the contents of the parts that differ. Its colors are
+    /// replaced by the ones of the diff.
+    pub fn with_printer(mut self, printer: PrettyPrinter) -> Self {
+        self.printer = printer.with_colors(false);
+        self
+    }
+
+    /// Format the differences between two values to a string, empty if there are none
+    pub fn format<'facet, T: Facet<'facet>>(&self, old: &T, new: &T) -> String {
+        self.format_diff(&diff(Peek::new(old), Peek::new(new)))
+    }
+
+    /// Format a [`Diff`] to a string, empty if there are no changes in it
+    pub fn format_diff(&self, diff: &Diff<'_>) -> String {
+        let mut output = String::new();
+        self.format_diff_to(diff, &mut output)
+            .expect("Formatting failed");
+        output
+    }
+
+    /// Format a [`Diff`] to a writer
+    pub fn format_diff_to(&self, diff: &Diff<'_>, f: &mut impl Write) -> fmt::Result {
+        let removed = self.style(Style::new().red());
+        let added = self.style(Style::new().green());
+
+        for change in diff.changes() {
+            let path = change.path();
+            if !path.is_empty() {
+                writeln!(f, "{}", path.style(self.style(Style::new().bold())))?;
+            }
+            match change {
+                Change::Changed { old, new, .. } => {
+                    self.write_lines(f, '-', *old, removed)?;
+                    self.write_lines(f, '+', *new, added)?;
+                }
+                Change::Removed { value, .. } => self.write_lines(f, '-', *value, removed)?,
+                Change::Added { value, .. } => self.write_lines(f, '+', *value, added)?,
+                _ => writeln!(f, "(unsupported change)")?,
+            }
+        }
+        Ok(())
+    }
+
+    /// Writes a value, each of its lines after `marker`
+    fn write_lines(
+        &self,
+        f: &mut impl Write,
+        marker: char,
+        value: Peek<'_>,
+        style: Style,
+    ) -> fmt::Result {
+        for line in self.printer.format_peek(value).lines() {
+            writeln!(f, "{}", format!("{marker} {line}").style(style))?;
+        }
+        Ok(())
+    }
+
+    fn style(&self, style: Style) -> Style {
+        if self.use_colors { style } else { Style::new() }
+    }
+}
+
+/// Asserts that two values are equal, comparing them through their shape, see
+/// [`facet_reflect::diff`]
+///
+/// On failure, the panic message only shows the parts that differ, rendered by
+/// [`DiffPrinter`], instead of both values in full. Colors are on unless the `NO_COLOR`
+/// environment variable is set.
+///
+/// # Example
+/// ```
+/// # use facet::Facet;
+/// # use facet_pretty::assert_facet_eq;
+/// #[derive(Facet)]
+/// struct Server {
+///     host: String,
+///     port: u16,
+/// }
+///
+/// let a = Server { host: "localhost".into(), port: 8080 };
+/// assert_facet_eq!(a, Server { host: "localhost".into(), port: 8080 });
+/// assert_facet_eq!(a, Server { host: "localhost".into(), port: 8080 }, "for {}", a.host);
+/// ```
+#[macro_export]
+macro_rules! assert_facet_eq {
+    ($left:expr, $right:expr $(,)?) => {
+        $crate::assert_facet_eq_impl(&$left, &$right, ::core::option::Option::None)
+    };
+    ($left:expr, $right:expr, $($arg:tt)+) => {
+        $crate::assert_facet_eq_impl(
+            &$left,
+            &$right,
+            ::core::option::Option::Some(::core::format_args!($($arg)+)),
+        )
+    };
+}
+
+#[doc(hidden)]
+#[track_caller]
+pub fn assert_facet_eq_impl<'facet, T: Facet<'facet>>(
+    left: &T,
+    right: &T,
+    message: Option<fmt::Arguments<'_>>,
+) {
+    let diff = diff(Peek::new(left), Peek::new(right));
+    if diff.is_empty() {
+        return;
+    }
+
+    let use_colors = std::env::var_os("NO_COLOR").is_none();
+    let rendered = DiffPrinter::new()
+        .with_colors(use_colors)
+        .format_diff(&diff);
+    match message {
+        Some(message) => {
+            panic!("assertion `left == right` failed: {message}\n--- left\n+++ right\n{rendered}")
+        }
+        None => panic!("assertion `left == right` failed\n--- left\n+++ right\n{rendered}"),
+    }
+}
@@ -2,9 +2,11 @@
 #![doc = include_str!("../README.md")]
 
 mod color;
+mod diff;
 mod display;
 mod printer;
 
 pub use color::*;
+pub use diff::*;
 pub use display::*;
 pub use printer::*;
@@ -0,0 +1,85 @@
+use facet::Facet;
+use facet_pretty::{DiffPrinter, assert_facet_eq};
+
+#[derive(Facet)]
+struct Deployment {
+    name: String,
+    replicas: u32,
+    containers: Vec<Container>,
+}
+
+#[derive(Facet)]
+struct Container {
+    image: String,
+    ports: Vec<u16>,
+}
+
+fn deployment() -> Deployment {
+    Deployment {
+        name: "api".to_string(),
+        replicas: 2,
+        containers: vec![Container {
+            image: "api:1.0".to_string(),
+            ports: vec![8080],
+        }],
+    }
+}
+
+#[test]
+fn unified_view() {
+    let a = deployment();
+    let mut b = deployment();
+    b.replicas = 3;
+    b.containers[0].ports.push(9090);
+    b.containers.push(Container {
+        image: "sidecar:2".to_string(),
+        ports: vec![],
+    });
+
+    let rendered = DiffPrinter::new().with_colors(false).format(&a, &b);
+    assert_eq!(
+        rendered,
+        "\
+replicas
+- 2
++ 3
+containers[0].ports[1]
++ 9090
+containers[1]
++ Container {
++   image: sidecar:2,
++   ports: Vec<u16> [
++   ],
++ }
+"
+    );
+
+    let colored = DiffPrinter::new().format(&a, &b);
+    assert!(colored.contains("\x1b["));
+    assert!(DiffPrinter::new().format(&a, &deployment()).is_empty());
+}
+
+#[test]
+fn assert_facet_eq_passes_on_equal_values() {
+    assert_facet_eq!(deployment(), deployment());
+    assert_facet_eq!(deployment(), deployment(), "deployment {}", 1);
+}
+
+#[test]
+#[should_panic(expected = "assertion `left == right` failed: after rollout")]
+fn assert_facet_eq_fails_with_message() {
+    let mut b = deployment();
+    b.containers[0].image = "api:1.1".to_string();
+    assert_facet_eq!(deployment(), b, "after rollout");
+}
+
+#[test]
+fn assert_facet_eq_shows_only_the_diff() {
+    let mut b = deployment();
+    b.name = "web".to_string();
+    let panic = std::panic::catch_unwind(|| assert_facet_eq!(deployment(), b)).unwrap_err();
+    let message = panic.downcast_ref::<String>().unwrap();
+    assert!(message.contains("--- left\n+++ right\n"), "{message}");
+    assert!(message.contains("name"), "{message}");
+    assert!(!message.contains("containers"), "{message}");
+}
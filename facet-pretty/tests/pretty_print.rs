@@ -133,3 +133,85 @@ fn test_sensitive_fields() {
     assert!(buffer.contains("[REDACTED]"));
     assert!(!buffer.contains("TOP SECRET PASSWORD"));
 }
+
+#[test]
+fn test_skipped_fields() {
+    #[derive(Debug, Facet)]
+    struct Connection {
+        host: String,
+        #[facet(skip)]
+        handle: u64,
+        #[facet(skip_serializing_if = "String::is_empty")]
+        note: String,
+    }
+
+    let connection = Connection {
+        host: "localhost".to_string(),
+        handle: 0xdead_beef,
+        note: String::new(),
+    };
+
+    let output = PrettyPrinter::new().format(&connection);
+    assert!(output.contains("host"));
+    assert!(!output.contains("handle"));
+    assert!(!output.contains("note"));
+}
+
+#[test]
+fn test_nested_output() {
+    use std::collections::BTreeMap;
+
+    #[derive(Facet)]
+    #[repr(u8)]
+    #[allow(dead_code)]
+    enum Shape {
+        Point,
+        Circle(u32),
+        Rect { w: u32, h: u32 },
+    }
+
+    #[derive(Facet)]
+    struct Drawing {
+        shapes: Vec<Shape>,
+        layers: BTreeMap<String, u32>,
+        title: Option<String>,
+        data: Vec<u8>,
+    }
+
+    let drawing = Drawing {
+        shapes: vec![Shape::Point, Shape::Circle(2), Shape::Rect { w: 3, h: 4 }],
+        layers: BTreeMap::from([("base".to_string(), 0)]),
+        title: None,
+        data: vec![0xde, 0xad],
+    };
+
+    let output = PrettyPrinter::new().with_colors(false).format(&drawing);
+    assert_eq!(
+        output,
+        "\
+Drawing {
+  shapes: Vec<Shape> [
+    Shape::Point,
+    Shape::Circle(
+      2,
+    ),
+    Shape::Rect {
+      w: 3,
+      h: 4,
+    },
+  ],
+  layers: BTreeMap<String, u32> {
+    base => 0,
+  },
+  title: Option::None,
+  data: Vec<u8>
+    de ad,
+}"
+    );
+
+    let shallow = PrettyPrinter::new()
+        .with_colors(false)
+        .with_max_depth(1)
+        .format(&drawing);
+    assert!(shallow.contains("shapes: Vec<Shape> [\n    [...,\n"));
+}
@@ -0,0 +1,1290 @@
+//! A format-agnostic way to deserialize values, see [`Deserializer`] and [`deserialize`]
+
+extern crate alloc;
+use alloc::borrow::Cow;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+use facet_core::{
+    Def, EnumTagging, Facet, FieldFlags, NumberAffinity, NumberBits, Opaque, OpaqueConst,
+    ScalarAffinity, Shape, Signedness, StructKind, Variant, VariantKind,
+};
+
+use crate::{
+    FlattenedFieldIndex, Guard, PathError, PathSegment, PokeEnum, PokeEnumNoVariant, PokeList,
+    PokeMap, PokeOptionUninit, PokeSet, PokeSmartPointerUninit, PokeStruct, PokeStructFlattened,
+    PokeUninit, PokeValueUninit, ReflectError, ReflectPath,
+};
+
+/// A piece of a value, as read by a [`Deserializer`].
+///
+/// Values are either a single scalar token, or a container: `StartList`, the items, `EndList`,
+/// or `StartMap`, each key followed by its value, `EndMap`. Struct fields and enum variant
+/// names are map keys, as `Str` tokens.
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum Token<'input> {
+    /// The absence of a value: `None`, `()`, unit structs and the payload of unit variants
+    Null,
+    /// A boolean
+    Bool(bool),
+    /// A signed integer
+    I64(i64),
+    /// An unsigned integer
+    U64(u64),
+    /// A floating-point number
+    F64(f64),
+    /// A string. Borrowed strings can end up in `&str` and `Cow<str>` fields.
+    Str(Cow<'input, str>),
+    /// Binary data, which can be deserialized into a list of `u8`
+    Bytes(Cow<'input, [u8]>),
+    /// A point in time, relative to the Unix epoch, which can be deserialized into a
+    /// `SystemTime`
+    Timestamp {
+        /// Whole seconds since (or, when negative, before) the epoch
+        secs: i64,
+        /// Nanoseconds to add to `secs`, less than a billion
+        nanos: u32,
+    },
+    /// Starts a list, of that many items if the format knows it in advance
+    StartList(Option<usize>),
+    /// Ends the current list
+    EndList,
+    /// Starts a map, of that many entries if the format knows it in advance
+    StartMap(Option<usize>),
+    /// Ends the current map
+    EndMap,
+}
+
+impl Token<'_> {
+    /// What this token is, for error messages
+    fn describe(&self) -> &'static str {
+        match self {
+            Token::Null => "null",
+            Token::Bool(_) => "a boolean",
+            Token::I64(_) | Token::U64(_) => "an integer",
+            Token::F64(_) => "a float",
+            Token::Str(_) => "a string",
+            Token::Bytes(_) => "bytes",
+            Token::Timestamp { .. } => "a timestamp",
+            Token::StartList(_) => "a list",
+            Token::EndList => "the end of a list",
+            Token::StartMap(_) => "a map",
+            Token::EndMap => "the end of a map",
+        }
+    }
+
+    /// The string form of a scalar token, which map keys are parsed from when they don't
+    /// have the key's type
+    fn to_key_string(&self) -> Option<String> {
+        match self {
+            Token::Bool(b) => Some(b.to_string()),
+            Token::I64(n) => Some(n.to_string()),
+            Token::U64(n) => Some(n.to_string()),
+            Token::F64(n) => Some(n.to_string()),
+            Token::Str(s) => Some(s.to_string()),
+            _ => None,
+        }
+    }
+}
+
+/// Reads a value, one [`Token`] at a time, in some format, for [`deserialize`] to build.
+///
+/// The driver takes care of everything that doesn't depend on the format: looking fields up
+/// (renames, flattening, defaults for missing fields), enum tagging, options, smart pointers,
+/// and converting scalar tokens to whatever type is expected, according to its
+/// [`ScalarAffinity`]. A deserializer only has to hand out tokens.
+pub trait Deserializer<'input> {
+    /// The error this deserializer fails with, which must be able to describe values that
+    /// don't fit the expected type whatever the format
+    type Error: From<DeserializeError>;
+
+    /// Reads the next token
+    fn next(&mut self) -> Result<Token<'input>, Self::Error>;
+
+    /// Skips the next value, e.g. the value of an unknown field. By default, tokens are read
+    /// until the value is over; formats that can jump over values should override this.
+    fn skip(&mut self) -> Result<(), Self::Error> {
+        let mut depth = 0usize;
+        loop {
+            let token = self.next()?;
+            match token {
+                Token::StartList(_) | Token::StartMap(_) => depth += 1,
+                Token::EndList | Token::EndMap if depth == 0 => {
+                    return Err(self.error(unexpected("a value", &token)));
+                }
+                Token::EndList | Token::EndMap => depth -= 1,
+                _ => {}
+            }
+            if depth == 0 {
+                return Ok(());
+            }
+        }
+    }
+
+    /// Turns an error of the driver into this deserializer's error, e.g. to tell where in
+    /// the input it happened
+    fn error(&self, error: DeserializeError) -> Self::Error {
+        error.into()
+    }
+
+    /// Whether map keys that don't match any field are rejected, for every struct, as if they
+    /// all had a `#[facet(deny_unknown_fields)]` attribute. Otherwise their values are skipped.
+    fn deny_unknown_fields(&self) -> bool {
+        false
+    }
+}
+
+/// A value that doesn't fit the type it's deserialized into, whatever the format
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum DeserializeError {
+    /// A token that can't be where it is, e.g. a string where a list was expected
+    UnexpectedToken {
+        /// What the driver expected instead
+        expected: &'static str,
+        /// What it got
+        got: &'static str,
+    },
+    /// A number that doesn't fit in the expected type
+    OutOfRange {
+        /// The type of the number
+        shape: &'static Shape,
+        /// The number, as written in the input
+        value: String,
+    },
+    /// A string that couldn't be parsed into the expected type
+    InvalidValue {
+        /// The expected type
+        shape: &'static Shape,
+        /// The string
+        value: String,
+    },
+    /// A `&str` can only be deserialized from a string borrowed from the input, e.g. one
+    /// without escape sequences
+    BorrowedString {
+        /// The shape of the string
+        shape: &'static Shape,
+    },
+    /// A key that doesn't match any field, for structs that deny unknown fields
+    UnknownField {
+        /// The key
+        field: String,
+        /// The struct or enum
+        shape: &'static Shape,
+        /// A field with a close enough name, likely what was meant
+        suggestion: Option<&'static str>,
+    },
+    /// A name that doesn't match any variant
+    UnknownVariant {
+        /// The name
+        variant: String,
+        /// The enum
+        shape: &'static Shape,
+    },
+    /// The tag of an internally or adjacently tagged enum is missing, or isn't a string
+    MissingTag {
+        /// The name of the tag
+        tag: &'static str,
+        /// The enum
+        shape: &'static Shape,
+    },
+    /// No variant of an untagged enum could be deserialized from the value
+    NoMatchingVariant {
+        /// The enum
+        shape: &'static Shape,
+    },
+    /// The selected variant of an internally tagged enum is a tuple variant: its fields have
+    /// no names to go next to the tag
+    InternallyTaggedTuple {
+        /// The enum
+        shape: &'static Shape,
+        /// The name of the variant
+        variant: &'static str,
+    },
+    /// The value's shape isn't supported, e.g. a scalar that can't be parsed from a string
+    Unsupported {
+        /// The shape of the value
+        shape: &'static Shape,
+    },
+    /// The value couldn't be built, e.g. because fields were missing
+    Reflect(ReflectError),
+}
+
+impl core::fmt::Display for DeserializeError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            DeserializeError::UnexpectedToken { expected, got } => {
+                write!(f, "expected {expected}, got {got}")
+            }
+            DeserializeError::OutOfRange { shape, value } => {
+                write!(f, "{value} is out of range for {shape}")
+            }
+            DeserializeError::InvalidValue { shape, value } => {
+                write!(f, "invalid value for {shape}: {value:?}")
+            }
+            DeserializeError::BorrowedString { shape } => {
+                write!(f, "{shape} can only borrow strings from the input")
+            }
+            DeserializeError::UnknownField {
+                field,
+                shape,
+                suggestion,
+            } => {
+                write!(f, "unknown field `{field}` for {shape}")?;
+                if let Some(suggestion) = suggestion {
+                    write!(f, ", did you mean `{suggestion}`?")?;
+                }
+                Ok(())
+            }
+            DeserializeError::UnknownVariant { variant, shape } => {
+                write!(f, "unknown variant `{variant}` for {shape}")
+            }
+            DeserializeError::MissingTag { tag, shape } => {
+                write!(f, "missing tag `{tag}` for {shape}")
+            }
+            DeserializeError::NoMatchingVariant { shape } => {
+                write!(f, "data did not match any variant of untagged enum {shape}")
+            }
+            DeserializeError::InternallyTaggedTuple { shape, variant } => {
+                write!(f, "variant {variant} of {shape} can't be internally tagged")
+            }
+            DeserializeError::Unsupported { shape } => write!(f, "unsupported shape: {shape}"),
+            DeserializeError::Reflect(err) => write!(f, "{err}"),
+        }
+    }
+}
+
+impl core::error::Error for DeserializeError {}
+
+impl From<ReflectError> for DeserializeError {
+    fn from(err: ReflectError) -> Self {
+        DeserializeError::Reflect(err)
+    }
+}
+
+fn unexpected(expected: &'static str, got: &Token<'_>) -> DeserializeError {
+    DeserializeError::UnexpectedToken {
+        expected,
+        got: got.describe(),
+    }
+}
+
+/// Deserializes a `T` from the tokens of `deserializer`.
+///
+/// Structs, lists and maps are built iteratively, so deeply nested values don't overflow the
+/// stack; enums recurse, since telling their variants apart may need buffering a value.
+///
+/// Errors, the driver's as well as the deserializer's, come with the path of the value that
+/// was being built when they happened.
+pub fn deserialize<'input, T: Facet<'input>, D: Deserializer<'input>>(
+    deserializer: &mut D,
+) -> Result<T, PathError<D::Error>> {
+    let (poke, _guard) = PokeUninit::alloc::<T>();
+    // SAFETY: `T: Facet<'input>`, so it may hold strings borrowed from the input
+    let opaque = unsafe { deserialize_opaque(poke, deserializer)? };
+    Ok(unsafe { opaque.read::<T>() })
+}
+
+/// Deserializes the value `poke` points to from the tokens of `deserializer`, see
+/// [`deserialize`].
+///
+/// # Safety
+///
+/// Borrowed [`Token::Str`] strings may end up in `&str` and `Cow<str>` values: the
+/// deserialized value must not be used once the input is gone, i.e. its type must be
+/// `Facet<'input>`. Deserializers that only hand out owned strings are always fine.
+pub unsafe fn deserialize_opaque<'input, 'mem, D: Deserializer<'input>>(
+    poke: PokeUninit<'mem>,
+    deserializer: &mut D,
+) -> Result<Opaque<'mem>, PathError<D::Error>> {
+    let shape = poke.shape();
+    let mut tokens = Tokens {
+        deserializer,
+        pending: Vec::new(),
+        path: ReflectPath::new(),
+        shapes: Vec::new(),
+    };
+    deserialize_value(&mut tokens, poke, false).map_err(|error| PathError {
+        error,
+        shape: tokens.shapes.last().copied().unwrap_or(shape),
+        path: core::mem::take(&mut tokens.path),
+    })
+}
+
+/// The tokens of a deserializer, along with tokens that must be handed out again first: the
+/// first token of an option, found not to be null, and buffered values, read ahead to find
+/// an enum's tag or to try each variant of an untagged enum.
+///
+/// It also keeps track of where in the value the driver is, for errors: the path of the value
+/// being built, and the shapes of the values being built along it.
+struct Tokens<'d, 'input, D> {
+    deserializer: &'d mut D,
+    /// Next token last
+    pending: Vec<Token<'input>>,
+    path: ReflectPath,
+    /// Innermost last
+    shapes: Vec<&'static Shape>,
+}
+
+impl<'input, D: Deserializer<'input>> Tokens<'_, 'input, D> {
+    fn next(&mut self) -> Result<Token<'input>, D::Error> {
+        match self.pending.pop() {
+            Some(token) => Ok(token),
+            None => self.deserializer.next(),
+        }
+    }
+
+    fn push_back(&mut self, token: Token<'input>) {
+        self.pending.push(token);
+    }
+
+    /// Hands `tokens` out again, before anything else
+    fn replay(&mut self, tokens: &[Token<'input>]) {
+        self.pending.extend(tokens.iter().rev().cloned());
+    }
+
+    fn skip(&mut self) -> Result<(), D::Error> {
+        if self.pending.is_empty() {
+            return self.deserializer.skip();
+        }
+        self.read_value(|_| {})
+    }
+
+    /// Reads the next value, in full
+    fn capture(&mut self) -> Result<Vec<Token<'input>>, D::Error> {
+        let mut value = Vec::new();
+        self.read_value(|token| value.push(token))?;
+        Ok(value)
+    }
+
+    fn read_value(&mut self, mut f: impl FnMut(Token<'input>)) -> Result<(), D::Error> {
+        let mut depth = 0usize;
+        loop {
+            let token = self.next()?;
+            match token {
+                Token::StartList(_) | Token::StartMap(_) => depth += 1,
+                Token::EndList | Token::EndMap if depth == 0 => {
+                    return Err(self.error(unexpected("a value", &token)));
+                }
+                Token::EndList | Token::EndMap => depth -= 1,
+                _ => {}
+            }
+            f(token);
+            if depth == 0 {
+                return Ok(());
+            }
+        }
+    }
+
+    fn error(&self, error: DeserializeError) -> D::Error {
+        self.deserializer.error(error)
+    }
+
+    /// Deserializes a part of the value being built, at `segment`
+    fn deserialize_at<'mem>(
+        &mut self,
+        segment: PathSegment,
+        poke: PokeUninit<'mem>,
+    ) -> Result<Opaque<'mem>, D::Error> {
+        self.path.push(segment);
+        let value = deserialize_value(self, poke, false)?;
+        self.path.pop();
+        Ok(value)
+    }
+}
+
+/// A container being deserialized, waiting for its contents
+enum Frame<'mem> {
+    Struct {
+        ps: PokeStructFlattened<'mem>,
+        /// The field being deserialized
+        field: Option<FlattenedFieldIndex>,
+    },
+    /// A tuple or tuple struct, deserialized from a list
+    Tuple {
+        ps: PokeStruct<'mem>,
+        /// The field being deserialized, or the next one
+        index: usize,
+    },
+    List {
+        pl: PokeList<'mem>,
+        item: Option<Guard>,
+        /// The index of the item being deserialized, or of the next one
+        index: usize,
+    },
+    Set {
+        ps: PokeSet<'mem>,
+        item: Option<Guard>,
+        index: usize,
+    },
+    Map {
+        pm: PokeMap<'mem>,
+        /// The key of the entry being deserialized, once it's done
+        key: Option<(Opaque<'mem>, Guard)>,
+        /// Where the key or value being deserialized lives
+        slot: Option<Guard>,
+    },
+    Option {
+        po: PokeOptionUninit<'mem>,
+        inner: Guard,
+    },
+    SmartPointer {
+        psp: PokeSmartPointerUninit<'mem>,
+        inner: Guard,
+    },
+}
+
+/// The frames of a value being deserialized, outermost first.
+///
+/// On error they're dropped innermost first, so that partially built values are dropped
+/// before the memory they live in is freed.
+struct Frames<'mem>(Vec<Frame<'mem>>);
+
+impl Drop for Frames<'_> {
+    fn drop(&mut self) {
+        while self.0.pop().is_some() {}
+    }
+}
+
+/// What the driver does next
+enum Step<'mem> {
+    /// Starts deserializing a value, a map key if the flag is set
+    Start(PokeUninit<'mem>, bool),
+    /// Reads what's next in the innermost container
+    Resume,
+    /// Hands a finished value to the innermost container, or returns it
+    Done(Opaque<'mem>),
+}
+
+fn deserialize_value<'input, 'mem, D: Deserializer<'input>>(
+    tokens: &mut Tokens<'_, 'input, D>,
+    poke: PokeUninit<'mem>,
+    is_key: bool,
+) -> Result<Opaque<'mem>, D::Error> {
+    let mut frames = Frames(Vec::new());
+    let mut step = Step::Start(poke, is_key);
+    loop {
+        step = match step {
+            Step::Start(poke, is_key) => {
+                tokens.shapes.push(poke.shape());
+                start(tokens, poke, is_key, &mut frames.0)?
+            }
+            Step::Resume => resume(tokens, &mut frames.0)?,
+            Step::Done(value) => {
+                tokens.shapes.pop();
+                if frames.0.is_empty() {
+                    return Ok(value);
+                }
+                complete(&mut tokens.path, value, &mut frames.0)
+            }
+        };
+    }
+}
+
+/// Reads the first token of a value, pushing a frame for containers
+fn start<'input, 'mem, D: Deserializer<'input>>(
+    tokens: &mut Tokens<'_, 'input, D>,
+    poke: PokeUninit<'mem>,
+    is_key: bool,
+    frames: &mut Vec<Frame<'mem>>,
+) -> Result<Step<'mem>, D::Error> {
+    let shape = poke.shape();
+    let unsupported =
+        |tokens: &Tokens<'_, 'input, D>| tokens.error(DeserializeError::Unsupported { shape });
+    // map keys that aren't scalars, e.g. newtypes, are parsed from their string form when they
+    // can be
+    if is_key && shape.vtable.parse.is_some() && !matches!(poke, PokeUninit::Scalar(_)) {
+        let token = tokens.next()?;
+        match token.to_key_string() {
+            Some(key) => {
+                let value = parse(poke.into_value(), &key).map_err(|e| tokens.error(e))?;
+                return Ok(Step::Done(value));
+            }
+            None => tokens.push_back(token),
+        }
+    }
+    let step = match poke {
+        PokeUninit::Scalar(pv) => {
+            let token = tokens.next()?;
+            let value = deserialize_scalar(pv, token, is_key).map_err(|e| tokens.error(e))?;
+            Step::Done(value)
+        }
+        PokeUninit::Struct(ps) => match (ps.def().kind, tokens.next()?) {
+            (_, Token::StartMap(_)) => {
+                let ps = PokeStructFlattened::new(ps);
+                frames.push(Frame::Struct { ps, field: None });
+                Step::Resume
+            }
+            (StructKind::Tuple | StructKind::TupleStruct, Token::StartList(_)) => {
+                frames.push(Frame::Tuple { ps, index: 0 });
+                Step::Resume
+            }
+            (StructKind::Unit, Token::Null) => Step::Done(build(tokens, ps.try_build_in_place())?),
+            (StructKind::Tuple | StructKind::TupleStruct, token) => {
+                return Err(tokens.error(unexpected("a list", &token)));
+            }
+            (_, token) => return Err(tokens.error(unexpected("a map", &token))),
+        },
+        PokeUninit::List(pl) => match tokens.next()? {
+            Token::StartList(len) => {
+                let pl = pl.init(len).map_err(|_| unsupported(tokens))?;
+                frames.push(Frame::List {
+                    pl,
+                    item: None,
+                    index: 0,
+                });
+                Step::Resume
+            }
+            Token::Bytes(bytes) if pl.def().t.is_type::<u8>() => {
+                let mut pl = pl
+                    .init(Some(bytes.len()))
+                    .map_err(|_| unsupported(tokens))?;
+                for mut byte in bytes.iter().copied() {
+                    unsafe { pl.push(Opaque::new(&mut byte)) };
+                }
+                Step::Done(pl.build_in_place())
+            }
+            token => return Err(tokens.error(unexpected("a list", &token))),
+        },
+        PokeUninit::Set(ps) => match tokens.next()? {
+            Token::StartList(len) => {
+                let ps = ps.init(len).map_err(|_| unsupported(tokens))?;
+                frames.push(Frame::Set {
+                    ps,
+                    item: None,
+                    index: 0,
+                });
+                Step::Resume
+            }
+            token => return Err(tokens.error(unexpected("a list", &token))),
+        },
+        PokeUninit::Map(pm) => match tokens.next()? {
+            Token::StartMap(len) => {
+                let pm = pm.init(len).map_err(|_| unsupported(tokens))?;
+                frames.push(Frame::Map {
+                    pm,
+                    key: None,
+                    slot: None,
+                });
+                Step::Resume
+            }
+            token => return Err(tokens.error(unexpected("a map", &token))),
+        },
+        PokeUninit::Option(po) => match tokens.next()? {
+            Token::Null => Step::Done(unsafe { po.init_none() }.build_in_place()),
+            token => {
+                tokens.push_back(token);
+                let (inner_poke, inner) = po.alloc_inner();
+                frames.push(Frame::Option { po, inner });
+                Step::Start(inner_poke, is_key)
+            }
+        },
+        // Smart pointers are transparent, when they can own their pointee (`Weak<T>` can't)
+        PokeUninit::SmartPointer(psp) => {
+            if psp.vtable().new_into_fn.is_none() {
+                return Err(unsupported(tokens));
+            }
+            let (inner_poke, inner) = psp.alloc_inner();
+            frames.push(Frame::SmartPointer { psp, inner });
+            Step::Start(inner_poke, is_key)
+        }
+        PokeUninit::Enum(pe) => Step::Done(deserialize_enum(tokens, pe)?),
+    };
+    Ok(step)
+}
+
+/// Reads what's next in the innermost container: either the start of one of its values, or
+/// its end
+fn resume<'input, 'mem, D: Deserializer<'input>>(
+    tokens: &mut Tokens<'_, 'input, D>,
+    frames: &mut Vec<Frame<'mem>>,
+) -> Result<Step<'mem>, D::Error> {
+    let frame = frames.last_mut().expect("resuming a container");
+    let step = match frame {
+        Frame::Struct { ps, field } => loop {
+            match tokens.next()? {
+                Token::EndMap => {
+                    let Some(Frame::Struct { ps, .. }) = frames.pop() else {
+                        unreachable!()
+                    };
+                    break Step::Done(build(tokens, ps.try_build_in_place())?);
+                }
+                Token::Str(key) => match ps.field_by_serialized_name(&key) {
+                    Ok((index, poke)) => {
+                        *field = Some(index);
+                        let name = ps.serialized_field_names().find(|name| *name == key);
+                        tokens
+                            .path
+                            .push(PathSegment::Field(name.unwrap_or_default()));
+                        break Step::Start(poke, false);
+                    }
+                    Err(_)
+                        if tokens.deserializer.deny_unknown_fields()
+                            || ps.def().deny_unknown_fields =>
+                    {
+                        return Err(tokens.error(DeserializeError::UnknownField {
+                            suggestion: did_you_mean(&key, ps.serialized_field_names()),
+                            field: key.into_owned(),
+                            shape: ps.shape(),
+                        }));
+                    }
+                    Err(_) => tokens.skip()?,
+                },
+                token => return Err(tokens.error(unexpected("a field name", &token))),
+            }
+        },
+        // The index is part of the path before the item is read, so that errors reading it
+        // point at it too
+        Frame::Tuple { ps, index } => {
+            tokens.path.push(PathSegment::Index(*index));
+            match tokens.next()? {
+                Token::EndList => {
+                    tokens.path.pop();
+                    let Some(Frame::Tuple { ps, .. }) = frames.pop() else {
+                        unreachable!()
+                    };
+                    Step::Done(build(tokens, ps.try_build_in_place())?)
+                }
+                token if *index < ps.def().fields.len() => {
+                    tokens.push_back(token);
+                    Step::Start(ps.field(*index).expect("index is in bounds"), false)
+                }
+                token => return Err(tokens.error(unexpected("the end of a list", &token))),
+            }
+        }
+        Frame::List { pl, item, index } => {
+            tokens.path.push(PathSegment::Index(*index));
+            match tokens.next()? {
+                Token::EndList => {
+                    tokens.path.pop();
+                    let Some(Frame::List { pl, .. }) = frames.pop() else {
+                        unreachable!()
+                    };
+                    Step::Done(pl.build_in_place())
+                }
+                token => {
+                    tokens.push_back(token);
+                    let (poke, guard) = PokeUninit::alloc_shape(pl.def().t);
+                    *item = Some(guard);
+                    Step::Start(poke, false)
+                }
+            }
+        }
+        Frame::Set { ps, item, index } => {
+            tokens.path.push(PathSegment::Index(*index));
+            match tokens.next()? {
+                Token::EndList => {
+                    tokens.path.pop();
+                    let Some(Frame::Set { ps, .. }) = frames.pop() else {
+                        unreachable!()
+                    };
+                    Step::Done(ps.build_in_place())
+                }
+                token => {
+                    tokens.push_back(token);
+                    let (poke, guard) = PokeUninit::alloc_shape(ps.def().t);
+                    *item = Some(guard);
+                    Step::Start(poke, false)
+                }
+            }
+        }
+        Frame::Map { pm, slot, .. } => match tokens.next()? {
+            Token::EndMap => {
+                let Some(Frame::Map { pm, .. }) = frames.pop() else {
+                    unreachable!()
+                };
+                Step::Done(pm.build_in_place())
+            }
+            token => {
+                let key = token.to_key_string().unwrap_or_default();
+                tokens.push_back(token);
+                tokens.path.push(PathSegment::Key(key));
+                let (poke, guard) = PokeUninit::alloc_shape(pm.def().k);
+                *slot = Some(guard);
+                Step::Start(poke, true)
+            }
+        },
+        Frame::Option { .. } | Frame::SmartPointer { .. } => {
+            unreachable!("options and smart pointers are done with their value")
+        }
+    };
+    Ok(step)
+}
+
+/// Moves a finished value into the innermost container, stepping back out of its path
+fn complete<'mem>(
+    path: &mut ReflectPath,
+    value: Opaque<'mem>,
+    frames: &mut Vec<Frame<'mem>>,
+) -> Step<'mem> {
+    match frames.last_mut().expect("completing a container") {
+        Frame::Struct { ps, field } => {
+            unsafe { ps.mark_initialized(field.take().expect("a field is being deserialized")) };
+            path.pop();
+            Step::Resume
+        }
+        Frame::Tuple { ps, index } => {
+            unsafe { ps.mark_initialized(*index) };
+            *index += 1;
+            path.pop();
+            Step::Resume
+        }
+        Frame::List { pl, item, index } => {
+            unsafe { pl.push(value) };
+            // the item has been moved into the list
+            *item = None;
+            *index += 1;
+            path.pop();
+            Step::Resume
+        }
+        Frame::Set { ps, item, index } => {
+            unsafe { ps.insert(value) };
+            *item = None;
+            *index += 1;
+            path.pop();
+            Step::Resume
+        }
+        Frame::Map { pm, key, slot } => match key.take() {
+            None => {
+                *key = Some((value, slot.take().expect("a key is being deserialized")));
+                let (poke, guard) = PokeUninit::alloc_shape(pm.def().v);
+                *slot = Some(guard);
+                Step::Start(poke, false)
+            }
+            Some((key, _key_guard)) => {
+                unsafe { pm.insert(key, value) };
+                *slot = None;
+                path.pop();
+                Step::Resume
+            }
+        },
+        Frame::Option { .. } => {
+            let Some(Frame::Option { po, inner }) = frames.pop() else {
+                unreachable!()
+            };
+            let po = unsafe { po.write(value.as_const()) };
+            drop(inner);
+            Step::Done(po.build_in_place())
+        }
+        Frame::SmartPointer { .. } => {
+            let Some(Frame::SmartPointer { psp, inner }) = frames.pop() else {
+                unreachable!()
+            };
+            let psp = unsafe { psp.write(value.as_const()) };
+            drop(inner);
+            Step::Done(psp.build_in_place())
+        }
+    }
+}
+
+fn build<'input, 'mem, D: Deserializer<'input>>(
+    tokens: &Tokens<'_, 'input, D>,
+    built: Result<Opaque<'mem>, ReflectError>,
+) -> Result<Opaque<'mem>, D::Error> {
+    built.map_err(|e| tokens.error(DeserializeError::Reflect(e)))
+}
+
+/// Converts a scalar token into the type `pv` expects. Map keys (`is_key`) are more lenient:
+/// any scalar can be parsed into any type from its string form.
+fn deserialize_scalar<'mem>(
+    pv: PokeValueUninit<'mem>,
+    token: Token<'_>,
+    is_key: bool,
+) -> Result<Opaque<'mem>, DeserializeError> {
+    let shape = pv.shape();
+
+    // String, &str and Cow<str> share the string affinity, and can't be parsed into
+    if shape.is_type::<String>() {
+        return match token {
+            Token::Str(s) => Ok(pv.put(s.into_owned())),
+            token => match token.to_key_string() {
+                Some(s) if is_key => Ok(pv.put(s)),
+                _ => Err(unexpected("a string", &token)),
+            },
+        };
+    }
+    if shape.is_type::<Cow<'_, str>>() {
+        return match token {
+            Token::Str(s) => Ok(pv.put(s)),
+            token => match token.to_key_string() {
+                Some(s) if is_key => Ok(pv.put(Cow::<'_, str>::Owned(s))),
+                _ => Err(unexpected("a string", &token)),
+            },
+        };
+    }
+    if shape.is_type::<&str>() {
+        return match token {
+            Token::Str(Cow::Borrowed(s)) => Ok(pv.put(s)),
+            Token::Str(Cow::Owned(_)) => Err(DeserializeError::BorrowedString { shape }),
+            token => Err(unexpected("a string", &token)),
+        };
+    }
+    #[cfg(feature = "std")]
+    if shape.is_type::<std::time::SystemTime>() {
+        if let Token::Timestamp { secs, nanos } = token {
+            return timestamp(secs, nanos)
+                .map(|time| pv.put(time))
+                .ok_or_else(|| DeserializeError::InvalidValue {
+                    shape,
+                    value: alloc::format!("{secs}s {nanos}ns"),
+                });
+        }
+    }
+
+    let Def::Scalar(def) = shape.def else {
+        unreachable!("scalar pokes have scalar defs")
+    };
+    match (def.affinity, token) {
+        (ScalarAffinity::Number(affinity), Token::I64(n)) => {
+            put_number(pv, &affinity, Number::Signed(n))
+        }
+        (ScalarAffinity::Number(affinity), Token::U64(n)) => {
+            put_number(pv, &affinity, Number::Unsigned(n))
+        }
+        (ScalarAffinity::Number(affinity), Token::F64(n)) => {
+            put_number(pv, &affinity, Number::Float(n))
+        }
+        (ScalarAffinity::Boolean(_), Token::Bool(b)) if shape.is_type::<bool>() => Ok(pv.put(b)),
+        (ScalarAffinity::Empty(_), Token::Null) => pv
+            .default_in_place()
+            .map_err(|_| DeserializeError::Unsupported { shape }),
+        (_, Token::Str(s)) => parse(pv, &s),
+        (affinity, token) => match token.to_key_string() {
+            Some(s) if is_key => parse(pv, &s),
+            _ => Err(unexpected(expected_scalar(affinity), &token)),
+        },
+    }
+}
+
+fn expected_scalar(affinity: ScalarAffinity) -> &'static str {
+    match affinity {
+        ScalarAffinity::Number(_) => "a number",
+        ScalarAffinity::Boolean(_) => "a boolean",
+        ScalarAffinity::Empty(_) => "null",
+        _ => "a string",
+    }
+}
+
+/// Parses a scalar from its string form, with its `FromStr` implementation
+fn parse<'mem>(pv: PokeValueUninit<'mem>, s: &str) -> Result<Opaque<'mem>, DeserializeError> {
+    let shape = pv.shape();
+    if shape.vtable.parse.is_none() {
+        return Err(DeserializeError::Unsupported { shape });
+    }
+    pv.parse(s).map_err(|_| DeserializeError::InvalidValue {
+        shape,
+        value: s.to_string(),
+    })
+}
+
+#[cfg(feature = "std")]
+fn timestamp(secs: i64, nanos: u32) -> Option<std::time::SystemTime> {
+    use std::time::{Duration, UNIX_EPOCH};
+
+    if nanos >= 1_000_000_000 {
+        return None;
+    }
+    if secs >= 0 {
+        UNIX_EPOCH.checked_add(Duration::new(secs as u64, nanos))
+    } else {
+        UNIX_EPOCH
+            .checked_sub(Duration::from_secs(secs.unsigned_abs()))?
+            .checked_add(Duration::from_nanos(nanos.into()))
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Number {
+    Signed(i64),
+    Unsigned(u64),
+    Float(f64),
+}
+
+impl core::fmt::Display for Number {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Number::Signed(n) => write!(f, "{n}"),
+            Number::Unsigned(n) => write!(f, "{n}"),
+            Number::Float(n) => write!(f, "{n}"),
+        }
+    }
+}
+
+/// Applies `$m` to the integer type with the given bits and sign
+macro_rules! with_int_type {
+    ($bits:expr, $sign:expr, $m:ident) => {
+        match ($bits, $sign) {
+            (8, Signedness::Signed) => $m!(i8),
+            (16, Signedness::Signed) => $m!(i16),
+            (32, Signedness::Signed) => $m!(i32),
+            (64, Signedness::Signed) => $m!(i64),
+            (128, Signedness::Signed) => $m!(i128),
+            (8, Signedness::Unsigned) => $m!(u8),
+            (16, Signedness::Unsigned) => $m!(u16),
+            (32, Signedness::Unsigned) => $m!(u32),
+            (64, Signedness::Unsigned) => $m!(u64),
+            (128, Signedness::Unsigned) => $m!(u128),
+            _ => None,
+        }
+    };
+}
+
+/// Writes a number into any type with the number affinity, as long as it fits between the
+/// affinity's bounds: so `NonZero` integers, whose minimum is 1, work like the others.
+fn put_number<'mem>(
+    mut pv: PokeValueUninit<'mem>,
+    affinity: &NumberAffinity,
+    n: Number,
+) -> Result<Opaque<'mem>, DeserializeError> {
+    let shape = pv.shape();
+    let unsupported = || DeserializeError::Unsupported { shape };
+    let out_of_range = || DeserializeError::OutOfRange {
+        shape,
+        value: n.to_string(),
+    };
+    let size_bits = shape.layout.size() * 8;
+    let data = unsafe { pv.data() };
+    match affinity.bits {
+        NumberBits::Integer { bits, sign } if bits == size_bits => {
+            let n = match n {
+                Number::Signed(n) => i128::from(n),
+                Number::Unsigned(n) => i128::from(n),
+                Number::Float(_) => return Err(unexpected("an integer", &Token::F64(0.0))),
+            };
+            // 64-bit tokens always fit in an i128, so u128 bounds can saturate
+            let bound = |bound: OpaqueConst<'static>| {
+                macro_rules! read {
+                    ($t:ty) => {
+                        Some(i128::try_from(unsafe { *bound.as_ref::<$t>() }).unwrap_or(i128::MAX))
+                    };
+                }
+                with_int_type!(bits, sign, read)
+            };
+            let (min, max) = bound(affinity.min)
+                .zip(bound(affinity.max))
+                .ok_or_else(unsupported)?;
+            if n < min || n > max {
+                return Err(out_of_range());
+            }
+            macro_rules! write {
+                ($t:ty) => {
+                    Some(unsafe { data.put(n as $t) })
+                };
+            }
+            with_int_type!(bits, sign, write).ok_or_else(unsupported)
+        }
+        NumberBits::Float { exponent_bits, .. } => {
+            let n = match n {
+                Number::Signed(n) => n as f64,
+                Number::Unsigned(n) => n as f64,
+                Number::Float(n) => n,
+            };
+            match (exponent_bits, size_bits) {
+                (8, 32) => Ok(unsafe { data.put(n as f32) }),
+                (11, 64) => Ok(unsafe { data.put(n) }),
+                _ => Err(unsupported()),
+            }
+        }
+        _ => Err(unsupported()),
+    }
+}
+
+/// Deserializes an enum, following its [`EnumTagging`].
+///
+/// Unlike the rest of the driver this recurses: every variant field goes through its own
+/// [`deserialize_value`] call.
+fn deserialize_enum<'input, 'mem, D: Deserializer<'input>>(
+    tokens: &mut Tokens<'_, 'input, D>,
+    pe: PokeEnumNoVariant<'mem>,
+) -> Result<Opaque<'mem>, D::Error> {
+    let shape = pe.shape();
+    let pe = match pe.def().tagging {
+        EnumTagging::External => match tokens.next()? {
+            Token::Str(name) => select_variant(tokens, pe, &name)?,
+            Token::StartMap(_) => {
+                let name = match tokens.next()? {
+                    Token::Str(name) => name,
+                    token => return Err(tokens.error(unexpected("a variant name", &token))),
+                };
+                let pe = select_variant(tokens, pe, &name)?;
+                let pe = deserialize_payload(tokens, pe)?;
+                match tokens.next()? {
+                    Token::EndMap => pe,
+                    token => return Err(tokens.error(unexpected("the end of a map", &token))),
+                }
+            }
+            token => {
+                return Err(tokens.error(unexpected("a variant name or a map", &token)));
+            }
+        },
+        EnumTagging::Internal { tag } => {
+            let value = tokens.capture()?;
+            let name = find_tag(&value, tag)
+                .ok_or_else(|| tokens.error(DeserializeError::MissingTag { tag, shape }))?;
+            let mut pe = select_variant(tokens, pe, name)?;
+            if let VariantKind::Tuple { .. } = selected_variant(&pe).kind {
+                return Err(tokens.error(DeserializeError::InternallyTaggedTuple {
+                    shape,
+                    variant: selected_variant(&pe).name,
+                }));
+            }
+            // the tag was found in a map, which starts the value
+            tokens.replay(&value[1..]);
+            deserialize_variant_fields(tokens, &mut pe, Some(tag))?;
+            pe
+        }
+        EnumTagging::Adjacent { tag, content } => {
+            let value = tokens.capture()?;
+            let name = find_tag(&value, tag)
+                .ok_or_else(|| tokens.error(DeserializeError::MissingTag { tag, shape }))?;
+            let mut pe = select_variant(tokens, pe, name)?;
+            tokens.replay(&value[1..]);
+            loop {
+                match tokens.next()? {
+                    Token::EndMap => break,
+                    Token::Str(key) if key == tag => tokens.skip()?,
+                    Token::Str(key) if key == content => {
+                        tokens.path.push(PathSegment::Field(content));
+                        pe = deserialize_payload(tokens, pe)?;
+                        tokens.path.pop();
+                    }
+                    Token::Str(key) if tokens.deserializer.deny_unknown_fields() => {
+                        return Err(tokens.error(DeserializeError::UnknownField {
+                            suggestion: did_you_mean(&key, [tag, content].into_iter()),
+                            field: key.into_owned(),
+                            shape,
+                        }));
+                    }
+                    Token::Str(_) => tokens.skip()?,
+                    token => return Err(tokens.error(unexpected("a field name", &token))),
+                }
+            }
+            pe
+        }
+        EnumTagging::Untagged => {
+            let value = tokens.capture()?;
+            let variant_count = pe.def().variants.len();
+            let mut pv = pe.into_value();
+            let data = unsafe { pv.data() };
+            let (path_len, shapes_len) = (tokens.path.len(), tokens.shapes.len());
+            for index in 0..variant_count {
+                let base = tokens.pending.len();
+                tokens.replay(&value);
+                let pe = unsafe { PokeUninit::unchecked_new(data, shape) }
+                    .into_enum()
+                    .set_variant_by_index(index)
+                    .expect("variant index is in bounds");
+                let attempt = deserialize_payload(tokens, pe)
+                    .and_then(|pe| build(tokens, pe.try_build_in_place()));
+                match attempt {
+                    Ok(opaque) => return Ok(opaque),
+                    // drop what's left of the value, and step back out of where it failed
+                    Err(_) => {
+                        tokens.pending.truncate(base);
+                        tokens.path.truncate(path_len);
+                        tokens.shapes.truncate(shapes_len);
+                    }
+                }
+            }
+            return Err(tokens.error(DeserializeError::NoMatchingVariant { shape }));
+        }
+        _ => return Err(tokens.error(DeserializeError::Unsupported { shape })),
+    };
+    build(tokens, pe.try_build_in_place())
+}
+
+fn selected_variant(pe: &PokeEnum<'_>) -> &'static Variant {
+    &pe.def().variants[pe.selected_variant_index()]
+}
+
+fn select_variant<'input, 'mem, D: Deserializer<'input>>(
+    tokens: &Tokens<'_, 'input, D>,
+    pe: PokeEnumNoVariant<'mem>,
+    name: &str,
+) -> Result<PokeEnum<'mem>, D::Error> {
+    let shape = pe.shape();
+    pe.set_variant_by_serialized_name(name).map_err(|_| {
+        tokens.error(DeserializeError::UnknownVariant {
+            variant: name.to_string(),
+            shape,
+        })
+    })
+}
+
+/// Looks for the string value of `tag` in `value`, if it's a map
+fn find_tag<'v>(value: &'v [Token<'_>], tag: &str) -> Option<&'v str> {
+    let Some(Token::StartMap(_)) = value.first() else {
+        return None;
+    };
+    let mut index = 1;
+    loop {
+        match value.get(index)? {
+            Token::Str(key) if key == tag => {
+                return match value.get(index + 1)? {
+                    Token::Str(name) => Some(name),
+                    _ => None,
+                };
+            }
+            Token::EndMap => return None,
+            _ => index = value_end(value, value_end(value, index)),
+        }
+    }
+}
+
+/// The index right after the value that starts at `start`
+fn value_end(tokens: &[Token<'_>], start: usize) -> usize {
+    let mut depth = 0usize;
+    for (index, token) in tokens.iter().enumerate().skip(start) {
+        match token {
+            Token::StartList(_) | Token::StartMap(_) => depth += 1,
+            Token::EndList | Token::EndMap => depth = depth.saturating_sub(1),
+            _ => {}
+        }
+        if depth == 0 {
+            return index + 1;
+        }
+    }
+    tokens.len()
+}
+
+/// Deserializes the payload of the selected variant: null for unit variants, the value itself
+/// for newtype variants, a list for other tuple variants and a map for struct variants.
+/// The variant's name is part of the path while it does.
+fn deserialize_payload<'input, 'mem, D: Deserializer<'input>>(
+    tokens: &mut Tokens<'_, 'input, D>,
+    pe: PokeEnum<'mem>,
+) -> Result<PokeEnum<'mem>, D::Error> {
+    let name = selected_variant(&pe).serialized_name();
+    tokens.path.push(PathSegment::Field(name));
+    let pe = deserialize_payload_value(tokens, pe)?;
+    tokens.path.pop();
+    Ok(pe)
+}
+
+fn deserialize_payload_value<'input, 'mem, D: Deserializer<'input>>(
+    tokens: &mut Tokens<'_, 'input, D>,
+    mut pe: PokeEnum<'mem>,
+) -> Result<PokeEnum<'mem>, D::Error> {
+    match selected_variant(&pe).kind {
+        VariantKind::Unit => match tokens.next()? {
+            Token::Null => {}
+            token => return Err(tokens.error(unexpected("null", &token))),
+        },
+        VariantKind::Tuple { fields } if fields.len() == 1 => {
+            let field = pe.tuple_field(0).expect("newtype variant has a field");
+            deserialize_value(tokens, field, false)?;
+            unsafe { pe.mark_initialized(0) };
+        }
+        VariantKind::Tuple { fields } => {
+            match tokens.next()? {
+                Token::StartList(_) => {}
+                token => return Err(tokens.error(unexpected("a list", &token))),
+            }
+            let mut index = 0;
+            loop {
+                tokens.path.push(PathSegment::Index(index));
+                match tokens.next()? {
+                    Token::EndList => break,
+                    token if index < fields.len() => tokens.push_back(token),
+                    token => return Err(tokens.error(unexpected("the end of a list", &token))),
+                }
+                tokens.path.pop();
+                let field = pe.tuple_field(index).expect("index is in bounds");
+                tokens.deserialize_at(PathSegment::Index(index), field)?;
+                unsafe { pe.mark_initialized(index) };
+                index += 1;
+            }
+            tokens.path.pop();
+        }
+        VariantKind::Struct { .. } => {
+            match tokens.next()? {
+                Token::StartMap(_) => {}
+                token => return Err(tokens.error(unexpected("a map", &token))),
+            }
+            deserialize_variant_fields(tokens, &mut pe, None)?;
+        }
+        _ => return Err(tokens.error(DeserializeError::Unsupported { shape: pe.shape() })),
+    }
+    Ok(pe)
+}
+
+/// Deserializes the entries of a map, whose start has been read, into the fields of the
+/// selected variant, skipping the `skip_key` entry (the tag of internally tagged enums)
+fn deserialize_variant_fields<'input, D: Deserializer<'input>>(
+    tokens: &mut Tokens<'_, 'input, D>,
+    pe: &mut PokeEnum<'_>,
+    skip_key: Option<&str>,
+) -> Result<(), D::Error> {
+    loop {
+        let key = match tokens.next()? {
+            Token::EndMap => return Ok(()),
+            Token::Str(key) => key,
+            token => return Err(tokens.error(unexpected("a field name", &token))),
+        };
+        if skip_key == Some(&*key) {
+            tokens.skip()?;
+        } else if let Ok((index, field)) = pe.field_by_serialized_name(&key) {
+            let name = match selected_variant(pe).kind {
+                VariantKind::Struct { fields } => fields[index].serialized_name(),
+                _ => "",
+            };
+            tokens.deserialize_at(PathSegment::Field(name), field)?;
+            unsafe { pe.mark_initialized(index) };
+        } else if tokens.deserializer.deny_unknown_fields() {
+            let fields = match selected_variant(pe).kind {
+                VariantKind::Struct { fields } => fields,
+                _ => &[],
+            };
+            let names = fields
+                .iter()
+                .filter(|f| !f.flags.contains(FieldFlags::SKIP_DESERIALIZING))
+                .map(|f| f.serialized_name());
+            return Err(tokens.error(DeserializeError::UnknownField {
+                suggestion: did_you_mean(&key, names),
+                field: key.into_owned(),
+                shape: pe.shape(),
+            }));
+        } else {
+            tokens.skip()?;
+        }
+    }
+}
+
+/// Picks the candidate closest to `name`, as long as it's close enough to be a likely typo:
+/// at most a third of its characters edited, a swap of two neighbours counting as one edit.
+pub fn did_you_mean(
+    name: &str,
+    candidates: impl Iterator<Item = &'static str>,
+) -> Option<&'static str> {
+    candidates
+        .map(|candidate| (edit_distance(name, candidate), candidate))
+        .filter(|(distance, candidate)| distance * 3 <= name.len().max(candidate.len()))
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, candidate)| candidate)
+}
+
+/// Optimal string alignment distance between `a` and `b`
+fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    // rows for i - 2, i - 1 and i
+    let mut before: Vec<usize> = alloc::vec![0; b.len() + 1];
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    let mut current = alloc::vec![0; b.len() + 1];
+    for i in 1..=a.len() {
+        current[0] = i;
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            current[j] = (previous[j] + 1)
+                .min(current[j - 1] + 1)
+                .min(previous[j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                current[j] = current[j].min(before[j - 2] + 1);
+            }
+        }
+        core::mem::swap(&mut before, &mut previous);
+        core::mem::swap(&mut previous, &mut current);
+    }
+    previous[b.len()]
+}
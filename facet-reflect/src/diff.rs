@@ -0,0 +1,295 @@
+//! Finding the parts that differ between two values, see [`diff`]
+
+extern crate alloc;
+use alloc::string::ToString;
+use alloc::vec::Vec;
+
+use facet_core::{Def, StructKind, VariantKind};
+
+use crate::{PathSegment, Peek, ReflectPath, structural_eq};
+
+/// The differences between two values, see [`diff`]
+#[derive(Debug, Clone, Default)]
+pub struct Diff<'mem> {
+    changes: Vec<Change<'mem>>,
+}
+
+impl<'mem> Diff<'mem> {
+    /// Whether the two values are the same
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    /// How many parts of the values differ
+    pub fn len(&self) -> usize {
+        self.changes.len()
+    }
+
+    /// The parts of the values that differ, in the order they appear in the values
+    pub fn changes(&self) -> &[Change<'mem>] {
+        &self.changes
+    }
+}
+
+impl<'mem> IntoIterator for Diff<'mem> {
+    type Item = Change<'mem>;
+    type IntoIter = alloc::vec::IntoIter<Change<'mem>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.changes.into_iter()
+    }
+}
+
+/// A part that differs between two values, see [`diff`]
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub enum Change<'mem> {
+    /// The part is in both values, with different contents
+    Changed {
+        /// Where the part is
+        path: ReflectPath,
+        /// The part in the first value
+        old: Peek<'mem>,
+        /// The part in the second value
+        new: Peek<'mem>,
+    },
+
+    /// A list item, map entry or set item that's only in the second value
+    Added {
+        /// Where the part is in the second value. For set items, that's the set.
+        path: ReflectPath,
+        /// The part in the second value
+        value: Peek<'mem>,
+    },
+
+    /// A list item, map entry or set item that's only in the first value
+    Removed {
+        /// Where the part was in the first value. For set items, that's the set.
+        path: ReflectPath,
+        /// The part in the first value
+        value: Peek<'mem>,
+    },
+}
+
+impl Change<'_> {
+    /// Where the part that differs is
+    pub fn path(&self) -> &ReflectPath {
+        match self {
+            Change::Changed { path, .. }
+            | Change::Added { path, .. }
+            | Change::Removed { path, .. } => path,
+        }
+    }
+}
+
+/// Walks two values side by side and reports the parts that differ between them: struct and
+/// enum fields, list items, map entries and set items, each with its path.
+///
+/// Parts whose type implements `PartialEq` and that compare equal with it are not looked into.
+/// Values of different types, enums with different variants and options where only one is
+/// `Some` are reported as changed as a whole. So are parts that can't be compared at all, so
+/// that an empty diff always means the values are the same.
+///
+/// List items are matched up after skipping the items both lists start and end with, so that
+/// inserting or removing an item only reports that item. Map entries are reported in the order
+/// of their keys, set items in the order of their values.
+///
+/// # Example
+/// ```
+/// # use facet::Facet;
+/// # use facet_reflect::{Change, Peek, diff};
+/// #[derive(Facet)]
+/// struct Config {
+///     name: String,
+///     ports: Vec<u16>,
+/// }
+///
+/// let a = Config { name: "web".into(), ports: vec![80, 443] };
+/// let b = Config { name: "web".into(), ports: vec![80, 8080, 443] };
+///
+/// let diff = diff(Peek::new(&a), Peek::new(&b));
+/// assert_eq!(diff.len(), 1);
+/// let Change::Added { path, value } = &diff.changes()[0] else { unreachable!() };
+/// assert_eq!(path.to_string(), "ports[1]");
+/// assert_eq!(value.to_string(), "8080");
+/// ```
+pub fn diff<'mem>(a: Peek<'mem>, b: Peek<'mem>) -> Diff<'mem> {
+    let mut differ = Differ {
+        path: ReflectPath::new(),
+        changes: Vec::new(),
+    };
+    differ.diff(a, b);
+    Diff {
+        changes: differ.changes,
+    }
+}
+
+struct Differ<'mem> {
+    path: ReflectPath,
+    changes: Vec<Change<'mem>>,
+}
+
+impl<'mem> Differ<'mem> {
+    fn diff(&mut self, a: Peek<'mem>, b: Peek<'mem>) {
+        if a.shape().id != b.shape().id {
+            return self.changed(a, b);
+        }
+        // values that compare equal are left alone, even if some of their parts differ
+        match a.as_value().eq(&b.as_value()) {
+            Some(true) => return,
+            Some(false) if matches!(a, Peek::Value(_)) => return self.changed(a, b),
+            _ => {}
+        }
+
+        match (a, b) {
+            (Peek::Struct(a), Peek::Struct(b)) => {
+                let kind = a.def().kind;
+                for (index, ((name, a), (_, b))) in a.fields().zip(b.fields()).enumerate() {
+                    let segment = match kind {
+                        StructKind::TupleStruct | StructKind::Tuple => PathSegment::Index(index),
+                        _ => PathSegment::Field(name),
+                    };
+                    self.diff_at(segment, a, b);
+                }
+            }
+            (Peek::Enum(a), Peek::Enum(b)) => {
+                if a.variant_index() != b.variant_index() {
+                    return self.changed(Peek::Enum(a), Peek::Enum(b));
+                }
+                let tuple = matches!(a.variant_kind_active(), VariantKind::Tuple { .. });
+                self.path.push(PathSegment::Field(a.variant_name_active()));
+                for (index, ((name, a), (_, b))) in a.fields().zip(b.fields()).enumerate() {
+                    let segment = if tuple {
+                        PathSegment::Index(index)
+                    } else {
+                        PathSegment::Field(name)
+                    };
+                    self.diff_at(segment, a, b);
+                }
+                self.path.pop();
+            }
+            (Peek::List(a), Peek::List(b)) => {
+                let a: Vec<_> = a.iter().collect();
+                let b: Vec<_> = b.iter().collect();
+                let same = |(a, b): (&Peek<'_>, &Peek<'_>)| structural_eq(*a, *b) == Some(true);
+                let prefix = a.iter().zip(&b).take_while(|pair| same(*pair)).count();
+                let suffix = a[prefix..]
+                    .iter()
+                    .rev()
+                    .zip(b[prefix..].iter().rev())
+                    .take_while(|pair| same(*pair))
+                    .count();
+                let (a_end, b_end) = (a.len() - suffix, b.len() - suffix);
+
+                let mut index = prefix;
+                while index < a_end.min(b_end) {
+                    self.diff_at(PathSegment::Index(index), a[index], b[index]);
+                    index += 1;
+                }
+                for (index, &value) in a.iter().enumerate().take(a_end).skip(index) {
+                    self.at(PathSegment::Index(index), |path| Change::Removed {
+                        path,
+                        value,
+                    });
+                }
+                for (index, &value) in b.iter().enumerate().take(b_end).skip(index) {
+                    self.at(PathSegment::Index(index), |path| Change::Added {
+                        path,
+                        value,
+                    });
+                }
+            }
+            (Peek::Map(a), Peek::Map(b)) => {
+                let Def::Map(def) = a.shape().def else {
+                    unreachable!("maps have a map def")
+                };
+                let lookup = |map: Peek<'mem>, key: Peek<'_>| {
+                    let value = unsafe { (def.vtable.get_value_ptr_fn)(map.data(), key.data()) }?;
+                    Some(unsafe { Peek::unchecked_new(value, def.v) })
+                };
+
+                let mut entries = Vec::with_capacity(a.len());
+                for (key, value) in a.iter() {
+                    entries.push((key.to_string(), Some(value), lookup(Peek::Map(b), key)));
+                }
+                for (key, value) in b.iter() {
+                    if lookup(Peek::Map(a), key).is_none() {
+                        entries.push((key.to_string(), None, Some(value)));
+                    }
+                }
+                entries.sort_by(|(a, ..), (b, ..)| a.cmp(b));
+
+                for (key, a, b) in entries {
+                    let segment = PathSegment::Key(key);
+                    match (a, b) {
+                        (Some(a), Some(b)) => self.diff_at(segment, a, b),
+                        (Some(value), None) => {
+                            self.at(segment, |path| Change::Removed { path, value })
+                        }
+                        (None, Some(value)) => {
+                            self.at(segment, |path| Change::Added { path, value })
+                        }
+                        (None, None) => unreachable!("entries come from either map"),
+                    }
+                }
+            }
+            (Peek::Set(a), Peek::Set(b)) => {
+                let Def::Set(def) = a.shape().def else {
+                    unreachable!("sets have a set def")
+                };
+                let contains = |set: Peek<'_>, item: Peek<'_>| unsafe {
+                    (def.vtable.contains_fn)(set.data(), item.data())
+                };
+
+                let mut items = Vec::new();
+                for item in a.iter().filter(|item| !contains(Peek::Set(b), *item)) {
+                    items.push((item.to_string(), false, item));
+                }
+                for item in b.iter().filter(|item| !contains(Peek::Set(a), *item)) {
+                    items.push((item.to_string(), true, item));
+                }
+                items.sort_by(|(a, ..), (b, ..)| a.cmp(b));
+
+                for (_, added, value) in items {
+                    let path = self.path.clone();
+                    self.changes.push(if added {
+                        Change::Added { path, value }
+                    } else {
+                        Change::Removed { path, value }
+                    });
+                }
+            }
+            (Peek::Option(oa), Peek::Option(ob)) => match (oa.value(), ob.value()) {
+                (Some(a), Some(b)) => self.diff(a, b),
+                (None, None) => {}
+                _ => self.changed(a, b),
+            },
+            (Peek::SmartPointer(pa), Peek::SmartPointer(pb)) => match (pa.inner(), pb.inner()) {
+                (Some(a), Some(b)) => self.diff(a, b),
+                (None, None) => {}
+                _ => self.changed(a, b),
+            },
+            _ => self.changed(a, b),
+        }
+    }
+
+    fn diff_at(&mut self, segment: PathSegment, a: Peek<'mem>, b: Peek<'mem>) {
+        self.path.push(segment);
+        self.diff(a, b);
+        self.path.pop();
+    }
+
+    fn changed(&mut self, old: Peek<'mem>, new: Peek<'mem>) {
+        self.changes.push(Change::Changed {
+            path: self.path.clone(),
+            old,
+            new,
+        });
+    }
+
+    fn at(&mut self, segment: PathSegment, change: impl FnOnce(ReflectPath) -> Change<'mem>) {
+        let mut path = self.path.clone();
+        path.push(segment);
+        self.changes.push(change(path));
+    }
+}
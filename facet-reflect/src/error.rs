@@ -0,0 +1,49 @@
+extern crate alloc;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use facet_core::Shape;
+
+/// Errors that can happen while building a value through reflection
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ReflectError {
+    /// Some fields were never initialized, and had no default to fall back on
+    MissingFields {
+        /// The struct or enum that was being built
+        shape: &'static Shape,
+        /// Path of every missing field, by serialized name: `field` for structs, `Variant.field`
+        /// for enums
+        paths: Vec<String>,
+    },
+    /// The value's type doesn't support an operation, e.g. removing items from an array
+    OperationNotSupported {
+        /// The type of the value
+        shape: &'static Shape,
+        /// The operation that was attempted
+        operation: &'static str,
+    },
+}
+
+impl core::fmt::Display for ReflectError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            ReflectError::MissingFields { shape, paths } => {
+                let plural = if paths.len() == 1 { "" } else { "s" };
+                write!(f, "missing field{plural} in {shape}: ")?;
+                for (i, path) in paths.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "`{path}`")?;
+                }
+                Ok(())
+            }
+            ReflectError::OperationNotSupported { shape, operation } => {
+                write!(f, "{shape} does not support {operation}")
+            }
+        }
+    }
+}
+
+impl core::error::Error for ReflectError {}
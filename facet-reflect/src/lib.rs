@@ -3,9 +3,24 @@
 //! This crate combines functionality that was previously split between separate crates:
 //! - Peeking: Reading or inspecting data structures
 //! - Poking: Modifying or manipulating data structures
+//! - Serializing: Walking a value for any format, see [`Serializer`]
+//! - Deserializing: Building a value from the tokens of any format, see [`Deserializer`]
+//! - Cloning, comparing and hashing any value through its shape, see [`deep_clone`],
+//!   [`structural_eq`] and [`structural_hash`]
+//! - Diffing: Finding the parts that differ between two values, see [`diff`]
 //!
 //! Both capabilities are essential for reflection operations in the Facet ecosystem.
 
+#[cfg(feature = "alloc")]
+mod error;
+#[cfg(feature = "alloc")]
+pub use error::*;
+
+#[cfg(feature = "alloc")]
+mod path;
+#[cfg(feature = "alloc")]
+pub use path::*;
+
 #[cfg(feature = "alloc")]
 mod poke;
 #[cfg(feature = "alloc")]
@@ -13,3 +28,23 @@ pub use poke::*;
 
 mod peek;
 pub use peek::*;
+
+#[cfg(feature = "alloc")]
+mod serialize;
+#[cfg(feature = "alloc")]
+pub use serialize::*;
+
+#[cfg(feature = "alloc")]
+mod deserialize;
+#[cfg(feature = "alloc")]
+pub use deserialize::*;
+
+#[cfg(feature = "alloc")]
+mod structural;
+#[cfg(feature = "alloc")]
+pub use structural::*;
+
+#[cfg(feature = "alloc")]
+mod diff;
+#[cfg(feature = "alloc")]
+pub use diff::*;
@@ -0,0 +1,110 @@
+extern crate alloc;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use facet_core::{Shape, TypeNameOpts};
+
+/// A step from a value into one of its parts
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum PathSegment {
+    /// A struct field, or a field of a struct variant, by its serialized name
+    Field(&'static str),
+    /// An item of a list, set or tuple, or a field of a tuple variant
+    Index(usize),
+    /// A map value, by its key as written in the input
+    Key(String),
+}
+
+/// Where a part of a value is, from the root of the value, e.g. `servers[2].tls.cert_path`
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ReflectPath {
+    segments: Vec<PathSegment>,
+}
+
+impl ReflectPath {
+    /// The path of the root value
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Steps into a part of the current value
+    pub fn push(&mut self, segment: PathSegment) {
+        self.segments.push(segment);
+    }
+
+    /// Steps back out to the parent value
+    pub fn pop(&mut self) -> Option<PathSegment> {
+        self.segments.pop()
+    }
+
+    /// The steps from the root value, outermost first
+    pub fn segments(&self) -> &[PathSegment] {
+        &self.segments
+    }
+
+    /// Whether this is the path of the root value
+    pub fn is_empty(&self) -> bool {
+        self.segments.is_empty()
+    }
+
+    /// How many steps there are from the root value
+    pub fn len(&self) -> usize {
+        self.segments.len()
+    }
+
+    /// Steps back out until there are only `len` steps left
+    pub fn truncate(&mut self, len: usize) {
+        self.segments.truncate(len);
+    }
+}
+
+impl core::fmt::Display for ReflectPath {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        for (i, segment) in self.segments.iter().enumerate() {
+            match segment {
+                PathSegment::Field(name) if i == 0 => write!(f, "{name}")?,
+                PathSegment::Field(name) => write!(f, ".{name}")?,
+                PathSegment::Index(index) => write!(f, "[{index}]")?,
+                PathSegment::Key(key) => write!(f, "[{key:?}]")?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// An error, along with where it happened in the value being built and the shape that was
+/// expected there
+#[derive(Debug, Clone, PartialEq)]
+pub struct PathError<E> {
+    /// What went wrong
+    pub error: E,
+    /// Where it went wrong
+    pub path: ReflectPath,
+    /// The shape of the value that was being built there
+    pub shape: &'static Shape,
+}
+
+impl<E> PathError<E> {
+    /// Turns the error into another type, keeping where it happened
+    pub fn map<F>(self, f: impl FnOnce(E) -> F) -> PathError<F> {
+        PathError {
+            error: f(self.error),
+            path: self.path,
+            shape: self.shape,
+        }
+    }
+}
+
+impl<E: core::fmt::Display> core::fmt::Display for PathError<E> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        if !self.path.is_empty() {
+            write!(f, "{}: ", self.path)?;
+        }
+        write!(f, "{} (expected ", self.error)?;
+        self.shape.write_type_name(f, TypeNameOpts::infinite())?;
+        write!(f, ")")
+    }
+}
+
+impl<E: core::error::Error> core::error::Error for PathError<E> {}
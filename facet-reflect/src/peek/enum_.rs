@@ -227,6 +227,39 @@ impl<'mem> PeekEnum<'mem> {
         }
     }
 
+    /// Returns an iterator over the fields of a struct or tuple variant that should be
+    /// serialized (cf. [`PeekStruct::fields_for_serialize`])
+    ///
+    /// [`PeekStruct::fields_for_serialize`]: crate::PeekStruct::fields_for_serialize
+    #[cfg(feature = "alloc")]
+    pub fn fields_for_serialize(
+        self,
+    ) -> Box<
+        dyn Iterator<
+                Item = (
+                    usize,
+                    &'static str,
+                    crate::Peek<'mem>,
+                    &'static facet_core::Field,
+                ),
+            > + 'mem,
+    > {
+        Box::new(
+            self.fields_with_metadata()
+                .filter(|(_, _, value, field)| unsafe {
+                    !field.should_skip_serializing(value.as_value().data())
+                })
+                .flat_map(|(i, name, value, field)| match value {
+                    crate::Peek::Struct(inner)
+                        if field.flags.contains(facet_core::FieldFlags::FLATTEN) =>
+                    {
+                        inner.fields_for_serialize()
+                    }
+                    _ => Box::new(core::iter::once((i, name, value, field))),
+                }),
+        )
+    }
+
     /// Returns an iterator over fields of a struct or tuple variant
     #[cfg(feature = "alloc")]
     pub fn fields(self) -> Box<dyn Iterator<Item = (&'static str, crate::Peek<'mem>)> + 'mem> {
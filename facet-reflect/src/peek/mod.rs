@@ -20,9 +20,15 @@ pub use list::*;
 mod map;
 pub use map::*;
 
+mod set;
+pub use set::*;
+
 mod option;
 pub use option::*;
 
+mod smartptr;
+pub use smartptr::*;
+
 use facet_core::{Def, OpaqueConst, Shape};
 
 /// Lets you peek at the innards of a value
@@ -41,6 +47,9 @@ pub enum Peek<'mem> {
     /// cf. [`PeekMap`]
     Map(PeekMap<'mem>),
 
+    /// cf. [`PeekSet`]
+    Set(PeekSet<'mem>),
+
     /// cf. [`PeekStruct`]
     Struct(PeekStruct<'mem>),
 
@@ -49,6 +58,9 @@ pub enum Peek<'mem> {
 
     /// cf. [`PeekOption`]
     Option(PeekOption<'mem>),
+
+    /// cf. [`PeekSmartPointer`]
+    SmartPointer(PeekSmartPointer<'mem>),
 }
 
 impl<'mem> core::ops::Deref for Peek<'mem> {
@@ -59,16 +71,18 @@ impl<'mem> core::ops::Deref for Peek<'mem> {
             Peek::Value(value) => value,
             Peek::List(list) => list,
             Peek::Map(map) => map,
+            Peek::Set(set) => set,
             Peek::Struct(struct_) => struct_,
             Peek::Enum(enum_) => enum_,
             Peek::Option(option) => option,
+            Peek::SmartPointer(smart_pointer) => smart_pointer,
         }
     }
 }
 
 impl<'mem> Peek<'mem> {
     /// Creates a new peek from a reference to some initialized value that implements `Facet`
-    pub fn new<S: Facet>(s: &'mem S) -> Self {
+    pub fn new<'facet, S: Facet<'facet>>(s: &'mem S) -> Self {
         // This is safe because we're creating an Opaque pointer to read-only data
         // The pointer will be valid for the lifetime 'mem
         let data = OpaqueConst::new(s);
@@ -86,10 +100,12 @@ impl<'mem> Peek<'mem> {
         match shape.def {
             Def::Struct(def) => Peek::Struct(PeekStruct::new(value, def)),
             Def::Map(def) => Peek::Map(PeekMap::new(value, def)),
+            Def::Set(def) => Peek::Set(PeekSet::new(value, def)),
             Def::List(def) => Peek::List(PeekList::new(value, def)),
             Def::Scalar { .. } => Peek::Value(value),
             Def::Enum(def) => Peek::Enum(PeekEnum::new(value, def)),
             Def::Option(def) => Peek::Option(PeekOption::new(value, def)),
+            Def::SmartPointer(def) => Peek::SmartPointer(PeekSmartPointer::new(value, def)),
             _ => todo!("unsupported def: {:?}", shape.def),
         }
     }
@@ -100,9 +116,11 @@ impl<'mem> Peek<'mem> {
             Self::Value(v) => v,
             Self::List(l) => *l,
             Self::Map(m) => *m,
+            Self::Set(s) => *s,
             Self::Struct(s) => *s,
             Self::Enum(e) => *e,
             Self::Option(o) => *o,
+            Self::SmartPointer(sp) => *sp,
         }
     }
 }
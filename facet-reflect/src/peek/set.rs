@@ -0,0 +1,89 @@
+use facet_core::{Opaque, OpaqueConst, SetDef};
+
+use crate::Peek;
+
+use super::PeekValue;
+
+/// Iterator over values in a `PeekSet`
+pub struct PeekSetIter<'mem> {
+    set: PeekSet<'mem>,
+    iter: Opaque<'mem>,
+}
+
+impl<'mem> Iterator for PeekSetIter<'mem> {
+    type Item = Peek<'mem>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        unsafe {
+            let next = (self.set.def.vtable.iter_vtable.next)(self.iter);
+            next.map(|item_ptr| Peek::unchecked_new(item_ptr, self.set.def.t))
+        }
+    }
+}
+
+impl Drop for PeekSetIter<'_> {
+    fn drop(&mut self) {
+        unsafe { (self.set.def.vtable.iter_vtable.dealloc)(self.iter) }
+    }
+}
+
+impl<'mem> IntoIterator for &'mem PeekSet<'mem> {
+    type Item = Peek<'mem>;
+    type IntoIter = PeekSetIter<'mem>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// Lets you read from a set (implements read-only [`facet_core::SetVTable`] proxies)
+#[derive(Clone, Copy)]
+pub struct PeekSet<'mem> {
+    value: PeekValue<'mem>,
+    def: SetDef,
+}
+
+impl<'mem> core::ops::Deref for PeekSet<'mem> {
+    type Target = PeekValue<'mem>;
+
+    #[inline(always)]
+    fn deref(&self) -> &Self::Target {
+        &self.value
+    }
+}
+
+impl<'mem> PeekSet<'mem> {
+    /// Constructor
+    pub fn new(value: PeekValue<'mem>, def: SetDef) -> Self {
+        Self { value, def }
+    }
+
+    /// Get the number of values in the set
+    pub fn len(&self) -> usize {
+        unsafe { (self.def.vtable.len_fn)(self.value.data()) }
+    }
+
+    /// Returns true if the set is empty
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Check if the set contains a value
+    pub fn contains<'facet>(&self, value: &impl facet_core::Facet<'facet>) -> bool {
+        unsafe {
+            let value_ptr = OpaqueConst::new(value);
+            (self.def.vtable.contains_fn)(self.value.data(), value_ptr)
+        }
+    }
+
+    /// Returns an iterator over the values in the set
+    pub fn iter(self) -> PeekSetIter<'mem> {
+        let iter = unsafe { (self.def.vtable.iter_fn)(self.value.data()) };
+        PeekSetIter { set: self, iter }
+    }
+
+    /// Returns the set definition
+    pub fn def(&self) -> &SetDef {
+        &self.def
+    }
+}
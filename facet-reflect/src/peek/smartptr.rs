@@ -0,0 +1,46 @@
+use facet_core::{SmartPointerDef, SmartPointerVTable};
+
+/// Lets you read from a smart pointer (`Box<T>`, `Rc<T>`, `Arc<T>`, `Weak<T>`, etc.)
+#[derive(Clone, Copy)]
+pub struct PeekSmartPointer<'mem> {
+    value: crate::PeekValue<'mem>,
+    def: SmartPointerDef,
+}
+
+impl<'mem> core::ops::Deref for PeekSmartPointer<'mem> {
+    type Target = crate::PeekValue<'mem>;
+
+    #[inline(always)]
+    fn deref(&self) -> &Self::Target {
+        &self.value
+    }
+}
+
+impl<'mem> PeekSmartPointer<'mem> {
+    /// Create a new peek smart pointer
+    pub(crate) fn new(value: crate::PeekValue<'mem>, def: SmartPointerDef) -> Self {
+        Self { value, def }
+    }
+
+    /// Returns the smart pointer definition
+    #[inline(always)]
+    pub fn def(self) -> SmartPointerDef {
+        self.def
+    }
+
+    /// Returns the smart pointer vtable
+    #[inline(always)]
+    pub fn vtable(self) -> &'static SmartPointerVTable {
+        self.def.vtable
+    }
+
+    /// Returns the pointee as a Peek, or None if the pointer can't be borrowed
+    /// through (weak pointers)
+    pub fn inner(self) -> Option<crate::Peek<'mem>> {
+        let borrow_fn = self.vtable().borrow_fn?;
+        unsafe {
+            let inner_data = borrow_fn(self.value.data());
+            Some(crate::Peek::unchecked_new(inner_data, self.def.t))
+        }
+    }
+}
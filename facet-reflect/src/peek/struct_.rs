@@ -1,7 +1,12 @@
-use facet_core::StructDef;
+use facet_core::{FieldFlags, StructDef};
 
 use crate::{Peek, PeekValue};
 
+#[cfg(feature = "alloc")]
+extern crate alloc;
+#[cfg(feature = "alloc")]
+use alloc::boxed::Box;
+
 /// Lets you read from a struct (implements read-only struct operations)
 #[derive(Clone, Copy)]
 pub struct PeekStruct<'mem> {
@@ -85,4 +90,39 @@ impl<'mem> PeekStruct<'mem> {
             Some((i, name, value, field))
         })
     }
+
+    /// Iterates over the fields that should be serialized: like [`Self::fields_with_metadata`],
+    /// minus the fields skipped with `#[facet(skip_serializing)]` or whose
+    /// `#[facet(skip_serializing_if = "...")]` predicate holds.
+    ///
+    /// Fields marked `#[facet(flatten)]` are replaced by the fields of the struct they hold,
+    /// recursively; the index yielded for those is their index in that inner struct.
+    #[cfg(feature = "alloc")]
+    pub fn fields_for_serialize(
+        self,
+    ) -> Box<
+        dyn Iterator<Item = (usize, &'static str, Peek<'mem>, &'static facet_core::Field)> + 'mem,
+    > {
+        Box::new(
+            self.def
+                .fields
+                .iter()
+                .enumerate()
+                .map(move |(i, field)| {
+                    let value = unsafe {
+                        Peek::unchecked_new(self.data().field(field.offset), field.shape)
+                    };
+                    (i, field.name, value, field)
+                })
+                .filter(|(_, _, value, field)| unsafe {
+                    !field.should_skip_serializing(value.as_value().data())
+                })
+                .flat_map(|(i, name, value, field)| match value {
+                    Peek::Struct(inner) if field.flags.contains(FieldFlags::FLATTEN) => {
+                        inner.fields_for_serialize()
+                    }
+                    _ => Box::new(core::iter::once((i, name, value, field))),
+                }),
+        )
+    }
 }
@@ -2,11 +2,13 @@ use core::ptr::NonNull;
 #[cfg(feature = "alloc")]
 extern crate alloc;
 #[cfg(feature = "alloc")]
-use alloc::boxed::Box;
+use alloc::{boxed::Box, format, vec::Vec};
 
-use facet_core::{EnumDef, EnumRepr, Facet, FieldError, Opaque, OpaqueUninit, Shape, VariantKind};
+use facet_core::{
+    EnumDef, EnumRepr, Facet, FieldError, FieldFlags, Opaque, OpaqueUninit, Shape, VariantKind,
+};
 
-use crate::Guard;
+use crate::{Guard, PeekEnum, PeekValue, ReflectError};
 
 use super::{ISet, PokeValueUninit};
 
@@ -29,6 +31,13 @@ impl<'mem> PokeEnumNoVariant<'mem> {
     pub fn shape(&self) -> &'static Shape {
         self.shape
     }
+
+    /// Gets the enum definition
+    #[inline(always)]
+    pub fn def(&self) -> EnumDef {
+        self.def
+    }
+
     /// Creates a new PokeEnumNoVariant from raw data
     ///
     /// # Safety
@@ -61,6 +70,29 @@ impl<'mem> PokeEnumNoVariant<'mem> {
         self.set_variant_by_index(variant_index)
     }
 
+    /// Sets the variant of an enum by the name it has in serialized formats
+    /// (see [`Variant::serialized_name`]).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// - No variant with the given serialized name exists.
+    ///
+    /// [`Variant::serialized_name`]: facet_core::Variant::serialized_name
+    pub fn set_variant_by_serialized_name(
+        self,
+        variant_name: &str,
+    ) -> Result<PokeEnum<'mem>, FieldError> {
+        let variant_index = self
+            .def
+            .variants
+            .iter()
+            .position(|v| v.serialized_name() == variant_name)
+            .ok_or(FieldError::NoSuchStaticField)?;
+
+        self.set_variant_by_index(variant_index)
+    }
+
     /// Sets the variant of an enum by index.
     ///
     /// # Errors
@@ -72,79 +104,89 @@ impl<'mem> PokeEnumNoVariant<'mem> {
             return Err(FieldError::IndexOutOfBounds);
         }
 
-        // Get the current variant info
-        let variant = &self.def.variants[variant_index];
+        unsafe { write_variant(self.data, self.shape, self.def, variant_index) };
 
-        // Prepare memory for the enum
-        unsafe {
-            // Zero out the memory first to ensure clean state
-            core::ptr::write_bytes(self.data.as_mut_bytes(), 0, self.shape.layout.size());
-
-            // Set up the discriminant (tag)
-            // For enums in Rust, the first bytes contain the discriminant
-            let discriminant_value = match &variant.discriminant {
-                // If we have an explicit discriminant, use it
-                Some(discriminant) => *discriminant,
-                // Otherwise, use the variant index directly
-                None => variant_index as i64,
-            };
-
-            // Write the discriminant value based on the representation
-            match self.def.repr {
-                EnumRepr::U8 => {
-                    let tag_ptr = self.data.as_mut_bytes();
-                    *tag_ptr = discriminant_value as u8;
-                }
-                EnumRepr::U16 => {
-                    let tag_ptr = self.data.as_mut_bytes() as *mut u16;
-                    *tag_ptr = discriminant_value as u16;
-                }
-                EnumRepr::U32 => {
-                    let tag_ptr = self.data.as_mut_bytes() as *mut u32;
-                    *tag_ptr = discriminant_value as u32;
-                }
-                EnumRepr::U64 => {
-                    let tag_ptr = self.data.as_mut_bytes() as *mut u64;
-                    *tag_ptr = discriminant_value as u64;
-                }
-                EnumRepr::USize => {
-                    let tag_ptr = self.data.as_mut_bytes() as *mut usize;
-                    *tag_ptr = discriminant_value as usize;
-                }
-                EnumRepr::I8 => {
-                    let tag_ptr = self.data.as_mut_bytes() as *mut i8;
-                    *tag_ptr = discriminant_value as i8;
-                }
-                EnumRepr::I16 => {
-                    let tag_ptr = self.data.as_mut_bytes() as *mut i16;
-                    *tag_ptr = discriminant_value as i16;
-                }
-                EnumRepr::I32 => {
-                    let tag_ptr = self.data.as_mut_bytes() as *mut i32;
-                    *tag_ptr = discriminant_value as i32;
-                }
-                EnumRepr::I64 => {
-                    let tag_ptr = self.data.as_mut_bytes() as *mut i64;
-                    *tag_ptr = discriminant_value;
-                }
-                EnumRepr::ISize => {
-                    let tag_ptr = self.data.as_mut_bytes() as *mut isize;
-                    *tag_ptr = discriminant_value as isize;
-                }
-                _ => {
-                    panic!("Unsupported enum representation: {:?}", self.def.repr);
-                }
+        // Create PokeEnum with the selected variant
+        Ok(unsafe { PokeEnum::new(self.data, self.shape, self.def, variant_index) })
+    }
+}
+
+/// Zeroes the enum `data` points to and writes the discriminant of the variant at
+/// `variant_index`, leaving its fields uninitialized
+///
+/// # Safety
+///
+/// `data` must point to memory that fits the enum described by `shape`, and nothing in it may
+/// need dropping anymore.
+unsafe fn write_variant(
+    data: OpaqueUninit<'_>,
+    shape: &'static Shape,
+    def: EnumDef,
+    variant_index: usize,
+) {
+    // Get the current variant info
+    let variant = &def.variants[variant_index];
+
+    // Prepare memory for the enum
+    unsafe {
+        // Zero out the memory first to ensure clean state
+        core::ptr::write_bytes(data.as_mut_bytes(), 0, shape.layout.size());
+
+        // Set up the discriminant (tag)
+        // For enums in Rust, the first bytes contain the discriminant
+        let discriminant_value = match &variant.discriminant {
+            // If we have an explicit discriminant, use it
+            Some(discriminant) => *discriminant,
+            // Otherwise, use the variant index directly
+            None => variant_index as i64,
+        };
+
+        // Write the discriminant value based on the representation
+        match def.repr {
+            EnumRepr::U8 => {
+                let tag_ptr = data.as_mut_bytes();
+                *tag_ptr = discriminant_value as u8;
+            }
+            EnumRepr::U16 => {
+                let tag_ptr = data.as_mut_bytes() as *mut u16;
+                *tag_ptr = discriminant_value as u16;
+            }
+            EnumRepr::U32 => {
+                let tag_ptr = data.as_mut_bytes() as *mut u32;
+                *tag_ptr = discriminant_value as u32;
+            }
+            EnumRepr::U64 => {
+                let tag_ptr = data.as_mut_bytes() as *mut u64;
+                *tag_ptr = discriminant_value as u64;
+            }
+            EnumRepr::USize => {
+                let tag_ptr = data.as_mut_bytes() as *mut usize;
+                *tag_ptr = discriminant_value as usize;
+            }
+            EnumRepr::I8 => {
+                let tag_ptr = data.as_mut_bytes() as *mut i8;
+                *tag_ptr = discriminant_value as i8;
+            }
+            EnumRepr::I16 => {
+                let tag_ptr = data.as_mut_bytes() as *mut i16;
+                *tag_ptr = discriminant_value as i16;
+            }
+            EnumRepr::I32 => {
+                let tag_ptr = data.as_mut_bytes() as *mut i32;
+                *tag_ptr = discriminant_value as i32;
+            }
+            EnumRepr::I64 => {
+                let tag_ptr = data.as_mut_bytes() as *mut i64;
+                *tag_ptr = discriminant_value;
+            }
+            EnumRepr::ISize => {
+                let tag_ptr = data.as_mut_bytes() as *mut isize;
+                *tag_ptr = discriminant_value as isize;
+            }
+            _ => {
+                panic!("Unsupported enum representation: {:?}", def.repr);
             }
         }
-
-        // Create PokeEnum with the selected variant
-        Ok(PokeEnum {
-            data: self.data,
-            iset: Default::default(),
-            shape: self.shape,
-            def: self.def,
-            selected_variant: variant_index,
-        })
     }
 }
 
@@ -160,6 +202,9 @@ pub struct PokeEnum<'mem> {
     shape: &'static Shape,
     def: EnumDef,
     selected_variant: usize,
+    /// Whether `data` belongs to an initialized enum someone else owns, in which case its
+    /// fields are never dropped along with the PokeEnum
+    borrowed: bool,
 }
 
 impl<'mem> PokeEnum<'mem> {
@@ -179,7 +224,6 @@ impl<'mem> PokeEnum<'mem> {
     /// # Safety
     ///
     /// The data buffer must match the size and alignment of the enum shape described by shape
-    #[allow(dead_code)]
     pub(crate) unsafe fn new(
         data: OpaqueUninit<'mem>,
         shape: &'static Shape,
@@ -192,9 +236,45 @@ impl<'mem> PokeEnum<'mem> {
             shape,
             def,
             selected_variant,
+            borrowed: false,
         }
     }
 
+    /// Creates a PokeEnum for an initialized enum: its active variant, read from the
+    /// discriminant, is selected and all of its fields are marked as initialized.
+    ///
+    /// The enum stays owned by whoever it was borrowed from: dropping the PokeEnum leaves its
+    /// fields alone, only switching variants drops the old payload.
+    ///
+    /// # Safety
+    ///
+    /// `data` must be initialized and point to a value of the enum described by `shape`
+    pub(crate) unsafe fn from_initialized(
+        data: Opaque<'mem>,
+        shape: &'static Shape,
+        def: EnumDef,
+    ) -> Self {
+        let value = unsafe { PeekValue::unchecked_new(data.as_const(), shape) };
+        let selected_variant = PeekEnum::new(value, def).variant_index();
+        let mut pe = unsafe {
+            Self::new(
+                OpaqueUninit::new(data.as_mut_byte_ptr()),
+                shape,
+                def,
+                selected_variant,
+            )
+        };
+        pe.borrowed = true;
+        if let VariantKind::Tuple { fields } | VariantKind::Struct { fields } =
+            def.variants[selected_variant].kind
+        {
+            for index in 0..fields.len() {
+                pe.iset.set(index);
+            }
+        }
+        pe
+    }
+
     #[inline(always)]
     /// Shape getter
     pub fn shape(&self) -> &'static Shape {
@@ -206,6 +286,52 @@ impl<'mem> PokeEnum<'mem> {
         self.selected_variant
     }
 
+    /// Gets the enum definition
+    #[inline(always)]
+    pub fn def(&self) -> EnumDef {
+        self.def
+    }
+
+    /// Switches to the variant at the given index, dropping the initialized fields of the
+    /// currently selected one. The fields of the new variant start out uninitialized.
+    ///
+    /// This works on enums that were already initialized (e.g. through [`crate::Poke::borrow`])
+    /// as well as on ones being built. A borrowed enum is only valid again once the fields of
+    /// its new variant have all been set.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// - The index is out of bounds.
+    pub fn switch_variant_by_index(&mut self, variant_index: usize) -> Result<(), FieldError> {
+        if variant_index >= self.def.variants.len() {
+            return Err(FieldError::IndexOutOfBounds);
+        }
+
+        self.drop_fields();
+        self.iset = ISet::default();
+        unsafe { write_variant(self.data, self.shape, self.def, variant_index) };
+        self.selected_variant = variant_index;
+        Ok(())
+    }
+
+    /// Switches to the variant with the given name, see [`Self::switch_variant_by_index`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// - No variant with the given name exists.
+    pub fn switch_variant_by_name(&mut self, variant_name: &str) -> Result<(), FieldError> {
+        let variant_index = self
+            .def
+            .variants
+            .iter()
+            .position(|v| v.name == variant_name)
+            .ok_or(FieldError::NoSuchStaticField)?;
+
+        self.switch_variant_by_index(variant_index)
+    }
+
     /// Gets a field by name in the currently selected variant.
     ///
     /// # Errors
@@ -257,6 +383,40 @@ impl<'mem> PokeEnum<'mem> {
         }
     }
 
+    /// Gets a field in the currently selected variant by the name it has in serialized
+    /// formats (see [`Field::serialized_name`]).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// - No field with the given serialized name exists in the selected variant.
+    /// - The selected variant is a unit variant (which has no fields).
+    ///
+    /// Fields with [`FieldFlags::SKIP_DESERIALIZING`] are never returned.
+    ///
+    /// [`Field::serialized_name`]: facet_core::Field::serialized_name
+    pub fn field_by_serialized_name(
+        &self,
+        name: &str,
+    ) -> Result<(usize, crate::PokeUninit<'mem>), FieldError> {
+        let variant = &self.def.variants[self.selected_variant];
+        let fields = match &variant.kind {
+            VariantKind::Tuple { fields } | VariantKind::Struct { fields } => fields,
+            _ => return Err(FieldError::NoSuchStaticField),
+        };
+
+        let (index, field) = fields
+            .iter()
+            .enumerate()
+            .find(|(_, f)| {
+                f.serialized_name() == name && !f.flags.contains(FieldFlags::SKIP_DESERIALIZING)
+            })
+            .ok_or(FieldError::NoSuchStaticField)?;
+        let field_data = unsafe { self.variant_data().field_uninit(field.offset) };
+        let poke = unsafe { crate::PokeUninit::unchecked_new(field_data, field.shape) };
+        Ok((index, poke))
+    }
+
     /// Get a field writer for a tuple field by index in the currently selected variant.
     ///
     /// # Errors
@@ -300,6 +460,19 @@ impl<'mem> PokeEnum<'mem> {
         self.iset.set(field_index);
     }
 
+    /// Sets every field of the selected variant that wasn't initialized but has a default
+    /// (cf. [`FieldFlags::DEFAULT`])
+    ///
+    /// This is done automatically when building the enum.
+    ///
+    /// [`FieldFlags::DEFAULT`]: facet_core::FieldFlags::DEFAULT
+    pub fn fill_defaults(&mut self) {
+        let variant = &self.def.variants[self.selected_variant];
+        if let VariantKind::Tuple { fields } | VariantKind::Struct { fields } = variant.kind {
+            unsafe { super::fill_defaults(fields, self.variant_data(), &mut self.iset) }
+        }
+    }
+
     /// Checks if all required fields in the enum are initialized.
     ///
     /// # Panics
@@ -330,7 +503,39 @@ impl<'mem> PokeEnum<'mem> {
         }
     }
 
-    fn assert_matching_shape<T: Facet>(&self) {
+    /// Checks if all fields of the selected variant have been initialized.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ReflectError::MissingFields`] listing every field that isn't initialized,
+    /// as `Variant.field`.
+    pub fn check_all_fields_initialized(&self) -> Result<(), ReflectError> {
+        let variant = &self.def.variants[self.selected_variant];
+        let paths: Vec<_> = match &variant.kind {
+            VariantKind::Unit => Vec::new(),
+            VariantKind::Tuple { fields } | VariantKind::Struct { fields } => fields
+                .iter()
+                .enumerate()
+                .filter(|(i, _)| !self.iset.has(*i))
+                .map(|(_, field)| {
+                    format!("{}.{}", variant.serialized_name(), field.serialized_name())
+                })
+                .collect(),
+            _ => {
+                panic!("Unsupported enum variant kind: {:?}", variant.kind);
+            }
+        };
+        if paths.is_empty() {
+            Ok(())
+        } else {
+            Err(ReflectError::MissingFields {
+                shape: self.shape,
+                paths,
+            })
+        }
+    }
+
+    fn assert_matching_shape<'facet, T: Facet<'facet>>(&self) {
         if !self.shape.is_type::<T>() {
             panic!(
                 "This is a partial \x1b[1;34m{}\x1b[0m, you can't build a \x1b[1;32m{}\x1b[0m out of it",
@@ -343,13 +548,15 @@ impl<'mem> PokeEnum<'mem> {
     /// Asserts that every field in the selected variant has been initialized and forgets the PokeEnum.
     ///
     /// This method is only used when the origin is borrowed.
-    /// If this method is not called, all fields will be freed when the PokeEnum is dropped.
+    /// If this method is not called, the fields initialized through this PokeEnum are freed when
+    /// it is dropped, unless it was created from an initialized enum.
     ///
     /// # Panics
     ///
     /// This function will panic if any required field is not initialized.
-    pub fn build_in_place(self) -> Opaque<'mem> {
+    pub fn build_in_place(mut self) -> Opaque<'mem> {
         // ensure all fields are initialized
+        self.fill_defaults();
         self.assert_all_fields_initialized();
         let data = unsafe { self.data.assume_init() };
         // prevent field drops when the PokeEnum is dropped
@@ -357,6 +564,61 @@ impl<'mem> PokeEnum<'mem> {
         data
     }
 
+    /// Fills in defaults, checks that every field in the selected variant has been initialized
+    /// and forgets the PokeEnum.
+    ///
+    /// This is the fallible counterpart of [`Self::build_in_place`]. On error, the fields that
+    /// were initialized are dropped.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ReflectError::MissingFields`] if any field without a default is not initialized.
+    pub fn try_build_in_place(mut self) -> Result<Opaque<'mem>, ReflectError> {
+        self.fill_defaults();
+        self.check_all_fields_initialized()?;
+        let data = unsafe { self.data.assume_init() };
+        // prevent field drops when the PokeEnum is dropped
+        core::mem::forget(self);
+        Ok(data)
+    }
+
+    /// Builds a value of type `T` from the PokeEnum, then deallocates the memory
+    /// that this PokeEnum was pointing to.
+    ///
+    /// This is the fallible counterpart of [`Self::build`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ReflectError::MissingFields`] if any field without a default is not initialized.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if the generic type parameter T does not match the shape that
+    /// this PokeEnum is building.
+    pub fn try_build<'facet, T: Facet<'facet>>(
+        self,
+        guard: Option<Guard>,
+    ) -> Result<T, ReflectError> {
+        let mut guard = guard;
+        let mut this = self;
+        // this changes drop order: guard must be dropped _after_ this.
+
+        this.fill_defaults();
+        this.check_all_fields_initialized()?;
+        this.assert_matching_shape::<T>();
+        if let Some(guard) = &guard {
+            guard.shape.assert_type::<T>();
+        }
+
+        let result = unsafe {
+            let ptr = this.data.as_mut_bytes() as *const T;
+            core::ptr::read(ptr)
+        };
+        guard.take(); // dealloc
+        core::mem::forget(this);
+        Ok(result)
+    }
+
     /// Builds a value of type `T` from the PokeEnum, then deallocates the memory
     /// that this PokeEnum was pointing to.
     ///
@@ -365,11 +627,12 @@ impl<'mem> PokeEnum<'mem> {
     /// This function will panic if:
     /// - Not all fields in the selected variant have been initialized.
     /// - The generic type parameter T does not match the shape that this PokeEnum is building.
-    pub fn build<T: Facet>(self, guard: Option<Guard>) -> T {
+    pub fn build<'facet, T: Facet<'facet>>(self, guard: Option<Guard>) -> T {
         let mut guard = guard;
-        let this = self;
+        let mut this = self;
         // this changes drop order: guard must be dropped _after_ this.
 
+        this.fill_defaults();
         this.assert_all_fields_initialized();
         this.assert_matching_shape::<T>();
         if let Some(guard) = &guard {
@@ -393,7 +656,8 @@ impl<'mem> PokeEnum<'mem> {
     /// - Not all fields in the selected variant have been initialized.
     /// - The generic type parameter T does not match the shape that this PokeEnum is building.
     #[cfg(feature = "alloc")]
-    pub fn build_boxed<T: Facet>(self) -> Box<T> {
+    pub fn build_boxed<'facet, T: Facet<'facet>>(mut self) -> Box<T> {
+        self.fill_defaults();
         self.assert_all_fields_initialized();
         self.assert_matching_shape::<T>();
 
@@ -410,7 +674,8 @@ impl<'mem> PokeEnum<'mem> {
     /// and must be large enough to hold the value.
     /// The caller is responsible for ensuring that the target memory is properly deallocated
     /// when it's no longer needed.
-    pub unsafe fn move_into(self, target: NonNull<u8>) {
+    pub unsafe fn move_into(mut self, target: NonNull<u8>) {
+        self.fill_defaults();
         self.assert_all_fields_initialized();
         unsafe {
             core::ptr::copy_nonoverlapping(
@@ -425,6 +690,16 @@ impl<'mem> PokeEnum<'mem> {
 
 impl Drop for PokeEnum<'_> {
     fn drop(&mut self) {
+        // the owner of a borrowed enum drops it
+        if !self.borrowed {
+            self.drop_fields();
+        }
+    }
+}
+
+impl PokeEnum<'_> {
+    /// Drops the initialized fields of the selected variant, without unmarking them
+    fn drop_fields(&mut self) {
         let variant = &self.def.variants[self.selected_variant];
 
         // Drop fields based on the variant kind
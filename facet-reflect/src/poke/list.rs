@@ -1,4 +1,4 @@
-use crate::PokeValueUninit;
+use crate::{PokeValue, PokeValueUninit, ReflectError};
 use facet_core::{ListDef, ListVTable, Opaque, OpaqueConst, OpaqueUninit, Shape};
 
 /// Allows initializing an uninitialized list
@@ -45,12 +45,17 @@ impl<'mem> PokeListUninit<'mem> {
         let data = res.map_err(|_| self.data)?;
         Ok(unsafe { PokeList::new(data, self.shape, self.def) })
     }
+
+    /// Returns a reference to the `ListDef` of this `PokeListUninit`.
+    #[inline]
+    pub fn def(&self) -> &ListDef {
+        &self.def
+    }
 }
 
 /// Allows poking a list (appending, etc.)
 pub struct PokeList<'mem> {
     data: Opaque<'mem>,
-    #[allow(dead_code)]
     shape: &'static Shape,
     def: ListDef,
 }
@@ -106,6 +111,103 @@ impl<'mem> PokeList<'mem> {
         unsafe { (self.list_vtable().get_item_ptr)(self.data.as_const(), index) }
     }
 
+    /// Gets a proxy to modify the item at the given index in place
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the list is read-only, like slices.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the index is out of bounds.
+    pub fn item_mut(&mut self, index: usize) -> Result<PokeValue<'_>, ReflectError> {
+        let get_item_ptr_mut = self.supports(self.list_vtable().get_item_ptr_mut, "item_mut")?;
+        let item = unsafe { get_item_ptr_mut(self.data, index) };
+        Ok(unsafe { PokeValue::new(item, self.def.t) })
+    }
+
+    /// Swaps the items at the given indices
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the list is read-only, like slices.
+    ///
+    /// # Panics
+    ///
+    /// Panics if either index is out of bounds.
+    pub fn swap(&mut self, a: usize, b: usize) -> Result<(), ReflectError> {
+        let get_item_ptr_mut = self.supports(self.list_vtable().get_item_ptr_mut, "swap")?;
+        let item_a = unsafe { get_item_ptr_mut(self.data, a) };
+        let item_b = unsafe { get_item_ptr_mut(self.data, b) };
+        if a != b {
+            unsafe {
+                core::ptr::swap_nonoverlapping(
+                    item_a.as_mut_byte_ptr(),
+                    item_b.as_mut_byte_ptr(),
+                    self.def.t.layout.size(),
+                )
+            };
+        }
+        Ok(())
+    }
+
+    /// Inserts an item at the given index, shifting the items after it
+    ///
+    /// # Safety
+    ///
+    /// `item` is moved out of (with [`core::ptr::read`]) — it should be deallocated
+    /// afterwards but NOT dropped.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the list has a fixed size, like arrays. `item` is left untouched
+    /// then.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the index is greater than the length of the list.
+    pub unsafe fn insert(&mut self, index: usize, item: Opaque<'_>) -> Result<(), ReflectError> {
+        let insert = self.supports(self.list_vtable().insert, "insert")?;
+        unsafe { insert(self.data, index, item) };
+        Ok(())
+    }
+
+    /// Removes and drops the item at the given index, shifting the items after it
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the list has a fixed size, like arrays.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the index is out of bounds.
+    pub fn remove(&mut self, index: usize) -> Result<(), ReflectError> {
+        let remove = self.supports(self.list_vtable().remove, "remove")?;
+        unsafe { remove(self.data, index) };
+        Ok(())
+    }
+
+    /// Shortens the list to `len` items, dropping the rest. Does nothing if the list is
+    /// already shorter.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the list has a fixed size, like arrays.
+    pub fn truncate(&mut self, len: usize) -> Result<(), ReflectError> {
+        let truncate = self.supports(self.list_vtable().truncate, "truncate")?;
+        unsafe { truncate(self.data, len) };
+        Ok(())
+    }
+
+    /// Unwraps an optional vtable function, or reports that the list doesn't support
+    /// `operation`
+    fn supports<F>(&self, f: Option<F>, operation: &'static str) -> Result<F, ReflectError> {
+        f.ok_or(ReflectError::OperationNotSupported {
+            shape: self.shape,
+            operation,
+        })
+    }
+
     /// Takes ownership of this `PokeList` and returns the underlying data.
     pub fn build_in_place(self) -> Opaque<'mem> {
         self.data
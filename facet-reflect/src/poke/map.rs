@@ -1,4 +1,4 @@
-use crate::PokeValueUninit;
+use crate::{PokeValue, PokeValueUninit};
 use facet_core::{MapDef, MapVTable, Opaque, OpaqueConst, OpaqueUninit, Shape};
 
 /// Allows initializing an uninitialized map
@@ -111,6 +111,29 @@ impl<'mem> PokeMap<'mem> {
         unsafe { (self.map_vtable().get_value_ptr_fn)(self.data.as_const(), key) }
     }
 
+    /// Gets a proxy to modify the value for a given key in place
+    ///
+    /// Returns `None` if the key is not found.
+    #[inline]
+    pub fn get_mut(&mut self, key: OpaqueConst<'_>) -> Option<PokeValue<'_>> {
+        let value = unsafe { (self.map_vtable().get_value_ptr_mut_fn)(self.data, key) }?;
+        Some(unsafe { PokeValue::new(value, self.def.v) })
+    }
+
+    /// Removes the entry for a given key, dropping its key and value
+    ///
+    /// Returns whether the key was in the map.
+    #[inline]
+    pub fn remove(&mut self, key: OpaqueConst<'_>) -> bool {
+        unsafe { (self.map_vtable().remove_fn)(self.data, key) }
+    }
+
+    /// Removes all entries from the map
+    #[inline]
+    pub fn clear(&mut self) {
+        unsafe { (self.map_vtable().clear_fn)(self.data) }
+    }
+
     /// Takes ownership of this `PokeList` and returns the underlying data.
     pub fn build_in_place(self) -> Opaque<'mem> {
         self.data
@@ -2,7 +2,7 @@ extern crate alloc;
 
 use core::alloc::Layout;
 
-use facet_core::{Def, Facet, Opaque, OpaqueUninit, Shape};
+use facet_core::{Def, Facet, Field, FieldFlags, Opaque, OpaqueUninit, Shape};
 
 mod value;
 pub use value::*;
@@ -13,6 +13,9 @@ pub use list::*;
 mod map;
 pub use map::*;
 
+mod set;
+pub use set::*;
+
 mod struct_;
 pub use struct_::*;
 
@@ -22,6 +25,9 @@ pub use enum_::*;
 mod option;
 pub use option::*;
 
+mod smartptr;
+pub use smartptr::*;
+
 /// Allows initializing values of different kinds.
 #[non_exhaustive]
 pub enum PokeUninit<'mem> {
@@ -31,12 +37,16 @@ pub enum PokeUninit<'mem> {
     List(PokeListUninit<'mem>),
     /// A map (HashMap/BTreeMap/etc). See [`PokeMap`].
     Map(PokeMapUninit<'mem>),
+    /// A set (HashSet/BTreeSet/etc). See [`PokeSet`].
+    Set(PokeSetUninit<'mem>),
     /// A struct, tuple struct, or tuple. See [`PokeStruct`].
     Struct(PokeStruct<'mem>),
     /// An enum variant. See [`PokeEnum`].
     Enum(PokeEnumNoVariant<'mem>),
     /// An option value. See [`PokeOption`].
     Option(PokeOptionUninit<'mem>),
+    /// A smart pointer (Box/Rc/Arc/etc). See [`PokeSmartPointer`].
+    SmartPointer(PokeSmartPointerUninit<'mem>),
 }
 
 /// Ensures a value is dropped when the guard is dropped.
@@ -58,7 +68,7 @@ impl Drop for Guard {
 
 impl<'mem> PokeUninit<'mem> {
     /// Allocates a new poke of a type that implements facet
-    pub fn alloc<S: Facet>() -> (Self, Guard) {
+    pub fn alloc<'facet, S: Facet<'facet>>() -> (Self, Guard) {
         let data = S::SHAPE.allocate();
         let layout = Layout::new::<S>();
         let guard = Guard {
@@ -98,6 +108,10 @@ impl<'mem> PokeUninit<'mem> {
                 let pmu = unsafe { PokeMapUninit::new(data, shape, map_def) };
                 PokeUninit::Map(pmu)
             }
+            Def::Set(set_def) => {
+                let psu = unsafe { PokeSetUninit::new(data, shape, set_def) };
+                PokeUninit::Set(psu)
+            }
             Def::List(list_def) => {
                 let plu = unsafe { PokeListUninit::new(data, shape, list_def) };
                 PokeUninit::List(plu)
@@ -110,6 +124,10 @@ impl<'mem> PokeUninit<'mem> {
                 let pou = unsafe { PokeOptionUninit::new(data, shape, option_def) };
                 PokeUninit::Option(pou)
             }
+            Def::SmartPointer(smart_pointer_def) => {
+                let pspu = unsafe { PokeSmartPointerUninit::new(data, shape, smart_pointer_def) };
+                PokeUninit::SmartPointer(pspu)
+            }
             _ => todo!("unsupported def: {:?}", shape.def),
         }
     }
@@ -138,6 +156,14 @@ impl<'mem> PokeUninit<'mem> {
         }
     }
 
+    /// Converts this Poke into a PokeSet, panicking if it's not a Set variant
+    pub fn into_set(self) -> PokeSetUninit<'mem> {
+        match self {
+            PokeUninit::Set(s) => s,
+            _ => panic!("expected Set variant"),
+        }
+    }
+
     /// Converts this Poke into a PokeValue, panicking if it's not a Scalar variant
     pub fn into_scalar(self) -> PokeValueUninit<'mem> {
         match self {
@@ -162,6 +188,14 @@ impl<'mem> PokeUninit<'mem> {
         }
     }
 
+    /// Converts this Poke into a PokeSmartPointer, panicking if it's not a SmartPointer variant
+    pub fn into_smart_pointer(self) -> PokeSmartPointerUninit<'mem> {
+        match self {
+            PokeUninit::SmartPointer(sp) => sp,
+            _ => panic!("expected SmartPointer variant"),
+        }
+    }
+
     /// Converts into a value, so we can manipulate it
     #[inline(always)]
     pub fn into_value(self) -> PokeValueUninit<'mem> {
@@ -169,9 +203,11 @@ impl<'mem> PokeUninit<'mem> {
             PokeUninit::Scalar(s) => s.into_value(),
             PokeUninit::List(l) => l.into_value(),
             PokeUninit::Map(m) => m.into_value(),
+            PokeUninit::Set(s) => s.into_value(),
             PokeUninit::Struct(s) => s.into_value(),
             PokeUninit::Enum(e) => e.into_value(),
             PokeUninit::Option(o) => o.into_value(),
+            PokeUninit::SmartPointer(sp) => sp.into_value(),
         }
     }
 
@@ -182,49 +218,109 @@ impl<'mem> PokeUninit<'mem> {
             PokeUninit::Scalar(poke_value) => poke_value.shape(),
             PokeUninit::List(poke_list_uninit) => poke_list_uninit.shape(),
             PokeUninit::Map(poke_map_uninit) => poke_map_uninit.shape(),
+            PokeUninit::Set(poke_set_uninit) => poke_set_uninit.shape(),
             PokeUninit::Struct(poke_struct) => poke_struct.shape(),
             PokeUninit::Enum(poke_enum_no_variant) => poke_enum_no_variant.shape(),
             PokeUninit::Option(poke_option_uninit) => poke_option_uninit.shape(),
+            PokeUninit::SmartPointer(poke_smart_pointer_uninit) => {
+                poke_smart_pointer_uninit.shape()
+            }
         }
     }
 }
 
-/// Keeps track of which fields were initialized, up to 64 fields
-#[derive(Clone, Copy, Default)]
-pub struct ISet(u64);
+/// Keeps track of which fields were initialized.
+///
+/// The first 64 fields fit inline, only wider structs and variants allocate.
+#[derive(Clone, Default)]
+pub struct ISet {
+    /// Fields 0 to 63
+    inline: u64,
+    /// Fields 64 and up, 64 per word
+    spill: Vec<u64>,
+}
 
 impl ISet {
+    /// The word and the mask of the bit at the given index, if it's been allocated
+    fn bit(&self, index: usize) -> (Option<&u64>, u64) {
+        let mask = 1 << (index % 64);
+        match index / 64 {
+            0 => (Some(&self.inline), mask),
+            word => (self.spill.get(word - 1), mask),
+        }
+    }
+
+    /// The word of the bit at the given index, allocating it if needed
+    fn word_mut(&mut self, index: usize) -> &mut u64 {
+        match index / 64 {
+            0 => &mut self.inline,
+            word => {
+                if self.spill.len() < word {
+                    self.spill.resize(word, 0);
+                }
+                &mut self.spill[word - 1]
+            }
+        }
+    }
+
     /// Sets the bit at the given index.
     pub fn set(&mut self, index: usize) {
-        if index >= 64 {
-            panic!("ISet can only track up to 64 fields. Index {index} is out of bounds.");
-        }
-        self.0 |= 1 << index;
+        *self.word_mut(index) |= 1 << (index % 64);
     }
 
     /// Unsets the bit at the given index.
     pub fn unset(&mut self, index: usize) {
-        if index >= 64 {
-            panic!("ISet can only track up to 64 fields. Index {index} is out of bounds.");
+        if let (Some(_), mask) = self.bit(index) {
+            *self.word_mut(index) &= !mask;
         }
-        self.0 &= !(1 << index);
     }
 
     /// Checks if the bit at the given index is set.
     pub fn has(&self, index: usize) -> bool {
-        if index >= 64 {
-            panic!("ISet can only track up to 64 fields. Index {index} is out of bounds.");
+        match self.bit(index) {
+            (Some(word), mask) => word & mask != 0,
+            (None, _) => false,
         }
-        (self.0 & (1 << index)) != 0
     }
 
     /// Checks if all bits up to the given count are set.
     pub fn all_set(&self, count: usize) -> bool {
-        if count > 64 {
-            panic!("ISet can only track up to 64 fields. Count {count} is out of bounds.");
+        let full_words = count / 64;
+        let full = (0..full_words).all(|word| match word {
+            0 => self.inline == u64::MAX,
+            word => self.spill.get(word - 1) == Some(&u64::MAX),
+        });
+        let rest = count % 64;
+        if !full || rest == 0 {
+            return full;
+        }
+        let mask = (1 << rest) - 1;
+        match self.bit(full_words * 64) {
+            (Some(word), _) => word & mask == mask,
+            (None, _) => false,
+        }
+    }
+}
+
+/// Initializes every field that hasn't been set yet but has a default
+/// (cf. [`FieldFlags::DEFAULT`]), marking it as initialized in `iset`.
+///
+/// # Safety
+///
+/// `data` must point to the struct (or enum variant) that `fields` describes.
+pub(crate) unsafe fn fill_defaults(
+    fields: &'static [Field],
+    data: OpaqueUninit<'_>,
+    iset: &mut ISet,
+) {
+    for (index, field) in fields.iter().enumerate() {
+        if iset.has(index) || !field.flags.contains(FieldFlags::DEFAULT) {
+            continue;
+        }
+        if let Some(default_fn) = field.default.or(field.shape.vtable.default_in_place) {
+            unsafe { default_fn(data.field_uninit(field.offset)) };
+            iset.set(index);
         }
-        let mask = (1 << count) - 1;
-        self.0 & mask == mask
     }
 }
 
@@ -237,12 +333,16 @@ pub enum Poke<'mem> {
     List(PokeList<'mem>),
     /// A map (HashMap/BTreeMap/etc). See [`PokeMap`].
     Map(PokeMap<'mem>),
+    /// A set (HashSet/BTreeSet/etc). See [`PokeSet`].
+    Set(PokeSet<'mem>),
     /// A struct, tuple struct, or tuple. See [`PokeStruct`].
     Struct(PokeStruct<'mem>),
     /// An enum variant. See [`PokeEnum`].
     Enum(PokeEnum<'mem>),
     /// An option value. See [`PokeOption`].
     Option(PokeOption<'mem>),
+    /// A smart pointer (Box/Rc/Arc/etc). See [`PokeSmartPointer`].
+    SmartPointer(PokeSmartPointer<'mem>),
 }
 
 impl<'mem> Poke<'mem> {
@@ -266,25 +366,33 @@ impl<'mem> Poke<'mem> {
                 let pm = unsafe { PokeMap::new(data, shape, map_def) };
                 Poke::Map(pm)
             }
+            Def::Set(set_def) => {
+                let ps = unsafe { PokeSet::new(data, shape, set_def) };
+                Poke::Set(ps)
+            }
             Def::List(list_def) => {
                 let pl = unsafe { PokeList::new(data, shape, list_def) };
                 Poke::List(pl)
             }
             Def::Scalar { .. } => Poke::Scalar(unsafe { PokeValue::new(data, shape) }),
-            Def::Enum(_enum_def) => {
-                todo!("we need to get the active variant somehow")
+            Def::Enum(enum_def) => {
+                Poke::Enum(unsafe { PokeEnum::from_initialized(data, shape, enum_def) })
             }
             Def::Option(option_def) => {
                 let po = unsafe { PokeOption::new(data, shape, option_def) };
                 Poke::Option(po)
             }
+            Def::SmartPointer(smart_pointer_def) => {
+                let psp = unsafe { PokeSmartPointer::new(data, shape, smart_pointer_def) };
+                Poke::SmartPointer(psp)
+            }
             _ => todo!("unsupported def: {:?}", shape.def),
         }
     }
 
     /// Borrows the value for a different kind of inspection.
     #[inline(always)]
-    pub fn borrow<T: Facet>(data: &'mem mut T) -> Poke<'mem> {
+    pub fn borrow<'facet, T: Facet<'facet>>(data: &'mem mut T) -> Poke<'mem> {
         let shape = T::SHAPE;
         let data = Opaque::new(data);
         unsafe { Poke::unchecked_new(data, shape) }
@@ -314,6 +422,14 @@ impl<'mem> Poke<'mem> {
         }
     }
 
+    /// Converts this Poke into a PokeSet, panicking if it's not a Set variant
+    pub fn into_set(self) -> PokeSet<'mem> {
+        match self {
+            Poke::Set(s) => s,
+            _ => panic!("expected Set variant"),
+        }
+    }
+
     /// Converts this Poke into a PokeStruct, panicking if it's not a Struct variant
     pub fn into_struct(self) -> PokeStruct<'mem> {
         match self {
@@ -338,6 +454,14 @@ impl<'mem> Poke<'mem> {
         }
     }
 
+    /// Converts this Poke into a PokeSmartPointer, panicking if it's not a SmartPointer variant
+    pub fn into_smart_pointer(self) -> PokeSmartPointer<'mem> {
+        match self {
+            Poke::SmartPointer(sp) => sp,
+            _ => panic!("expected SmartPointer variant"),
+        }
+    }
+
     /// Get the shape of this Poke.
     #[inline(always)]
     pub fn shape(&self) -> &'static Shape {
@@ -345,9 +469,11 @@ impl<'mem> Poke<'mem> {
             Poke::Scalar(poke_value) => poke_value.shape(),
             Poke::List(poke_list) => poke_list.shape(),
             Poke::Map(poke_map) => poke_map.shape(),
+            Poke::Set(poke_set) => poke_set.shape(),
             Poke::Struct(poke_struct) => poke_struct.shape(),
             Poke::Enum(poke_enum) => poke_enum.shape(),
             Poke::Option(poke_option) => poke_option.shape(),
+            Poke::SmartPointer(poke_smart_pointer) => poke_smart_pointer.shape(),
         }
     }
 }
@@ -1,6 +1,6 @@
 use facet_core::{Facet, Opaque, OpaqueConst, OpaqueUninit, OptionDef, OptionVTable, Shape};
 
-use crate::Guard;
+use crate::{Guard, PokeUninit};
 
 /// Allows initializing an uninitialized option
 pub struct PokeOptionUninit<'mem> {
@@ -44,6 +44,12 @@ impl<'mem> PokeOptionUninit<'mem> {
         unsafe { crate::PokeValueUninit::new(self.data, self.shape) }
     }
 
+    /// Allocates memory for the `Some` value, so it can be initialized separately
+    /// and then moved into the option with [`Self::write`].
+    pub fn alloc_inner(&self) -> (PokeUninit<'static>, Guard) {
+        PokeUninit::alloc_shape(self.def.t)
+    }
+
     /// Initialize the option as None
     ///
     /// # Safety
@@ -141,6 +147,11 @@ impl<'mem> PokeOption<'mem> {
         }
     }
 
+    /// Takes ownership of this `PokeOption` and returns the underlying data.
+    pub fn build_in_place(self) -> Opaque<'mem> {
+        self.data
+    }
+
     /// Builds an `Option<T>` from the PokeOption, then deallocates the memory
     /// that this PokeOption was pointing to.
     ///
@@ -148,7 +159,7 @@ impl<'mem> PokeOption<'mem> {
     ///
     /// This function will panic if:
     /// - The generic type parameter T does not match the shape that this PokeOption is building.
-    pub fn build<T: Facet>(self, guard: Option<Guard>) -> Option<T> {
+    pub fn build<'facet, T: Facet<'facet>>(self, guard: Option<Guard>) -> Option<T> {
         let mut guard = guard;
         let this = self;
         // this changes drop order: guard must be dropped _after_ this.
@@ -0,0 +1,122 @@
+use crate::PokeValueUninit;
+use facet_core::{Opaque, OpaqueConst, OpaqueUninit, SetDef, SetVTable, Shape};
+
+/// Allows initializing an uninitialized set
+pub struct PokeSetUninit<'mem> {
+    data: OpaqueUninit<'mem>,
+    shape: &'static Shape,
+    def: SetDef,
+}
+
+impl<'mem> PokeSetUninit<'mem> {
+    #[inline(always)]
+    /// Coerce back into a `PokeValue`
+    pub fn into_value(self) -> PokeValueUninit<'mem> {
+        unsafe { PokeValueUninit::new(self.data, self.shape) }
+    }
+
+    #[inline(always)]
+    /// Shape getter
+    pub fn shape(&self) -> &'static Shape {
+        self.shape
+    }
+
+    /// Creates a new uninitialized set write-proxy
+    ///
+    /// # Safety
+    ///
+    /// The data buffer must match the size and alignment of the shape.
+    pub(crate) unsafe fn new(data: OpaqueUninit<'mem>, shape: &'static Shape, def: SetDef) -> Self {
+        Self { data, shape, def }
+    }
+
+    /// Initializes the set with an optional size hint
+    pub fn init(self, size_hint: Option<usize>) -> Result<PokeSet<'mem>, OpaqueUninit<'mem>> {
+        let res = if let Some(capacity) = size_hint {
+            let init_in_place_with_capacity = self.def.vtable.init_in_place_with_capacity_fn;
+            unsafe { init_in_place_with_capacity(self.data, capacity) }
+        } else {
+            let pv = unsafe { PokeValueUninit::new(self.data, self.shape) };
+            pv.default_in_place().map_err(|_| ())
+        };
+        let data = res.map_err(|_| self.data)?;
+        Ok(unsafe { PokeSet::new(data, self.shape, self.def) })
+    }
+
+    /// Returns a reference to the `SetDef` of this `PokeSetUninit`.
+    #[inline]
+    pub fn def(&self) -> &SetDef {
+        &self.def
+    }
+}
+
+/// Allows poking a set (inserting, etc.)
+pub struct PokeSet<'mem> {
+    data: Opaque<'mem>,
+    shape: &'static Shape,
+    def: SetDef,
+}
+
+impl<'mem> PokeSet<'mem> {
+    /// Creates a value-proxy for a set
+    ///
+    /// # Safety
+    ///
+    /// The data buffer must match the size and alignment of the shape.
+    #[inline]
+    pub(crate) unsafe fn new(data: Opaque<'mem>, shape: &'static Shape, def: SetDef) -> Self {
+        Self { data, shape, def }
+    }
+
+    /// Get the shape of the set
+    #[inline(always)]
+    pub fn shape(&self) -> &'static Shape {
+        self.shape
+    }
+
+    /// Gets the vtable for the set
+    #[inline(always)]
+    pub fn set_vtable(&self) -> &'static SetVTable {
+        self.def.vtable
+    }
+
+    /// Inserts a value into the set, returning `false` if an equal value was already present
+    ///
+    /// # Safety
+    ///
+    /// `value` is moved out of (with [`core::ptr::read`]) — it should be deallocated
+    /// afterwards but NOT dropped.
+    #[inline]
+    pub unsafe fn insert(&mut self, value: Opaque<'_>) -> bool {
+        unsafe { (self.set_vtable().insert_fn)(self.data, value) }
+    }
+
+    /// Gets the number of values in the set
+    #[inline]
+    pub fn len(&self) -> usize {
+        unsafe { (self.set_vtable().len_fn)(self.data.as_const()) }
+    }
+
+    /// Checks if the set contains no values
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Checks if the set contains a value
+    #[inline]
+    pub fn contains(&self, value: OpaqueConst<'_>) -> bool {
+        unsafe { (self.set_vtable().contains_fn)(self.data.as_const(), value) }
+    }
+
+    /// Takes ownership of this `PokeSet` and returns the underlying data.
+    pub fn build_in_place(self) -> Opaque<'mem> {
+        self.data
+    }
+
+    /// Returns a reference to the `SetDef` of this `PokeSet`.
+    #[inline]
+    pub fn def(&self) -> &SetDef {
+        &self.def
+    }
+}
@@ -0,0 +1,173 @@
+use facet_core::{
+    Facet, Opaque, OpaqueConst, OpaqueUninit, Shape, SmartPointerDef, SmartPointerVTable,
+};
+
+use crate::{Guard, Peek, PokeUninit};
+
+/// Allows initializing an uninitialized smart pointer
+pub struct PokeSmartPointerUninit<'mem> {
+    data: OpaqueUninit<'mem>,
+    shape: &'static Shape,
+    def: SmartPointerDef,
+}
+
+impl<'mem> PokeSmartPointerUninit<'mem> {
+    /// Creates a new uninitialized smart pointer poke
+    ///
+    /// # Safety
+    ///
+    /// `data` must be properly aligned and sized for this shape.
+    pub(crate) unsafe fn new(
+        data: OpaqueUninit<'mem>,
+        shape: &'static Shape,
+        def: SmartPointerDef,
+    ) -> Self {
+        Self { data, shape, def }
+    }
+
+    /// Returns the shape of this smart pointer
+    pub fn shape(&self) -> &'static Shape {
+        self.shape
+    }
+
+    /// Returns the smart pointer definition
+    pub fn def(&self) -> SmartPointerDef {
+        self.def
+    }
+
+    /// Returns the smart pointer vtable
+    pub fn vtable(&self) -> &'static SmartPointerVTable {
+        self.def.vtable
+    }
+
+    /// Get a reference to the underlying PokeValue
+    #[inline(always)]
+    pub fn into_value(self) -> crate::PokeValueUninit<'mem> {
+        unsafe { crate::PokeValueUninit::new(self.data, self.shape) }
+    }
+
+    /// Allocates memory for the pointee, so it can be initialized separately
+    /// and then moved into the smart pointer with [`Self::write`].
+    pub fn alloc_inner(&self) -> (PokeUninit<'static>, Guard) {
+        PokeUninit::alloc_shape(self.def.t)
+    }
+
+    /// Initialize the smart pointer around a pointee, taking ownership of it
+    ///
+    /// # Panics
+    ///
+    /// Panics if the smart pointer can't own its pointee (e.g. `Weak<T>`).
+    ///
+    /// # Safety
+    ///
+    /// `value` must point to an initialized value of the pointee's shape.
+    ///
+    /// Caller must free the memory pointed to by `value` after the smart pointer is
+    /// initialized, but must not drop it in place — it's been moved into the pointer.
+    pub unsafe fn write(self, value: OpaqueConst<'_>) -> PokeSmartPointer<'mem> {
+        let new_into_fn = self.vtable().new_into_fn.unwrap_or_else(|| {
+            panic!(
+                "Smart pointer {} cannot be constructed from its pointee",
+                self.shape
+            )
+        });
+        unsafe {
+            let inited = new_into_fn(self.data, value);
+            PokeSmartPointer::new(inited, self.shape, self.def)
+        }
+    }
+
+    /// Initialize the smart pointer by providing a pointee of type `T`
+    ///
+    /// # Safety
+    ///
+    /// Caller must ensure that `T` matches the pointee type of the smart pointer.
+    pub unsafe fn put<T>(self, value: T) -> PokeSmartPointer<'mem> {
+        let value_opaque = OpaqueConst::new(&raw const value);
+        let result = unsafe { self.write(value_opaque) };
+        core::mem::forget(value);
+        result
+    }
+}
+
+/// Allows inspecting an initialized smart pointer
+pub struct PokeSmartPointer<'mem> {
+    data: Opaque<'mem>,
+    shape: &'static Shape,
+    def: SmartPointerDef,
+}
+
+impl<'mem> PokeSmartPointer<'mem> {
+    /// Creates a new smart pointer poke
+    ///
+    /// # Safety
+    ///
+    /// `data` must be properly aligned and sized for this shape.
+    pub(crate) unsafe fn new(
+        data: Opaque<'mem>,
+        shape: &'static Shape,
+        def: SmartPointerDef,
+    ) -> Self {
+        Self { data, shape, def }
+    }
+
+    /// Returns the shape of this smart pointer
+    pub fn shape(&self) -> &'static Shape {
+        self.shape
+    }
+
+    /// Returns the smart pointer definition
+    pub fn def(&self) -> SmartPointerDef {
+        self.def
+    }
+
+    /// Returns the smart pointer vtable
+    pub fn vtable(&self) -> &'static SmartPointerVTable {
+        self.def.vtable
+    }
+
+    /// Returns the pointee as a Peek, or None if the pointer can't be borrowed
+    /// through (weak pointers). Pointees of shared pointers are never handed out mutably.
+    pub fn inner(&self) -> Option<Peek<'_>> {
+        let borrow_fn = self.vtable().borrow_fn?;
+        unsafe {
+            let inner_data = borrow_fn(self.data.as_const());
+            Some(Peek::unchecked_new(inner_data, self.def.t))
+        }
+    }
+
+    /// Get a reference to the underlying value
+    #[inline(always)]
+    pub fn into_value(self) -> crate::PokeValueUninit<'mem> {
+        unsafe {
+            crate::PokeValueUninit::new(OpaqueUninit::new(self.data.as_mut_byte_ptr()), self.shape)
+        }
+    }
+
+    /// Takes ownership of this `PokeSmartPointer` and returns the underlying data.
+    pub fn build_in_place(self) -> Opaque<'mem> {
+        self.data
+    }
+
+    /// Builds a smart pointer of type `T` from the PokeSmartPointer, then deallocates
+    /// the memory that this PokeSmartPointer was pointing to.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if:
+    /// - The generic type parameter T does not match the shape that this PokeSmartPointer is building.
+    pub fn build<'facet, T: Facet<'facet>>(self, guard: Option<Guard>) -> T {
+        let mut guard = guard;
+        let this = self;
+        // this changes drop order: guard must be dropped _after_ this.
+
+        this.shape.assert_type::<T>();
+        if let Some(guard) = &guard {
+            guard.shape.assert_type::<T>();
+        }
+
+        let result = unsafe { this.data.read::<T>() };
+        guard.take(); // dealloc
+        result
+    }
+}
@@ -1,12 +1,15 @@
 use core::ptr::NonNull;
-use facet_core::{Facet, FieldError, Opaque, OpaqueConst, OpaqueUninit, Shape, StructDef};
+use facet_core::{
+    Def, Facet, Field, FieldError, FieldFlags, Opaque, OpaqueConst, OpaqueUninit, Shape, StructDef,
+};
 
 #[cfg(feature = "alloc")]
 extern crate alloc;
 #[cfg(feature = "alloc")]
-use alloc::boxed::Box;
+use alloc::{boxed::Box, string::ToString, vec::Vec};
 
 use super::{Guard, ISet, PokeValueUninit};
+use crate::ReflectError;
 
 /// Allows poking a struct (setting fields, etc.)
 pub struct PokeStruct<'mem> {
@@ -42,6 +45,15 @@ impl<'mem> PokeStruct<'mem> {
         }
     }
 
+    /// Sets every field that wasn't initialized but has a default (cf. [`FieldFlags::DEFAULT`])
+    ///
+    /// This is done automatically when building the struct.
+    ///
+    /// [`FieldFlags::DEFAULT`]: facet_core::FieldFlags::DEFAULT
+    pub fn fill_defaults(&mut self) {
+        unsafe { super::fill_defaults(self.def.fields, self.data, &mut self.iset) }
+    }
+
     /// Checks if all fields in the struct have been initialized.
     /// Panics if any field is not initialized, providing details about the uninitialized field.
     pub fn assert_all_fields_initialized(&self) {
@@ -55,16 +67,98 @@ impl<'mem> PokeStruct<'mem> {
         }
     }
 
-    /// Asserts that every field has been initialized and forgets the PokeStruct.
+    /// Checks if all fields in the struct have been initialized.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ReflectError::MissingFields`] listing every field that isn't initialized.
+    pub fn check_all_fields_initialized(&self) -> Result<(), ReflectError> {
+        let paths: Vec<_> = self
+            .def
+            .fields
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| !self.iset.has(*i))
+            .map(|(_, field)| field.serialized_name().to_string())
+            .collect();
+        if paths.is_empty() {
+            Ok(())
+        } else {
+            Err(ReflectError::MissingFields {
+                shape: self.shape,
+                paths,
+            })
+        }
+    }
+
+    /// Fills in defaults, checks that every field has been initialized and forgets the PokeStruct.
+    ///
+    /// This is the fallible counterpart of [`Self::build_in_place`]. On error, the fields that
+    /// were initialized are dropped.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ReflectError::MissingFields`] if any field without a default is not initialized.
+    pub fn try_build_in_place(mut self) -> Result<Opaque<'mem>, ReflectError> {
+        self.fill_defaults();
+        self.check_all_fields_initialized()?;
+
+        let data = unsafe { self.data.assume_init() };
+
+        // prevent field drops when the PokeStruct is dropped
+        core::mem::forget(self);
+
+        Ok(data)
+    }
+
+    /// Builds a value of type `T` from the PokeStruct, then deallocates the memory
+    /// that this PokeStruct was pointing to.
+    ///
+    /// This is the fallible counterpart of [`Self::build`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ReflectError::MissingFields`] if any field without a default is not initialized.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if the generic type parameter T does not match the shape that
+    /// this PokeStruct is building.
+    pub fn try_build<'facet, T: Facet<'facet>>(
+        self,
+        guard: Option<Guard>,
+    ) -> Result<T, ReflectError> {
+        let mut guard = guard;
+        let mut this = self;
+        // this changes drop order: guard must be dropped _after_ this.
+
+        this.fill_defaults();
+        this.check_all_fields_initialized()?;
+        this.shape.assert_type::<T>();
+        if let Some(guard) = &guard {
+            guard.shape.assert_type::<T>();
+        }
+
+        let result = unsafe {
+            let ptr = this.data.as_mut_bytes() as *const T;
+            core::ptr::read(ptr)
+        };
+        guard.take(); // dealloc
+        core::mem::forget(this);
+        Ok(result)
+    }
+
+    /// Fills in defaults, asserts that every field has been initialized and forgets the PokeStruct.
     ///
     /// This method is only used when the origin is borrowed.
     /// If this method is not called, all fields will be freed when the PokeStruct is dropped.
     ///
     /// # Panics
     ///
-    /// This function will panic if any field is not initialized.
-    pub fn build_in_place(self) -> Opaque<'mem> {
+    /// This function will panic if any field without a default is not initialized.
+    pub fn build_in_place(mut self) -> Opaque<'mem> {
         // ensure all fields are initialized
+        self.fill_defaults();
         self.assert_all_fields_initialized();
 
         let data = unsafe { self.data.assume_init() };
@@ -81,13 +175,14 @@ impl<'mem> PokeStruct<'mem> {
     /// # Panics
     ///
     /// This function will panic if:
-    /// - Not all the fields have been initialized.
+    /// - Not all the fields without a default have been initialized.
     /// - The generic type parameter T does not match the shape that this PokeStruct is building.
-    pub fn build<T: Facet>(self, guard: Option<Guard>) -> T {
+    pub fn build<'facet, T: Facet<'facet>>(self, guard: Option<Guard>) -> T {
         let mut guard = guard;
-        let this = self;
+        let mut this = self;
         // this changes drop order: guard must be dropped _after_ this.
 
+        this.fill_defaults();
         this.assert_all_fields_initialized();
         this.shape.assert_type::<T>();
         if let Some(guard) = &guard {
@@ -108,10 +203,11 @@ impl<'mem> PokeStruct<'mem> {
     /// # Panics
     ///
     /// This function will panic if:
-    /// - Not all the fields have been initialized.
+    /// - Not all the fields without a default have been initialized.
     /// - The generic type parameter T does not match the shape that this PokeStruct is building.
     #[cfg(feature = "alloc")]
-    pub fn build_boxed<T: Facet>(self) -> Box<T> {
+    pub fn build_boxed<'facet, T: Facet<'facet>>(mut self) -> Box<T> {
+        self.fill_defaults();
         self.assert_all_fields_initialized();
         self.shape.assert_type::<T>();
 
@@ -128,7 +224,8 @@ impl<'mem> PokeStruct<'mem> {
     /// and must be large enough to hold the value.
     /// The caller is responsible for ensuring that the target memory is properly deallocated
     /// when it's no longer needed.
-    pub unsafe fn move_into(self, target: NonNull<u8>, guard: Option<Guard>) {
+    pub unsafe fn move_into(mut self, target: NonNull<u8>, guard: Option<Guard>) {
+        self.fill_defaults();
         self.assert_all_fields_initialized();
         if let Some(guard) = &guard {
             guard.shape.assert_shape(self.shape);
@@ -158,6 +255,24 @@ impl<'mem> PokeStruct<'mem> {
         Ok((index, self.field(index)?))
     }
 
+    /// Gets a field by the name it has in serialized formats (see [`Field::serialized_name`])
+    ///
+    /// Fields with [`FieldFlags::SKIP_DESERIALIZING`] are never returned.
+    ///
+    /// [`Field::serialized_name`]: facet_core::Field::serialized_name
+    pub fn field_by_serialized_name(
+        &self,
+        name: &str,
+    ) -> Result<(usize, crate::PokeUninit<'mem>), FieldError> {
+        let index = self
+            .def
+            .fields
+            .iter()
+            .position(|f| f.serialized_name() == name && is_settable_by_name(f))
+            .ok_or(FieldError::NoSuchStaticField)?;
+        Ok((index, self.field(index)?))
+    }
+
     /// Get a field writer for a field by index.
     ///
     /// # Errors
@@ -250,7 +365,11 @@ impl<'mem> PokeStruct<'mem> {
     /// Returns an error if:
     /// - The index is out of bounds
     /// - The field shapes don't match
-    pub fn set<T: Facet>(&mut self, index: usize, value: T) -> Result<(), FieldError> {
+    pub fn set<'facet, T: Facet<'facet>>(
+        &mut self,
+        index: usize,
+        value: T,
+    ) -> Result<(), FieldError> {
         let field_shape = self
             .def
             .fields
@@ -280,7 +399,11 @@ impl<'mem> PokeStruct<'mem> {
     /// Returns an error if:
     /// - The field name doesn't exist
     /// - The field shapes don't match
-    pub fn set_by_name<T: Facet>(&mut self, name: &str, value: T) -> Result<(), FieldError> {
+    pub fn set_by_name<'facet, T: Facet<'facet>>(
+        &mut self,
+        name: &str,
+        value: T,
+    ) -> Result<(), FieldError> {
         let index = self
             .def
             .fields
@@ -325,3 +448,130 @@ impl Drop for PokeStruct<'_> {
             });
     }
 }
+
+/// Whether `field` can be found by [`PokeStruct::field_by_serialized_name`]
+fn is_settable_by_name(field: &Field) -> bool {
+    !field.flags.contains(FieldFlags::SKIP_DESERIALIZING) && !is_flattened_struct(field)
+}
+
+/// Whether the fields of `field` are merged into its parent's, cf. [`FieldFlags::FLATTEN`].
+/// Only struct-typed fields can be flattened, others are treated as regular fields.
+fn is_flattened_struct(field: &Field) -> bool {
+    field.flags.contains(FieldFlags::FLATTEN) && matches!(field.shape.def, Def::Struct(_))
+}
+
+/// A [`PokeStruct`] along with the structs of its `#[facet(flatten)]` fields (and of theirs,
+/// recursively), so that keys found on the wire can be routed to whichever struct declares them.
+pub struct PokeStructFlattened<'mem> {
+    root: PokeStruct<'mem>,
+    /// Each flattened struct, after the struct it is flattened into: `(parent, field_index, ps)`,
+    /// where `parent` is 0 for the root and `i + 1` for `flattened[i]`
+    flattened: Vec<(usize, usize, PokeStruct<'mem>)>,
+}
+
+/// Where a field found through [`PokeStructFlattened::field_by_serialized_name`] lives
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FlattenedFieldIndex {
+    struct_index: usize,
+    field_index: usize,
+}
+
+impl<'mem> PokeStructFlattened<'mem> {
+    /// Gathers the flattened structs of `root`
+    pub fn new(root: PokeStruct<'mem>) -> Self {
+        let mut this = Self {
+            root,
+            flattened: Vec::new(),
+        };
+        let mut struct_index = 0;
+        while struct_index <= this.flattened.len() {
+            let ps = this.struct_at(struct_index);
+            let children: Vec<_> = ps
+                .def
+                .fields
+                .iter()
+                .enumerate()
+                .filter(|(_, field)| is_flattened_struct(field))
+                .map(|(field_index, _)| {
+                    let child = ps.field(field_index).expect("index is in bounds");
+                    (struct_index, field_index, child.into_struct())
+                })
+                .collect();
+            this.flattened.extend(children);
+            struct_index += 1;
+        }
+        this
+    }
+
+    /// Shape getter
+    pub fn shape(&self) -> &'static Shape {
+        self.root.shape
+    }
+
+    /// Gets the root struct's definition
+    pub fn def(&self) -> StructDef {
+        self.root.def
+    }
+
+    /// Iterates over the serialized names of every field [`Self::field_by_serialized_name`]
+    /// can find, e.g. to suggest one when a key is unknown
+    pub fn serialized_field_names(&self) -> impl Iterator<Item = &'static str> + '_ {
+        core::iter::once(&self.root)
+            .chain(self.flattened.iter().map(|f| &f.2))
+            .flat_map(|ps| ps.def.fields.iter())
+            .filter(|field| is_settable_by_name(field))
+            .map(|field| field.serialized_name())
+    }
+
+    fn struct_at(&mut self, struct_index: usize) -> &mut PokeStruct<'mem> {
+        match struct_index {
+            0 => &mut self.root,
+            i => &mut self.flattened[i - 1].2,
+        }
+    }
+
+    /// Looks a field up by its serialized name, first in the root struct, then in the
+    /// flattened structs
+    pub fn field_by_serialized_name(
+        &self,
+        name: &str,
+    ) -> Result<(FlattenedFieldIndex, crate::PokeUninit<'mem>), FieldError> {
+        let structs = core::iter::once(&self.root).chain(self.flattened.iter().map(|f| &f.2));
+        for (struct_index, ps) in structs.enumerate() {
+            if let Ok((field_index, poke)) = ps.field_by_serialized_name(name) {
+                let index = FlattenedFieldIndex {
+                    struct_index,
+                    field_index,
+                };
+                return Ok((index, poke));
+            }
+        }
+        Err(FieldError::NoSuchStaticField)
+    }
+
+    /// Marks a field as initialized.
+    ///
+    /// # Safety
+    ///
+    /// The caller must ensure that the field is initialized. Only call this after writing to
+    /// an address gotten through [`Self::field_by_serialized_name`].
+    pub unsafe fn mark_initialized(&mut self, index: FlattenedFieldIndex) {
+        unsafe {
+            self.struct_at(index.struct_index)
+                .mark_initialized(index.field_index)
+        }
+    }
+
+    /// Builds the flattened structs, innermost first, then the root struct.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ReflectError::MissingFields`] for the first struct that is missing fields.
+    pub fn try_build_in_place(mut self) -> Result<Opaque<'mem>, ReflectError> {
+        while let Some((parent, field_index, ps)) = self.flattened.pop() {
+            ps.try_build_in_place()?;
+            unsafe { self.struct_at(parent).mark_initialized(field_index) };
+        }
+        self.root.try_build_in_place()
+    }
+}
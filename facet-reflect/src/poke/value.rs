@@ -2,12 +2,12 @@ use crate::peek::Peek;
 use facet_core::{Facet, Opaque, OpaqueConst, OpaqueUninit, Shape, TryFromError, ValueVTable};
 
 /// A strongly-typed value writer that ensures type safety at compile-time
-pub struct TypedPokeValueUninit<'mem, T: Facet> {
+pub struct TypedPokeValueUninit<'mem, T> {
     poke_value: PokeValueUninit<'mem>,
     _phantom: core::marker::PhantomData<T>,
 }
 
-impl<'mem, T: Facet> TypedPokeValueUninit<'mem, T> {
+impl<'mem, 'facet, T: Facet<'facet>> TypedPokeValueUninit<'mem, T> {
     /// Create a new TypedPokeValue from a PokeValue
     fn new(poke_value: PokeValueUninit<'mem>) -> Self {
         Self {
@@ -48,7 +48,7 @@ impl<'mem> PokeValueUninit<'mem> {
     /// Converts to a type-checked [`TypedPokeValue<T>`] if the shape matches type `T`
     ///
     /// Returns `None` if the shape doesn't match the type `T`.
-    pub fn typed<T: Facet>(self) -> Result<TypedPokeValueUninit<'mem, T>, Self> {
+    pub fn typed<'facet, T: Facet<'facet>>(self) -> Result<TypedPokeValueUninit<'mem, T>, Self> {
         if self.shape.is_type::<T>() {
             Ok(TypedPokeValueUninit::new(self))
         } else {
@@ -124,7 +124,7 @@ impl<'mem> PokeValueUninit<'mem> {
     /// checking that T exactly matches the expected shape.
     pub fn put<'src, T>(self, value: T) -> Opaque<'mem>
     where
-        T: Facet + 'src,
+        T: Facet<'src>,
     {
         self.shape.assert_type::<T>();
         unsafe { self.data.put(value) }
@@ -157,12 +157,12 @@ impl<'mem> PokeValueUninit<'mem> {
 }
 
 /// A strongly-typed value writer for initialized values that ensures type safety at compile-time
-pub struct TypedPokeValue<'mem, T: Facet> {
+pub struct TypedPokeValue<'mem, T> {
     poke_value: PokeValue<'mem>,
     _phantom: core::marker::PhantomData<T>,
 }
 
-impl<'mem, T: Facet> TypedPokeValue<'mem, T> {
+impl<'mem, 'facet, T: Facet<'facet>> TypedPokeValue<'mem, T> {
     /// Create a new TypedPokeValue from a PokeValue
     fn new(poke_value: PokeValue<'mem>) -> Self {
         Self {
@@ -203,7 +203,7 @@ impl<'mem> PokeValue<'mem> {
     /// Converts to a type-checked [`TypedPokeValue<T>`] if the shape matches type `T`
     ///
     /// Returns `None` if the shape doesn't match the type `T`.
-    pub fn typed<T: Facet>(self) -> Result<TypedPokeValue<'mem, T>, Self> {
+    pub fn typed<'facet, T: Facet<'facet>>(self) -> Result<TypedPokeValue<'mem, T>, Self> {
         if self.shape.is_type::<T>() {
             Ok(TypedPokeValue::new(self))
         } else {
@@ -252,7 +252,7 @@ impl<'mem> PokeValue<'mem> {
     /// checking that T exactly matches the expected shape.
     pub fn replace<'src, T>(self, value: T) -> Opaque<'mem>
     where
-        T: Facet + 'src,
+        T: Facet<'src>,
     {
         self.shape.assert_type::<T>();
         unsafe { self.data.replace(value) }
@@ -0,0 +1,442 @@
+//! A format-agnostic way to serialize values, see [`Serializer`] and [`serialize`]
+
+extern crate alloc;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use facet_core::{EnumTagging, Field, FieldFlags, Shape, StructKind, VariantKind};
+
+use crate::{Peek, PeekEnum, PeekValue};
+
+/// Receives the structure of a value, as it's walked by [`serialize`], and writes it out in
+/// some format.
+///
+/// The driver takes care of everything that doesn't depend on the format: skipped and
+/// flattened fields, renames, enum tagging, sensitive fields, and looking through options and
+/// smart pointers. A serializer only sees scalars, strings, nothing, and three kinds of
+/// containers: structs (string keys known in advance), lists and maps.
+///
+/// Containers are announced with their length, so that formats that write lengths up front
+/// don't have to buffer anything. Their contents are the events between `start_*` and the
+/// matching `end_*`; every item is preceded by a call to [`Self::field`], [`Self::list_item`]
+/// or [`Self::map_key`], which is where separators go.
+///
+/// Serializers meant for humans can ask for more: every value is announced with
+/// [`Self::start_value`], so its type and doc comments can be shown, fields come with their
+/// declaration through [`Self::declared_field`], and enums can be written as they're declared
+/// rather than tagged, see [`Self::variants_as_declared`].
+pub trait Serializer {
+    /// The error this serializer fails with, which must be able to describe values that
+    /// can't be serialized whatever the format.
+    type Error: From<SerializeError>;
+
+    /// Writes a scalar: anything whose shape has a [`facet_core::Def::Scalar`] def, `()` included
+    fn scalar(&mut self, value: PeekValue<'_>) -> Result<(), Self::Error>;
+
+    /// Writes a string that's not stored in the value: enum variant names and tags
+    fn str(&mut self, s: &str) -> Result<(), Self::Error>;
+
+    /// Writes the absence of a value: `None`, the payload of unit variants, unit structs
+    /// (see [`Self::tuples_as_lists`]) and dangling weak pointers
+    fn none(&mut self) -> Result<(), Self::Error>;
+
+    /// Writes a list of `u8`. By default, it's written like any other list.
+    fn bytes(&mut self, bytes: &[u8]) -> Result<(), Self::Error> {
+        self.start_list(bytes.len())?;
+        for byte in bytes {
+            self.list_item()?;
+            self.scalar(Peek::new(byte).as_value())?;
+        }
+        self.end_list()
+    }
+
+    /// Starts a struct with `len` fields, each of them a [`Self::field`] followed by its value
+    fn start_struct(&mut self, len: usize) -> Result<(), Self::Error>;
+
+    /// Writes the name of the next struct field, whose value follows
+    fn field(&mut self, name: &str) -> Result<(), Self::Error>;
+
+    /// Ends the current struct
+    fn end_struct(&mut self) -> Result<(), Self::Error>;
+
+    /// Starts a list of `len` items, each of them a [`Self::list_item`] followed by its value
+    fn start_list(&mut self, len: usize) -> Result<(), Self::Error>;
+
+    /// Called before each list item
+    fn list_item(&mut self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+
+    /// Ends the current list
+    fn end_list(&mut self) -> Result<(), Self::Error>;
+
+    /// Starts a map of `len` entries, each of them a [`Self::map_key`] followed by a
+    /// [`Self::map_value`] and the value
+    fn start_map(&mut self, len: usize) -> Result<(), Self::Error>;
+
+    /// Writes the key of the next map entry. By default, the key is serialized like any
+    /// other value; formats whose keys are restricted (e.g. to strings) override this.
+    fn map_key(&mut self, key: Peek<'_>) -> Result<(), Self::Error>
+    where
+        Self: Sized,
+    {
+        serialize(key, self)
+    }
+
+    /// Called between the key and the value of a map entry
+    fn map_value(&mut self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+
+    /// Ends the current map
+    fn end_map(&mut self) -> Result<(), Self::Error>;
+
+    /// Whether tuples and tuple structs are written as lists, and unit structs as
+    /// [`Self::none`]. Otherwise they're written as structs, with fields named `0`, `1`, etc.
+    fn tuples_as_lists(&self) -> bool {
+        true
+    }
+
+    /// Whether the values of fields marked `#[facet(sensitive)]` are replaced by a call to
+    /// [`Self::redacted`]. Serializers meant for humans (logs, debug output) should say yes.
+    fn redact_sensitive(&self) -> bool {
+        false
+    }
+
+    /// Writes a placeholder for the value of a sensitive field, when
+    /// [`Self::redact_sensitive`] is true
+    fn redacted(&mut self, field: &'static Field) -> Result<(), Self::Error> {
+        let _ = field;
+        self.str("[REDACTED]")
+    }
+
+    /// Called before each value, options and smart pointers included, with the value itself.
+    /// Returning `false` skips it: the serializer has written whatever stands for it, and
+    /// [`Self::end_value`] isn't called. By default, every value is written.
+    fn start_value(&mut self, value: Peek<'_>) -> Result<bool, Self::Error> {
+        let _ = value;
+        Ok(true)
+    }
+
+    /// Called after each value for which [`Self::start_value`] returned `true`
+    fn end_value(&mut self, value: Peek<'_>) -> Result<(), Self::Error> {
+        let _ = value;
+        Ok(())
+    }
+
+    /// Writes the name of a field declared by a struct or a struct variant, whose value
+    /// follows. By default, it's [`Self::field`] with the field's serialized name.
+    fn declared_field(&mut self, field: &'static Field) -> Result<(), Self::Error> {
+        self.field(field.serialized_name())
+    }
+
+    /// Whether enums are written the way they're declared, whatever their tagging: the
+    /// active variant as a [`Self::variant`], followed by nothing for unit variants, a list
+    /// of its fields for tuple variants and a struct for struct variants
+    fn variants_as_declared(&self) -> bool {
+        false
+    }
+
+    /// Writes the active variant of an enum, when [`Self::variants_as_declared`] is true.
+    /// By default, its serialized name is written as a [`Self::str`].
+    fn variant(&mut self, value: PeekEnum<'_>) -> Result<(), Self::Error> {
+        self.str(value.active_variant().serialized_name())
+    }
+}
+
+/// A value that can't be serialized, whatever the format
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum SerializeError {
+    /// The active variant of an internally tagged enum is a tuple variant: its fields have
+    /// no names to go next to the tag
+    InternallyTaggedTuple {
+        /// The enum
+        shape: &'static Shape,
+        /// The name of the variant
+        variant: &'static str,
+    },
+    /// The value's shape isn't supported, e.g. an enum tagging added after this driver
+    Unsupported {
+        /// The shape of the value
+        shape: &'static Shape,
+    },
+    /// The serializer can't write this value, e.g. a map key that isn't a string
+    Custom(String),
+}
+
+impl core::fmt::Display for SerializeError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            SerializeError::InternallyTaggedTuple { shape, variant } => {
+                write!(f, "variant {variant} of {shape} can't be internally tagged")
+            }
+            SerializeError::Unsupported { shape } => write!(f, "unsupported shape: {shape}"),
+            SerializeError::Custom(message) => write!(f, "{message}"),
+        }
+    }
+}
+
+impl core::error::Error for SerializeError {}
+
+#[cfg(feature = "std")]
+impl From<SerializeError> for std::io::Error {
+    fn from(err: SerializeError) -> Self {
+        std::io::Error::new(std::io::ErrorKind::InvalidData, err)
+    }
+}
+
+/// What's left to do to serialize a value, innermost last
+enum Task<'mem> {
+    Value(Peek<'mem>),
+    EndValue(Peek<'mem>),
+    /// The payload of the active variant, without its tag
+    Payload(PeekEnum<'mem>),
+    Redacted(&'static Field),
+    Str(&'static str),
+    Field(&'static str),
+    DeclaredField(&'static Field),
+    ListItem,
+    MapKey(Peek<'mem>),
+    MapValue,
+    EndStruct,
+    EndList,
+    EndMap,
+}
+
+/// Serializes `peek` with `serializer`.
+///
+/// The value is walked iteratively, so deeply nested values don't overflow the stack.
+pub fn serialize<S: Serializer>(peek: Peek<'_>, serializer: &mut S) -> Result<(), S::Error> {
+    let mut tasks = Vec::new();
+    tasks.push(Task::Value(peek));
+
+    while let Some(task) = tasks.pop() {
+        match task {
+            Task::Value(peek) => {
+                if serializer.start_value(peek)? {
+                    tasks.push(Task::EndValue(peek));
+                    push_value(peek, serializer, &mut tasks)?;
+                }
+            }
+            Task::EndValue(peek) => serializer.end_value(peek)?,
+            Task::Payload(pe) => push_payload(pe, serializer, &mut tasks)?,
+            Task::Redacted(field) => serializer.redacted(field)?,
+            Task::Str(s) => serializer.str(s)?,
+            Task::Field(name) => serializer.field(name)?,
+            Task::DeclaredField(field) => serializer.declared_field(field)?,
+            Task::ListItem => serializer.list_item()?,
+            Task::MapKey(key) => serializer.map_key(key)?,
+            Task::MapValue => serializer.map_value()?,
+            Task::EndStruct => serializer.end_struct()?,
+            Task::EndList => serializer.end_list()?,
+            Task::EndMap => serializer.end_map()?,
+        }
+    }
+    Ok(())
+}
+
+/// Starts serializing `peek`, pushing whatever it contains
+fn push_value<'mem, S: Serializer>(
+    peek: Peek<'mem>,
+    serializer: &mut S,
+    tasks: &mut Vec<Task<'mem>>,
+) -> Result<(), S::Error> {
+    match peek {
+        Peek::Value(pv) => serializer.scalar(pv)?,
+        Peek::Struct(ps) => {
+            let fields: Vec<_> = ps.fields_for_serialize().collect();
+            let as_list = serializer.tuples_as_lists();
+            match ps.def().kind {
+                StructKind::Unit if as_list => serializer.none()?,
+                StructKind::Tuple | StructKind::TupleStruct if as_list => {
+                    push_items(fields, serializer, tasks)?
+                }
+                _ => push_fields(fields, serializer, tasks)?,
+            }
+        }
+        Peek::List(pl) => {
+            if pl.def().t.is_type::<u8>() {
+                let bytes: Vec<u8> = pl
+                    .iter()
+                    .map(|item| *unsafe { item.as_value().data().as_ref::<u8>() })
+                    .collect();
+                return serializer.bytes(&bytes);
+            }
+            serializer.start_list(pl.len())?;
+            tasks.push(Task::EndList);
+            let items: Vec<_> = pl.iter().collect();
+            for item in items.into_iter().rev() {
+                tasks.push(Task::Value(item));
+                tasks.push(Task::ListItem);
+            }
+        }
+        Peek::Set(ps) => {
+            serializer.start_list(ps.len())?;
+            tasks.push(Task::EndList);
+            let items: Vec<_> = ps.iter().collect();
+            for item in items.into_iter().rev() {
+                tasks.push(Task::Value(item));
+                tasks.push(Task::ListItem);
+            }
+        }
+        Peek::Map(pm) => {
+            serializer.start_map(pm.len())?;
+            tasks.push(Task::EndMap);
+            let entries: Vec<_> = pm.iter().collect();
+            for (key, value) in entries.into_iter().rev() {
+                tasks.push(Task::Value(value));
+                tasks.push(Task::MapValue);
+                tasks.push(Task::MapKey(key));
+            }
+        }
+        Peek::Option(po) => match po.value() {
+            Some(inner) => tasks.push(Task::Value(inner)),
+            None => serializer.none()?,
+        },
+        // Smart pointers are transparent; dangling weak pointers are nothing
+        Peek::SmartPointer(psp) => match psp.inner() {
+            Some(inner) => tasks.push(Task::Value(inner)),
+            None => serializer.none()?,
+        },
+        Peek::Enum(pe) => push_enum(pe, serializer, tasks)?,
+    }
+    Ok(())
+}
+
+/// Starts serializing an enum, following its [`EnumTagging`] unless the serializer wants
+/// variants as they're declared
+fn push_enum<'mem, S: Serializer>(
+    pe: PeekEnum<'mem>,
+    serializer: &mut S,
+    tasks: &mut Vec<Task<'mem>>,
+) -> Result<(), S::Error> {
+    let variant = pe.active_variant();
+    let name = variant.serialized_name();
+    let is_unit = matches!(variant.kind, VariantKind::Unit);
+    if serializer.variants_as_declared() {
+        serializer.variant(pe)?;
+        return match variant.kind {
+            VariantKind::Unit => Ok(()),
+            VariantKind::Tuple { .. } => {
+                push_items(pe.fields_for_serialize().collect(), serializer, tasks)
+            }
+            _ => push_fields(pe.fields_for_serialize().collect(), serializer, tasks),
+        };
+    }
+    match pe.def().tagging {
+        EnumTagging::External if is_unit => serializer.str(name)?,
+        EnumTagging::External => {
+            serializer.start_struct(1)?;
+            tasks.push(Task::EndStruct);
+            tasks.push(Task::Payload(pe));
+            tasks.push(Task::Field(name));
+        }
+        EnumTagging::Internal { tag } => {
+            let fields: Vec<_> = match variant.kind {
+                VariantKind::Unit => Vec::new(),
+                VariantKind::Struct { .. } => pe.fields_for_serialize().collect(),
+                _ => {
+                    return Err(SerializeError::InternallyTaggedTuple {
+                        shape: pe.shape(),
+                        variant: variant.name,
+                    }
+                    .into());
+                }
+            };
+            // fields go right after the tag, in the same struct
+            serializer.start_struct(1 + fields.len())?;
+            tasks.push(Task::EndStruct);
+            push_field_tasks(fields, serializer, tasks);
+            tasks.push(Task::Str(name));
+            tasks.push(Task::Field(tag));
+        }
+        EnumTagging::Adjacent { tag, content } => {
+            serializer.start_struct(if is_unit { 1 } else { 2 })?;
+            tasks.push(Task::EndStruct);
+            if !is_unit {
+                tasks.push(Task::Payload(pe));
+                tasks.push(Task::Field(content));
+            }
+            tasks.push(Task::Str(name));
+            tasks.push(Task::Field(tag));
+        }
+        EnumTagging::Untagged => tasks.push(Task::Payload(pe)),
+        _ => return Err(SerializeError::Unsupported { shape: pe.shape() }.into()),
+    }
+    Ok(())
+}
+
+/// Starts serializing the payload of the active variant: nothing for unit variants, the value
+/// itself for newtype variants, a list for other tuple variants and a struct for struct variants
+fn push_payload<'mem, S: Serializer>(
+    pe: PeekEnum<'mem>,
+    serializer: &mut S,
+    tasks: &mut Vec<Task<'mem>>,
+) -> Result<(), S::Error> {
+    match pe.variant_kind_active() {
+        VariantKind::Unit => serializer.none(),
+        VariantKind::Tuple { fields } if fields.len() == 1 => {
+            let (_, _, peek, field) = pe.fields_with_metadata().next().unwrap();
+            tasks.push(field_value_task(peek, field, serializer));
+            Ok(())
+        }
+        VariantKind::Tuple { .. } => {
+            push_items(pe.fields_for_serialize().collect(), serializer, tasks)
+        }
+        _ => push_fields(pe.fields_for_serialize().collect(), serializer, tasks),
+    }
+}
+
+type FieldEntry<'mem> = (usize, &'static str, Peek<'mem>, &'static Field);
+
+/// Starts a struct holding `fields`
+fn push_fields<'mem, S: Serializer>(
+    fields: Vec<FieldEntry<'mem>>,
+    serializer: &mut S,
+    tasks: &mut Vec<Task<'mem>>,
+) -> Result<(), S::Error> {
+    serializer.start_struct(fields.len())?;
+    tasks.push(Task::EndStruct);
+    push_field_tasks(fields, serializer, tasks);
+    Ok(())
+}
+
+/// Pushes the names and values of `fields`, in reverse order so they come out in order
+fn push_field_tasks<'mem, S: Serializer>(
+    fields: Vec<FieldEntry<'mem>>,
+    serializer: &S,
+    tasks: &mut Vec<Task<'mem>>,
+) {
+    for (_, _, peek, field) in fields.into_iter().rev() {
+        tasks.push(field_value_task(peek, field, serializer));
+        tasks.push(Task::DeclaredField(field));
+    }
+}
+
+/// Starts a list holding the values of `fields`
+fn push_items<'mem, S: Serializer>(
+    fields: Vec<FieldEntry<'mem>>,
+    serializer: &mut S,
+    tasks: &mut Vec<Task<'mem>>,
+) -> Result<(), S::Error> {
+    serializer.start_list(fields.len())?;
+    tasks.push(Task::EndList);
+    for (_, _, peek, field) in fields.into_iter().rev() {
+        tasks.push(field_value_task(peek, field, serializer));
+        tasks.push(Task::ListItem);
+    }
+    Ok(())
+}
+
+fn field_value_task<'mem, S: Serializer>(
+    peek: Peek<'mem>,
+    field: &'static Field,
+    serializer: &S,
+) -> Task<'mem> {
+    if field.flags.contains(FieldFlags::SENSITIVE) && serializer.redact_sensitive() {
+        Task::Redacted(field)
+    } else {
+        Task::Value(peek)
+    }
+}
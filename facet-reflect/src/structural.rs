@@ -0,0 +1,393 @@
+//! Cloning, comparing and hashing values through their shape, for types that don't implement
+//! `Clone`, `PartialEq` or `Hash` themselves (e.g. FFI types)
+
+use core::hash::Hasher;
+
+use facet_core::{Def, Facet, Opaque, OpaqueUninit, Shape, VariantKind};
+
+use crate::{Guard, Peek, PokeUninit, ReflectError};
+
+/// A value in its own heap allocation, as returned by [`deep_clone`]
+///
+/// The value is dropped along with it, unless it's moved out with [`HeapValue::materialize`].
+pub struct HeapValue {
+    data: Opaque<'static>,
+    shape: &'static Shape,
+    /// Frees the allocation, `None` once the value has been moved out
+    guard: Option<Guard>,
+}
+
+impl HeapValue {
+    /// Shape getter
+    #[inline(always)]
+    pub fn shape(&self) -> &'static Shape {
+        self.shape
+    }
+
+    /// Gets a read-only view of the value
+    pub fn peek(&self) -> Peek<'_> {
+        unsafe { Peek::unchecked_new(self.data.as_const(), self.shape) }
+    }
+
+    /// Moves the value out, if it's a `T`
+    pub fn materialize<'facet, T: Facet<'facet>>(self) -> Result<T, Self> {
+        if !self.shape.is_type::<T>() {
+            return Err(self);
+        }
+        Ok(self.move_out(|data| unsafe { data.read::<T>() }))
+    }
+
+    /// Lets `f` move the value out (with [`core::ptr::read`]), then frees the allocation
+    /// without dropping the value
+    fn move_out<R>(mut self, f: impl FnOnce(Opaque<'static>) -> R) -> R {
+        let res = f(self.data);
+        self.guard = None;
+        res
+    }
+}
+
+impl Drop for HeapValue {
+    fn drop(&mut self) {
+        if self.guard.is_some() {
+            unsafe { drop_in_place(self.data, self.shape) };
+        }
+    }
+}
+
+impl core::fmt::Debug for HeapValue {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("HeapValue")
+            .field("shape", &self.shape)
+            .field("value", &self.peek())
+            .finish()
+    }
+}
+
+/// Clones a value into a new allocation, recursing through structs, enums, lists, maps, sets,
+/// options and smart pointers.
+///
+/// Every part of the value whose type implements `Clone` is cloned with it, so an `Arc` ends up
+/// shared rather than copied.
+///
+/// # Errors
+///
+/// Returns [`ReflectError::OperationNotSupported`] if some part of the value can't be cloned:
+/// a scalar that doesn't implement `Clone`, an array whose items don't, or a smart pointer that
+/// can't be built around a clone of its pointee (like `Weak`).
+///
+/// # Example
+///
+/// ```
+/// use facet::Facet;
+/// use facet_reflect::{Peek, deep_clone};
+///
+/// #[derive(Facet, Debug, PartialEq)]
+/// struct Point {
+///     x: i32,
+///     y: i32,
+/// }
+///
+/// let point = Point { x: 1, y: 2 };
+/// let clone = deep_clone(Peek::new(&point)).unwrap();
+/// assert_eq!(clone.materialize::<Point>().unwrap(), point);
+/// ```
+pub fn deep_clone(source: Peek<'_>) -> Result<HeapValue, ReflectError> {
+    let shape = source.shape();
+    let (poke, guard) = PokeUninit::alloc_shape(shape);
+    let target = unsafe { poke.into_value().data() };
+    let data = unsafe { clone_into(source, target) }?;
+    Ok(HeapValue {
+        data,
+        shape,
+        guard: Some(guard),
+    })
+}
+
+/// Clones `source` into `target`, which must fit its shape. Nothing is left to drop in `target`
+/// on error.
+unsafe fn clone_into<'mem>(
+    source: Peek<'_>,
+    target: OpaqueUninit<'mem>,
+) -> Result<Opaque<'mem>, ReflectError> {
+    let shape = source.shape();
+    if let Some(clone_fn) = shape.vtable.clone_into {
+        return Ok(unsafe { clone_fn(source.data(), target) });
+    }
+
+    let unsupported = ReflectError::OperationNotSupported {
+        shape,
+        operation: "deep_clone",
+    };
+    match (source, unsafe { PokeUninit::unchecked_new(target, shape) }) {
+        (Peek::Struct(source), PokeUninit::Struct(mut ps)) => {
+            // fields that were cloned get dropped with `ps` on error
+            for (index, (_, field)) in source.fields().enumerate() {
+                let mut target = ps.field(index).expect("field exists").into_value();
+                unsafe { clone_into(field, target.data()) }?;
+                unsafe { ps.mark_initialized(index) };
+            }
+            Ok(ps.build_in_place())
+        }
+        (Peek::Enum(source), PokeUninit::Enum(pe)) => {
+            let mut pe = pe
+                .set_variant_by_index(source.variant_index())
+                .expect("variant index is in bounds");
+            let is_tuple = matches!(source.variant_kind_active(), VariantKind::Tuple { .. });
+            for (index, name, field, _) in source.fields_with_metadata() {
+                let target = if is_tuple {
+                    pe.tuple_field(index)
+                } else {
+                    pe.field_by_name(name).map(|(_, target)| target)
+                };
+                let mut target = target.expect("field exists").into_value();
+                unsafe { clone_into(field, target.data()) }?;
+                unsafe { pe.mark_initialized(index) };
+            }
+            Ok(pe.build_in_place())
+        }
+        (Peek::List(source), PokeUninit::List(pl)) => {
+            // fixed-size lists (arrays) can't be built item by item
+            let mut pl = pl.init(Some(source.len())).map_err(|_| unsupported)?;
+            let res = source.iter().try_for_each(|item| {
+                deep_clone(item)?.move_out(|item| unsafe { pl.push(item) });
+                Ok(())
+            });
+            unsafe { dropped_on_error(pl.build_in_place(), shape, res) }
+        }
+        (Peek::Map(source), PokeUninit::Map(pm)) => {
+            let mut pm = pm.init(Some(source.len())).map_err(|_| unsupported)?;
+            let res = source.iter().try_for_each(|(key, value)| {
+                let key = deep_clone(key)?;
+                let value = deep_clone(value)?;
+                key.move_out(|key| value.move_out(|value| unsafe { pm.insert(key, value) }));
+                Ok(())
+            });
+            unsafe { dropped_on_error(pm.build_in_place(), shape, res) }
+        }
+        (Peek::Set(source), PokeUninit::Set(ps)) => {
+            let mut ps = ps.init(Some(source.len())).map_err(|_| unsupported)?;
+            let res = source.iter().try_for_each(|item| {
+                deep_clone(item)?.move_out(|item| unsafe { ps.insert(item) });
+                Ok(())
+            });
+            unsafe { dropped_on_error(ps.build_in_place(), shape, res) }
+        }
+        (Peek::Option(source), PokeUninit::Option(po)) => {
+            let po = match source.value() {
+                None => unsafe { po.init_none() },
+                Some(inner) => {
+                    deep_clone(inner)?.move_out(|inner| unsafe { po.write(inner.as_const()) })
+                }
+            };
+            Ok(po.build_in_place())
+        }
+        (Peek::SmartPointer(source), PokeUninit::SmartPointer(psp)) => {
+            let inner = match source.inner() {
+                Some(inner) if psp.vtable().new_into_fn.is_some() => deep_clone(inner)?,
+                _ => return Err(unsupported),
+            };
+            let psp = inner.move_out(|inner| unsafe { psp.write(inner.as_const()) });
+            Ok(psp.build_in_place())
+        }
+        _ => Err(unsupported),
+    }
+}
+
+/// Drops the collection at `data` if filling it failed
+unsafe fn dropped_on_error<'mem>(
+    data: Opaque<'mem>,
+    shape: &'static Shape,
+    res: Result<(), ReflectError>,
+) -> Result<Opaque<'mem>, ReflectError> {
+    if let Err(e) = res {
+        unsafe { drop_in_place(data, shape) };
+        return Err(e);
+    }
+    Ok(data)
+}
+
+unsafe fn drop_in_place(data: Opaque<'_>, shape: &'static Shape) {
+    if let Some(drop_fn) = shape.vtable.drop_in_place {
+        unsafe { drop_fn(data) };
+    }
+}
+
+/// Compares two values, recursing through structs, enums, lists, maps, sets, options and smart
+/// pointers.
+///
+/// Every part of the values whose type implements `PartialEq` is compared with it. Values of
+/// different types are never equal.
+///
+/// Returns `None` if some part of the values can't be compared, like a scalar that doesn't
+/// implement `PartialEq`, and no other part differs.
+pub fn structural_eq(a: Peek<'_>, b: Peek<'_>) -> Option<bool> {
+    if a.shape().id != b.shape().id {
+        return Some(false);
+    }
+    if let Some(eq) = a.as_value().eq(&b.as_value()) {
+        return Some(eq);
+    }
+
+    match (a, b) {
+        (Peek::Struct(a), Peek::Struct(b)) => {
+            all_eq(a.fields().map(|(_, f)| f).zip(b.fields().map(|(_, f)| f)))
+        }
+        (Peek::Enum(a), Peek::Enum(b)) => {
+            if a.variant_index() != b.variant_index() {
+                return Some(false);
+            }
+            all_eq(a.fields().map(|(_, f)| f).zip(b.fields().map(|(_, f)| f)))
+        }
+        (Peek::List(a), Peek::List(b)) => {
+            if a.len() != b.len() {
+                return Some(false);
+            }
+            all_eq(a.iter().zip(b.iter()))
+        }
+        (Peek::Map(a), Peek::Map(b)) => {
+            let Def::Map(def) = b.shape().def else {
+                unreachable!("maps have a map def")
+            };
+            if a.len() != b.len() {
+                return Some(false);
+            }
+            let mut pairs = Vec::with_capacity(a.len());
+            for (key, value) in a.iter() {
+                let Some(other) = (unsafe { (def.vtable.get_value_ptr_fn)(b.data(), key.data()) })
+                else {
+                    return Some(false);
+                };
+                pairs.push((value, unsafe { Peek::unchecked_new(other, def.v) }));
+            }
+            all_eq(pairs.into_iter())
+        }
+        (Peek::Set(a), Peek::Set(b)) => {
+            let Def::Set(def) = b.shape().def else {
+                unreachable!("sets have a set def")
+            };
+            // sets hold items that implement `Eq`, they can be looked up
+            let eq = a.len() == b.len()
+                && a.iter()
+                    .all(|item| unsafe { (def.vtable.contains_fn)(b.data(), item.data()) });
+            Some(eq)
+        }
+        (Peek::Option(a), Peek::Option(b)) => match (a.value(), b.value()) {
+            (Some(a), Some(b)) => structural_eq(a, b),
+            (a, b) => Some(a.is_none() && b.is_none()),
+        },
+        (Peek::SmartPointer(a), Peek::SmartPointer(b)) => match (a.inner(), b.inner()) {
+            (Some(a), Some(b)) => structural_eq(a, b),
+            (a, b) => Some(a.is_none() && b.is_none()),
+        },
+        _ => None,
+    }
+}
+
+/// `Some(false)` as soon as a pair differs, `None` if a pair couldn't be compared and none
+/// differ
+fn all_eq<'a, 'b>(pairs: impl Iterator<Item = (Peek<'a>, Peek<'b>)>) -> Option<bool> {
+    let mut comparable = true;
+    for (a, b) in pairs {
+        match structural_eq(a, b) {
+            Some(false) => return Some(false),
+            Some(true) => {}
+            None => comparable = false,
+        }
+    }
+    comparable.then_some(true)
+}
+
+/// Hashes a value, recursing through structs, enums, lists, maps, sets, options and smart
+/// pointers. Values that are [`structural_eq`] hash the same.
+///
+/// Every part of the value whose type implements `Hash` is hashed with it. The entries of maps
+/// and sets are hashed regardless of their order.
+///
+/// Returns `false` if some part of the value can't be hashed, like a scalar that doesn't
+/// implement `Hash`. The hasher may have been fed the other parts.
+pub fn structural_hash<H: Hasher>(value: Peek<'_>, hasher: &mut H) -> bool {
+    if value.as_value().hash(hasher) {
+        return true;
+    }
+
+    match value {
+        Peek::Struct(ps) => ps.fields().all(|(_, field)| structural_hash(field, hasher)),
+        Peek::Enum(pe) => {
+            hasher.write_usize(pe.variant_index());
+            pe.fields().all(|(_, field)| structural_hash(field, hasher))
+        }
+        Peek::List(pl) => {
+            hasher.write_usize(pl.len());
+            pl.iter().all(|item| structural_hash(item, hasher))
+        }
+        Peek::Map(pm) => {
+            let entries = pm.iter().map(|(key, value)| {
+                let mut entry = EntryHasher::default();
+                let hashed = structural_hash(key, &mut entry) && structural_hash(value, &mut entry);
+                hashed.then(|| entry.finish())
+            });
+            hash_unordered(pm.len(), entries, hasher)
+        }
+        Peek::Set(ps) => {
+            let items = ps.iter().map(|item| {
+                let mut entry = EntryHasher::default();
+                structural_hash(item, &mut entry).then(|| entry.finish())
+            });
+            hash_unordered(ps.len(), items, hasher)
+        }
+        Peek::Option(po) => match po.value() {
+            None => {
+                hasher.write_u8(0);
+                true
+            }
+            Some(inner) => {
+                hasher.write_u8(1);
+                structural_hash(inner, hasher)
+            }
+        },
+        Peek::SmartPointer(psp) => psp
+            .inner()
+            .is_some_and(|inner| structural_hash(inner, hasher)),
+        _ => false,
+    }
+}
+
+/// Feeds the sum of the entries' own hashes to `hasher`, so that their order doesn't matter
+fn hash_unordered<H: Hasher>(
+    len: usize,
+    entries: impl Iterator<Item = Option<u64>>,
+    hasher: &mut H,
+) -> bool {
+    let mut sum = 0u64;
+    for entry in entries {
+        let Some(entry) = entry else {
+            return false;
+        };
+        sum = sum.wrapping_add(entry);
+    }
+    hasher.write_usize(len);
+    hasher.write_u64(sum);
+    true
+}
+
+/// FNV-1a, to hash map and set entries on their own
+struct EntryHasher(u64);
+
+impl Default for EntryHasher {
+    fn default() -> Self {
+        Self(0xcbf2_9ce4_8422_2325)
+    }
+}
+
+impl Hasher for EntryHasher {
+    fn write(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.0 ^= u64::from(byte);
+            self.0 = self.0.wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+
+    fn finish(&self) -> u64 {
+        self.0
+    }
+}
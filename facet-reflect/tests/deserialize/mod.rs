@@ -0,0 +1,709 @@
+use std::borrow::Cow;
+use std::collections::{BTreeMap, HashSet};
+use std::num::NonZero;
+use std::sync::Arc;
+
+use facet::Facet;
+use facet_reflect::{DeserializeError, Deserializer, PathError, PathSegment, Token, deserialize};
+
+/// Hands out a fixed list of tokens
+struct Tokens<'input> {
+    tokens: std::vec::IntoIter<Token<'input>>,
+    deny_unknown_fields: bool,
+}
+
+impl<'input> Deserializer<'input> for Tokens<'input> {
+    type Error = DeserializeError;
+
+    fn next(&mut self) -> Result<Token<'input>, DeserializeError> {
+        Ok(self.tokens.next().expect("ran out of tokens"))
+    }
+
+    fn deny_unknown_fields(&self) -> bool {
+        self.deny_unknown_fields
+    }
+}
+
+fn from_tokens<'input, T: Facet<'input>>(
+    tokens: impl IntoIterator<Item = Token<'input>>,
+) -> Result<T, PathError<DeserializeError>> {
+    let mut de = Tokens {
+        tokens: tokens.into_iter().collect::<Vec<_>>().into_iter(),
+        deny_unknown_fields: false,
+    };
+    let value = deserialize(&mut de)?;
+    assert_eq!(de.tokens.next(), None, "tokens left over");
+    Ok(value)
+}
+
+fn s(s: &str) -> Token<'_> {
+    Token::Str(Cow::Borrowed(s))
+}
+
+#[test]
+fn deserialize_struct_fields() {
+    facet_testhelpers::setup();
+
+    #[derive(Facet, Debug, PartialEq)]
+    struct Inner {
+        x: u32,
+    }
+
+    #[derive(Facet, Debug, PartialEq)]
+    struct Outer {
+        #[facet(rename = "renamed")]
+        name: String,
+        #[facet(default)]
+        defaulted: u32,
+        #[facet(flatten)]
+        inner: Inner,
+        nested: Inner,
+    }
+
+    let value: Outer = from_tokens([
+        Token::StartMap(None),
+        s("renamed"),
+        s("n"),
+        s("unknown"),
+        Token::StartList(None),
+        Token::StartMap(None),
+        Token::EndMap,
+        Token::EndList,
+        s("x"),
+        Token::U64(2),
+        s("nested"),
+        Token::StartMap(Some(1)),
+        s("x"),
+        Token::I64(3),
+        Token::EndMap,
+        Token::EndMap,
+    ])
+    .unwrap();
+    assert_eq!(
+        value,
+        Outer {
+            name: "n".to_string(),
+            defaulted: 0,
+            inner: Inner { x: 2 },
+            nested: Inner { x: 3 },
+        }
+    );
+
+    let err = from_tokens::<Outer>([Token::StartMap(None), s("x"), Token::U64(2), Token::EndMap])
+        .unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "missing fields in Outer: `renamed`, `nested` (expected Outer)"
+    );
+
+    let mut de = Tokens {
+        tokens: vec![Token::StartMap(None), s("nmae"), s("n")].into_iter(),
+        deny_unknown_fields: true,
+    };
+    let err = deserialize::<Outer, _>(&mut de).unwrap_err();
+    assert!(
+        matches!(
+            &err.error,
+            DeserializeError::UnknownField {
+                field,
+                suggestion: None,
+                ..
+            } if field == "nmae"
+        ),
+        "{err}"
+    );
+
+    #[derive(Facet, Debug)]
+    #[facet(deny_unknown_fields)]
+    struct Strict {
+        #[allow(dead_code)]
+        count: u32,
+    }
+
+    let err =
+        from_tokens::<Strict>([Token::StartMap(None), s("cuont"), Token::U64(1)]).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "unknown field `cuont` for Strict, did you mean `count`? (expected Strict)"
+    );
+}
+
+#[test]
+fn deserialize_tuples_and_unit() {
+    facet_testhelpers::setup();
+
+    #[derive(Facet, Debug, PartialEq)]
+    struct Pair(u8, String);
+
+    #[derive(Facet, Debug, PartialEq)]
+    struct Unit;
+
+    let pair: Pair = from_tokens([
+        Token::StartList(Some(2)),
+        Token::U64(1),
+        s("a"),
+        Token::EndList,
+    ])
+    .unwrap();
+    assert_eq!(pair, Pair(1, "a".to_string()));
+
+    let err =
+        from_tokens::<Pair>([Token::StartList(None), Token::U64(1), Token::EndList]).unwrap_err();
+    assert!(matches!(err.error, DeserializeError::Reflect(_)), "{err}");
+
+    let err = from_tokens::<Pair>([Token::StartList(None), Token::U64(1), s("a"), Token::U64(2)])
+        .unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "[2]: expected the end of a list, got an integer (expected Pair)"
+    );
+
+    assert_eq!(from_tokens::<Unit>([Token::Null]).unwrap(), Unit);
+}
+
+#[test]
+fn deserialize_scalars() {
+    facet_testhelpers::setup();
+
+    #[derive(Facet, Debug, PartialEq)]
+    struct Scalars<'a> {
+        small: i8,
+        big: u128,
+        float: f32,
+        whole: f64,
+        non_zero: NonZero<u32>,
+        flag: bool,
+        c: char,
+        ip: std::net::IpAddr,
+        borrowed: &'a str,
+        cow: Cow<'a, str>,
+        unit: (),
+    }
+
+    let value: Scalars = from_tokens([
+        Token::StartMap(None),
+        s("small"),
+        Token::I64(-128),
+        s("big"),
+        Token::U64(u64::MAX),
+        s("float"),
+        Token::F64(1.5),
+        s("whole"),
+        Token::I64(-3),
+        s("non_zero"),
+        Token::U64(7),
+        s("flag"),
+        Token::Bool(true),
+        s("c"),
+        s("x"),
+        s("ip"),
+        s("127.0.0.1"),
+        s("borrowed"),
+        s("b"),
+        s("cow"),
+        s("c"),
+        s("unit"),
+        Token::Null,
+        Token::EndMap,
+    ])
+    .unwrap();
+    assert_eq!(
+        value,
+        Scalars {
+            small: -128,
+            big: u64::MAX.into(),
+            float: 1.5,
+            whole: -3.0,
+            non_zero: NonZero::new(7).unwrap(),
+            flag: true,
+            c: 'x',
+            ip: "127.0.0.1".parse().unwrap(),
+            borrowed: "b",
+            cow: Cow::Borrowed("c"),
+            unit: (),
+        }
+    );
+    assert!(matches!(value.cow, Cow::Borrowed(_)));
+
+    let err = from_tokens::<i8>([Token::I64(300)]).unwrap_err();
+    assert_eq!(err.to_string(), "300 is out of range for i8 (expected i8)");
+    let err = from_tokens::<u32>([Token::I64(-1)]).unwrap_err();
+    assert!(
+        matches!(err.error, DeserializeError::OutOfRange { .. }),
+        "{err}"
+    );
+    let err = from_tokens::<NonZero<u8>>([Token::U64(0)]).unwrap_err();
+    assert!(
+        matches!(err.error, DeserializeError::OutOfRange { .. }),
+        "{err}"
+    );
+    let err = from_tokens::<u32>([Token::F64(1.5)]).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "expected an integer, got a float (expected u32)"
+    );
+    let err = from_tokens::<bool>([Token::U64(1)]).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "expected a boolean, got an integer (expected bool)"
+    );
+    let err = from_tokens::<std::net::IpAddr>([s("nope")]).unwrap_err();
+    assert!(
+        matches!(err.error, DeserializeError::InvalidValue { .. }),
+        "{err}"
+    );
+    let err = from_tokens::<&str>([Token::Str(Cow::Owned("owned".to_string()))]).unwrap_err();
+    assert!(
+        matches!(err.error, DeserializeError::BorrowedString { .. }),
+        "{err}"
+    );
+
+    let time: std::time::SystemTime = from_tokens([Token::Timestamp {
+        secs: 1,
+        nanos: 500,
+    }])
+    .unwrap();
+    assert_eq!(
+        time,
+        std::time::UNIX_EPOCH + std::time::Duration::new(1, 500)
+    );
+}
+
+#[test]
+fn deserialize_options_and_collections() {
+    facet_testhelpers::setup();
+
+    #[derive(Facet, Debug, PartialEq)]
+    struct Collections {
+        none: Option<u32>,
+        some: Option<Option<u32>>,
+        list: Vec<Vec<u8>>,
+        set: HashSet<String>,
+        map: BTreeMap<u16, bool>,
+        shared: Arc<String>,
+    }
+
+    let value: Collections = from_tokens([
+        Token::StartMap(None),
+        s("none"),
+        Token::Null,
+        s("some"),
+        Token::U64(1),
+        s("list"),
+        Token::StartList(None),
+        Token::StartList(Some(1)),
+        Token::U64(1),
+        Token::EndList,
+        Token::Bytes(Cow::Borrowed(&[2, 3])),
+        Token::EndList,
+        s("set"),
+        Token::StartList(None),
+        s("a"),
+        s("a"),
+        Token::EndList,
+        s("map"),
+        Token::StartMap(None),
+        s("1"),
+        Token::Bool(true),
+        Token::U64(2),
+        Token::Bool(false),
+        Token::EndMap,
+        s("shared"),
+        s("arc"),
+        Token::EndMap,
+    ])
+    .unwrap();
+    assert_eq!(
+        value,
+        Collections {
+            none: None,
+            some: Some(Some(1)),
+            list: vec![vec![1], vec![2, 3]],
+            set: HashSet::from(["a".to_string()]),
+            map: BTreeMap::from([(1, true), (2, false)]),
+            shared: Arc::new("arc".to_string()),
+        }
+    );
+
+    // numbers can be parsed from strings, for formats that only have those
+    let list: Vec<u32> = from_tokens([
+        Token::StartList(None),
+        s("1"),
+        Token::U64(2),
+        Token::EndList,
+    ])
+    .unwrap();
+    assert_eq!(list, [1, 2]);
+    let err =
+        from_tokens::<Vec<u32>>([Token::StartList(None), Token::U64(1), s("two")]).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "[1]: invalid value for u32: \"two\" (expected u32)"
+    );
+    let err = from_tokens::<Vec<u32>>([Token::StartList(None), Token::Bool(true)]).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "[0]: expected a number, got a boolean (expected u32)"
+    );
+}
+
+#[test]
+fn deserialize_newtype_map_keys() {
+    facet_testhelpers::setup();
+
+    #[derive(Facet, Debug, PartialEq, Eq, PartialOrd, Ord)]
+    struct Port(u16);
+
+    impl core::str::FromStr for Port {
+        type Err = core::num::ParseIntError;
+
+        fn from_str(s: &str) -> Result<Self, Self::Err> {
+            s.parse().map(Port)
+        }
+    }
+
+    let map: BTreeMap<Port, bool> = from_tokens([
+        Token::StartMap(None),
+        s("80"),
+        Token::Bool(true),
+        Token::U64(443),
+        Token::Bool(false),
+        Token::EndMap,
+    ])
+    .unwrap();
+    assert_eq!(map, BTreeMap::from([(Port(80), true), (Port(443), false)]));
+
+    // values are still deserialized as structs
+    let ports: Vec<Port> = from_tokens([
+        Token::StartList(None),
+        Token::StartList(None),
+        Token::U64(80),
+        Token::EndList,
+        Token::EndList,
+    ])
+    .unwrap();
+    assert_eq!(ports, [Port(80)]);
+}
+
+#[test]
+fn deserialize_enums() {
+    facet_testhelpers::setup();
+
+    #[derive(Facet, Debug, PartialEq)]
+    #[repr(u8)]
+    enum External {
+        Unit,
+        Newtype(u32),
+        Tuple(u32, String),
+        Struct { a: u32 },
+    }
+
+    assert_eq!(
+        from_tokens::<External>([s("Unit")]).unwrap(),
+        External::Unit
+    );
+    assert_eq!(
+        from_tokens::<External>([
+            Token::StartMap(None),
+            s("Newtype"),
+            Token::U64(1),
+            Token::EndMap
+        ])
+        .unwrap(),
+        External::Newtype(1)
+    );
+    assert_eq!(
+        from_tokens::<External>([
+            Token::StartMap(None),
+            s("Tuple"),
+            Token::StartList(None),
+            Token::U64(1),
+            s("a"),
+            Token::EndList,
+            Token::EndMap
+        ])
+        .unwrap(),
+        External::Tuple(1, "a".to_string())
+    );
+    assert_eq!(
+        from_tokens::<External>([
+            Token::StartMap(None),
+            s("Struct"),
+            Token::StartMap(None),
+            s("a"),
+            Token::U64(1),
+            Token::EndMap,
+            Token::EndMap
+        ])
+        .unwrap(),
+        External::Struct { a: 1 }
+    );
+    let err = from_tokens::<External>([s("Nope")]).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "unknown variant `Nope` for External (expected External)"
+    );
+
+    #[derive(Facet, Debug, PartialEq)]
+    #[repr(u8)]
+    #[facet(tag = "t")]
+    #[allow(dead_code)]
+    enum Internal {
+        Unit,
+        Struct { a: u32 },
+        Tuple(u32, u32),
+    }
+
+    assert_eq!(
+        from_tokens::<Internal>([
+            Token::StartMap(None),
+            s("a"),
+            Token::U64(1),
+            s("t"),
+            s("Struct"),
+            Token::EndMap
+        ])
+        .unwrap(),
+        Internal::Struct { a: 1 }
+    );
+    assert_eq!(
+        from_tokens::<Internal>([Token::StartMap(None), s("t"), s("Unit"), Token::EndMap]).unwrap(),
+        Internal::Unit
+    );
+    let err =
+        from_tokens::<Internal>([Token::StartMap(None), s("a"), Token::U64(1), Token::EndMap])
+            .unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "missing tag `t` for Internal (expected Internal)"
+    );
+    let err = from_tokens::<Internal>([Token::StartMap(None), s("t"), s("Tuple"), Token::EndMap])
+        .unwrap_err();
+    assert!(
+        matches!(
+            err.error,
+            DeserializeError::InternallyTaggedTuple {
+                variant: "Tuple",
+                ..
+            }
+        ),
+        "{err}"
+    );
+
+    #[derive(Facet, Debug, PartialEq)]
+    #[repr(u8)]
+    #[facet(tag = "t")]
+    #[facet(content = "c")]
+    enum Adjacent {
+        Unit,
+        Newtype(u32),
+    }
+
+    assert_eq!(
+        from_tokens::<Adjacent>([
+            Token::StartMap(None),
+            s("c"),
+            Token::U64(1),
+            s("t"),
+            s("Newtype"),
+            Token::EndMap
+        ])
+        .unwrap(),
+        Adjacent::Newtype(1)
+    );
+    assert_eq!(
+        from_tokens::<Adjacent>([Token::StartMap(None), s("t"), s("Unit"), Token::EndMap]).unwrap(),
+        Adjacent::Unit
+    );
+
+    #[derive(Facet, Debug, PartialEq)]
+    #[repr(u8)]
+    #[facet(untagged)]
+    enum Untagged {
+        Unit,
+        Number(u32),
+        Text(String),
+        Point { x: u32, y: u32 },
+    }
+
+    assert_eq!(
+        from_tokens::<Untagged>([Token::Null]).unwrap(),
+        Untagged::Unit
+    );
+    assert_eq!(
+        from_tokens::<Untagged>([Token::U64(1)]).unwrap(),
+        Untagged::Number(1)
+    );
+    assert_eq!(
+        from_tokens::<Untagged>([s("a")]).unwrap(),
+        Untagged::Text("a".to_string())
+    );
+    assert_eq!(
+        from_tokens::<Untagged>([
+            Token::StartMap(None),
+            s("x"),
+            Token::U64(1),
+            s("y"),
+            Token::U64(2),
+            Token::EndMap
+        ])
+        .unwrap(),
+        Untagged::Point { x: 1, y: 2 }
+    );
+    let err = from_tokens::<Untagged>([Token::Bool(true)]).unwrap_err();
+    assert!(
+        matches!(err.error, DeserializeError::NoMatchingVariant { .. }),
+        "{err}"
+    );
+
+    // variants hold their fields, which are dropped when the value is
+    let list: Vec<External> = from_tokens([
+        Token::StartList(None),
+        s("Unit"),
+        Token::StartMap(None),
+        s("Tuple"),
+        Token::StartList(None),
+        Token::U64(1),
+        s("a"),
+        Token::EndList,
+        Token::EndMap,
+        Token::EndList,
+    ])
+    .unwrap();
+    assert_eq!(list, [External::Unit, External::Tuple(1, "a".to_string())]);
+}
+
+#[test]
+fn deserialize_error_paths() {
+    facet_testhelpers::setup();
+
+    #[derive(Facet, Debug)]
+    #[allow(dead_code)]
+    struct Tls {
+        cert_path: String,
+    }
+
+    #[derive(Facet, Debug)]
+    #[allow(dead_code)]
+    struct Server {
+        port: u16,
+        tls: Option<Tls>,
+        tags: BTreeMap<String, Vec<u8>>,
+    }
+
+    #[derive(Facet, Debug)]
+    #[repr(u8)]
+    #[allow(dead_code)]
+    enum Listener {
+        Tcp { servers: Vec<Server> },
+        Unix(String, u8),
+    }
+
+    fn server(port: u64) -> Vec<Token<'static>> {
+        vec![
+            Token::StartMap(None),
+            s("port"),
+            Token::U64(port),
+            s("tls"),
+            Token::Null,
+            s("tags"),
+            Token::StartMap(None),
+            Token::EndMap,
+            Token::EndMap,
+        ]
+    }
+
+    let tcp = |last: Vec<Token<'static>>| {
+        let mut tokens = vec![
+            Token::StartMap(None),
+            s("Tcp"),
+            Token::StartMap(None),
+            s("servers"),
+            Token::StartList(None),
+        ];
+        tokens.extend(server(1));
+        tokens.extend(server(2));
+        tokens.extend(last);
+        tokens
+    };
+
+    let err = from_tokens::<Listener>(tcp(server(70000))).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Tcp.servers[2].port: 70000 is out of range for u16 (expected u16)"
+    );
+
+    let err = from_tokens::<Listener>(tcp(vec![
+        Token::StartMap(None),
+        s("tls"),
+        Token::StartMap(None),
+        s("cert_path"),
+        Token::Bool(true),
+    ]))
+    .unwrap_err();
+    assert_eq!(
+        err.path.segments(),
+        [
+            PathSegment::Field("Tcp"),
+            PathSegment::Field("servers"),
+            PathSegment::Index(2),
+            PathSegment::Field("tls"),
+            PathSegment::Field("cert_path"),
+        ]
+    );
+    assert_eq!(
+        err.to_string(),
+        "Tcp.servers[2].tls.cert_path: expected a string, got a boolean (expected String)"
+    );
+
+    let err = from_tokens::<Listener>(tcp(vec![
+        Token::StartMap(None),
+        s("tags"),
+        Token::StartMap(None),
+        s("a"),
+        Token::StartList(None),
+        Token::U64(1),
+        s("x"),
+    ]))
+    .unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Tcp.servers[2].tags[\"a\"][1]: invalid value for u8: \"x\" (expected u8)"
+    );
+
+    let err = from_tokens::<Listener>(tcp(vec![
+        Token::StartMap(None),
+        s("port"),
+        Token::U64(3),
+        Token::EndMap,
+    ]))
+    .unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Tcp.servers[2]: missing fields in Server: `tls`, `tags` (expected Server)"
+    );
+
+    let err = from_tokens::<Listener>([
+        Token::StartMap(None),
+        s("Unix"),
+        Token::StartList(None),
+        s("/tmp/socket"),
+        Token::I64(-1),
+    ])
+    .unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Unix[1]: -1 is out of range for u8 (expected u8)"
+    );
+
+    // a `Weak<T>` can't own what it points to, so there is nothing to deserialize it into
+    #[derive(Facet, Debug)]
+    #[allow(dead_code)]
+    struct Cache {
+        parent: std::sync::Weak<String>,
+    }
+
+    let err = from_tokens::<Cache>([Token::StartMap(None), s("parent"), s("root")]).unwrap_err();
+    assert!(matches!(err.error, DeserializeError::Unsupported { .. }));
+    assert_eq!(err.path.segments(), [PathSegment::Field("parent")]);
+}
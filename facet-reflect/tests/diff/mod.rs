@@ -0,0 +1,201 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use facet::Facet;
+use facet_reflect::{Change, Peek, diff};
+use facet_samplelibc::{Bar, Foo};
+
+#[derive(Facet)]
+struct Config {
+    name: String,
+    listen: Listen,
+    upstreams: Vec<Upstream>,
+    env: HashMap<String, String>,
+    features: HashSet<String>,
+    timeout: Option<u32>,
+    mode: Mode,
+}
+
+#[derive(Facet)]
+struct Listen(String, u16);
+
+#[derive(Facet)]
+struct Upstream {
+    host: String,
+    weight: u8,
+}
+
+#[derive(Facet)]
+#[repr(u8)]
+#[allow(dead_code)]
+enum Mode {
+    Off,
+    Proxy { retries: u8, backoff: Vec<u32> },
+    Static(String, bool),
+}
+
+fn config() -> Config {
+    Config {
+        name: "edge".to_string(),
+        listen: Listen("0.0.0.0".to_string(), 80),
+        upstreams: ["a", "b", "c"]
+            .into_iter()
+            .map(|host| Upstream {
+                host: host.to_string(),
+                weight: 1,
+            })
+            .collect(),
+        env: HashMap::from([
+            ("LOG".to_string(), "info".to_string()),
+            ("TZ".to_string(), "UTC".to_string()),
+        ]),
+        features: HashSet::from(["gzip".to_string(), "h2".to_string()]),
+        timeout: Some(30),
+        mode: Mode::Proxy {
+            retries: 3,
+            backoff: vec![100, 200],
+        },
+    }
+}
+
+/// Each change as `~ path: old => new`, `+ path: value` or `- path: value`
+fn summary(a: &Config, b: &Config) -> Vec<String> {
+    diff(Peek::new(a), Peek::new(b))
+        .into_iter()
+        .map(|change| match change {
+            Change::Changed { path, old, new } => format!("~ {path}: {old:?} => {new:?}"),
+            Change::Added { path, value } => format!("+ {path}: {value:?}"),
+            Change::Removed { path, value } => format!("- {path}: {value:?}"),
+            _ => unreachable!(),
+        })
+        .collect()
+}
+
+#[test]
+fn equal_values() {
+    facet_testhelpers::setup();
+
+    assert!(diff(Peek::new(&config()), Peek::new(&config())).is_empty());
+    assert!(diff(Peek::new(&1u32), Peek::new(&1u32)).is_empty());
+
+    let changed = diff(Peek::new(&1u32), Peek::new(&2u32));
+    assert_eq!(changed.len(), 1);
+    assert!(changed.changes()[0].path().is_empty());
+}
+
+#[test]
+fn nested_changes_have_paths() {
+    facet_testhelpers::setup();
+
+    let a = config();
+    let mut b = config();
+    b.listen.1 = 8080;
+    b.upstreams[1].weight = 5;
+    b.env.insert("TZ".to_string(), "CET".to_string());
+    b.timeout = Some(60);
+    b.mode = Mode::Proxy {
+        retries: 4,
+        backoff: vec![100, 200, 400],
+    };
+
+    assert_eq!(
+        summary(&a, &b),
+        [
+            "~ listen[1]: 80 => 8080",
+            "~ upstreams[1].weight: 1 => 5",
+            "~ env[\"TZ\"]: \"UTC\" => \"CET\"",
+            "~ timeout: 30 => 60",
+            "~ mode.Proxy.retries: 3 => 4",
+            "+ mode.Proxy.backoff[2]: 400",
+        ]
+    );
+
+    b.timeout = None;
+    b.mode = Mode::Static("/srv".to_string(), true);
+    let changes = diff(Peek::new(&a), Peek::new(&b));
+    let Change::Changed { path, old, .. } = &changes.changes()[3] else {
+        panic!("unsetting an option changes the whole option")
+    };
+    assert_eq!(path.to_string(), "timeout");
+    assert!(matches!(old, Peek::Option(old) if old.is_some()));
+    let Change::Changed { path, old, new } = &changes.changes()[4] else {
+        panic!("switching variants changes the whole enum")
+    };
+    assert_eq!(path.to_string(), "mode");
+    let (Peek::Enum(old), Peek::Enum(new)) = (old, new) else {
+        unreachable!()
+    };
+    assert_eq!(
+        (old.variant_name_active(), new.variant_name_active()),
+        ("Proxy", "Static")
+    );
+}
+
+#[test]
+fn added_and_removed_items() {
+    facet_testhelpers::setup();
+
+    let a = config();
+    let mut b = config();
+    b.upstreams.insert(
+        1,
+        Upstream {
+            host: "a2".to_string(),
+            weight: 2,
+        },
+    );
+    b.env.remove("LOG");
+    b.env.insert("HOME".to_string(), "/root".to_string());
+    b.features.remove("h2");
+    b.features.insert("br".to_string());
+
+    // an item inserted in the middle of a list doesn't show up as every later item changing
+    let changes = summary(&a, &b);
+    assert_eq!(changes.len(), 5, "{changes:#?}");
+    assert!(changes[0].starts_with("+ upstreams[1]: "), "{changes:#?}");
+    assert_eq!(
+        changes[1..],
+        [
+            "+ env[\"HOME\"]: \"/root\"",
+            "- env[\"LOG\"]: \"info\"",
+            "+ features: \"br\"",
+            "- features: \"h2\"",
+        ]
+    );
+
+    b.upstreams.truncate(1);
+    let changes = summary(&a, &b);
+    assert!(changes[0].starts_with("- upstreams[1]: "), "{changes:#?}");
+    assert!(changes[1].starts_with("- upstreams[2]: "), "{changes:#?}");
+}
+
+#[test]
+fn maps_in_key_order() {
+    facet_testhelpers::setup();
+
+    let a = BTreeMap::from([(1, vec![1]), (2, vec![2]), (3, vec![3])]);
+    let b = BTreeMap::from([(1, vec![1]), (2, vec![2, 2]), (4, vec![4])]);
+    let paths: Vec<_> = diff(Peek::new(&a), Peek::new(&b))
+        .into_iter()
+        .map(|change| change.path().to_string())
+        .collect();
+    assert_eq!(paths, ["[\"2\"][1]", "[\"3\"]", "[\"4\"]"]);
+}
+
+#[test]
+fn values_without_partial_eq() {
+    facet_testhelpers::setup();
+
+    let a = Foo {
+        x: 1,
+        bar: Bar { a: 2, b: 3 },
+        y: 4,
+    };
+    let b = Foo {
+        bar: Bar { a: 2, b: 30 },
+        ..a
+    };
+    assert!(diff(Peek::new(&a), Peek::new(&a)).is_empty());
+    let changes = diff(Peek::new(&a), Peek::new(&b));
+    assert_eq!(changes.len(), 1);
+    assert_eq!(changes.changes()[0].path().to_string(), "bar.b");
+}
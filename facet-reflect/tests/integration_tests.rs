@@ -1,2 +1,6 @@
+mod deserialize;
+mod diff;
 mod peek;
 mod poke;
+mod serialize;
+mod structural;
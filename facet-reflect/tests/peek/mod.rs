@@ -1 +1,3 @@
 mod option;
+mod set;
+mod smartptr;
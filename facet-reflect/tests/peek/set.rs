@@ -0,0 +1,42 @@
+use std::collections::{BTreeSet, HashSet};
+
+use facet_reflect::Peek;
+
+#[test]
+fn peek_btreeset() {
+    facet_testhelpers::setup();
+
+    let set = BTreeSet::from([3_u32, 1, 2]);
+    let peek = Peek::new(&set);
+
+    let Peek::Set(peek_set) = peek else {
+        panic!("Expected a PeekSet");
+    };
+    assert_eq!(peek_set.len(), 3);
+    assert!(peek_set.contains(&2_u32));
+    assert!(!peek_set.contains(&4_u32));
+
+    let items: Vec<u32> = peek_set
+        .iter()
+        .map(|item| unsafe { *item.data().as_ref::<u32>() })
+        .collect();
+    assert_eq!(items, vec![1, 2, 3]);
+}
+
+#[test]
+fn peek_hashset() {
+    facet_testhelpers::setup();
+
+    let set: HashSet<String> = HashSet::from(["a".to_string(), "b".to_string()]);
+    let peek = Peek::new(&set);
+
+    let Peek::Set(peek_set) = peek else {
+        panic!("Expected a PeekSet");
+    };
+    assert_eq!(peek_set.len(), 2);
+    assert!(peek_set.contains(&"a".to_string()));
+    assert_eq!(peek_set.iter().count(), 2);
+
+    let other: HashSet<String> = HashSet::from(["b".to_string(), "a".to_string()]);
+    assert!(peek.as_value() == Peek::new(&other).as_value());
+}
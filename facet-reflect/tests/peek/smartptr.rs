@@ -0,0 +1,78 @@
+use std::rc::Rc;
+use std::sync::{Arc, Weak};
+
+use facet::{Facet, KnownSmartPointer};
+use facet_reflect::{Peek, PokeUninit};
+
+#[test]
+fn peek_box() {
+    facet_testhelpers::setup();
+
+    let value = Box::new(42_i32);
+    let peek = Peek::new(&value);
+
+    if let Peek::SmartPointer(psp) = peek {
+        assert_eq!(psp.def().known, Some(KnownSmartPointer::Box));
+        assert!(psp.def().is_strong());
+        assert!(!psp.def().is_atomic());
+
+        let inner = psp.inner().unwrap();
+        let inner = unsafe { inner.data().as_ref::<i32>() };
+        assert_eq!(*inner, 42);
+    } else {
+        panic!("Expected a PeekSmartPointer");
+    }
+}
+
+#[test]
+fn peek_arc_and_weak() {
+    facet_testhelpers::setup();
+
+    let strong = Arc::new(String::from("hello"));
+    let peek = Peek::new(&strong);
+
+    let Peek::SmartPointer(psp) = peek else {
+        panic!("Expected a PeekSmartPointer");
+    };
+    assert_eq!(psp.def().known, Some(KnownSmartPointer::Arc));
+    assert!(psp.def().is_atomic());
+    let inner = psp.inner().unwrap();
+    assert_eq!(unsafe { inner.data().as_ref::<String>() }, "hello");
+
+    let weak: Weak<String> = Arc::downgrade(&strong);
+    let peek = Peek::new(&weak);
+    let Peek::SmartPointer(psp) = peek else {
+        panic!("Expected a PeekSmartPointer");
+    };
+    assert_eq!(psp.def().known, Some(KnownSmartPointer::ArcWeak));
+    assert!(psp.def().is_weak());
+    assert!(psp.inner().is_none());
+}
+
+#[test]
+fn smart_pointer_vtables_delegate_to_pointee() {
+    facet_testhelpers::setup();
+
+    let a = Rc::new(5_u32);
+    let b = Rc::new(5_u32);
+    let c = Box::new(6_u32);
+
+    assert!(Peek::new(&a).as_value() == Peek::new(&b).as_value());
+    assert_eq!(format!("{:?}", Peek::new(&c)), "6");
+    assert_eq!(format!("{}", Rc::<u32>::SHAPE), "Rc<u32>");
+    assert!(Arc::<u32>::SHAPE.is_send());
+    assert!(!Rc::<u32>::SHAPE.is_send());
+}
+
+#[test]
+fn poke_box_from_inner() {
+    facet_testhelpers::setup();
+
+    let (poke, guard) = PokeUninit::alloc::<Box<u64>>();
+    let psp = unsafe { poke.into_smart_pointer().put(1234_u64) };
+    let inner = psp.inner().unwrap();
+    assert_eq!(unsafe { *inner.data().as_ref::<u64>() }, 1234);
+
+    let value: Box<u64> = psp.build(Some(guard));
+    assert_eq!(*value, 1234);
+}
@@ -1,6 +1,8 @@
 use facet::Facet;
 use facet_pretty::FacetPretty as _;
-use facet_reflect::PokeUninit;
+use std::sync::Arc;
+
+use facet_reflect::{Poke, PokeUninit, ReflectError};
 
 #[test]
 fn build_enum() {
@@ -92,3 +94,290 @@ fn build_enum() {
         );
     }
 }
+
+#[test]
+fn try_build_enum_reports_missing_variant_fields() {
+    facet_testhelpers::setup();
+
+    #[derive(Facet, PartialEq, Debug)]
+    #[repr(u8)]
+    #[allow(dead_code)]
+    enum Shape {
+        Point,
+        Rect { width: u32, height: u32 },
+    }
+
+    let (poke, guard) = PokeUninit::alloc::<Shape>();
+    let mut pe = poke.into_enum().set_variant_by_name("Rect").unwrap();
+    unsafe {
+        let (index, field) = pe.field_by_name("height").unwrap();
+        field.into_value().put(10_u32);
+        pe.mark_initialized(index);
+    }
+
+    let err = pe.try_build::<Shape>(Some(guard)).unwrap_err();
+    let ReflectError::MissingFields { paths, .. } = err else {
+        panic!("expected missing fields, got {err:?}");
+    };
+    assert_eq!(paths, vec!["Rect.width".to_string()]);
+}
+
+macro_rules! wide_enum {
+    ($name:ident { $($field:ident),* }) => {
+        #[derive(Debug, PartialEq, Facet)]
+        #[repr(u8)]
+        #[allow(dead_code, clippy::large_enum_variant)]
+        enum $name {
+            Narrow,
+            Wide { $($field: u32),* },
+        }
+    };
+}
+
+wide_enum!(WideEnum {
+    f0,
+    f1,
+    f2,
+    f3,
+    f4,
+    f5,
+    f6,
+    f7,
+    f8,
+    f9,
+    f10,
+    f11,
+    f12,
+    f13,
+    f14,
+    f15,
+    f16,
+    f17,
+    f18,
+    f19,
+    f20,
+    f21,
+    f22,
+    f23,
+    f24,
+    f25,
+    f26,
+    f27,
+    f28,
+    f29,
+    f30,
+    f31,
+    f32,
+    f33,
+    f34,
+    f35,
+    f36,
+    f37,
+    f38,
+    f39,
+    f40,
+    f41,
+    f42,
+    f43,
+    f44,
+    f45,
+    f46,
+    f47,
+    f48,
+    f49,
+    f50,
+    f51,
+    f52,
+    f53,
+    f54,
+    f55,
+    f56,
+    f57,
+    f58,
+    f59,
+    f60,
+    f61,
+    f62,
+    f63,
+    f64,
+    f65,
+    f66,
+    f67,
+    f68,
+    f69,
+    f70,
+    f71,
+    f72,
+    f73,
+    f74,
+    f75,
+    f76,
+    f77,
+    f78,
+    f79
+});
+
+#[test]
+fn build_wide_enum_variant() {
+    facet_testhelpers::setup();
+
+    let names = (0..80).map(|i| format!("f{i}")).collect::<Vec<_>>();
+
+    let (poke, guard) = PokeUninit::alloc::<WideEnum>();
+    let mut pe = poke.into_enum().set_variant_by_name("Wide").unwrap();
+    for (i, name) in names.iter().enumerate() {
+        unsafe {
+            let (index, field) = pe.field_by_name(name).unwrap();
+            field.into_value().put(i as u32);
+            pe.mark_initialized(index);
+        }
+    }
+    let WideEnum::Wide { f0, f64, f79, .. } = pe.build::<WideEnum>(Some(guard)) else {
+        panic!("expected the wide variant");
+    };
+    assert_eq!((f0, f64, f79), (0, 64, 79));
+
+    let (poke, guard) = PokeUninit::alloc::<WideEnum>();
+    let mut pe = poke.into_enum().set_variant_by_name("Wide").unwrap();
+    for (i, name) in names.iter().enumerate().filter(|(i, _)| *i != 65) {
+        unsafe {
+            let (index, field) = pe.field_by_name(name).unwrap();
+            field.into_value().put(i as u32);
+            pe.mark_initialized(index);
+        }
+    }
+    let err = pe.try_build::<WideEnum>(Some(guard)).unwrap_err();
+    let ReflectError::MissingFields { paths, .. } = err else {
+        panic!("expected missing fields, got {err:?}");
+    };
+    assert_eq!(paths, vec!["Wide.f65".to_string()]);
+}
+
+#[derive(Facet, PartialEq, Debug)]
+#[repr(u16)]
+#[allow(dead_code)]
+enum Machine {
+    Idle,
+    Running { job: Arc<String>, progress: u32 },
+    Failed(String),
+}
+
+#[test]
+fn borrow_enum_reads_active_variant() {
+    facet_testhelpers::setup();
+
+    let mut machine = Machine::Failed("oops".to_string());
+    let pe = Poke::borrow(&mut machine).into_enum();
+    assert_eq!(pe.selected_variant_index(), 2);
+    // the borrowed fields are initialized, building leaves them be
+    pe.build_in_place();
+    assert_eq!(machine, Machine::Failed("oops".to_string()));
+}
+
+#[test]
+fn switch_variant_of_borrowed_enum() {
+    facet_testhelpers::setup();
+
+    let job = Arc::new("build".to_string());
+    let mut machine = Machine::Running {
+        job: job.clone(),
+        progress: 50,
+    };
+    assert_eq!(Arc::strong_count(&job), 2);
+
+    let mut pe = Poke::borrow(&mut machine).into_enum();
+    assert_eq!(pe.selected_variant_index(), 1);
+    pe.switch_variant_by_name("Failed").unwrap();
+    // the old payload was dropped
+    assert_eq!(Arc::strong_count(&job), 1);
+    unsafe {
+        pe.tuple_field(0)
+            .unwrap()
+            .into_value()
+            .put("cancelled".to_string());
+        pe.mark_initialized(0);
+    }
+    pe.build_in_place();
+    assert_eq!(machine, Machine::Failed("cancelled".to_string()));
+
+    let mut pe = Poke::borrow(&mut machine).into_enum();
+    pe.switch_variant_by_index(0).unwrap();
+    pe.build_in_place();
+    assert_eq!(machine, Machine::Idle);
+
+    let mut pe = Poke::borrow(&mut machine).into_enum();
+    assert!(pe.switch_variant_by_name("Nope").is_err());
+    assert!(pe.switch_variant_by_index(3).is_err());
+    pe.build_in_place();
+    assert_eq!(machine, Machine::Idle);
+}
+
+#[test]
+fn drop_borrowed_enum_without_building() {
+    facet_testhelpers::setup();
+
+    let job = Arc::new("build".to_string());
+    let mut machine = Machine::Running {
+        job: job.clone(),
+        progress: 50,
+    };
+
+    // the payload belongs to `machine`, dropping the poke leaves it be
+    drop(Poke::borrow(&mut machine).into_enum());
+    assert_eq!(Arc::strong_count(&job), 2);
+    assert_eq!(
+        machine,
+        Machine::Running {
+            job: job.clone(),
+            progress: 50
+        }
+    );
+
+    let mut pe = Poke::borrow(&mut machine).into_enum();
+    pe.switch_variant_by_name("Failed").unwrap();
+    assert_eq!(Arc::strong_count(&job), 1);
+    unsafe {
+        pe.tuple_field(0)
+            .unwrap()
+            .into_value()
+            .put("lost".to_string());
+        pe.mark_initialized(0);
+    }
+    drop(pe);
+    assert_eq!(machine, Machine::Failed("lost".to_string()));
+
+    let mut pe = Poke::borrow(&mut machine).into_enum();
+    pe.switch_variant_by_name("Idle").unwrap();
+    drop(pe);
+    assert_eq!(machine, Machine::Idle);
+    drop(machine);
+    assert_eq!(Arc::strong_count(&job), 1);
+}
+
+#[test]
+fn switch_variant_while_building() {
+    facet_testhelpers::setup();
+
+    let job = Arc::new("build".to_string());
+
+    let (poke, guard) = PokeUninit::alloc::<Machine>();
+    let mut pe = poke.into_enum().set_variant_by_name("Running").unwrap();
+    unsafe {
+        let (index, field) = pe.field_by_name("job").unwrap();
+        field.into_value().put(job.clone());
+        pe.mark_initialized(index);
+    }
+    assert_eq!(Arc::strong_count(&job), 2);
+
+    // only the fields that were set are dropped
+    pe.switch_variant_by_name("Running").unwrap();
+    assert_eq!(Arc::strong_count(&job), 1);
+    let err = pe.check_all_fields_initialized().unwrap_err();
+    let ReflectError::MissingFields { paths, .. } = err else {
+        panic!("expected missing fields, got {err:?}");
+    };
+    assert_eq!(paths, ["Running.job", "Running.progress"]);
+
+    pe.switch_variant_by_name("Idle").unwrap();
+    assert_eq!(pe.build::<Machine>(Some(guard)), Machine::Idle);
+}
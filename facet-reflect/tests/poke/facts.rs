@@ -1,12 +1,17 @@
-use std::{cmp::Ordering, collections::HashSet};
+use std::{
+    cmp::Ordering,
+    collections::{HashMap, HashSet, VecDeque},
+    mem::MaybeUninit,
+};
 
 use facet::Facet;
 use facet_ansi::{Style, Stylize as _};
+use facet_core::{OpaqueConst, OpaqueUninit};
 use facet_reflect::{Peek, PokeUninit};
 
-fn check_facts<T>(val1: T, val2: T, expected_facts: HashSet<Fact>)
+fn check_facts<'a, T>(val1: T, val2: T, expected_facts: HashSet<Fact>)
 where
-    T: Facet + 'static,
+    T: Facet<'a> + 'static,
 {
     let mut facts: HashSet<Fact> = HashSet::new();
     let name = format!("{}", T::SHAPE);
@@ -554,6 +559,53 @@ fn test_array_traits() {
     );
 }
 
+/// Clones a value through its shape's `clone_into`
+fn clone_via_vtable<'a, T: Facet<'a>>(value: &T) -> T {
+    let clone_into = T::SHAPE.vtable.clone_into.expect("the shape is Clone");
+    let mut clone = MaybeUninit::<T>::uninit();
+    unsafe {
+        clone_into(
+            OpaqueConst::new(value),
+            OpaqueUninit::from_maybe_uninit(&mut clone),
+        );
+        clone.assume_init()
+    }
+}
+
+#[test]
+fn test_collections_clone_their_items() {
+    facet_testhelpers::setup();
+
+    let deque = VecDeque::from(["hello".to_string(), "world".to_string()]);
+    let clone = clone_via_vtable(&deque);
+    assert_eq!(clone, deque);
+    assert_ne!(clone[0].as_ptr(), deque[0].as_ptr());
+    drop(deque);
+    assert_eq!(clone[1], "world");
+
+    let vec = vec![vec![1, 2], vec![3]];
+    let clone = clone_via_vtable(&vec);
+    assert_eq!(clone, vec);
+    assert_ne!(clone[0].as_ptr(), vec[0].as_ptr());
+
+    let map = HashMap::from([("key".to_string(), vec!["value".to_string()])]);
+    let clone = clone_via_vtable(&map);
+    assert_eq!(clone, map);
+    assert_ne!(clone["key"].as_ptr(), map["key"].as_ptr());
+
+    let string = "owned".to_string();
+    let clone = clone_via_vtable(&string);
+    assert_eq!(clone, string);
+    assert_ne!(clone.as_ptr(), string.as_ptr());
+
+    // collections of items that aren't Clone aren't Clone either
+    #[derive(Facet)]
+    struct NotClone(u32);
+    assert!(<VecDeque<NotClone>>::SHAPE.vtable.clone_into.is_none());
+    assert!(<Vec<NotClone>>::SHAPE.vtable.clone_into.is_none());
+    assert!(<HashMap<u32, NotClone>>::SHAPE.vtable.clone_into.is_none());
+}
+
 #[test]
 fn test_vecs() {
     facet_testhelpers::setup();
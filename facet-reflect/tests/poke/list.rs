@@ -0,0 +1,114 @@
+use std::collections::VecDeque;
+use std::mem::ManuallyDrop;
+use std::sync::Arc;
+
+use facet_core::Opaque;
+use facet_reflect::{Peek, Poke, ReflectError};
+
+#[test]
+fn poke_vec_insert_remove_truncate() {
+    facet_testhelpers::setup();
+
+    let one = Arc::new(1);
+    let mut list = vec![one.clone(), Arc::new(2), Arc::new(3)];
+
+    let mut pl = Poke::borrow(&mut list).into_list();
+    let mut zero = ManuallyDrop::new(Arc::new(0));
+    unsafe { pl.insert(0, Opaque::new(&mut *zero)).unwrap() };
+    assert_eq!(pl.len(), 4);
+
+    pl.remove(1).unwrap();
+    // the removed item was dropped
+    assert_eq!(Arc::strong_count(&one), 1);
+
+    pl.swap(0, 2).unwrap();
+    pl.truncate(2).unwrap();
+    pl.truncate(5).unwrap();
+    pl.build_in_place();
+
+    assert_eq!(list, vec![Arc::new(3), Arc::new(2)]);
+}
+
+#[test]
+fn poke_vec_item_mut() {
+    facet_testhelpers::setup();
+
+    let mut list = vec!["a".to_string(), "b".to_string()];
+
+    let mut pl = Poke::borrow(&mut list).into_list();
+    pl.item_mut(1).unwrap().replace("c".to_string());
+    pl.build_in_place();
+
+    assert_eq!(list, ["a", "c"]);
+}
+
+#[test]
+fn poke_vecdeque() {
+    facet_testhelpers::setup();
+
+    let mut list = VecDeque::from([1u32, 2, 3]);
+
+    let mut pl = Poke::borrow(&mut list).into_list();
+    let mut four = 4u32;
+    unsafe { pl.insert(3, Opaque::new(&mut four)).unwrap() };
+    pl.remove(0).unwrap();
+    pl.item_mut(0).unwrap().replace(20u32);
+    pl.swap(1, 2).unwrap();
+    pl.truncate(2).unwrap();
+    pl.build_in_place();
+
+    assert_eq!(list, [20, 4]);
+}
+
+#[test]
+fn poke_array() {
+    facet_testhelpers::setup();
+
+    let mut array = [1u32, 2, 3];
+
+    let mut pl = Poke::borrow(&mut array).into_list();
+    pl.item_mut(1).unwrap().replace(20u32);
+    pl.swap(0, 2).unwrap();
+    assert_eq!(
+        unsafe { pl.get_item_ptr(1).read::<u32>() },
+        20,
+        "items are found by index"
+    );
+
+    // arrays can't change size
+    assert!(matches!(
+        pl.remove(0),
+        Err(ReflectError::OperationNotSupported {
+            operation: "remove",
+            ..
+        })
+    ));
+    assert!(pl.truncate(1).is_err());
+    let mut four = 4u32;
+    assert!(unsafe { pl.insert(0, Opaque::new(&mut four)) }.is_err());
+    pl.build_in_place();
+
+    assert_eq!(array, [3, 20, 1]);
+    let Peek::List(peek) = Peek::new(&array) else {
+        panic!("arrays are lists");
+    };
+    let items: Vec<String> = peek.iter().map(|item| item.to_string()).collect();
+    assert_eq!(items, ["3", "20", "1"]);
+}
+
+#[test]
+fn poke_slice_is_read_only() {
+    facet_testhelpers::setup();
+
+    let items = [1u32, 2];
+    let mut slice: &[u32] = &items;
+
+    let mut pl = Poke::borrow(&mut slice).into_list();
+    assert_eq!(pl.len(), 2);
+    let err = pl.item_mut(0).unwrap_err();
+    assert_eq!(err.to_string(), "&[u32] does not support item_mut");
+    assert!(pl.swap(0, 1).is_err());
+    assert!(pl.remove(0).is_err());
+    assert_eq!(unsafe { pl.get_item_ptr(1).read::<u32>() }, 2);
+    pl.build_in_place();
+}
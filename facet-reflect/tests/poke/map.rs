@@ -0,0 +1,57 @@
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+
+use facet_core::OpaqueConst;
+use facet_reflect::Poke;
+
+#[test]
+fn poke_hashmap_get_mut_remove_clear() {
+    facet_testhelpers::setup();
+
+    let value = Arc::new(1);
+    let mut map = HashMap::from([
+        ("a".to_string(), value.clone()),
+        ("b".to_string(), Arc::new(2)),
+    ]);
+
+    let a = "a".to_string();
+    let b = "b".to_string();
+    let mut pm = Poke::borrow(&mut map).into_map();
+    pm.get_mut(OpaqueConst::new(&b))
+        .unwrap()
+        .replace(Arc::new(20));
+    assert!(pm.get_mut(OpaqueConst::new(&"c".to_string())).is_none());
+
+    assert!(pm.remove(OpaqueConst::new(&a)));
+    assert!(!pm.remove(OpaqueConst::new(&a)));
+    // the removed value was dropped
+    assert_eq!(Arc::strong_count(&value), 1);
+    pm.build_in_place();
+    assert_eq!(map, HashMap::from([(b.clone(), Arc::new(20))]));
+
+    let mut pm = Poke::borrow(&mut map).into_map();
+    pm.clear();
+    assert!(pm.is_empty());
+    pm.build_in_place();
+    assert!(map.is_empty());
+}
+
+#[test]
+fn poke_btreemap_get_mut_remove_clear() {
+    facet_testhelpers::setup();
+
+    let mut map = BTreeMap::from([(1u32, "one".to_string()), (2, "two".to_string())]);
+
+    let mut pm = Poke::borrow(&mut map).into_map();
+    pm.get_mut(OpaqueConst::new(&1u32))
+        .unwrap()
+        .replace("uno".to_string());
+    assert!(pm.remove(OpaqueConst::new(&2u32)));
+    pm.build_in_place();
+    assert_eq!(map, BTreeMap::from([(1, "uno".to_string())]));
+
+    let mut pm = Poke::borrow(&mut map).into_map();
+    pm.clear();
+    pm.build_in_place();
+    assert!(map.is_empty());
+}
@@ -1,5 +1,7 @@
 mod c_test;
 mod enum_;
 mod facts;
+mod list;
+mod map;
 mod option;
 mod struct_;
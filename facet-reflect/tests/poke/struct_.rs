@@ -1,5 +1,5 @@
 use facet::{Facet, OpaqueConst, OpaqueUninit};
-use facet_reflect::PokeUninit;
+use facet_reflect::{ISet, PokeStructFlattened, PokeUninit, ReflectError};
 
 use std::fmt::Debug;
 
@@ -139,3 +139,318 @@ fn build_u64_properly() {
     // Verify the value was set correctly
     assert_eq!(value, 42);
 }
+
+#[test]
+fn build_fills_in_defaults() {
+    facet_testhelpers::setup();
+
+    fn default_port() -> u16 {
+        8080
+    }
+
+    #[derive(Debug, PartialEq, Facet)]
+    struct Config {
+        host: String,
+        #[facet(default)]
+        tags: Vec<String>,
+        #[facet(default = default_port)]
+        port: u16,
+    }
+
+    let (poke, guard) = PokeUninit::alloc::<Config>();
+    let mut poke = poke.into_struct();
+    poke.set_by_name("host", String::from("localhost")).unwrap();
+    let config = poke.build::<Config>(Some(guard));
+    assert_eq!(
+        config,
+        Config {
+            host: "localhost".to_string(),
+            tags: vec![],
+            port: 8080,
+        }
+    );
+
+    // Fields that were set explicitly are left alone
+    let (poke, guard) = PokeUninit::alloc::<Config>();
+    let mut poke = poke.into_struct();
+    poke.set_by_name("host", String::from("localhost")).unwrap();
+    poke.set_by_name("port", 443u16).unwrap();
+    let config = poke.build::<Config>(Some(guard));
+    assert_eq!(config.port, 443);
+}
+
+#[test]
+#[should_panic(expected = "Field 'host' was not initialized")]
+fn build_without_default_still_panics() {
+    facet_testhelpers::setup();
+
+    #[derive(Debug, PartialEq, Facet)]
+    struct Config {
+        host: String,
+        #[facet(default)]
+        port: u16,
+    }
+
+    let (poke, guard) = PokeUninit::alloc::<Config>();
+    let poke = poke.into_struct();
+    let _config = poke.build::<Config>(Some(guard));
+}
+
+#[test]
+fn try_build_lists_every_missing_field() {
+    facet_testhelpers::setup();
+
+    #[derive(Debug, PartialEq, Facet)]
+    struct Config {
+        host: String,
+        #[facet(default)]
+        port: u16,
+        user: String,
+        password: String,
+    }
+
+    let (poke, guard) = PokeUninit::alloc::<Config>();
+    let mut poke = poke.into_struct();
+    poke.set_by_name("user", String::from("root")).unwrap();
+
+    let err = poke.try_build::<Config>(Some(guard)).unwrap_err();
+    assert_eq!(
+        err,
+        ReflectError::MissingFields {
+            shape: Config::SHAPE,
+            paths: vec!["host".to_string(), "password".to_string()],
+        }
+    );
+    assert_eq!(
+        err.to_string(),
+        "missing fields in Config: `host`, `password`"
+    );
+}
+
+#[test]
+fn try_build_succeeds_when_complete() {
+    facet_testhelpers::setup();
+
+    let (poke, guard) = PokeUninit::alloc::<FooBar>();
+    let mut poke = poke.into_struct();
+    poke.set_by_name("foo", 42u64).unwrap();
+    poke.set_by_name("bar", String::from("baz")).unwrap();
+
+    let foo_bar = poke.try_build::<FooBar>(Some(guard)).unwrap();
+    assert_eq!(
+        foo_bar,
+        FooBar {
+            foo: 42,
+            bar: "baz".to_string()
+        }
+    );
+}
+
+#[test]
+fn flattened_struct_routes_fields_to_the_inner_struct() {
+    facet_testhelpers::setup();
+
+    #[derive(Debug, PartialEq, Facet)]
+    struct Inner {
+        b: u64,
+    }
+
+    #[derive(Debug, PartialEq, Facet)]
+    struct Outer {
+        a: u64,
+        #[facet(flatten)]
+        inner: Inner,
+    }
+
+    let (poke, guard) = PokeUninit::alloc::<Outer>();
+    let mut ps = PokeStructFlattened::new(poke.into_struct());
+
+    // the flattened field itself can't be set by name
+    assert!(ps.field_by_serialized_name("inner").is_err());
+
+    for (name, value) in [("b", 2u64), ("a", 1u64)] {
+        let (index, field) = ps.field_by_serialized_name(name).unwrap();
+        field.into_value().put(value);
+        unsafe { ps.mark_initialized(index) };
+    }
+
+    let opaque = ps.try_build_in_place().unwrap();
+    let outer = unsafe { opaque.read::<Outer>() };
+    drop(guard);
+    assert_eq!(
+        outer,
+        Outer {
+            a: 1,
+            inner: Inner { b: 2 }
+        }
+    );
+}
+
+#[test]
+fn iset_beyond_64_fields() {
+    facet_testhelpers::setup();
+
+    let mut iset = ISet::default();
+    assert!(iset.all_set(0));
+    assert!(!iset.has(200));
+
+    for index in 0..130 {
+        assert!(!iset.all_set(index + 1));
+        iset.set(index);
+        assert!(iset.has(index));
+        assert!(iset.all_set(index + 1));
+    }
+    assert!(!iset.all_set(131));
+
+    iset.unset(64);
+    assert!(!iset.has(64));
+    assert!(iset.has(63) && iset.has(65));
+    assert!(iset.all_set(64));
+    assert!(!iset.all_set(65));
+
+    // unsetting what was never allocated is a no-op
+    iset.unset(1000);
+    assert!(!iset.has(1000));
+}
+
+macro_rules! wide_struct {
+    ($name:ident { $($field:ident),* }) => {
+        #[derive(Debug, PartialEq, Facet)]
+        struct $name {
+            $($field: u32),*
+        }
+    };
+}
+
+wide_struct!(Wide {
+    f0,
+    f1,
+    f2,
+    f3,
+    f4,
+    f5,
+    f6,
+    f7,
+    f8,
+    f9,
+    f10,
+    f11,
+    f12,
+    f13,
+    f14,
+    f15,
+    f16,
+    f17,
+    f18,
+    f19,
+    f20,
+    f21,
+    f22,
+    f23,
+    f24,
+    f25,
+    f26,
+    f27,
+    f28,
+    f29,
+    f30,
+    f31,
+    f32,
+    f33,
+    f34,
+    f35,
+    f36,
+    f37,
+    f38,
+    f39,
+    f40,
+    f41,
+    f42,
+    f43,
+    f44,
+    f45,
+    f46,
+    f47,
+    f48,
+    f49,
+    f50,
+    f51,
+    f52,
+    f53,
+    f54,
+    f55,
+    f56,
+    f57,
+    f58,
+    f59,
+    f60,
+    f61,
+    f62,
+    f63,
+    f64,
+    f65,
+    f66,
+    f67,
+    f68,
+    f69,
+    f70,
+    f71,
+    f72,
+    f73,
+    f74,
+    f75,
+    f76,
+    f77,
+    f78,
+    f79,
+    f80,
+    f81,
+    f82,
+    f83,
+    f84,
+    f85,
+    f86,
+    f87,
+    f88,
+    f89,
+    f90,
+    f91,
+    f92,
+    f93,
+    f94,
+    f95,
+    f96,
+    f97,
+    f98,
+    f99
+});
+
+#[test]
+fn build_wide_struct() {
+    facet_testhelpers::setup();
+
+    let (poke, guard) = PokeUninit::alloc::<Wide>();
+    let mut poke = poke.into_struct();
+    let field_count = poke.def().fields.len();
+    assert_eq!(field_count, 100);
+    for index in 0..field_count {
+        poke.set(index, index as u32).unwrap();
+    }
+    let wide = poke.build::<Wide>(Some(guard));
+    assert_eq!(wide.f0, 0);
+    assert_eq!(wide.f63, 63);
+    assert_eq!(wide.f64, 64);
+    assert_eq!(wide.f99, 99);
+
+    // fields past the 64th are tracked too
+    let (poke, guard) = PokeUninit::alloc::<Wide>();
+    let mut poke = poke.into_struct();
+    for index in (0..field_count).filter(|&i| i != 70 && i != 99) {
+        poke.set(index, index as u32).unwrap();
+    }
+    let err = poke.try_build::<Wide>(Some(guard)).unwrap_err();
+    let ReflectError::MissingFields { paths, .. } = err else {
+        panic!("expected missing fields, got {err:?}");
+    };
+    assert_eq!(paths, ["f70", "f99"]);
+}
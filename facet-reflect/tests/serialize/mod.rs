@@ -0,0 +1,474 @@
+use std::collections::BTreeMap;
+
+use facet::Facet;
+use facet_core::Field;
+use facet_reflect::{Peek, PeekEnum, PeekValue, SerializeError, Serializer, serialize};
+
+/// Records what the driver tells it, one event per entry
+#[derive(Default)]
+struct Events {
+    events: Vec<String>,
+    tuples_as_lists: bool,
+    redact_sensitive: bool,
+    variants_as_declared: bool,
+    /// Whether the start and end of values are recorded; strings are then skipped
+    values: bool,
+}
+
+impl Events {
+    fn of<'a, T: Facet<'a>>(value: &T) -> Vec<String> {
+        Self::with(value, |_| {})
+    }
+
+    fn with<'a, T: Facet<'a>>(value: &T, configure: impl FnOnce(&mut Self)) -> Vec<String> {
+        let mut events = Events {
+            tuples_as_lists: true,
+            ..Default::default()
+        };
+        configure(&mut events);
+        serialize(Peek::new(value), &mut events).unwrap();
+        events.events
+    }
+
+    fn push(&mut self, event: String) -> Result<(), SerializeError> {
+        self.events.push(event);
+        Ok(())
+    }
+}
+
+impl Serializer for Events {
+    type Error = SerializeError;
+
+    fn scalar(&mut self, value: PeekValue<'_>) -> Result<(), SerializeError> {
+        self.push(format!("{}", Peek::Value(value)))
+    }
+
+    fn str(&mut self, s: &str) -> Result<(), SerializeError> {
+        self.push(format!("str {s}"))
+    }
+
+    fn none(&mut self) -> Result<(), SerializeError> {
+        self.push("none".to_string())
+    }
+
+    fn start_struct(&mut self, len: usize) -> Result<(), SerializeError> {
+        self.push(format!("struct {len}"))
+    }
+
+    fn field(&mut self, name: &str) -> Result<(), SerializeError> {
+        self.push(format!(".{name}"))
+    }
+
+    fn end_struct(&mut self) -> Result<(), SerializeError> {
+        self.push("end struct".to_string())
+    }
+
+    fn start_list(&mut self, len: usize) -> Result<(), SerializeError> {
+        self.push(format!("list {len}"))
+    }
+
+    fn list_item(&mut self) -> Result<(), SerializeError> {
+        self.push("-".to_string())
+    }
+
+    fn end_list(&mut self) -> Result<(), SerializeError> {
+        self.push("end list".to_string())
+    }
+
+    fn start_map(&mut self, len: usize) -> Result<(), SerializeError> {
+        self.push(format!("map {len}"))
+    }
+
+    fn map_value(&mut self) -> Result<(), SerializeError> {
+        self.push("=>".to_string())
+    }
+
+    fn end_map(&mut self) -> Result<(), SerializeError> {
+        self.push("end map".to_string())
+    }
+
+    fn tuples_as_lists(&self) -> bool {
+        self.tuples_as_lists
+    }
+
+    fn redact_sensitive(&self) -> bool {
+        self.redact_sensitive
+    }
+
+    fn redacted(&mut self, field: &'static Field) -> Result<(), SerializeError> {
+        self.push(format!("redacted {}", field.name))
+    }
+
+    fn start_value(&mut self, value: Peek<'_>) -> Result<bool, SerializeError> {
+        if !self.values {
+            return Ok(true);
+        }
+        if value.shape().is_type::<String>() {
+            self.push("skipped".to_string())?;
+            return Ok(false);
+        }
+        self.push(format!("<{}", value.shape()))?;
+        Ok(true)
+    }
+
+    fn end_value(&mut self, value: Peek<'_>) -> Result<(), SerializeError> {
+        if self.values {
+            self.push(format!("{}>", value.shape()))?;
+        }
+        Ok(())
+    }
+
+    fn variants_as_declared(&self) -> bool {
+        self.variants_as_declared
+    }
+
+    fn variant(&mut self, value: PeekEnum<'_>) -> Result<(), SerializeError> {
+        self.push(format!("variant {}", value.active_variant().name))
+    }
+}
+
+#[test]
+fn serialize_struct_fields() {
+    facet_testhelpers::setup();
+
+    #[derive(Facet)]
+    struct Inner {
+        x: u32,
+    }
+
+    #[derive(Facet)]
+    struct Outer {
+        #[facet(rename = "renamed")]
+        name: String,
+        #[facet(skip_serializing)]
+        #[allow(dead_code)]
+        skipped: u32,
+        #[facet(skip_serializing_if = "Option::is_none")]
+        maybe: Option<u32>,
+        #[facet(flatten)]
+        inner: Inner,
+        #[facet(sensitive)]
+        secret: String,
+    }
+
+    let value = Outer {
+        name: "n".to_string(),
+        skipped: 1,
+        maybe: None,
+        inner: Inner { x: 2 },
+        secret: "hunter2".to_string(),
+    };
+    assert_eq!(
+        Events::of(&value),
+        [
+            "struct 3",
+            ".renamed",
+            "n",
+            ".x",
+            "2",
+            ".secret",
+            "hunter2",
+            "end struct"
+        ]
+    );
+    assert_eq!(
+        Events::with(&value, |e| e.redact_sensitive = true),
+        [
+            "struct 3",
+            ".renamed",
+            "n",
+            ".x",
+            "2",
+            ".secret",
+            "redacted secret",
+            "end struct"
+        ]
+    );
+}
+
+#[test]
+fn serialize_tuples() {
+    facet_testhelpers::setup();
+
+    #[derive(Facet)]
+    struct Pair(u32, String);
+
+    #[derive(Facet)]
+    struct Unit;
+
+    let pair = Pair(1, "a".to_string());
+    assert_eq!(
+        Events::of(&pair),
+        ["list 2", "-", "1", "-", "a", "end list"]
+    );
+    assert_eq!(
+        Events::with(&pair, |e| e.tuples_as_lists = false),
+        ["struct 2", ".0", "1", ".1", "a", "end struct"]
+    );
+    assert_eq!(Events::of(&Unit), ["none"]);
+    assert_eq!(
+        Events::with(&Unit, |e| e.tuples_as_lists = false),
+        ["struct 0", "end struct"]
+    );
+}
+
+#[test]
+fn serialize_collections() {
+    facet_testhelpers::setup();
+
+    #[derive(Facet)]
+    struct Collections {
+        bytes: Vec<u8>,
+        nested: Vec<Option<Box<u32>>>,
+        map: BTreeMap<String, u32>,
+    }
+
+    let value = Collections {
+        bytes: vec![7],
+        nested: vec![Some(Box::new(1)), None],
+        map: BTreeMap::from([("k".to_string(), 3)]),
+    };
+    assert_eq!(
+        Events::of(&value),
+        [
+            "struct 3",
+            ".bytes",
+            "list 1",
+            "-",
+            "7",
+            "end list",
+            ".nested",
+            "list 2",
+            "-",
+            "1",
+            "-",
+            "none",
+            "end list",
+            ".map",
+            "map 1",
+            "k",
+            "=>",
+            "3",
+            "end map",
+            "end struct"
+        ]
+    );
+}
+
+#[test]
+fn serialize_enum_tagging() {
+    facet_testhelpers::setup();
+
+    #[derive(Facet)]
+    #[repr(u8)]
+    #[allow(dead_code)]
+    enum External {
+        Unit,
+        Newtype(u32),
+        Tuple(u32, u32),
+        Struct { a: u32 },
+    }
+
+    assert_eq!(Events::of(&External::Unit), ["str Unit"]);
+    assert_eq!(
+        Events::of(&External::Newtype(1)),
+        ["struct 1", ".Newtype", "1", "end struct"]
+    );
+    assert_eq!(
+        Events::of(&External::Tuple(1, 2)),
+        [
+            "struct 1",
+            ".Tuple",
+            "list 2",
+            "-",
+            "1",
+            "-",
+            "2",
+            "end list",
+            "end struct"
+        ]
+    );
+    assert_eq!(
+        Events::of(&External::Struct { a: 1 }),
+        [
+            "struct 1",
+            ".Struct",
+            "struct 1",
+            ".a",
+            "1",
+            "end struct",
+            "end struct"
+        ]
+    );
+
+    #[derive(Facet)]
+    #[repr(u8)]
+    #[facet(tag = "t")]
+    #[allow(dead_code)]
+    enum Internal {
+        Unit,
+        Struct { a: u32 },
+        Tuple(u32, u32),
+    }
+
+    assert_eq!(
+        Events::of(&Internal::Struct { a: 1 }),
+        ["struct 2", ".t", "str Struct", ".a", "1", "end struct"]
+    );
+    assert_eq!(
+        Events::of(&Internal::Unit),
+        ["struct 1", ".t", "str Unit", "end struct"]
+    );
+    let err = serialize(Peek::new(&Internal::Tuple(1, 2)), &mut Events::default()).unwrap_err();
+    assert!(
+        matches!(
+            err,
+            SerializeError::InternallyTaggedTuple {
+                variant: "Tuple",
+                ..
+            }
+        ),
+        "{err}"
+    );
+
+    #[derive(Facet)]
+    #[repr(u8)]
+    #[facet(tag = "t")]
+    #[facet(content = "c")]
+    #[allow(dead_code)]
+    enum Adjacent {
+        Unit,
+        Newtype(u32),
+    }
+
+    assert_eq!(
+        Events::of(&Adjacent::Unit),
+        ["struct 1", ".t", "str Unit", "end struct"]
+    );
+    assert_eq!(
+        Events::of(&Adjacent::Newtype(1)),
+        ["struct 2", ".t", "str Newtype", ".c", "1", "end struct"]
+    );
+
+    #[derive(Facet)]
+    #[repr(u8)]
+    #[facet(untagged)]
+    #[allow(dead_code)]
+    enum Untagged {
+        Unit,
+        Newtype(u32),
+    }
+
+    assert_eq!(Events::of(&Untagged::Unit), ["none"]);
+    assert_eq!(Events::of(&Untagged::Newtype(1)), ["1"]);
+}
+
+#[test]
+fn serialize_variants_as_declared() {
+    facet_testhelpers::setup();
+
+    #[derive(Facet)]
+    #[repr(u8)]
+    #[facet(tag = "t")]
+    #[allow(dead_code)]
+    enum Tagged {
+        Unit,
+        Newtype(u32),
+        Struct { a: u32 },
+    }
+
+    let declared = |e: &mut Events| e.variants_as_declared = true;
+    assert_eq!(Events::with(&Tagged::Unit, declared), ["variant Unit"]);
+    assert_eq!(
+        Events::with(&Tagged::Newtype(1), declared),
+        ["variant Newtype", "list 1", "-", "1", "end list"]
+    );
+    assert_eq!(
+        Events::with(&Tagged::Struct { a: 1 }, declared),
+        ["variant Struct", "struct 1", ".a", "1", "end struct"]
+    );
+}
+
+#[test]
+fn serialize_value_hooks() {
+    facet_testhelpers::setup();
+
+    #[derive(Facet)]
+    struct Named {
+        name: String,
+        id: Option<u32>,
+    }
+
+    let value = Named {
+        name: "a".to_string(),
+        id: Some(1),
+    };
+    assert_eq!(
+        Events::with(&value, |e| e.values = true),
+        [
+            "<Named",
+            "struct 2",
+            ".name",
+            "skipped",
+            ".id",
+            "<Option",
+            "<u32",
+            "1",
+            "u32>",
+            "Option>",
+            "end struct",
+            "Named>"
+        ]
+    );
+}
+
+#[test]
+fn serialize_variant_fields() {
+    facet_testhelpers::setup();
+
+    #[derive(Facet)]
+    struct Inner {
+        x: u32,
+    }
+
+    #[derive(Facet)]
+    #[repr(u8)]
+    #[allow(dead_code)]
+    enum Secret {
+        Token(#[facet(sensitive)] String),
+        Nested {
+            id: u32,
+            #[facet(flatten)]
+            inner: Inner,
+        },
+    }
+
+    let token = Secret::Token("hunter2".to_string());
+    assert_eq!(
+        Events::with(&token, |e| e.redact_sensitive = true),
+        ["struct 1", ".Token", "redacted _0", "end struct"]
+    );
+    assert_eq!(
+        Events::of(&token),
+        ["struct 1", ".Token", "hunter2", "end struct"]
+    );
+
+    let nested = Secret::Nested {
+        id: 1,
+        inner: Inner { x: 2 },
+    };
+    assert_eq!(
+        Events::of(&nested),
+        [
+            "struct 1",
+            ".Nested",
+            "struct 2",
+            ".id",
+            "1",
+            ".x",
+            "2",
+            "end struct",
+            "end struct"
+        ]
+    );
+}
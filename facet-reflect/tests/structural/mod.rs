@@ -0,0 +1,229 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::hash::{DefaultHasher, Hasher};
+use std::sync::Arc;
+
+use facet::Facet;
+use facet_reflect::{Peek, ReflectError, deep_clone, structural_eq, structural_hash};
+use facet_samplelibc::{Bar, Foo};
+
+fn hash(value: Peek<'_>) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    assert!(structural_hash(value, &mut hasher), "{value} can be hashed");
+    hasher.finish()
+}
+
+#[test]
+fn ffi_struct_without_std_traits() {
+    facet_testhelpers::setup();
+
+    let foo = Foo {
+        x: 1,
+        bar: Bar { a: 2, b: 3 },
+        y: 4,
+    };
+
+    let clone = deep_clone(Peek::new(&foo)).unwrap();
+    assert_eq!(structural_eq(clone.peek(), Peek::new(&foo)), Some(true));
+    assert_eq!(hash(clone.peek()), hash(Peek::new(&foo)));
+
+    let clone = clone.materialize::<Foo>().unwrap();
+    assert_eq!((clone.x, clone.bar.a, clone.bar.b, clone.y), (1, 2, 3, 4));
+
+    let other = Foo {
+        bar: Bar { a: 2, b: 30 },
+        ..clone
+    };
+    assert_eq!(
+        structural_eq(Peek::new(&other), Peek::new(&foo)),
+        Some(false)
+    );
+    assert_ne!(hash(Peek::new(&other)), hash(Peek::new(&foo)));
+
+    // values of different types are never equal
+    let bar = Bar { a: 2, b: 3 };
+    assert_eq!(structural_eq(Peek::new(&bar), Peek::new(&foo)), Some(false));
+    let err = deep_clone(Peek::new(&bar))
+        .unwrap()
+        .materialize::<Foo>()
+        .unwrap_err();
+    assert_eq!(err.shape(), Bar::SHAPE);
+}
+
+#[derive(Facet, Debug)]
+struct Item {
+    name: String,
+    weight: u32,
+}
+
+#[derive(Facet, Debug, PartialEq, Eq, Hash)]
+struct Tag(String);
+
+#[derive(Facet, Debug)]
+#[repr(u8)]
+#[allow(dead_code)]
+enum Slot {
+    Empty,
+    One(Item),
+    Two { first: Item, count: u32 },
+}
+
+#[derive(Facet, Debug)]
+struct Inventory {
+    items: Vec<Item>,
+    by_name: HashMap<String, Item>,
+    tags: HashSet<Tag>,
+    favorite: Option<Item>,
+    boxed: Box<Item>,
+    shared: Arc<Item>,
+    slots: Vec<Slot>,
+}
+
+fn item(name: &str, weight: u32) -> Item {
+    Item {
+        name: name.to_string(),
+        weight,
+    }
+}
+
+fn inventory() -> Inventory {
+    Inventory {
+        items: vec![item("a", 1), item("b", 2)],
+        by_name: HashMap::from([("c".to_string(), item("c", 3))]),
+        tags: HashSet::from([Tag("x".to_string()), Tag("y".to_string())]),
+        favorite: Some(item("d", 4)),
+        boxed: Box::new(item("e", 5)),
+        shared: Arc::new(item("f", 6)),
+        slots: vec![
+            Slot::Empty,
+            Slot::One(item("g", 7)),
+            Slot::Two {
+                first: item("h", 8),
+                count: 2,
+            },
+        ],
+    }
+}
+
+#[test]
+fn composite_values() {
+    facet_testhelpers::setup();
+
+    let value = inventory();
+    let clone = deep_clone(Peek::new(&value))
+        .unwrap()
+        .materialize::<Inventory>()
+        .unwrap();
+
+    assert_eq!(
+        structural_eq(Peek::new(&clone), Peek::new(&value)),
+        Some(true)
+    );
+    assert_eq!(hash(Peek::new(&clone)), hash(Peek::new(&value)));
+    assert_eq!(clone.by_name["c"].weight, 3);
+    assert_eq!(clone.boxed.name, "e");
+    assert!(matches!(&clone.slots[2], Slot::Two { first, count: 2 } if first.name == "h"));
+    // Arc implements Clone, the pointee is shared rather than copied
+    assert!(Arc::ptr_eq(&clone.shared, &value.shared));
+
+    let changes: [fn(&mut Inventory); 6] = [
+        |v| v.items[1].weight = 20,
+        |v| v.by_name.get_mut("c").unwrap().name = "z".to_string(),
+        |v| _ = v.tags.remove(&Tag("x".to_string())),
+        |v| v.favorite = None,
+        |v| v.slots[1] = Slot::Empty,
+        |v| {
+            if let Slot::Two { count, .. } = &mut v.slots[2] {
+                *count = 3;
+            }
+        },
+    ];
+    for change in changes {
+        let mut other = inventory();
+        change(&mut other);
+        assert_eq!(
+            structural_eq(Peek::new(&other), Peek::new(&value)),
+            Some(false)
+        );
+        assert_ne!(hash(Peek::new(&other)), hash(Peek::new(&value)));
+    }
+}
+
+#[test]
+fn map_hash_ignores_order() {
+    facet_testhelpers::setup();
+
+    let mut a = HashMap::new();
+    let mut b = HashMap::new();
+    for i in 0..32 {
+        a.insert(i, item("i", i));
+        b.insert(31 - i, item("i", 31 - i));
+    }
+    assert_eq!(structural_eq(Peek::new(&a), Peek::new(&b)), Some(true));
+    assert_eq!(hash(Peek::new(&a)), hash(Peek::new(&b)));
+
+    b.insert(32, item("i", 32));
+    assert_eq!(structural_eq(Peek::new(&a), Peek::new(&b)), Some(false));
+
+    let sorted = BTreeMap::from([(1, item("one", 1))]);
+    let clone = deep_clone(Peek::new(&sorted)).unwrap();
+    assert_eq!(structural_eq(clone.peek(), Peek::new(&sorted)), Some(true));
+}
+
+#[test]
+fn unsupported_parts() {
+    facet_testhelpers::setup();
+
+    #[derive(Facet, Debug)]
+    struct Partial {
+        shared: Arc<u32>,
+        items: Vec<Arc<u32>>,
+        fixed: [Item; 1],
+    }
+
+    let shared = Arc::new(1);
+    let value = Partial {
+        shared: shared.clone(),
+        items: vec![shared.clone()],
+        fixed: [item("a", 1)],
+    };
+
+    // arrays of items that don't implement Clone can't be built item by item
+    let err = deep_clone(Peek::new(&value)).unwrap_err();
+    assert_eq!(
+        err,
+        ReflectError::OperationNotSupported {
+            shape: <[Item; 1]>::SHAPE,
+            operation: "deep_clone",
+        }
+    );
+    // the fields cloned before the error were dropped
+    assert_eq!(Arc::strong_count(&shared), 3);
+    let list = vec![value];
+    assert!(deep_clone(Peek::new(&list)).is_err());
+    assert_eq!(Arc::strong_count(&shared), 3);
+
+    // but they can still be compared
+    assert_eq!(
+        structural_eq(Peek::new(&list), Peek::new(&list)),
+        Some(true)
+    );
+
+    #[derive(Facet)]
+    struct Measure {
+        label: String,
+        value: f64,
+    }
+
+    let a = Measure {
+        label: "a".to_string(),
+        value: 1.0,
+    };
+    let b = Measure {
+        label: "b".to_string(),
+        value: f64::NAN,
+    };
+    // f64 doesn't implement Hash
+    assert!(!structural_hash(Peek::new(&a), &mut DefaultHasher::new()));
+    assert_eq!(structural_eq(Peek::new(&a), Peek::new(&b)), Some(false));
+    assert_eq!(structural_eq(Peek::new(&b), Peek::new(&b)), Some(false));
+}
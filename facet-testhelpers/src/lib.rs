@@ -1,6 +1,7 @@
 use facet_ansi::{Style, Stylize as _};
 use log::{Level, LevelFilter, Log, Metadata, Record};
 use std::io::Write;
+use std::sync::Once;
 
 struct SimpleLogger;
 
@@ -32,9 +33,14 @@ impl Log for SimpleLogger {
 }
 
 pub fn setup() {
-    #[cfg(not(miri))]
-    color_backtrace::install();
-    let logger = Box::new(SimpleLogger);
-    log::set_boxed_logger(logger).unwrap();
-    log::set_max_level(LevelFilter::Trace);
+    // `cargo test` runs every test of a binary in the same process, so this
+    // may be called many times — only the first call installs anything.
+    static SETUP: Once = Once::new();
+    SETUP.call_once(|| {
+        #[cfg(not(miri))]
+        color_backtrace::install();
+        let logger = Box::new(SimpleLogger);
+        log::set_boxed_logger(logger).unwrap();
+        log::set_max_level(LevelFilter::Trace);
+    });
 }
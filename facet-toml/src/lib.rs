@@ -1,20 +1,21 @@
 #![warn(missing_docs)]
 #![doc = include_str!("../README.md")]
 
-mod to_scalar;
+mod serialize;
 
-use std::{
-    borrow::Cow,
-    net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr},
-    num::NonZero,
-};
+pub use serialize::*;
+
+use std::borrow::Cow;
 
 use facet_core::{Facet, Opaque};
-use facet_reflect::PokeUninit;
-use toml_edit::{DocumentMut, Item, TomlError};
+use facet_reflect::{
+    DeserializeError, Deserializer, PathError, PokeUninit, ReflectError, SerializeError, Token,
+    deserialize_opaque,
+};
+use toml_edit::{DocumentMut, Item, Table, TomlError, Value};
 
 /// Deserializes a TOML string into a value of type `T` that implements `Facet`.
-pub fn from_str<T: Facet>(toml: &str) -> Result<T, AnyErr> {
+pub fn from_str<'facet, T: Facet<'facet>>(toml: &str) -> Result<T, AnyErr> {
     let (poke, _guard) = PokeUninit::alloc::<T>();
     let opaque = from_str_opaque(poke, toml)?;
     Ok(unsafe { opaque.read::<T>() })
@@ -44,151 +45,127 @@ impl From<&str> for AnyErr {
     }
 }
 
+impl From<ReflectError> for AnyErr {
+    fn from(e: ReflectError) -> Self {
+        Self(e.to_string())
+    }
+}
+
+impl From<SerializeError> for AnyErr {
+    fn from(e: SerializeError) -> Self {
+        Self(e.to_string())
+    }
+}
+
+impl From<DeserializeError> for AnyErr {
+    fn from(e: DeserializeError) -> Self {
+        Self(e.to_string())
+    }
+}
+
+impl From<PathError<AnyErr>> for AnyErr {
+    fn from(e: PathError<AnyErr>) -> Self {
+        Self(e.to_string())
+    }
+}
+
 fn from_str_opaque<'mem>(poke: PokeUninit<'mem>, toml: &str) -> Result<Opaque<'mem>, AnyErr> {
     let docs: DocumentMut = toml.parse().map_err(|e| TomlError::to_string(&e))?;
-    deserialize_item(poke, docs.as_item())
+    let mut de = TomlDeserializer {
+        root: Some(Node::Item(docs.as_item())),
+        open: Vec::new(),
+    };
+    // SAFETY: strings are copied out of the document, never borrowed
+    Ok(unsafe { deserialize_opaque(poke, &mut de)? })
 }
 
-fn deserialize_item<'mem>(poke: PokeUninit<'mem>, item: &Item) -> Result<Opaque<'mem>, AnyErr> {
-    let opaque = match poke {
-        PokeUninit::Scalar(ps) => {
-            let shape = ps.shape();
-            if shape.is_type::<String>() {
-                ps.put(to_scalar::string(item)?)
-            } else if shape.is_type::<Cow<'_, str>>() {
-                ps.put(Cow::Owned(to_scalar::string(item)?))
-            } else if shape.is_type::<bool>() {
-                ps.put(to_scalar::boolean(item)?)
-            } else if shape.is_type::<f64>() {
-                ps.put(to_scalar::number::<f64>(item)?)
-            } else if shape.is_type::<f32>() {
-                ps.put(to_scalar::number::<f32>(item)?)
-            } else if shape.is_type::<usize>() {
-                ps.put(to_scalar::number::<usize>(item)?)
-            } else if shape.is_type::<u128>() {
-                ps.put(to_scalar::number::<u128>(item)?)
-            } else if shape.is_type::<u64>() {
-                ps.put(to_scalar::number::<u64>(item)?)
-            } else if shape.is_type::<u32>() {
-                ps.put(to_scalar::number::<u32>(item)?)
-            } else if shape.is_type::<u16>() {
-                ps.put(to_scalar::number::<u16>(item)?)
-            } else if shape.is_type::<u8>() {
-                ps.put(to_scalar::number::<u8>(item)?)
-            } else if shape.is_type::<isize>() {
-                ps.put(to_scalar::number::<isize>(item)?)
-            } else if shape.is_type::<i128>() {
-                ps.put(to_scalar::number::<i128>(item)?)
-            } else if shape.is_type::<i64>() {
-                ps.put(to_scalar::number::<i64>(item)?)
-            } else if shape.is_type::<i32>() {
-                ps.put(to_scalar::number::<i32>(item)?)
-            } else if shape.is_type::<i16>() {
-                ps.put(to_scalar::number::<i16>(item)?)
-            } else if shape.is_type::<i8>() {
-                ps.put(to_scalar::number::<i8>(item)?)
-            } else if shape.is_type::<NonZero<usize>>() {
-                // TODO: create a to_scalar::nonzero_number method when we can use a trait to do so
-                ps.put(
-                    NonZero::new(to_scalar::number::<usize>(item)?).ok_or_else(|| {
-                        AnyErr("Could not convert number to non-zero variant".to_string())
-                    })?,
-                )
-            } else if shape.is_type::<NonZero<u128>>() {
-                ps.put(
-                    NonZero::new(to_scalar::number::<u128>(item)?).ok_or_else(|| {
-                        AnyErr("Could not convert number to non-zero variant".to_string())
-                    })?,
-                )
-            } else if shape.is_type::<NonZero<u64>>() {
-                ps.put(
-                    NonZero::new(to_scalar::number::<u64>(item)?).ok_or_else(|| {
-                        AnyErr("Could not convert number to non-zero variant".to_string())
-                    })?,
-                )
-            } else if shape.is_type::<NonZero<u32>>() {
-                ps.put(
-                    NonZero::new(to_scalar::number::<u32>(item)?).ok_or_else(|| {
-                        AnyErr("Could not convert number to non-zero variant".to_string())
-                    })?,
-                )
-            } else if shape.is_type::<NonZero<u16>>() {
-                ps.put(
-                    NonZero::new(to_scalar::number::<u16>(item)?).ok_or_else(|| {
-                        AnyErr("Could not convert number to non-zero variant".to_string())
-                    })?,
-                )
-            } else if shape.is_type::<NonZero<u8>>() {
-                ps.put(NonZero::new(to_scalar::number::<u8>(item)?).ok_or_else(|| {
-                    AnyErr("Could not convert number to non-zero variant".to_string())
-                })?)
-            } else if shape.is_type::<NonZero<isize>>() {
-                ps.put(
-                    NonZero::new(to_scalar::number::<isize>(item)?).ok_or_else(|| {
-                        AnyErr("Could not convert number to non-zero variant".to_string())
-                    })?,
-                )
-            } else if shape.is_type::<NonZero<i128>>() {
-                ps.put(
-                    NonZero::new(to_scalar::number::<i128>(item)?).ok_or_else(|| {
-                        AnyErr("Could not convert number to non-zero variant".to_string())
-                    })?,
-                )
-            } else if shape.is_type::<NonZero<i64>>() {
-                ps.put(
-                    NonZero::new(to_scalar::number::<i64>(item)?).ok_or_else(|| {
-                        AnyErr("Could not convert number to non-zero variant".to_string())
-                    })?,
-                )
-            } else if shape.is_type::<NonZero<i32>>() {
-                ps.put(
-                    NonZero::new(to_scalar::number::<i32>(item)?).ok_or_else(|| {
-                        AnyErr("Could not convert number to non-zero variant".to_string())
-                    })?,
-                )
-            } else if shape.is_type::<NonZero<i16>>() {
-                ps.put(
-                    NonZero::new(to_scalar::number::<i16>(item)?).ok_or_else(|| {
-                        AnyErr("Could not convert number to non-zero variant".to_string())
-                    })?,
-                )
-            } else if shape.is_type::<NonZero<i8>>() {
-                ps.put(NonZero::new(to_scalar::number::<i8>(item)?).ok_or_else(|| {
-                    AnyErr("Could not convert number to non-zero variant".to_string())
-                })?)
-            } else if shape.is_type::<SocketAddr>() {
-                ps.put(to_scalar::from_str::<SocketAddr>(item, "socket address")?)
-            } else if shape.is_type::<IpAddr>() {
-                ps.put(to_scalar::from_str::<IpAddr>(item, "ip address")?)
-            } else if shape.is_type::<Ipv4Addr>() {
-                ps.put(to_scalar::from_str::<Ipv4Addr>(item, "ipv4 address")?)
-            } else if shape.is_type::<Ipv6Addr>() {
-                ps.put(to_scalar::from_str::<Ipv6Addr>(item, "ipv6 address")?)
-            } else {
-                return Err(format!("Unsupported scalar type: {}", ps.shape()).into());
+/// Something in a TOML document that turns into a token
+#[derive(Clone, Copy)]
+enum Node<'t> {
+    Key(&'t str),
+    Item(&'t Item),
+    Value(&'t Value),
+    Table(&'t Table),
+}
+
+/// Walks a parsed TOML document, one token at a time
+struct TomlDeserializer<'t> {
+    /// The document, until it's been read
+    root: Option<Node<'t>>,
+    /// The arrays and tables being read, innermost last: whether it's a table, and what's
+    /// left in it (keys and values alternate in tables)
+    open: Vec<(bool, std::vec::IntoIter<Node<'t>>)>,
+}
+
+impl<'t> TomlDeserializer<'t> {
+    fn open_table(&mut self, entries: impl Iterator<Item = (&'t str, Node<'t>)>) -> Token<'static> {
+        let nodes = entries
+            .flat_map(|(k, v)| [Node::Key(k), v])
+            .collect::<Vec<_>>();
+        let len = nodes.len() / 2;
+        self.open.push((true, nodes.into_iter()));
+        Token::StartMap(Some(len))
+    }
+
+    fn open_array(&mut self, items: Vec<Node<'t>>) -> Token<'static> {
+        let len = items.len();
+        self.open.push((false, items.into_iter()));
+        Token::StartList(Some(len))
+    }
+}
+
+impl<'input> Deserializer<'input> for TomlDeserializer<'_> {
+    type Error = AnyErr;
+
+    fn next(&mut self) -> Result<Token<'input>, AnyErr> {
+        let node = match self.root.take() {
+            Some(root) => root,
+            None => match self.open.last_mut() {
+                Some((is_table, nodes)) => match nodes.next() {
+                    Some(node) => node,
+                    None => {
+                        let is_table = *is_table;
+                        self.open.pop();
+                        return Ok(if is_table {
+                            Token::EndMap
+                        } else {
+                            Token::EndList
+                        });
+                    }
+                },
+                None => return Err("Unexpected end of the TOML document".into()),
+            },
+        };
+        let value = match node {
+            Node::Key(key) => return Ok(Token::Str(Cow::Owned(key.to_string()))),
+            Node::Item(Item::None) => return Ok(Token::Null),
+            Node::Item(Item::Value(value)) | Node::Value(value) => value,
+            Node::Item(Item::Table(table)) | Node::Table(table) => {
+                return Ok(self.open_table(table.iter().map(|(k, v)| (k, Node::Item(v)))));
             }
-        }
-        PokeUninit::List(_) => todo!(),
-        PokeUninit::Map(_) => todo!(),
-        PokeUninit::Struct(mut ps) => {
-            let table = item.as_table_like().ok_or_else(|| {
-                format!("Expected table like structure, got {}", item.type_name())
-            })?;
-
-            for (k, v) in table.iter() {
-                let (index, field_poke) = ps
-                    .field_by_name(k)
-                    .map_err(|e| format!("Field '{}' error: {}", k, e))?;
-                let _v = deserialize_item(field_poke, v)
-                    .map_err(|e| format!("Error deserializing field '{}': {}", k, e))?;
-                unsafe {
-                    ps.mark_initialized(index);
-                }
+            Node::Item(Item::ArrayOfTables(tables)) => {
+                return Ok(self.open_array(tables.iter().map(Node::Table).collect()));
+            }
+        };
+        let token = match value {
+            Value::String(s) => Token::Str(Cow::Owned(s.value().clone())),
+            Value::Integer(i) => Token::I64(*i.value()),
+            Value::Float(f) => Token::F64(*f.value()),
+            Value::Boolean(b) => Token::Bool(*b.value()),
+            // dates and times are parsed from their string form
+            Value::Datetime(d) => Token::Str(Cow::Owned(d.value().to_string())),
+            Value::Array(array) => self.open_array(array.iter().map(Node::Value).collect()),
+            Value::InlineTable(table) => {
+                self.open_table(table.iter().map(|(k, v)| (k, Node::Value(v))))
             }
-            ps.build_in_place()
+        };
+        Ok(token)
+    }
+
+    fn skip(&mut self) -> Result<(), AnyErr> {
+        match self.open.last_mut().and_then(|(_, nodes)| nodes.next()) {
+            Some(_) => Ok(()),
+            None => Err("Expected a value to skip".into()),
         }
-        PokeUninit::Enum(_) => todo!(),
-        _ => todo!("unsupported poke type"),
-    };
-    Ok(opaque)
+    }
 }
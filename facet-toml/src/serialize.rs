@@ -0,0 +1,491 @@
+use std::borrow::Cow;
+use std::num::NonZero;
+
+use facet_core::{Facet, Field, Shape};
+use facet_reflect::{Peek, PeekValue, Serializer, serialize};
+use toml_edit::{ArrayOfTables, DocumentMut, InlineTable, Item, Table, TableLike, Value};
+
+use crate::AnyErr;
+
+/// Options for [`to_string_with_options`]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SerializeOptions {
+    /// Emit the doc comments of struct fields as TOML comments
+    pub doc_comments: bool,
+}
+
+impl SerializeOptions {
+    /// Sets whether the doc comments of struct fields are emitted as TOML comments
+    pub fn with_doc_comments(mut self, doc_comments: bool) -> Self {
+        self.doc_comments = doc_comments;
+        self
+    }
+}
+
+/// Serializes a value of type `T` that implements `Facet` to a TOML string.
+///
+/// The value must be a struct or a map, since TOML documents are tables.
+///
+/// ```
+/// # use facet::Facet;
+/// #[derive(Facet)]
+/// struct Package {
+///     name: String,
+///     version: String,
+/// }
+///
+/// let package = Package { name: "facet".to_string(), version: "0.1.0".to_string() };
+/// let toml = facet_toml::to_string(&package).unwrap();
+/// assert_eq!(toml, "name = \"facet\"\nversion = \"0.1.0\"\n");
+/// ```
+pub fn to_string<'facet, T: Facet<'facet>>(value: &T) -> Result<String, AnyErr> {
+    to_string_with_options(value, SerializeOptions::default())
+}
+
+/// Serializes a value of type `T` that implements `Facet` to a TOML string, with the given options.
+pub fn to_string_with_options<'facet, T: Facet<'facet>>(
+    value: &T,
+    options: SerializeOptions,
+) -> Result<String, AnyErr> {
+    let table = peek_to_table(Peek::new(value), options)?;
+    Ok(DocumentMut::from(table).to_string())
+}
+
+/// Writes `value` into an existing TOML document, only touching the keys whose value changed.
+///
+/// Comments, formatting and key ordering of the document are kept for everything that is
+/// unchanged. Keys that `value` doesn't serialize (e.g. `None` fields) are removed, and new
+/// keys are appended at the end of their table.
+///
+/// ```
+/// # use facet::Facet;
+/// #[derive(Facet)]
+/// struct Config {
+///     name: String,
+///     port: u16,
+/// }
+///
+/// let mut doc: toml_edit::DocumentMut = "# the server\nname = \"web\"\nport = 80 # http\n"
+///     .parse()
+///     .unwrap();
+/// let config = Config { name: "web".to_string(), port: 8080 };
+/// facet_toml::update_document(&mut doc, &config).unwrap();
+/// assert_eq!(doc.to_string(), "# the server\nname = \"web\"\nport = 8080 # http\n");
+/// ```
+pub fn update_document<'facet, T: Facet<'facet>>(
+    doc: &mut DocumentMut,
+    value: &T,
+) -> Result<(), AnyErr> {
+    let table = peek_to_table(Peek::new(value), SerializeOptions::default())?;
+    merge_table(doc.as_table_mut(), table);
+    Ok(())
+}
+
+fn peek_to_table(peek: Peek<'_>, options: SerializeOptions) -> Result<Table, AnyErr> {
+    let mut serializer = TomlSerializer {
+        options,
+        stack: Vec::new(),
+        root: None,
+        shape: peek.as_value().shape(),
+        enums: Vec::new(),
+    };
+    serialize(peek, &mut serializer)?;
+    match serializer.root {
+        Some(Node::Table(entries)) => Ok(entries_to_table(entries)),
+        _ => Err(format!(
+            "Only structs and maps can be serialized to a TOML document, got: {}",
+            peek.as_value().shape()
+        )
+        .into()),
+    }
+}
+
+macro_rules! ints {
+    ($pv:expr, $($type:ty),*) => {
+        $(
+            if $pv.shape().is_type::<$type>() {
+                let value = unsafe { $pv.data().as_ref::<$type>() };
+                return int_to_value(*value).map(Some);
+            }
+            if $pv.shape().is_type::<NonZero<$type>>() {
+                let value = unsafe { $pv.data().as_ref::<NonZero<$type>>() };
+                return int_to_value(value.get()).map(Some);
+            }
+        )*
+    };
+}
+
+macro_rules! strings {
+    ($pv:expr, $($type:ty),*) => {
+        $(
+            if $pv.shape().is_type::<$type>() {
+                let value = unsafe { $pv.data().as_ref::<$type>() };
+                return Ok(Some(Value::from(value.to_string())));
+            }
+        )*
+    };
+}
+
+/// TOML integers are 64-bit signed
+fn int_to_value<T: TryInto<i64> + ToString + Copy>(value: T) -> Result<Value, AnyErr> {
+    let i = value.try_into().map_err(|_| {
+        AnyErr(format!(
+            "{} is out of range for a TOML integer",
+            value.to_string()
+        ))
+    })?;
+    Ok(Value::from(i))
+}
+
+/// Returns `None` for values that TOML can't represent, like `()`, so their key is left out
+fn peek_value_to_value(pv: PeekValue<'_>) -> Result<Option<Value>, AnyErr> {
+    if pv.shape().is_type::<()>() {
+        return Ok(None);
+    }
+    if pv.shape().is_type::<bool>() {
+        return Ok(Some(Value::from(*unsafe { pv.data().as_ref::<bool>() })));
+    }
+    if pv.shape().is_type::<f64>() {
+        return Ok(Some(Value::from(*unsafe { pv.data().as_ref::<f64>() })));
+    }
+    if pv.shape().is_type::<f32>() {
+        return Ok(Some(Value::from(
+            *unsafe { pv.data().as_ref::<f32>() } as f64
+        )));
+    }
+    ints!(
+        pv, u8, i8, u16, i16, u32, i32, u64, i64, u128, i128, usize, isize
+    );
+    strings!(pv, String, Cow<'_, str>, &str);
+
+    // anything else (e.g. `IpAddr`) is written in its string form
+    if pv.vtable().display.is_some() {
+        return Ok(Some(Value::from(Peek::Value(pv).to_string())));
+    }
+    Err(format!("Unsupported scalar type: {}", pv.shape()).into())
+}
+
+/// A serialized value, before it's known whether it's written as a table or inline
+enum Node {
+    Value(Value),
+    /// A struct, a map, or an enum tagged with a table: keys, values and doc comments
+    Table(Vec<(String, Node, Option<String>)>),
+    Array(Vec<Node>),
+}
+
+impl Node {
+    /// The node as it's written in a table: tables stay tables, and arrays of tables become
+    /// arrays of tables
+    fn into_item(self) -> Item {
+        match self {
+            Node::Table(entries) => Item::Table(entries_to_table(entries)),
+            Node::Array(items)
+                if !items.is_empty() && items.iter().all(|i| matches!(i, Node::Table(_))) =>
+            {
+                let mut tables = ArrayOfTables::new();
+                for item in items {
+                    if let Node::Table(entries) = item {
+                        tables.push(entries_to_table(entries));
+                    }
+                }
+                Item::ArrayOfTables(tables)
+            }
+            node => Item::Value(node.into_value()),
+        }
+    }
+
+    /// The node as it's written inside an array or an inline table
+    fn into_value(self) -> Value {
+        match self {
+            Node::Value(value) => value,
+            Node::Table(entries) => {
+                let mut table = InlineTable::new();
+                for (key, node, _) in entries {
+                    table.insert(&key, node.into_value());
+                }
+                Value::InlineTable(table)
+            }
+            Node::Array(items) => Value::Array(items.into_iter().map(Node::into_value).collect()),
+        }
+    }
+
+    /// Writes everything in the node inline. An enum tagged with a table is still written as a
+    /// table, but its payload isn't.
+    fn into_inline_contents(self) -> Node {
+        match self {
+            Node::Table(entries) => Node::Table(
+                entries
+                    .into_iter()
+                    .map(|(key, node, comment)| (key, Node::Value(node.into_value()), comment))
+                    .collect(),
+            ),
+            node => Node::Value(node.into_value()),
+        }
+    }
+}
+
+fn entries_to_table(entries: Vec<(String, Node, Option<String>)>) -> Table {
+    let mut table = Table::new();
+    for (name, node, comment) in entries {
+        let mut item = node.into_item();
+        match (&mut item, comment) {
+            (Item::Table(t), Some(comment)) => t.decor_mut().set_prefix(comment),
+            (Item::ArrayOfTables(a), Some(comment)) => {
+                if let Some(t) = a.get_mut(0) {
+                    t.decor_mut().set_prefix(comment)
+                }
+            }
+            (_, Some(comment)) => {
+                table.insert(&name, item);
+                if let Some(mut key) = table.key_mut(&name) {
+                    key.leaf_decor_mut().set_prefix(comment);
+                }
+                continue;
+            }
+            (_, None) => {}
+        }
+        table.insert(&name, item);
+    }
+    table
+}
+
+/// A struct, map or list being serialized
+enum Frame {
+    Table {
+        entries: Vec<(String, Node, Option<String>)>,
+        /// The key of the next value, and its doc comment
+        key: Option<(String, Option<String>)>,
+    },
+    Array(Vec<Node>),
+}
+
+/// Builds the [`Node`]s of a value, as the [`serialize`] driver walks it
+struct TomlSerializer {
+    options: SerializeOptions,
+    stack: Vec<Frame>,
+    root: Option<Node>,
+    /// The shape of the value being serialized, to describe values TOML can't represent
+    shape: &'static Shape,
+    /// How many frames were open when each of the enums being serialized started
+    enums: Vec<usize>,
+}
+
+impl TomlSerializer {
+    /// Adds a value to the innermost table or array. Values TOML can't represent, like
+    /// `None`, are `None`: their key is left out of tables, and they're an error in arrays.
+    fn push(&mut self, mut node: Option<Node>) -> Result<(), AnyErr> {
+        if self.enums.last() == Some(&self.stack.len()) {
+            node = node.map(Node::into_inline_contents);
+        }
+        match self.stack.last_mut() {
+            None => self.root = node,
+            Some(Frame::Table { entries, key }) => {
+                let (key, comment) = key.take().expect("a key before each value");
+                if let Some(node) = node {
+                    entries.push((key, node, comment));
+                }
+            }
+            Some(Frame::Array(items)) => {
+                let node = node.ok_or_else(|| {
+                    format!("{} cannot be represented in a TOML array", self.shape)
+                })?;
+                items.push(node);
+            }
+        }
+        Ok(())
+    }
+
+    fn set_key(&mut self, name: String, comment: Option<String>) {
+        if let Some(Frame::Table { key, .. }) = self.stack.last_mut() {
+            *key = Some((name, comment));
+        }
+    }
+
+    fn start_table(&mut self) -> Result<(), AnyErr> {
+        self.stack.push(Frame::Table {
+            entries: Vec::new(),
+            key: None,
+        });
+        Ok(())
+    }
+
+    fn end_table(&mut self) -> Result<(), AnyErr> {
+        match self.stack.pop() {
+            Some(Frame::Table { entries, .. }) => self.push(Some(Node::Table(entries))),
+            _ => unreachable!("unbalanced table"),
+        }
+    }
+}
+
+impl Serializer for TomlSerializer {
+    type Error = AnyErr;
+
+    fn scalar(&mut self, value: PeekValue<'_>) -> Result<(), AnyErr> {
+        let value = peek_value_to_value(value)?;
+        self.push(value.map(Node::Value))
+    }
+
+    fn str(&mut self, s: &str) -> Result<(), AnyErr> {
+        self.push(Some(Node::Value(Value::from(s))))
+    }
+
+    fn none(&mut self) -> Result<(), AnyErr> {
+        self.push(None)
+    }
+
+    fn start_struct(&mut self, _len: usize) -> Result<(), AnyErr> {
+        self.start_table()
+    }
+
+    fn field(&mut self, name: &str) -> Result<(), AnyErr> {
+        self.set_key(name.to_string(), None);
+        Ok(())
+    }
+
+    fn declared_field(&mut self, field: &'static Field) -> Result<(), AnyErr> {
+        let comment = self
+            .options
+            .doc_comments
+            .then(|| doc_comment(field))
+            .flatten();
+        self.set_key(field.serialized_name().to_string(), comment);
+        Ok(())
+    }
+
+    fn end_struct(&mut self) -> Result<(), AnyErr> {
+        self.end_table()
+    }
+
+    fn start_list(&mut self, len: usize) -> Result<(), AnyErr> {
+        self.stack.push(Frame::Array(Vec::with_capacity(len)));
+        Ok(())
+    }
+
+    fn end_list(&mut self) -> Result<(), AnyErr> {
+        match self.stack.pop() {
+            Some(Frame::Array(items)) => self.push(Some(Node::Array(items))),
+            _ => unreachable!("unbalanced list"),
+        }
+    }
+
+    fn start_map(&mut self, _len: usize) -> Result<(), AnyErr> {
+        self.start_table()
+    }
+
+    fn map_key(&mut self, key: Peek<'_>) -> Result<(), AnyErr> {
+        let key = map_key(key.as_value())?;
+        self.set_key(key, None);
+        Ok(())
+    }
+
+    fn end_map(&mut self) -> Result<(), AnyErr> {
+        self.end_table()
+    }
+
+    fn start_value(&mut self, value: Peek<'_>) -> Result<bool, AnyErr> {
+        self.shape = value.as_value().shape();
+        if let Peek::Enum(_) = value {
+            self.enums.push(self.stack.len());
+        }
+        Ok(true)
+    }
+
+    fn end_value(&mut self, value: Peek<'_>) -> Result<(), AnyErr> {
+        if let Peek::Enum(_) = value {
+            self.enums.pop();
+        }
+        Ok(())
+    }
+}
+
+fn doc_comment(field: &Field) -> Option<String> {
+    if field.doc.is_empty() {
+        return None;
+    }
+    Some(field.doc.iter().map(|line| format!("#{line}\n")).collect())
+}
+
+/// TOML keys are always strings: string keys are used as-is, any other key goes through
+/// its `Display` implementation.
+fn map_key(pv: PeekValue<'_>) -> Result<String, AnyErr> {
+    match peek_value_to_value(pv)? {
+        Some(Value::String(s)) => Ok(s.into_value()),
+        _ if pv.vtable().display.is_some() => Ok(Peek::Value(pv).to_string()),
+        _ => Err(format!("Map key of type {} cannot be displayed", pv.shape()).into()),
+    }
+}
+
+/// Merges `new` into `existing`, keeping the formatting of everything that didn't change
+fn merge_table(existing: &mut dyn TableLike, new: Table) {
+    let stale: Vec<String> = existing
+        .iter()
+        .filter(|(key, _)| !new.contains_key(key))
+        .map(|(key, _)| key.to_string())
+        .collect();
+    for key in stale {
+        existing.remove(&key);
+    }
+
+    for (key, item) in new {
+        match existing.get_mut(&key) {
+            Some(old) => merge_item(old, item),
+            None => {
+                existing.insert(&key, item);
+            }
+        }
+    }
+}
+
+fn merge_item(existing: &mut Item, new: Item) {
+    if existing.is_table_like() && new.is_table_like() {
+        let new = new.into_table().expect("item is table-like");
+        merge_table(existing.as_table_like_mut().unwrap(), new);
+        return;
+    }
+
+    match (existing, new) {
+        (Item::ArrayOfTables(old), Item::ArrayOfTables(new)) => {
+            let new_len = new.len();
+            for (index, table) in new.into_iter().enumerate() {
+                match old.get_mut(index) {
+                    Some(old) => merge_table(old, table),
+                    None => old.push(table),
+                }
+            }
+            while old.len() > new_len {
+                old.remove(old.len() - 1);
+            }
+        }
+        // values written inline stay inline
+        (Item::Value(old), new) => {
+            let Ok(mut new) = new.into_value() else {
+                return;
+            };
+            if !values_eq(old, &new) {
+                *new.decor_mut() = old.decor().clone();
+                *old = new;
+            }
+        }
+        (existing, new) => *existing = new,
+    }
+}
+
+/// Compares two values, ignoring their formatting
+fn values_eq(a: &Value, b: &Value) -> bool {
+    match (a, b) {
+        (Value::String(a), Value::String(b)) => a.value() == b.value(),
+        (Value::Integer(a), Value::Integer(b)) => a.value() == b.value(),
+        (Value::Float(a), Value::Float(b)) => a.value().to_bits() == b.value().to_bits(),
+        (Value::Boolean(a), Value::Boolean(b)) => a.value() == b.value(),
+        (Value::Datetime(a), Value::Datetime(b)) => a.value() == b.value(),
+        (Value::Array(a), Value::Array(b)) => {
+            a.len() == b.len() && a.iter().zip(b.iter()).all(|(a, b)| values_eq(a, b))
+        }
+        (Value::InlineTable(a), Value::InlineTable(b)) => {
+            a.len() == b.len()
+                && a.iter()
+                    .all(|(key, a)| b.get(key).is_some_and(|b| values_eq(a, b)))
+        }
+        _ => false,
+    }
+}
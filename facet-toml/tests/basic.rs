@@ -22,3 +22,127 @@ fn test_deserialize_person() {
         }
     );
 }
+
+#[test]
+fn test_deserialize_renamed_fields() {
+    #[derive(Debug, Facet, PartialEq)]
+    #[facet(rename_all = "kebab-case")]
+    struct Package {
+        package_name: String,
+        #[facet(rename = "rust-version")]
+        msrv: String,
+    }
+
+    let toml = r#"
+            package-name = "facet"
+            rust-version = "1.86"
+        "#;
+
+    let package: Package = facet_toml::from_str(toml).expect("Failed to parse TOML");
+    assert_eq!(
+        package,
+        Package {
+            package_name: "facet".to_string(),
+            msrv: "1.86".to_string(),
+        }
+    );
+}
+
+#[test]
+fn test_deserialize_missing_field() {
+    #[derive(Debug, Facet, PartialEq)]
+    struct Root {
+        name: String,
+        version: String,
+    }
+
+    let err = facet_toml::from_str::<Root>(r#"name = "facet""#).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "missing field in Root: `version` (expected Root)"
+    );
+}
+
+#[test]
+fn test_deserialize_flattened_struct() {
+    #[derive(Debug, Facet, PartialEq)]
+    struct Common {
+        edition: String,
+    }
+
+    #[derive(Debug, Facet, PartialEq)]
+    struct Package {
+        name: String,
+        #[facet(flatten)]
+        common: Common,
+    }
+
+    let toml = r#"
+            edition = "2024"
+            name = "facet"
+        "#;
+
+    let package: Package = facet_toml::from_str(toml).expect("Failed to parse TOML");
+    assert_eq!(
+        package,
+        Package {
+            name: "facet".to_string(),
+            common: Common {
+                edition: "2024".to_string(),
+            },
+        }
+    );
+}
+
+#[test]
+fn test_deserialize_lists_and_enums() {
+    #[derive(Debug, Facet, PartialEq)]
+    #[repr(u8)]
+    #[facet(tag = "kind")]
+    enum Target {
+        Bin { name: String },
+        Lib,
+    }
+
+    #[derive(Debug, Facet, PartialEq)]
+    #[repr(u8)]
+    #[allow(dead_code)]
+    enum Profile {
+        Debug,
+        Release,
+    }
+
+    #[derive(Debug, Facet, PartialEq)]
+    struct Root {
+        features: Vec<String>,
+        profile: Profile,
+        target: Vec<Target>,
+    }
+
+    let toml = r#"
+            features = ["std", "alloc"]
+            profile = "Release"
+
+            [[target]]
+            kind = "Bin"
+            name = "facet"
+
+            [[target]]
+            kind = "Lib"
+        "#;
+
+    let root: Root = facet_toml::from_str(toml).expect("Failed to parse TOML");
+    assert_eq!(
+        root,
+        Root {
+            features: vec!["std".to_string(), "alloc".to_string()],
+            profile: Profile::Release,
+            target: vec![
+                Target::Bin {
+                    name: "facet".to_string()
+                },
+                Target::Lib,
+            ],
+        }
+    );
+}
@@ -0,0 +1,59 @@
+//! Tests for maps.
+
+use std::collections::{BTreeMap, HashMap};
+
+use facet::Facet;
+
+#[test]
+fn test_string_keys() {
+    #[derive(Debug, Facet, PartialEq)]
+    struct Root {
+        values: HashMap<String, u64>,
+    }
+
+    assert_eq!(
+        facet_toml::from_str::<Root>(
+            r#"
+            [values]
+            a = 1
+            b = 2
+            "#
+        )
+        .expect("Failed to parse TOML"),
+        Root {
+            values: HashMap::from([("a".to_string(), 1), ("b".to_string(), 2)]),
+        },
+    );
+}
+
+#[test]
+fn test_integer_keys() {
+    #[derive(Debug, Facet, PartialEq)]
+    struct Root {
+        ports: BTreeMap<u16, String>,
+    }
+
+    assert_eq!(
+        facet_toml::from_str::<Root>(
+            r#"
+            [ports]
+            80 = "http"
+            443 = "https"
+            "#
+        )
+        .expect("Failed to parse TOML"),
+        Root {
+            ports: BTreeMap::from([(80, "http".to_string()), (443, "https".to_string())]),
+        },
+    );
+}
+
+#[test]
+fn test_invalid_key() {
+    #[derive(Debug, Facet, PartialEq)]
+    struct Root {
+        ports: BTreeMap<u16, String>,
+    }
+
+    assert!(facet_toml::from_str::<Root>("ports = { http = 'http' }").is_err());
+}
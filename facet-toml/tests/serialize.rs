@@ -0,0 +1,282 @@
+use std::collections::BTreeMap;
+
+use facet::Facet;
+
+#[derive(Debug, Facet, PartialEq)]
+struct Manifest {
+    package: Package,
+    features: BTreeMap<String, Vec<String>>,
+    bin: Vec<Target>,
+}
+
+#[derive(Debug, Facet, PartialEq)]
+struct Package {
+    name: String,
+    version: String,
+    edition: u16,
+    publish: bool,
+    description: Option<String>,
+}
+
+#[derive(Debug, Facet, PartialEq)]
+struct Target {
+    name: String,
+    path: String,
+}
+
+fn manifest() -> Manifest {
+    Manifest {
+        package: Package {
+            name: "facet".to_string(),
+            version: "0.1.0".to_string(),
+            edition: 2024,
+            publish: false,
+            description: None,
+        },
+        features: [("default".to_string(), vec!["std".to_string()])].into(),
+        bin: vec![
+            Target {
+                name: "a".to_string(),
+                path: "src/a.rs".to_string(),
+            },
+            Target {
+                name: "b".to_string(),
+                path: "src/b.rs".to_string(),
+            },
+        ],
+    }
+}
+
+#[test]
+fn test_to_string() {
+    let toml = facet_toml::to_string(&manifest()).unwrap();
+    assert_eq!(
+        toml,
+        r#"[package]
+name = "facet"
+version = "0.1.0"
+edition = 2024
+publish = false
+
+[features]
+default = ["std"]
+
+[[bin]]
+name = "a"
+path = "src/a.rs"
+
+[[bin]]
+name = "b"
+path = "src/b.rs"
+"#
+    );
+}
+
+#[test]
+fn test_roundtrip() {
+    #[derive(Debug, Facet, PartialEq)]
+    struct Server {
+        host: std::net::IpAddr,
+        port: u16,
+        ratio: f64,
+        limits: BTreeMap<String, u64>,
+        tls: Tls,
+    }
+
+    #[derive(Debug, Facet, PartialEq)]
+    struct Tls {
+        enabled: bool,
+        cert: String,
+    }
+
+    let server = Server {
+        host: "127.0.0.1".parse().unwrap(),
+        port: 8080,
+        ratio: 0.5,
+        limits: [("conns".to_string(), 100), ("rps".to_string(), 10)].into(),
+        tls: Tls {
+            enabled: true,
+            cert: "cert.pem".to_string(),
+        },
+    };
+
+    let toml = facet_toml::to_string(&server).unwrap();
+    let back: Server = facet_toml::from_str(&toml).unwrap();
+    assert_eq!(back, server);
+}
+
+#[test]
+fn test_doc_comments() {
+    /// A user
+    #[derive(Debug, Facet)]
+    struct User {
+        /// Displayed name
+        name: String,
+        /// Contact details
+        contact: Contact,
+    }
+
+    #[derive(Debug, Facet)]
+    struct Contact {
+        /// Where to send mail
+        /// (may be empty)
+        email: String,
+    }
+
+    let user = User {
+        name: "Alice".to_string(),
+        contact: Contact {
+            email: "alice@example.com".to_string(),
+        },
+    };
+
+    let options = facet_toml::SerializeOptions::default().with_doc_comments(true);
+    let toml = facet_toml::to_string_with_options(&user, options).unwrap();
+    assert_eq!(
+        toml,
+        r#"# Displayed name
+name = "Alice"
+# Contact details
+[contact]
+# Where to send mail
+# (may be empty)
+email = "alice@example.com"
+"#
+    );
+
+    let toml = facet_toml::to_string(&user).unwrap();
+    assert!(!toml.contains('#'));
+}
+
+#[test]
+fn test_enums() {
+    #[derive(Debug, Facet)]
+    #[repr(u8)]
+    #[allow(dead_code)]
+    enum Source {
+        Registry,
+        Git { url: String, rev: Option<String> },
+        Path(String),
+    }
+
+    #[derive(Debug, Facet)]
+    struct Dependencies {
+        serde: Source,
+        facet: Source,
+        local: Source,
+    }
+
+    let deps = Dependencies {
+        serde: Source::Registry,
+        facet: Source::Git {
+            url: "https://github.com/facet-rs/facet".to_string(),
+            rev: None,
+        },
+        local: Source::Path("../local".to_string()),
+    };
+
+    let toml = facet_toml::to_string(&deps).unwrap();
+    assert_eq!(
+        toml,
+        r#"serde = "Registry"
+
+[facet]
+Git = { url = "https://github.com/facet-rs/facet" }
+
+[local]
+Path = "../local"
+"#
+    );
+}
+
+#[test]
+fn test_non_table_is_an_error() {
+    assert!(facet_toml::to_string(&42u32).is_err());
+
+    #[derive(Debug, Facet)]
+    struct TooBig {
+        value: u64,
+    }
+    let err = facet_toml::to_string(&TooBig { value: u64::MAX }).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "18446744073709551615 is out of range for a TOML integer"
+    );
+}
+
+#[test]
+fn test_update_document_keeps_formatting() {
+    let original = r#"# Package metadata
+[package]
+name    = "facet"   # the name
+version = "0.1.0"
+edition = 2024
+publish = false
+description = "old"
+
+[features]
+default = [ "std" ]   # keep me
+
+# binaries
+[[bin]]
+name = "a"
+path = "src/a.rs"
+"#;
+
+    let mut doc: toml_edit::DocumentMut = original.parse().unwrap();
+    let mut manifest = manifest();
+    manifest.package.version = "0.2.0".to_string();
+    facet_toml::update_document(&mut doc, &manifest).unwrap();
+
+    assert_eq!(
+        doc.to_string(),
+        r#"# Package metadata
+[package]
+name    = "facet"   # the name
+version = "0.2.0"
+edition = 2024
+publish = false
+
+[features]
+default = [ "std" ]   # keep me
+
+# binaries
+[[bin]]
+name = "a"
+path = "src/a.rs"
+
+[[bin]]
+name = "b"
+path = "src/b.rs"
+"#
+    );
+}
+
+#[test]
+fn test_arrays() {
+    #[derive(Debug, Facet)]
+    struct Shapes {
+        point: (i32, i32),
+        tags: std::collections::BTreeSet<String>,
+        sizes: Vec<Option<u32>>,
+    }
+
+    let shapes = Shapes {
+        point: (1, -2),
+        tags: ["a".to_string(), "b".to_string()].into(),
+        sizes: vec![Some(1)],
+    };
+    assert_eq!(
+        facet_toml::to_string(&shapes).unwrap(),
+        "point = [1, -2]\ntags = [\"a\", \"b\"]\nsizes = [1]\n"
+    );
+
+    let holey = Shapes {
+        sizes: vec![Some(1), None],
+        ..shapes
+    };
+    assert_eq!(
+        facet_toml::to_string(&holey).unwrap_err().to_string(),
+        "Option cannot be represented in a TOML array"
+    );
+}
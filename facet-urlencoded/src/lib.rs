@@ -1,8 +1,12 @@
 #![warn(missing_docs)]
 #![doc = include_str!("../README.md")]
 
-use facet_core::{Facet, Opaque};
-use facet_reflect::{PokeStruct, PokeUninit};
+use std::borrow::Cow;
+
+use facet_core::{Def, Facet, Opaque, ScalarAffinity, ScalarDef};
+use facet_reflect::{
+    DeserializeError, Deserializer, PathError, PokeUninit, ReflectError, Token, deserialize_opaque,
+};
 use log::*;
 
 #[cfg(test)]
@@ -67,7 +71,26 @@ mod tests;
 ///     },
 /// });
 /// ```
-pub fn from_str<T: Facet>(urlencoded: &str) -> Result<T, UrlEncodedError> {
+///
+/// # Errors
+///
+/// Errors come with the path of the field they're about, e.g. `user.address.city`:
+///
+/// ```
+/// use facet::Facet;
+/// use facet_urlencoded::from_str;
+///
+/// #[derive(Debug, Facet)]
+/// struct User {
+///     age: u8,
+/// }
+///
+/// let err = from_str::<User>("age=300").unwrap_err();
+/// assert_eq!(err.path.to_string(), "age");
+/// ```
+pub fn from_str<'facet, T: Facet<'facet>>(
+    urlencoded: &str,
+) -> Result<T, PathError<UrlEncodedError>> {
     let (poke, _guard) = PokeUninit::alloc::<T>();
     let opaque = from_str_opaque(poke, urlencoded)?;
     Ok(unsafe { opaque.read::<T>() })
@@ -79,7 +102,7 @@ pub fn from_str<T: Facet>(urlencoded: &str) -> Result<T, UrlEncodedError> {
 fn from_str_opaque<'mem>(
     poke: PokeUninit<'mem>,
     urlencoded: &str,
-) -> Result<Opaque<'mem>, UrlEncodedError> {
+) -> Result<Opaque<'mem>, PathError<UrlEncodedError>> {
     trace!("Starting URL encoded form data deserialization");
 
     // Parse the URL encoded string into key-value pairs
@@ -92,7 +115,13 @@ fn from_str_opaque<'mem>(
     }
 
     // Process the deserialization
-    deserialize_value(poke, &nested_values)
+    let mut de = NestedValuesDeserializer {
+        root: Some(&nested_values),
+        open: Vec::new(),
+        key: "",
+    };
+    // SAFETY: strings are copied out of the nested values, never borrowed
+    unsafe { deserialize_opaque(poke, &mut de) }
 }
 
 /// Internal helper struct to represent nested values from URL-encoded data
@@ -159,104 +188,96 @@ impl NestedValues {
     }
 }
 
-/// Deserialize a value recursively using the nested values
-fn deserialize_value<'mem>(
-    poke: PokeUninit<'mem>,
-    values: &NestedValues,
-) -> Result<Opaque<'mem>, UrlEncodedError> {
-    match poke {
-        PokeUninit::Struct(mut ps) => {
-            trace!("Deserializing struct");
+/// Something in the nested values that turns into a token
+enum Node<'v> {
+    Key(&'v str),
+    Value(&'v str),
+    Nested(&'v NestedValues),
+}
 
-            // Process flat fields
-            for key in values.keys() {
-                if let Ok((index, field_poke)) = ps.field_by_name(key) {
-                    let value = values.get(key).unwrap(); // Safe because we're iterating over keys
-                    deserialize_scalar_field(key, value, field_poke, index, &mut ps)?;
-                } else {
-                    warn!("Unknown field: {}", key);
-                    // Skip unknown fields
-                }
-            }
+/// Walks the nested values as maps of strings, one token at a time
+struct NestedValuesDeserializer<'v> {
+    /// The values, until they've been read
+    root: Option<&'v NestedValues>,
+    /// The maps being read, innermost last, with what's left in them (keys and values
+    /// alternate)
+    open: Vec<std::vec::IntoIter<Node<'v>>>,
+    /// The last key read, which invalid numbers are reported for
+    key: &'v str,
+}
 
-            // Process nested fields
-            for key in values.nested_keys() {
-                if let Ok((index, field_poke)) = ps.field_by_name(key) {
-                    if let Some(nested_values) = values.get_nested(key) {
-                        match field_poke {
-                            PokeUninit::Struct(_) => {
-                                let _nested_opaque = deserialize_value(field_poke, nested_values)?;
-                                unsafe {
-                                    ps.mark_initialized(index);
-                                }
-                            }
-                            _ => {
-                                return Err(UrlEncodedError::UnsupportedShape(format!(
-                                    "Expected struct for nested field '{}'",
-                                    key
-                                )));
-                            }
-                        }
-                    }
-                } else {
-                    warn!("Unknown nested field: {}", key);
-                    // Skip unknown fields
+impl<'v> NestedValuesDeserializer<'v> {
+    fn open(&mut self, values: &'v NestedValues) -> Token<'static> {
+        let flat = values
+            .keys()
+            .map(|k| (k, Node::Value(values.get(k).unwrap())));
+        let nested = values
+            .nested_keys()
+            .map(|k| (k, Node::Nested(values.get_nested(k).unwrap())));
+        let nodes = flat
+            .chain(nested)
+            .flat_map(|(k, v)| [Node::Key(k), v])
+            .collect::<Vec<_>>();
+        let len = nodes.len() / 2;
+        self.open.push(nodes.into_iter());
+        Token::StartMap(Some(len))
+    }
+}
+
+impl<'input> Deserializer<'input> for NestedValuesDeserializer<'_> {
+    type Error = UrlEncodedError;
+
+    fn next(&mut self) -> Result<Token<'input>, UrlEncodedError> {
+        if let Some(root) = self.root.take() {
+            return Ok(self.open(root));
+        }
+        let node = match self.open.last_mut() {
+            Some(nodes) => match nodes.next() {
+                Some(node) => node,
+                None => {
+                    self.open.pop();
+                    return Ok(Token::EndMap);
                 }
+            },
+            None => {
+                return Err(UrlEncodedError::UnsupportedShape(
+                    "Unexpected end of the form data".to_string(),
+                ));
+            }
+        };
+        let token = match node {
+            Node::Key(key) => {
+                self.key = key;
+                Token::Str(Cow::Owned(key.to_string()))
             }
+            Node::Value(value) => Token::Str(Cow::Owned(value.to_string())),
+            Node::Nested(values) => self.open(values),
+        };
+        Ok(token)
+    }
 
-            trace!("Finished deserializing struct");
-            Ok(ps.build_in_place())
-        }
-        _ => {
-            error!("Unsupported root type");
-            Err(UrlEncodedError::UnsupportedShape(
-                "Unsupported root type".to_string(),
-            ))
+    fn skip(&mut self) -> Result<(), UrlEncodedError> {
+        if let Some(Node::Key(key)) = self.open.last_mut().and_then(|nodes| nodes.next()) {
+            warn!("Unknown field: {}", key);
         }
+        Ok(())
     }
-}
 
-/// Helper function to deserialize a scalar field
-fn deserialize_scalar_field<'mem>(
-    key: &str,
-    value: &str,
-    field_poke: PokeUninit<'mem>,
-    index: usize,
-    ps: &mut PokeStruct<'mem>,
-) -> Result<(), UrlEncodedError> {
-    match field_poke {
-        PokeUninit::Scalar(ps_scalar) => {
-            if ps_scalar.shape().is_type::<String>() {
-                let s = value.to_string();
-                ps_scalar.put(s);
-            } else if ps_scalar.shape().is_type::<u64>() {
-                match value.parse::<u64>() {
-                    Ok(num) => {
-                        ps_scalar.put(num);
-                    }
-                    Err(_) => {
-                        return Err(UrlEncodedError::InvalidNumber(
-                            key.to_string(),
-                            value.to_string(),
-                        ));
-                    }
-                }
-            } else {
-                warn!("Unsupported scalar type: {}", ps_scalar.shape());
-                return Err(UrlEncodedError::UnsupportedType(format!(
-                    "{}",
-                    ps_scalar.shape()
-                )));
+    fn error(&self, error: DeserializeError) -> UrlEncodedError {
+        match error {
+            DeserializeError::InvalidValue { shape, value }
+            | DeserializeError::OutOfRange { shape, value }
+                if matches!(
+                    shape.def,
+                    Def::Scalar(ScalarDef {
+                        affinity: ScalarAffinity::Number(_),
+                        ..
+                    })
+                ) =>
+            {
+                UrlEncodedError::InvalidNumber(self.key.to_string(), value)
             }
-            unsafe { ps.mark_initialized(index) };
-            Ok(())
-        }
-        _ => {
-            error!("Expected scalar field");
-            Err(UrlEncodedError::UnsupportedShape(format!(
-                "Expected scalar for field '{}'",
-                key
-            )))
+            error => error.into(),
         }
     }
 }
@@ -271,6 +292,12 @@ pub enum UrlEncodedError {
     UnsupportedShape(String),
     /// The type is not supported for deserialization.
     UnsupportedType(String),
+    /// A key didn't match any field of a struct marked `#[facet(deny_unknown_fields)]`.
+    UnknownField(String),
+    /// The value could not be built, e.g. because fields were missing.
+    Reflect(ReflectError),
+    /// The form data doesn't fit the value's type.
+    Deserialize(DeserializeError),
 }
 
 impl core::fmt::Display for UrlEncodedError {
@@ -285,8 +312,27 @@ impl core::fmt::Display for UrlEncodedError {
             UrlEncodedError::UnsupportedType(ty) => {
                 write!(f, "Unsupported type: {}", ty)
             }
+            UrlEncodedError::UnknownField(field) => {
+                write!(f, "Unknown field: {}", field)
+            }
+            UrlEncodedError::Reflect(err) => {
+                write!(f, "{}", err)
+            }
+            UrlEncodedError::Deserialize(err) => {
+                write!(f, "{}", err)
+            }
         }
     }
 }
 
 impl std::error::Error for UrlEncodedError {}
+
+impl From<DeserializeError> for UrlEncodedError {
+    fn from(err: DeserializeError) -> Self {
+        match err {
+            DeserializeError::UnknownField { field, .. } => UrlEncodedError::UnknownField(field),
+            DeserializeError::Reflect(err) => UrlEncodedError::Reflect(err),
+            err => UrlEncodedError::Deserialize(err),
+        }
+    }
+}
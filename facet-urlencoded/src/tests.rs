@@ -57,12 +57,18 @@ fn test_encoded_characters() {
 }
 
 #[test]
-#[should_panic(expected = "Field 'page' was not initialized")]
 fn test_missing_field() {
     let query_string = "query=rust+programming";
 
-    // This should panic because the 'page' field is not initialized
-    let _params: SearchParams = from_str(query_string).expect("Failed to parse URL encoded data");
+    let err = from_str::<SearchParams>(query_string).unwrap_err();
+    assert!(
+        matches!(err.error, crate::UrlEncodedError::Reflect(_)),
+        "{err:?}"
+    );
+    assert_eq!(
+        err.to_string(),
+        "missing field in SearchParams: `page` (expected SearchParams)"
+    );
 }
 
 #[test]
@@ -87,7 +93,8 @@ fn test_invalid_number() {
 
     assert!(result.is_err());
     if let Err(err) = result {
-        match err {
+        assert_eq!(err.path.to_string(), "page");
+        match err.error {
             crate::UrlEncodedError::InvalidNumber(field, value) => {
                 assert_eq!(field, "page");
                 assert_eq!(value, "not_a_number");
@@ -122,13 +129,13 @@ fn test_nested_struct() {
 }
 
 #[test]
-#[should_panic(expected = "Field 'city' was not initialized")]
 fn test_partial_nested_struct() {
     // Missing some nested fields
     let query_string = "user[name]=John+Doe&user[age]=30&user[address][street]=123+Main+St&product_id=ABC123&quantity=2";
 
-    // This should panic because some required nested fields are missing
-    let _order: OrderForm = from_str(query_string).expect("Failed to parse partial nested struct");
+    let err = from_str::<OrderForm>(query_string).unwrap_err();
+    assert!(err.to_string().contains("`city`"), "{err}");
+    assert_eq!(err.path.to_string(), "user.address");
 }
 
 #[test]
@@ -179,3 +186,62 @@ fn test_deep_nesting() {
         }
     );
 }
+
+#[test]
+fn test_renamed_fields() {
+    #[derive(Debug, Facet, PartialEq)]
+    #[facet(rename_all = "camelCase")]
+    struct Filter {
+        sort_by: String,
+        #[facet(rename = "n")]
+        page_size: u64,
+    }
+
+    let filter: Filter = from_str("sortBy=date&n=20").expect("Failed to parse URL encoded data");
+    assert_eq!(
+        filter,
+        Filter {
+            sort_by: "date".to_string(),
+            page_size: 20,
+        }
+    );
+}
+
+#[test]
+fn test_flattened_struct() {
+    #[derive(Debug, Facet, PartialEq)]
+    struct Paging {
+        page: u64,
+    }
+
+    #[derive(Debug, Facet, PartialEq)]
+    struct Search {
+        query: String,
+        #[facet(flatten)]
+        paging: Paging,
+    }
+
+    let search: Search = from_str("query=rust&page=3").unwrap();
+    assert_eq!(
+        search,
+        Search {
+            query: "rust".to_string(),
+            paging: Paging { page: 3 },
+        }
+    );
+}
+
+#[test]
+fn test_deny_unknown_fields() {
+    #[derive(Debug, Facet, PartialEq)]
+    #[facet(deny_unknown_fields)]
+    struct Strict {
+        query: String,
+    }
+
+    let err = from_str::<Strict>("query=rust&unknown=value").unwrap_err();
+    assert!(
+        matches!(&err.error, crate::UrlEncodedError::UnknownField(field) if field == "unknown"),
+        "{err:?}"
+    );
+}
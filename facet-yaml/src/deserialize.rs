@@ -0,0 +1,97 @@
+use std::borrow::Cow;
+
+use facet_core::{Facet, Opaque};
+use facet_reflect::{Deserializer, PokeUninit, Token, deserialize_opaque};
+use yaml_rust2::{Yaml, YamlLoader};
+
+use crate::AnyErr;
+
+/// Deserializes a YAML string into a value of type `T` that implements `Facet`.
+pub fn from_str<'facet, T: Facet<'facet>>(yaml: &str) -> Result<T, AnyErr> {
+    let (poke, _guard) = PokeUninit::alloc::<T>();
+    let opaque = from_str_opaque(poke, yaml)?;
+    Ok(unsafe { opaque.read::<T>() })
+}
+
+fn from_str_opaque<'mem>(poke: PokeUninit<'mem>, yaml: &str) -> Result<Opaque<'mem>, AnyErr> {
+    let docs = YamlLoader::load_from_str(yaml).map_err(|e| e.to_string())?;
+    if docs.len() != 1 {
+        return Err("Expected exactly one YAML document".into());
+    }
+    let mut de = YamlDeserializer {
+        root: Some(&docs[0]),
+        open: Vec::new(),
+    };
+    // SAFETY: strings are copied out of the document, never borrowed
+    Ok(unsafe { deserialize_opaque(poke, &mut de)? })
+}
+
+/// Walks a loaded YAML document, one token at a time
+struct YamlDeserializer<'y> {
+    /// The document, until it's been read
+    root: Option<&'y Yaml>,
+    /// The arrays and hashes being read, innermost last: whether it's a hash, and what's
+    /// left in it (keys and values alternate in hashes)
+    open: Vec<(bool, std::vec::IntoIter<&'y Yaml>)>,
+}
+
+impl<'input> Deserializer<'input> for YamlDeserializer<'_> {
+    type Error = AnyErr;
+
+    fn next(&mut self) -> Result<Token<'input>, AnyErr> {
+        let value = match self.root.take() {
+            Some(root) => root,
+            None => match self.open.last_mut() {
+                Some((is_hash, values)) => match values.next() {
+                    Some(value) => value,
+                    None => {
+                        let is_hash = *is_hash;
+                        self.open.pop();
+                        return Ok(if is_hash {
+                            Token::EndMap
+                        } else {
+                            Token::EndList
+                        });
+                    }
+                },
+                None => return Err("Unexpected end of the YAML document".into()),
+            },
+        };
+        let token = match value {
+            Yaml::Null => Token::Null,
+            Yaml::Boolean(b) => Token::Bool(*b),
+            Yaml::Integer(i) => Token::I64(*i),
+            // integers that don't fit in an i64 are loaded as reals, and parsed from their
+            // string form
+            Yaml::Real(s) if s.parse::<i128>().is_ok() || s.parse::<u128>().is_ok() => {
+                Token::Str(Cow::Owned(s.clone()))
+            }
+            Yaml::Real(s) => Token::F64(
+                value
+                    .as_f64()
+                    .ok_or_else(|| format!("Failed to parse real '{}'", s))?,
+            ),
+            Yaml::String(s) => Token::Str(Cow::Owned(s.clone())),
+            Yaml::Array(items) => {
+                self.open
+                    .push((false, items.iter().collect::<Vec<_>>().into_iter()));
+                Token::StartList(Some(items.len()))
+            }
+            Yaml::Hash(hash) => {
+                let entries = hash.iter().flat_map(|(k, v)| [k, v]).collect::<Vec<_>>();
+                self.open.push((true, entries.into_iter()));
+                Token::StartMap(Some(hash.len()))
+            }
+            Yaml::Alias(_) => return Err("YAML aliases are not supported".into()),
+            Yaml::BadValue => return Err("Bad YAML value".into()),
+        };
+        Ok(token)
+    }
+
+    fn skip(&mut self) -> Result<(), AnyErr> {
+        match self.open.last_mut().and_then(|(_, values)| values.next()) {
+            Some(_) => Ok(()),
+            None => Err("Expected a value to skip".into()),
+        }
+    }
+}
@@ -0,0 +1,230 @@
+use std::borrow::Cow;
+use std::io::Write;
+use std::num::NonZero;
+
+use facet_core::Facet;
+use facet_reflect::{Peek, PeekValue, Serializer, serialize};
+use yaml_rust2::{Yaml, YamlEmitter, yaml::Hash};
+
+use crate::AnyErr;
+
+/// Serializes a value of type `T` that implements `Facet` to a YAML string.
+///
+/// ```
+/// # use facet::Facet;
+/// #[derive(Facet)]
+/// struct Person {
+///     name: String,
+///     age: u64,
+/// }
+///
+/// let person = Person { name: "Alice".to_string(), age: 30 };
+/// let yaml = facet_yaml::to_string(&person).unwrap();
+/// assert_eq!(yaml, "---\nname: Alice\nage: 30");
+/// ```
+pub fn to_string<'facet, T: Facet<'facet>>(value: &T) -> Result<String, AnyErr> {
+    peek_to_string(Peek::new(value))
+}
+
+/// Serializes a value of type `T` that implements `Facet` as YAML into `writer`.
+pub fn to_writer<'facet, W: Write, T: Facet<'facet>>(
+    writer: &mut W,
+    value: &T,
+) -> Result<(), AnyErr> {
+    let yaml = to_string(value)?;
+    writer
+        .write_all(yaml.as_bytes())
+        .map_err(|e| AnyErr(e.to_string()))
+}
+
+/// Serializes any [`Peek`] to a YAML string.
+pub fn peek_to_string(peek: Peek<'_>) -> Result<String, AnyErr> {
+    let yaml = peek_to_yaml(peek)?;
+    let mut out = String::new();
+    YamlEmitter::new(&mut out)
+        .dump(&yaml)
+        .map_err(|e| AnyErr(e.to_string()))?;
+    Ok(out)
+}
+
+macro_rules! ints {
+    ($pv:expr, $($type:ty),*) => {
+        $(
+            if $pv.shape().is_type::<$type>() {
+                let value = unsafe { $pv.data().as_ref::<$type>() };
+                return Ok(int_to_yaml(*value));
+            }
+            if $pv.shape().is_type::<NonZero<$type>>() {
+                let value = unsafe { $pv.data().as_ref::<NonZero<$type>>() };
+                return Ok(int_to_yaml(value.get()));
+            }
+        )*
+    };
+}
+
+macro_rules! strings {
+    ($pv:expr, $($type:ty),*) => {
+        $(
+            if $pv.shape().is_type::<$type>() {
+                let value = unsafe { $pv.data().as_ref::<$type>() };
+                return Ok(Yaml::String(value.to_string()));
+            }
+        )*
+    };
+}
+
+/// Integers that don't fit in an `i64` are emitted verbatim, YAML loaders read them back as reals
+fn int_to_yaml<T: TryInto<i64> + ToString + Copy>(value: T) -> Yaml {
+    match value.try_into() {
+        Ok(i) => Yaml::Integer(i),
+        Err(_) => Yaml::Real(value.to_string()),
+    }
+}
+
+fn float_to_yaml(value: f64) -> Yaml {
+    let s = if value.is_nan() {
+        ".nan".to_string()
+    } else if value.is_infinite() {
+        if value > 0.0 { ".inf" } else { "-.inf" }.to_string()
+    } else {
+        // `Debug` keeps the fractional part (`1.0`), so the value reads back as a real
+        format!("{:?}", value)
+    };
+    Yaml::Real(s)
+}
+
+fn peek_value_to_yaml(pv: PeekValue<'_>) -> Result<Yaml, AnyErr> {
+    if pv.shape().is_type::<()>() {
+        return Ok(Yaml::Null);
+    }
+    if pv.shape().is_type::<bool>() {
+        return Ok(Yaml::Boolean(*unsafe { pv.data().as_ref::<bool>() }));
+    }
+    if pv.shape().is_type::<f64>() {
+        return Ok(float_to_yaml(*unsafe { pv.data().as_ref::<f64>() }));
+    }
+    if pv.shape().is_type::<f32>() {
+        return Ok(float_to_yaml(*unsafe { pv.data().as_ref::<f32>() } as f64));
+    }
+    ints!(
+        pv, u8, i8, u16, i16, u32, i32, u64, i64, u128, i128, usize, isize
+    );
+    strings!(pv, String, Cow<'_, str>, &str);
+
+    // anything else (e.g. `char`) is written in its string form
+    if pv.vtable().display.is_some() {
+        return Ok(Yaml::String(Peek::Value(pv).to_string()));
+    }
+    Err(format!("Unsupported scalar type: {}", pv.shape()).into())
+}
+
+fn peek_to_yaml(peek: Peek<'_>) -> Result<Yaml, AnyErr> {
+    let mut serializer = YamlSerializer::default();
+    serialize(peek, &mut serializer)?;
+    Ok(serializer.root.unwrap_or(Yaml::Null))
+}
+
+/// A YAML collection being built
+enum Frame {
+    Array(Vec<Yaml>),
+    /// A hash, and the key of the entry whose value comes next
+    Hash(Hash, Option<Yaml>),
+}
+
+/// Builds a [`Yaml`] document as a [`Serializer`] is told what's in the value
+#[derive(Default)]
+struct YamlSerializer {
+    stack: Vec<Frame>,
+    root: Option<Yaml>,
+}
+
+impl YamlSerializer {
+    /// Puts a complete value in the collection being built, or makes it the document
+    fn value(&mut self, yaml: Yaml) {
+        match self.stack.last_mut() {
+            Some(Frame::Array(items)) => items.push(yaml),
+            Some(Frame::Hash(hash, key)) => {
+                let key = key.take().expect("hash values come after their key");
+                hash.insert(key, yaml);
+            }
+            None => self.root = Some(yaml),
+        }
+    }
+
+    fn key(&mut self, key: Yaml) {
+        if let Some(Frame::Hash(_, pending)) = self.stack.last_mut() {
+            *pending = Some(key);
+        }
+    }
+
+    fn end(&mut self) {
+        let yaml = match self.stack.pop() {
+            Some(Frame::Array(items)) => Yaml::Array(items),
+            Some(Frame::Hash(hash, _)) => Yaml::Hash(hash),
+            None => return,
+        };
+        self.value(yaml);
+    }
+}
+
+impl Serializer for YamlSerializer {
+    type Error = AnyErr;
+
+    fn scalar(&mut self, value: PeekValue<'_>) -> Result<(), AnyErr> {
+        let yaml = peek_value_to_yaml(value)?;
+        self.value(yaml);
+        Ok(())
+    }
+
+    fn str(&mut self, s: &str) -> Result<(), AnyErr> {
+        self.value(Yaml::String(s.to_string()));
+        Ok(())
+    }
+
+    fn none(&mut self) -> Result<(), AnyErr> {
+        self.value(Yaml::Null);
+        Ok(())
+    }
+
+    fn start_struct(&mut self, _len: usize) -> Result<(), AnyErr> {
+        self.stack.push(Frame::Hash(Hash::new(), None));
+        Ok(())
+    }
+
+    fn field(&mut self, name: &str) -> Result<(), AnyErr> {
+        self.key(Yaml::String(name.to_string()));
+        Ok(())
+    }
+
+    fn end_struct(&mut self) -> Result<(), AnyErr> {
+        self.end();
+        Ok(())
+    }
+
+    fn start_list(&mut self, len: usize) -> Result<(), AnyErr> {
+        self.stack.push(Frame::Array(Vec::with_capacity(len)));
+        Ok(())
+    }
+
+    fn end_list(&mut self) -> Result<(), AnyErr> {
+        self.end();
+        Ok(())
+    }
+
+    fn start_map(&mut self, _len: usize) -> Result<(), AnyErr> {
+        self.stack.push(Frame::Hash(Hash::new(), None));
+        Ok(())
+    }
+
+    fn map_key(&mut self, key: Peek<'_>) -> Result<(), AnyErr> {
+        // keys are written in their scalar form
+        let key = peek_value_to_yaml(key.as_value())?;
+        self.key(key);
+        Ok(())
+    }
+
+    fn end_map(&mut self) -> Result<(), AnyErr> {
+        self.end();
+        Ok(())
+    }
+}
@@ -22,3 +22,350 @@ fn test_deserialize_person() {
         }
     );
 }
+
+#[test]
+fn test_deserialize_map_with_integer_keys() {
+    #[derive(Debug, Facet, PartialEq)]
+    struct Inventory {
+        items: std::collections::BTreeMap<u64, String>,
+        counts: std::collections::HashMap<String, u64>,
+    }
+
+    let yaml = r#"
+            items:
+              1: apple
+              42: pear
+            counts:
+              apple: 3
+        "#;
+
+    let inventory: Inventory = facet_yaml::from_str(yaml).expect("Failed to parse YAML");
+    assert_eq!(
+        inventory,
+        Inventory {
+            items: [(1, "apple".to_string()), (42, "pear".to_string())].into(),
+            counts: [("apple".to_string(), 3)].into(),
+        }
+    );
+}
+
+#[test]
+fn test_deserialize_renamed_fields() {
+    #[derive(Debug, Facet, PartialEq)]
+    #[facet(rename_all = "SCREAMING_SNAKE_CASE")]
+    struct Env {
+        home_dir: String,
+        #[facet(rename = "uid")]
+        user_id: u64,
+    }
+
+    let yaml = r#"
+            HOME_DIR: /home/alice
+            uid: 1000
+        "#;
+
+    let env: Env = facet_yaml::from_str(yaml).expect("Failed to parse YAML");
+    assert_eq!(
+        env,
+        Env {
+            home_dir: "/home/alice".to_string(),
+            user_id: 1000,
+        }
+    );
+}
+
+#[test]
+fn test_deserialize_missing_field() {
+    #[derive(Debug, Facet, PartialEq)]
+    struct Person {
+        name: String,
+        age: u64,
+    }
+
+    let err = facet_yaml::from_str::<Person>("name: Alice").unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "missing field in Person: `age` (expected Person)"
+    );
+}
+
+#[test]
+fn test_deserialize_error_path() {
+    #[derive(Debug, Facet)]
+    #[allow(dead_code)]
+    struct Server {
+        port: u16,
+    }
+
+    #[derive(Debug, Facet)]
+    #[allow(dead_code)]
+    struct Config {
+        servers: Vec<Server>,
+    }
+
+    let yaml = r#"
+        servers:
+          - port: 80
+          - port: 443
+          - port: 70000
+    "#;
+    let err = facet_yaml::from_str::<Config>(yaml).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "servers[2].port: 70000 is out of range for u16 (expected u16)"
+    );
+}
+
+#[test]
+fn test_deserialize_tagged_enums() {
+    #[derive(Debug, Facet, PartialEq)]
+    #[repr(u8)]
+    #[allow(dead_code)]
+    enum External {
+        Unit,
+        Newtype(u64),
+        Pair(u64, String),
+        Point { x: u64, y: u64 },
+    }
+
+    assert_eq!(
+        facet_yaml::from_str::<External>("Unit").unwrap(),
+        External::Unit
+    );
+    assert_eq!(
+        facet_yaml::from_str::<External>("Newtype: 7").unwrap(),
+        External::Newtype(7)
+    );
+    assert_eq!(
+        facet_yaml::from_str::<External>("Pair: [1, a]").unwrap(),
+        External::Pair(1, "a".to_string())
+    );
+    assert_eq!(
+        facet_yaml::from_str::<External>("Point: { x: 1, y: 2 }").unwrap(),
+        External::Point { x: 1, y: 2 }
+    );
+
+    #[derive(Debug, Facet, PartialEq)]
+    #[repr(u8)]
+    #[facet(tag = "type")]
+    enum Internal {
+        Empty,
+        Circle { radius: u64 },
+    }
+
+    let yaml = r#"
+            radius: 3
+            type: Circle
+        "#;
+    assert_eq!(
+        facet_yaml::from_str::<Internal>(yaml).unwrap(),
+        Internal::Circle { radius: 3 }
+    );
+    assert_eq!(
+        facet_yaml::from_str::<Internal>("type: Empty").unwrap(),
+        Internal::Empty
+    );
+    let err = facet_yaml::from_str::<Internal>("radius: 3").unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "missing tag `type` for Internal (expected Internal)"
+    );
+
+    #[derive(Debug, Facet, PartialEq)]
+    #[repr(u8)]
+    #[facet(tag = "t")]
+    #[facet(content = "c")]
+    enum Adjacent {
+        Ping,
+        Move(u64, u64),
+    }
+
+    assert_eq!(
+        facet_yaml::from_str::<Adjacent>("t: Ping").unwrap(),
+        Adjacent::Ping
+    );
+    assert_eq!(
+        facet_yaml::from_str::<Adjacent>("{ c: [1, 2], t: Move }").unwrap(),
+        Adjacent::Move(1, 2)
+    );
+
+    #[derive(Debug, Facet, PartialEq)]
+    #[repr(u8)]
+    #[facet(untagged)]
+    enum Untagged {
+        Number(u64),
+        Named { name: String },
+        Text(String),
+    }
+
+    assert_eq!(
+        facet_yaml::from_str::<Untagged>("42").unwrap(),
+        Untagged::Number(42)
+    );
+    assert_eq!(
+        facet_yaml::from_str::<Untagged>("name: n").unwrap(),
+        Untagged::Named {
+            name: "n".to_string()
+        }
+    );
+    assert_eq!(
+        facet_yaml::from_str::<Untagged>("hello").unwrap(),
+        Untagged::Text("hello".to_string())
+    );
+}
+
+#[test]
+fn test_deserialize_flattened_struct() {
+    #[derive(Debug, Facet, PartialEq)]
+    struct Owner {
+        uid: u64,
+    }
+
+    #[derive(Debug, Facet, PartialEq)]
+    struct File {
+        path: String,
+        #[facet(flatten)]
+        owner: Owner,
+    }
+
+    let yaml = r#"
+            uid: 1000
+            path: /etc/hosts
+        "#;
+
+    let file: File = facet_yaml::from_str(yaml).expect("Failed to parse YAML");
+    assert_eq!(
+        file,
+        File {
+            path: "/etc/hosts".to_string(),
+            owner: Owner { uid: 1000 },
+        }
+    );
+
+    let err = facet_yaml::from_str::<File>("path: /etc/hosts").unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "missing field in Owner: `uid` (expected File)"
+    );
+}
+
+#[test]
+fn test_deserialize_lists_and_options() {
+    #[derive(Debug, Facet, PartialEq)]
+    struct Container {
+        name: String,
+        image: Option<String>,
+        args: Vec<String>,
+        ports: Vec<Port>,
+    }
+
+    #[derive(Debug, Facet, PartialEq)]
+    struct Port {
+        container_port: u16,
+        protocol: Option<String>,
+    }
+
+    let yaml = r#"
+            name: web
+            image: ~
+            args: []
+            ports:
+              - container_port: 80
+                protocol: TCP
+              - container_port: 443
+                protocol: null
+        "#;
+
+    let container: Container = facet_yaml::from_str(yaml).expect("Failed to parse YAML");
+    assert_eq!(
+        container,
+        Container {
+            name: "web".to_string(),
+            image: None,
+            args: vec![],
+            ports: vec![
+                Port {
+                    container_port: 80,
+                    protocol: Some("TCP".to_string()),
+                },
+                Port {
+                    container_port: 443,
+                    protocol: None,
+                },
+            ],
+        }
+    );
+}
+
+#[test]
+fn test_deserialize_more_scalars() {
+    #[derive(Debug, Facet, PartialEq)]
+    struct Scalars {
+        small: i8,
+        big: u128,
+        ratio: f64,
+        whole: f32,
+        enabled: bool,
+        initial: char,
+        count: std::num::NonZero<u32>,
+    }
+
+    let yaml = r#"
+            small: -12
+            big: 340282366920938463463374607431768211455
+            ratio: 0.25
+            whole: 3
+            enabled: true
+            initial: z
+            count: 7
+        "#;
+
+    let scalars: Scalars = facet_yaml::from_str(yaml).expect("Failed to parse YAML");
+    assert_eq!(
+        scalars,
+        Scalars {
+            small: -12,
+            big: u128::MAX,
+            ratio: 0.25,
+            whole: 3.0,
+            enabled: true,
+            initial: 'z',
+            count: std::num::NonZero::new(7).unwrap(),
+        }
+    );
+
+    let err = facet_yaml::from_str::<Scalars>("small: 300").unwrap_err();
+    assert!(err.to_string().contains("out of range"), "{}", err);
+}
+
+#[test]
+fn test_deserialize_sets_tuples_and_smart_pointers() {
+    #[derive(Debug, Facet, PartialEq)]
+    struct Point(i32, i32);
+
+    #[derive(Debug, Facet, PartialEq)]
+    struct Shapes {
+        tags: std::collections::BTreeSet<String>,
+        origin: Point,
+        boxed: Box<Point>,
+        shared: std::sync::Arc<String>,
+    }
+
+    let yaml = r#"
+            tags: [b, a, b]
+            origin: [1, -2]
+            boxed: [3, 4]
+            shared: hello
+        "#;
+
+    let shapes: Shapes = facet_yaml::from_str(yaml).expect("Failed to parse YAML");
+    assert_eq!(
+        shapes,
+        Shapes {
+            tags: ["a".to_string(), "b".to_string()].into(),
+            origin: Point(1, -2),
+            boxed: Box::new(Point(3, 4)),
+            shared: std::sync::Arc::new("hello".to_string()),
+        }
+    );
+}
@@ -0,0 +1,167 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use facet::Facet;
+
+#[test]
+fn yaml_roundtrip_config() {
+    #[derive(Debug, PartialEq, Facet)]
+    struct Deployment {
+        api_version: String,
+        metadata: Metadata,
+        replicas: Option<u32>,
+        containers: Vec<Container>,
+        strategy: Strategy,
+    }
+
+    #[derive(Debug, PartialEq, Facet)]
+    struct Metadata {
+        name: String,
+        labels: BTreeMap<String, String>,
+        finalizers: BTreeSet<String>,
+    }
+
+    #[derive(Debug, PartialEq, Facet)]
+    struct Container {
+        name: String,
+        command: Vec<String>,
+        cpu: f64,
+        env: BTreeMap<String, String>,
+    }
+
+    #[derive(Debug, PartialEq, Facet)]
+    #[repr(u8)]
+    #[facet(tag = "type")]
+    enum Strategy {
+        Recreate,
+        RollingUpdate {
+            max_surge: u32,
+            max_unavailable: u32,
+        },
+    }
+
+    let deployment = Deployment {
+        api_version: "apps/v1".to_string(),
+        metadata: Metadata {
+            name: "web".to_string(),
+            labels: [("app".to_string(), "web".to_string())].into(),
+            finalizers: ["cleanup".to_string()].into(),
+        },
+        replicas: Some(3),
+        containers: vec![
+            Container {
+                name: "server".to_string(),
+                command: vec!["serve".to_string(), "--port=80".to_string()],
+                cpu: 0.5,
+                env: [("MODE".to_string(), "production".to_string())].into(),
+            },
+            Container {
+                name: "sidecar".to_string(),
+                command: vec![],
+                cpu: 1.0,
+                env: BTreeMap::new(),
+            },
+        ],
+        strategy: Strategy::RollingUpdate {
+            max_surge: 1,
+            max_unavailable: 0,
+        },
+    };
+
+    let yaml = facet_yaml::to_string(&deployment).unwrap();
+    let back: Deployment = facet_yaml::from_str(&yaml).unwrap();
+    assert_eq!(back, deployment);
+
+    let recreate = Deployment {
+        replicas: None,
+        strategy: Strategy::Recreate,
+        ..back
+    };
+    let yaml = facet_yaml::to_string(&recreate).unwrap();
+    let back: Deployment = facet_yaml::from_str(&yaml).unwrap();
+    assert_eq!(back, recreate);
+}
+
+#[test]
+fn yaml_roundtrip_scalars() {
+    #[derive(Debug, PartialEq, Facet)]
+    struct Scalars {
+        unsigned: u64,
+        signed: i64,
+        huge: u128,
+        float: f32,
+        infinity: f64,
+        flag: bool,
+        letter: char,
+        text: String,
+        numeric_text: String,
+        unit: (),
+        pair: (u8, String),
+    }
+
+    let scalars = Scalars {
+        unsigned: u64::MAX,
+        signed: i64::MIN,
+        huge: u128::MAX,
+        float: 1.0,
+        infinity: f64::NEG_INFINITY,
+        flag: false,
+        letter: 'é',
+        text: "multi\nline: text".to_string(),
+        numeric_text: "123".to_string(),
+        unit: (),
+        pair: (7, "seven".to_string()),
+    };
+
+    let yaml = facet_yaml::to_string(&scalars).unwrap();
+    let back: Scalars = facet_yaml::from_str(&yaml).unwrap();
+    assert_eq!(back, scalars);
+}
+
+#[test]
+fn yaml_roundtrip_enums() {
+    #[derive(Debug, PartialEq, Facet)]
+    #[repr(u8)]
+    enum External {
+        Unit,
+        Newtype(u32),
+        Tuple(u32, String),
+        Struct { x: u32 },
+    }
+
+    #[derive(Debug, PartialEq, Facet)]
+    #[repr(u8)]
+    #[facet(tag = "t")]
+    #[facet(content = "c")]
+    enum Adjacent {
+        Unit,
+        Tuple(u32, String),
+    }
+
+    #[derive(Debug, PartialEq, Facet)]
+    #[repr(u8)]
+    #[facet(untagged)]
+    enum Untagged {
+        Number(u32),
+        Text(String),
+    }
+
+    for value in [
+        External::Unit,
+        External::Newtype(1),
+        External::Tuple(2, "two".to_string()),
+        External::Struct { x: 3 },
+    ] {
+        let yaml = facet_yaml::to_string(&value).unwrap();
+        assert_eq!(facet_yaml::from_str::<External>(&yaml).unwrap(), value);
+    }
+
+    for value in [Adjacent::Unit, Adjacent::Tuple(1, "one".to_string())] {
+        let yaml = facet_yaml::to_string(&value).unwrap();
+        assert_eq!(facet_yaml::from_str::<Adjacent>(&yaml).unwrap(), value);
+    }
+
+    for value in [Untagged::Number(1), Untagged::Text("one".to_string())] {
+        let yaml = facet_yaml::to_string(&value).unwrap();
+        assert_eq!(facet_yaml::from_str::<Untagged>(&yaml).unwrap(), value);
+    }
+}
@@ -0,0 +1,239 @@
+use std::num::NonZero;
+
+use facet::Facet;
+
+#[test]
+fn test_to_string() {
+    #[derive(Debug, PartialEq, Clone, Facet)]
+    struct LinearFunction {
+        variable: String,
+        slope: f32,
+        intercept: i32,
+    }
+
+    let test_struct = LinearFunction {
+        variable: "x".to_string(),
+        slope: -3.5,
+        intercept: -5,
+    };
+
+    let yaml = facet_yaml::to_string(&test_struct).unwrap();
+    assert_eq!(yaml, "---\nvariable: x\nslope: -3.5\nintercept: -5");
+
+    let mut buffer = Vec::new();
+    facet_yaml::to_writer(&mut buffer, &test_struct).unwrap();
+    assert_eq!(String::from_utf8(buffer).unwrap(), yaml);
+}
+
+#[test]
+fn test_nonzero() {
+    #[derive(Debug, PartialEq, Clone, Facet)]
+    struct Foo {
+        foo: NonZero<u8>,
+    }
+
+    let test_struct = Foo {
+        foo: const { NonZero::new(1).unwrap() },
+    };
+
+    let yaml = facet_yaml::to_string(&test_struct).unwrap();
+    assert_eq!(yaml, "---\nfoo: 1");
+}
+
+#[test]
+fn test_lists_and_maps() {
+    #[derive(Debug, PartialEq, Clone, Facet)]
+    struct Deployment {
+        name: String,
+        replicas: u32,
+        ports: Vec<u16>,
+        labels: std::collections::BTreeMap<String, String>,
+        volumes: Vec<Volume>,
+    }
+
+    #[derive(Debug, PartialEq, Clone, Facet)]
+    struct Volume {
+        name: String,
+        read_only: bool,
+    }
+
+    let deployment = Deployment {
+        name: "web".to_string(),
+        replicas: 3,
+        ports: vec![80, 443],
+        labels: [("app".to_string(), "web".to_string())].into(),
+        volumes: vec![Volume {
+            name: "config".to_string(),
+            read_only: true,
+        }],
+    };
+
+    let yaml = facet_yaml::to_string(&deployment).unwrap();
+    assert_eq!(
+        yaml,
+        r#"---
+name: web
+replicas: 3
+ports:
+  - 80
+  - 443
+labels:
+  app: web
+volumes:
+  - name: config
+    read_only: true"#
+    );
+}
+
+#[test]
+fn test_static_strings() {
+    #[derive(Debug, PartialEq, Clone, Facet)]
+    struct StaticFoo {
+        foo: &'static str,
+    }
+
+    let yaml = facet_yaml::to_string(&StaticFoo { foo: "foo" }).unwrap();
+    assert_eq!(yaml, "---\nfoo: foo");
+
+    #[derive(Debug, PartialEq, Clone, Facet)]
+    struct OptStaticFoo {
+        foo: Option<&'static str>,
+    }
+
+    let yaml = facet_yaml::to_string(&OptStaticFoo { foo: None }).unwrap();
+    assert_eq!(yaml, "---\nfoo: ~");
+
+    let yaml = facet_yaml::to_string(&OptStaticFoo { foo: Some("foo") }).unwrap();
+    assert_eq!(yaml, "---\nfoo: foo");
+
+    #[derive(Debug, PartialEq, Clone, Facet)]
+    struct CowFoo {
+        foo: std::borrow::Cow<'static, str>,
+    }
+
+    let test_struct = CowFoo {
+        foo: std::borrow::Cow::from("foo"),
+    };
+    let yaml = facet_yaml::to_string(&test_struct).unwrap();
+    assert_eq!(yaml, "---\nfoo: foo");
+}
+
+#[test]
+fn test_strings_that_need_quoting() {
+    #[derive(Debug, PartialEq, Clone, Facet)]
+    struct Foo {
+        number: String,
+        boolean: String,
+        empty: String,
+    }
+
+    let test_struct = Foo {
+        number: "42".to_string(),
+        boolean: "true".to_string(),
+        empty: String::new(),
+    };
+
+    let yaml = facet_yaml::to_string(&test_struct).unwrap();
+    assert_eq!(yaml, "---\nnumber: \"42\"\nboolean: \"true\"\nempty: \"\"");
+}
+
+#[test]
+fn test_serialize_smart_pointers() {
+    #[derive(Debug, Facet)]
+    struct Node {
+        value: u32,
+        next: Option<Box<Leaf>>,
+        shared: std::sync::Arc<Leaf>,
+        weak: std::rc::Weak<Leaf>,
+    }
+
+    #[derive(Debug, Facet)]
+    struct Leaf {
+        name: String,
+    }
+
+    let node = Node {
+        value: 1,
+        next: None,
+        shared: std::sync::Arc::new(Leaf {
+            name: "shared".to_string(),
+        }),
+        weak: std::rc::Weak::new(),
+    };
+
+    let yaml = facet_yaml::to_string(&node).unwrap();
+    assert_eq!(
+        yaml,
+        "---\nvalue: 1\nnext: ~\nshared:\n  name: shared\nweak: ~"
+    );
+}
+
+#[test]
+fn test_skipped_fields() {
+    #[derive(Debug, Facet)]
+    struct Secret {
+        user: String,
+        #[facet(skip_serializing)]
+        password: String,
+    }
+
+    let secret = Secret {
+        user: "alice".to_string(),
+        password: "hunter2".to_string(),
+    };
+
+    let yaml = facet_yaml::to_string(&secret).unwrap();
+    assert_eq!(yaml, "---\nuser: alice");
+}
+
+#[test]
+fn test_tagged_enums() {
+    #[derive(Debug, Facet)]
+    #[repr(u8)]
+    #[allow(dead_code)]
+    enum External {
+        Unit,
+        Newtype(u32),
+        Tuple(u32, String),
+        Struct { x: u32 },
+    }
+
+    #[derive(Debug, Facet)]
+    #[repr(u8)]
+    #[facet(tag = "kind")]
+    #[allow(dead_code)]
+    enum Internal {
+        Struct { x: u32 },
+    }
+
+    #[derive(Debug, Facet)]
+    #[repr(u8)]
+    #[facet(tag = "t")]
+    #[facet(content = "c")]
+    #[allow(dead_code)]
+    enum Adjacent {
+        Newtype(u32),
+    }
+
+    assert_eq!(facet_yaml::to_string(&External::Unit).unwrap(), "---\nUnit");
+    assert_eq!(
+        facet_yaml::to_string(&External::Newtype(1)).unwrap(),
+        "---\nNewtype: 1"
+    );
+    assert_eq!(
+        facet_yaml::to_string(&External::Tuple(1, "a".to_string())).unwrap(),
+        "---\nTuple:\n  - 1\n  - a"
+    );
+    assert_eq!(
+        facet_yaml::to_string(&External::Struct { x: 1 }).unwrap(),
+        "---\nStruct:\n  x: 1"
+    );
+    assert_eq!(
+        facet_yaml::to_string(&Internal::Struct { x: 1 }).unwrap(),
+        "---\nkind: Struct\nx: 1"
+    );
+    assert_eq!(
+        facet_yaml::to_string(&Adjacent::Newtype(1)).unwrap(),
+        "---\nt: Newtype\nc: 1"
+    );
+}
@@ -29,7 +29,10 @@ pub struct KitchenSinkStruct {
 static KITCHEN_SINK_STRUCT_SHAPE: &'static ::facet::Shape =
     <KitchenSinkStruct as ::facet::Facet>::SHAPE;
 #[automatically_derived]
-unsafe impl ::facet::Facet for KitchenSinkStruct {
+unsafe impl<'__facet> ::facet::Facet<'__facet> for KitchenSinkStruct
+where
+    &'static [u8]: ::facet::Facet<'__facet>,
+{
     const SHAPE: &'static ::facet::Shape = &const {
         let fields: &'static [::facet::Field] = &const {
             [
@@ -413,7 +416,7 @@ pub struct Point {
 #[used]
 static POINT_SHAPE: &'static ::facet::Shape = <Point as ::facet::Facet>::SHAPE;
 #[automatically_derived]
-unsafe impl ::facet::Facet for Point {
+unsafe impl<'__facet> ::facet::Facet<'__facet> for Point {
     const SHAPE: &'static ::facet::Shape = &const {
         let fields: &'static [::facet::Field] = &const {
             [
@@ -790,7 +793,7 @@ pub enum KitchenSinkEnum {
 static KITCHEN_SINK_ENUM_SHAPE: &'static ::facet::Shape =
     <KitchenSinkEnum as ::facet::Facet>::SHAPE;
 #[automatically_derived]
-unsafe impl ::facet::Facet for KitchenSinkEnum {
+unsafe impl<'__facet> ::facet::Facet<'__facet> for KitchenSinkEnum {
     const SHAPE: &'static ::facet::Shape = &const {
         #[repr(C)]
         struct __ShadowKitchenSinkEnum_TupleVariantSimple {
@@ -1294,7 +1297,7 @@ pub enum SubEnum {
 #[used]
 static SUB_ENUM_SHAPE: &'static ::facet::Shape = <SubEnum as ::facet::Facet>::SHAPE;
 #[automatically_derived]
-unsafe impl ::facet::Facet for SubEnum {
+unsafe impl<'__facet> ::facet::Facet<'__facet> for SubEnum {
     const SHAPE: &'static ::facet::Shape = &const {
         #[repr(C)]
         struct __ShadowSubEnum_OptionB {
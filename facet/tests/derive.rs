@@ -1,5 +1,7 @@
 use core::{fmt::Debug, mem::offset_of};
-use facet::{Def, Facet, FieldFlags, OpaqueConst, Shape, StructDef, StructKind, VariantKind};
+use facet::{
+    Def, EnumTagging, Facet, FieldFlags, OpaqueConst, Shape, StructDef, StructKind, VariantKind,
+};
 
 #[test]
 fn unit_struct() {
@@ -291,7 +293,7 @@ fn tuple_struct_field_doc_comment() {
 #[test]
 fn record_struct_generic() {
     #[derive(Clone, Hash, PartialEq, Eq, ::facet::Facet)]
-    struct Blah<'a, T: Facet, const C: usize = 3>
+    struct Blah<'a, T: Facet<'a>, const C: usize = 3>
     where
         T: core::hash::Hash,
     {
@@ -303,7 +305,7 @@ fn record_struct_generic() {
 fn tuple_struct_generic() {
     #[derive(Clone, Hash, PartialEq, Eq, ::facet::Facet)]
     #[repr(transparent)]
-    struct Blah<'a, T: Facet, const C: usize = 3>(T, core::marker::PhantomData<&'a ()>)
+    struct Blah<'a, T: Facet<'a>, const C: usize = 3>(T, core::marker::PhantomData<&'a ()>)
     where
         T: core::hash::Hash;
 }
@@ -321,7 +323,7 @@ fn enum_generic() {
     #[allow(dead_code)]
     #[derive(Clone, Hash, PartialEq, Eq, ::facet::Facet)]
     #[repr(u8)]
-    enum E<'a, T: Facet, const C: usize = 3>
+    enum E<'a, T: Facet<'a>, const C: usize = 3>
     where
         T: core::hash::Hash,
     {
@@ -339,7 +341,7 @@ fn enum_generic() {
 //     #[allow(dead_code)]
 //     #[derive(Clone, Hash, PartialEq, Eq, ::facet::Facet)]
 //     #[repr(u8)]
-//     enum E<'a, T: Facet, const C: usize = 3>
+//     enum E<'a, T: Facet<'a>, const C: usize = 3>
 //     where
 //         T: core::hash::Hash,
 //     {
@@ -498,3 +500,253 @@ fn array_field() {
         _ => unreachable!(),
     }
 }
+
+#[test]
+fn struct_with_renamed_fields() {
+    #[derive(Debug, Facet)]
+    #[facet(rename_all = "camelCase")]
+    struct Blah {
+        first_name: String,
+        #[facet(rename = "years")]
+        age_in_years: u32,
+        id: u64,
+    }
+
+    let Def::Struct(StructDef { fields, .. }) = Blah::SHAPE.def else {
+        panic!("Expected Struct innards");
+    };
+
+    // `name` is always the Rust identifier, renames only affect the serialized name
+    assert_eq!(fields[0].name, "first_name");
+    assert_eq!(fields[0].serialized_name(), "firstName");
+    assert_eq!(fields[1].name, "age_in_years");
+    assert_eq!(fields[1].serialized_name(), "years");
+    assert_eq!(fields[2].rename, None);
+    assert_eq!(fields[2].serialized_name(), "id");
+}
+
+#[test]
+fn rename_with_escapes() {
+    #[derive(Debug, Facet)]
+    struct Blah {
+        #[facet(rename = "say \"hi\"\t\u{e9}")]
+        greeting: String,
+    }
+
+    let Def::Struct(StructDef { fields, .. }) = Blah::SHAPE.def else {
+        panic!("Expected Struct innards");
+    };
+    assert_eq!(fields[0].serialized_name(), "say \"hi\"\té");
+}
+
+#[test]
+fn enum_with_renamed_variants() {
+    #[derive(Debug, Facet)]
+    #[repr(u8)]
+    #[facet(rename_all = "SCREAMING_SNAKE_CASE")]
+    #[allow(dead_code)]
+    enum Status {
+        InProgress,
+        #[facet(rename = "finished")]
+        Done,
+        Failed {
+            error_code: u32,
+        },
+    }
+
+    let Def::Enum(def) = Status::SHAPE.def else {
+        panic!("Expected Enum innards");
+    };
+
+    assert_eq!(def.variants[0].serialized_name(), "IN_PROGRESS");
+    assert_eq!(def.variants[1].serialized_name(), "finished");
+    assert_eq!(def.variants[2].serialized_name(), "FAILED");
+
+    // rename_all on an enum only applies to variant names
+    let VariantKind::Struct { fields } = def.variants[2].kind else {
+        panic!("Expected struct variant");
+    };
+    assert_eq!(fields[0].serialized_name(), "error_code");
+}
+
+#[test]
+fn rename_all_rules() {
+    macro_rules! field_names {
+        ($rule:tt) => {{
+            #[derive(Facet)]
+            #[facet(rename_all = $rule)]
+            #[allow(dead_code)]
+            struct Blah {
+                user_id: u32,
+                http_status_2xx: u32,
+            }
+
+            let Def::Struct(StructDef { fields, .. }) = Blah::SHAPE.def else {
+                panic!("Expected Struct innards");
+            };
+            fields
+                .iter()
+                .map(|f| f.serialized_name())
+                .collect::<Vec<_>>()
+        }};
+    }
+
+    assert_eq!(field_names!("lowercase"), ["user_id", "http_status_2xx"]);
+    assert_eq!(field_names!("UPPERCASE"), ["USER_ID", "HTTP_STATUS_2XX"]);
+    assert_eq!(field_names!("PascalCase"), ["UserId", "HttpStatus2xx"]);
+    assert_eq!(field_names!("camelCase"), ["userId", "httpStatus2xx"]);
+    assert_eq!(field_names!("snake_case"), ["user_id", "http_status_2xx"]);
+    assert_eq!(
+        field_names!("SCREAMING_SNAKE_CASE"),
+        ["USER_ID", "HTTP_STATUS_2XX"]
+    );
+    assert_eq!(field_names!("kebab-case"), ["user-id", "http-status-2xx"]);
+    assert_eq!(
+        field_names!("SCREAMING-KEBAB-CASE"),
+        ["USER-ID", "HTTP-STATUS-2XX"]
+    );
+}
+
+#[test]
+fn struct_with_skipped_fields() {
+    #[derive(Debug, Facet)]
+    struct Session {
+        id: u64,
+        #[facet(skip)]
+        cache: Vec<u8>,
+        #[facet(skip_serializing)]
+        token: String,
+        #[facet(skip_deserializing)]
+        hits: u32,
+        #[facet(skip_serializing_if = "Option::is_none")]
+        user: Option<String>,
+    }
+
+    let Def::Struct(StructDef { fields, .. }) = Session::SHAPE.def else {
+        panic!("Expected Struct innards");
+    };
+
+    assert_eq!(fields[0].flags, FieldFlags::EMPTY);
+    assert_eq!(
+        fields[1].flags,
+        FieldFlags::SKIP_SERIALIZING
+            .union(FieldFlags::SKIP_DESERIALIZING)
+            .union(FieldFlags::DEFAULT)
+    );
+    assert_eq!(fields[2].flags, FieldFlags::SKIP_SERIALIZING);
+    assert_eq!(
+        fields[3].flags,
+        FieldFlags::SKIP_DESERIALIZING.union(FieldFlags::DEFAULT)
+    );
+    assert_eq!(fields[4].flags, FieldFlags::EMPTY);
+
+    let none: Option<String> = None;
+    let some = Some("alice".to_string());
+    unsafe {
+        assert!(fields[4].should_skip_serializing(OpaqueConst::new(&none)));
+        assert!(!fields[4].should_skip_serializing(OpaqueConst::new(&some)));
+        assert!(fields[2].should_skip_serializing(OpaqueConst::new(&"secret".to_string())));
+    }
+}
+
+#[test]
+fn enum_tagging_attributes() {
+    #[derive(Facet)]
+    #[repr(u8)]
+    #[allow(dead_code)]
+    enum Plain {
+        A,
+    }
+
+    #[derive(Facet)]
+    #[repr(u8)]
+    #[facet(tag = "type")]
+    #[allow(dead_code)]
+    enum Internal {
+        A,
+    }
+
+    #[derive(Facet)]
+    #[repr(u8)]
+    #[facet(tag = "t")]
+    #[facet(content = "c")]
+    #[allow(dead_code)]
+    enum Adjacent {
+        A,
+    }
+
+    #[derive(Facet)]
+    #[repr(u8)]
+    #[facet(untagged)]
+    #[allow(dead_code)]
+    enum Untagged {
+        A,
+    }
+
+    fn tagging(shape: &Shape) -> EnumTagging {
+        match shape.def {
+            Def::Enum(enum_def) => enum_def.tagging,
+            _ => panic!("Expected an enum"),
+        }
+    }
+
+    assert_eq!(tagging(Plain::SHAPE), EnumTagging::External);
+    assert_eq!(
+        tagging(Internal::SHAPE),
+        EnumTagging::Internal { tag: "type" }
+    );
+    assert_eq!(
+        tagging(Adjacent::SHAPE),
+        EnumTagging::Adjacent {
+            tag: "t",
+            content: "c"
+        }
+    );
+    assert_eq!(tagging(Untagged::SHAPE), EnumTagging::Untagged);
+}
+
+#[test]
+fn struct_with_flattened_field() {
+    #[derive(Debug, Facet)]
+    struct Meta {
+        id: u64,
+    }
+
+    #[derive(Debug, Facet)]
+    struct Item {
+        #[facet(flatten)]
+        meta: Meta,
+        name: String,
+    }
+
+    let Def::Struct(StructDef { fields, .. }) = Item::SHAPE.def else {
+        panic!("Expected Struct innards");
+    };
+
+    assert_eq!(fields[0].flags, FieldFlags::FLATTEN);
+    assert_eq!(fields[1].flags, FieldFlags::EMPTY);
+    assert_eq!(FieldFlags::FLATTEN.to_string(), "flatten");
+}
+
+#[test]
+fn struct_deny_unknown_fields() {
+    #[derive(Debug, Facet)]
+    struct Lenient {
+        a: u32,
+    }
+
+    #[derive(Debug, Facet)]
+    #[facet(deny_unknown_fields)]
+    struct Strict {
+        a: u32,
+    }
+
+    let Def::Struct(lenient) = Lenient::SHAPE.def else {
+        panic!("Expected Struct innards");
+    };
+    let Def::Struct(strict) = Strict::SHAPE.def else {
+        panic!("Expected Struct innards");
+    };
+    assert!(!lenient.deny_unknown_fields);
+    assert!(strict.deny_unknown_fields);
+}